@@ -16,18 +16,51 @@ pub struct OutputArgs {
 pub struct StoreArgs {
     #[arg(long)]
     pub store: Option<String>,
+    /// Digest used to identify stored workflow documents (sha256|blake3).
+    #[arg(long, default_value_t = arazzo_store::HashAlgorithm::Sha256)]
+    pub doc_hash: arazzo_store::HashAlgorithm,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ValidateConfigArgs {
+    /// Validation rule overrides (YAML or JSON). Defaults to `.arazzo-validate.yaml`
+    /// next to the document, when it exists.
+    #[arg(long = "validate-config", value_name = "FILE")]
+    pub validate_config: Option<PathBuf>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct EnvArgs {
+    /// Expand `${VAR}` placeholders in source URLs and parameter values from the
+    /// environment; unset variables fail the command.
+    #[arg(long)]
+    pub env: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct OverlayArgs {
+    /// Overlay (YAML or JSON) patching the document before it is planned (repeatable,
+    /// applied in order).
+    #[arg(long = "overlay", value_name = "FILE")]
+    pub overlays: Vec<PathBuf>,
 }
 
 #[derive(Debug, Args, Clone)]
 pub struct OpenApiArgs {
     #[arg(long = "openapi", value_name = "NAME=PATH")]
     pub openapi_sources: Vec<String>,
+    #[arg(long = "sources-override", value_name = "FILE")]
+    pub sources_override: Option<PathBuf>,
 }
 
 #[derive(Debug, Args, Clone)]
 pub struct SecretsArgs {
     #[arg(long, default_value = "env")]
     pub secrets: String,
+    /// Read secrets piped on stdin: `ID` takes the whole input as `secrets://ID`,
+    /// `-` reads `KEY=VALUE` lines. Env secrets are still used for other refs.
+    #[arg(long, value_name = "ID|-")]
+    pub secrets_stdin: Option<String>,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -44,10 +77,22 @@ pub struct PolicyArgs {
     pub allow_hosts_file: Option<PathBuf>,
     #[arg(long)]
     pub allow_http: bool,
+    /// Source whose URL path/query may carry resolved secrets, e.g. signed URLs (repeatable).
+    #[arg(long = "allow-url-secrets", value_name = "SOURCE")]
+    pub allow_url_secrets: Vec<String>,
+    /// Fail a step when a secret ref cannot be resolved instead of sending it literally.
+    #[arg(long)]
+    pub strict_secrets: bool,
+    /// JSON/YAML file mapping source names to auth (basic, bearer or apikey).
+    #[arg(long, value_name = "PATH")]
+    pub auth_file: Option<PathBuf>,
     #[arg(long)]
     pub follow_redirects: bool,
     #[arg(long, default_value_t = 5)]
     pub max_redirects: usize,
+    /// 3xx status accepted as success instead of being followed (repeatable).
+    #[arg(long = "redirect-success", value_name = "STATUS")]
+    pub redirect_success: Vec<u16>,
     #[arg(long, default_value_t = 30000)]
     pub timeout: u64,
     #[arg(long, default_value_t = 4_194_304)]
@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use arazzo_core::{bundle_document, BundleError};
+use serde::Serialize;
+
+use crate::exit_codes;
+use crate::output::{print_error, print_result, OutputFormat};
+use crate::OutputArgs;
+
+#[derive(Serialize)]
+struct BundleResult {
+    output: String,
+    files: Vec<String>,
+}
+
+/// Bundle a document and its local file references into one document. It is
+/// printed as YAML (JSON with `--format json`), or written to `out`.
+pub async fn bundle_cmd(path: &Path, out: Option<&Path>, output: OutputArgs) -> i32 {
+    let bundled = match bundle_document(path) {
+        Ok(b) => b,
+        Err(e) => {
+            print_error(output.format, output.quiet, &e.to_string());
+            return match e {
+                BundleError::Read { .. } => exit_codes::RUNTIME_ERROR,
+                _ => exit_codes::VALIDATION_FAILED,
+            };
+        }
+    };
+
+    let written = if output.format == OutputFormat::Json {
+        bundled.document.to_json()
+    } else {
+        bundled.document.to_yaml()
+    };
+    let text = match written {
+        Ok(t) => t,
+        Err(e) => {
+            print_error(output.format, output.quiet, &e.to_string());
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let Some(out) = out else {
+        print!("{text}");
+        return exit_codes::SUCCESS;
+    };
+    if let Err(e) = std::fs::write(out, text) {
+        print_error(
+            output.format,
+            output.quiet,
+            &format!("failed to write {}: {e}", out.display()),
+        );
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    let result = BundleResult {
+        output: out.display().to_string(),
+        files: bundled
+            .files
+            .iter()
+            .map(|f| f.display().to_string())
+            .collect(),
+    };
+    if output.format == OutputFormat::Text && !output.quiet {
+        println!(
+            "Bundled {} files into {}",
+            result.files.len(),
+            result.output
+        );
+    } else {
+        print_result(output.format, output.quiet, &result);
+    }
+    exit_codes::SUCCESS
+}
@@ -1,4 +1,4 @@
-use arazzo_store::StateStore;
+use arazzo_store::{RunStatus, StateStore};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -66,10 +66,11 @@ pub async fn cancel_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i
 
     let previous_status = run.status.clone();
 
-    if previous_status == "canceled" {
+    let parsed_status = previous_status.parse::<RunStatus>();
+    if parsed_status == Ok(RunStatus::Canceled) {
         let result = CancelResult {
             run_id: run_uuid.to_string(),
-            status: "canceled".to_string(),
+            status: RunStatus::Canceled.to_string(),
             previous_status: Some(previous_status),
         };
         if output.format == OutputFormat::Text && !output.quiet {
@@ -80,7 +81,7 @@ pub async fn cancel_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i
         return exit_codes::SUCCESS;
     }
 
-    if previous_status == "succeeded" || previous_status == "failed" {
+    if parsed_status.is_ok_and(|status| status.is_terminal()) {
         print_error(
             output.format,
             output.quiet,
@@ -89,7 +90,10 @@ pub async fn cancel_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i
         return exit_codes::RUNTIME_ERROR;
     }
 
-    if let Err(e) = pg.mark_run_finished(run_uuid, "canceled", None).await {
+    if let Err(e) = pg
+        .mark_run_finished(run_uuid, RunStatus::Canceled.as_str(), None)
+        .await
+    {
         print_error(
             output.format,
             output.quiet,
@@ -0,0 +1,229 @@
+use std::path::Path;
+
+use arazzo_core::{
+    parse_document_str, plan_document_with, validation_report, PlanOptions, Severity,
+};
+use arazzo_exec::openapi::DiagnosticSeverity;
+use serde::Serialize;
+
+use crate::exit_codes;
+use crate::output::{print_error, print_result, OutputFormat};
+use crate::utils::document_format;
+use crate::{OpenApiArgs, OutputArgs, ValidateConfigArgs};
+
+use super::config::{
+    document_resolver, load_openapi_sources, load_source_overrides, load_validation_options,
+};
+
+/// The stage that reported a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Stage {
+    Parser,
+    Validator,
+    Planner,
+    Compiler,
+}
+
+impl Stage {
+    fn as_str(self) -> &'static str {
+        match self {
+            Stage::Parser => "parser",
+            Stage::Validator => "validator",
+            Stage::Planner => "planner",
+            Stage::Compiler => "compiler",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CheckDiagnostic {
+    severity: Severity,
+    source: Stage,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workflow_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    step_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CheckReport {
+    ok: bool,
+    errors: usize,
+    warnings: usize,
+    diagnostics: Vec<CheckDiagnostic>,
+}
+
+impl CheckReport {
+    fn new(diagnostics: Vec<CheckDiagnostic>) -> Self {
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        Self {
+            ok: errors == 0,
+            errors,
+            warnings: diagnostics.len() - errors,
+            diagnostics,
+        }
+    }
+}
+
+/// Parse, validate, plan and compile a document in one pass and report every
+/// diagnostic together. Compilation runs even when validation fails, so OpenAPI
+/// problems show up alongside document errors.
+pub async fn check_cmd(
+    path: &Path,
+    workflow_id: Option<&str>,
+    output: OutputArgs,
+    openapi: OpenApiArgs,
+    validate_config: ValidateConfigArgs,
+) -> i32 {
+    let content = match std::fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to read {}: {e}", path.display()),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let Some(validation) = load_validation_options(&validate_config, path, &output) else {
+        return exit_codes::RUNTIME_ERROR;
+    };
+
+    let mut diagnostics = Vec::new();
+    let parsed = match parse_document_str(&content, document_format(path)) {
+        Ok(p) => Some(p),
+        Err(e) => {
+            diagnostics.push(CheckDiagnostic {
+                severity: Severity::Error,
+                source: Stage::Parser,
+                message: e.to_string(),
+                workflow_id: None,
+                step_id: None,
+            });
+            None
+        }
+    };
+
+    if let Some(parsed) = &parsed {
+        let doc = &parsed.document;
+        let report = validation_report(doc, &validation);
+        let valid = report.is_valid();
+        diagnostics.extend(report.violations.iter().map(|v| CheckDiagnostic {
+            severity: v.severity,
+            source: Stage::Validator,
+            message: format!("{}: {}", v.path, v.message),
+            workflow_id: None,
+            step_id: None,
+        }));
+
+        let workflows: Vec<_> = doc
+            .workflows
+            .iter()
+            .filter(|w| workflow_id.map_or(true, |id| w.workflow_id == id))
+            .collect();
+        if let Some(id) = workflow_id.filter(|_| workflows.is_empty()) {
+            diagnostics.push(CheckDiagnostic {
+                severity: Severity::Error,
+                source: Stage::Planner,
+                message: format!("workflow '{id}' not found in document"),
+                workflow_id: None,
+                step_id: None,
+            });
+        }
+
+        // The planner needs a valid document; compilation does not.
+        if valid {
+            for wf in &workflows {
+                let planned = plan_document_with(
+                    doc,
+                    PlanOptions {
+                        workflow_id: Some(wf.workflow_id.clone()),
+                        inputs: None,
+                    },
+                    &validation,
+                );
+                if let Err(e) = planned {
+                    diagnostics.push(CheckDiagnostic {
+                        severity: Severity::Error,
+                        source: Stage::Planner,
+                        message: e.to_string(),
+                        workflow_id: Some(wf.workflow_id.clone()),
+                        step_id: None,
+                    });
+                }
+            }
+        }
+
+        let Some(preloaded) = load_openapi_sources(&openapi, &output).await else {
+            return exit_codes::RUNTIME_ERROR;
+        };
+        let Some(source_overrides) = load_source_overrides(&openapi, &output) else {
+            return exit_codes::RUNTIME_ERROR;
+        };
+        let compiler = arazzo_exec::Compiler::default()
+            .with_resolver(document_resolver(path).with_source_overrides(source_overrides))
+            .with_sources(preloaded);
+        for wf in &workflows {
+            let compiled = compiler.compile_workflow(doc, wf).await;
+            let step_diagnostics = compiled.steps.into_iter().flat_map(|s| {
+                s.diagnostics
+                    .into_iter()
+                    .map(move |d| (Some(s.step_id.clone()), d))
+            });
+            for (step_id, d) in compiled
+                .diagnostics
+                .into_iter()
+                .map(|d| (None, d))
+                .chain(step_diagnostics)
+            {
+                let message = match &d.source_name {
+                    Some(source) => format!("{source}: {}", d.message),
+                    None => d.message,
+                };
+                diagnostics.push(CheckDiagnostic {
+                    severity: match d.severity {
+                        DiagnosticSeverity::Error => Severity::Error,
+                        DiagnosticSeverity::Warning => Severity::Warning,
+                    },
+                    source: Stage::Compiler,
+                    message,
+                    workflow_id: Some(wf.workflow_id.clone()),
+                    step_id,
+                });
+            }
+        }
+    }
+
+    let report = CheckReport::new(diagnostics);
+    if output.format == OutputFormat::Text && !output.quiet {
+        for d in &report.diagnostics {
+            let workflow = match (&d.workflow_id, &d.step_id) {
+                (Some(wf), Some(step)) => format!(" ({wf}.{step})"),
+                (Some(wf), None) => format!(" ({wf})"),
+                _ => String::new(),
+            };
+            println!(
+                "{} [{}]{workflow}: {}",
+                d.severity.as_str(),
+                d.source.as_str(),
+                d.message
+            );
+        }
+        println!("{} errors, {} warnings", report.errors, report.warnings);
+    } else {
+        print_result(output.format, output.quiet, &report);
+    }
+
+    if report.ok {
+        exit_codes::SUCCESS
+    } else {
+        exit_codes::VALIDATION_FAILED
+    }
+}
@@ -3,8 +3,28 @@ use std::collections::BTreeSet;
 use std::path::Path;
 use std::time::Duration;
 
+use arazzo_core::{
+    apply_overlay, parse_overlay_str, ArazzoDocument, EnvReport, ParseOptions, RuleOverride,
+    ValidationOptions,
+};
+use serde::Deserialize;
+
 use crate::output::print_error;
-use crate::{ConcurrencyArgs, OutputArgs, PolicyArgs, RetryArgs};
+use crate::{
+    ConcurrencyArgs, EnvArgs, OpenApiArgs, OutputArgs, OverlayArgs, PolicyArgs, RetryArgs,
+    SecretsArgs, ValidateConfigArgs,
+};
+
+/// Validation config picked up from the document's directory when
+/// `--validate-config` is not given.
+pub const VALIDATE_CONFIG_FILE: &str = ".arazzo-validate.yaml";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidateConfig {
+    #[serde(default)]
+    rules: Vec<RuleOverride>,
+}
 
 pub fn load_inputs(path: Option<&Path>, output: &OutputArgs) -> Option<serde_json::Value> {
     let path = path?;
@@ -47,6 +67,317 @@ pub fn merge_set_inputs(inputs: &mut Option<serde_json::Value>, set_inputs: &[St
     }
 }
 
+/// Build the provenance object stored with the run from `--provenance KEY=VALUE`.
+/// `source_path` defaults to the canonical document path unless given explicitly, so
+/// a later `resume` from another directory still finds the document's neighbours.
+/// An entry without `=` is rejected rather than dropped.
+pub fn build_provenance(path: &Path, entries: &[String]) -> Result<serde_json::Value, String> {
+    let source_path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut map = serde_json::Map::new();
+    map.insert(
+        "source_path".to_string(),
+        serde_json::Value::String(source_path.display().to_string()),
+    );
+    for s in entries {
+        let Some((k, v)) = s.split_once('=') else {
+            return Err(format!(
+                "invalid --provenance value '{s}', expected KEY=VALUE"
+            ));
+        };
+        map.insert(k.to_string(), serde_json::Value::String(v.to_string()));
+    }
+    Ok(serde_json::Value::Object(map))
+}
+
+/// Report why a run could not be created and return the matching exit code.
+pub fn prepare_run_failed(
+    error: &arazzo_exec::executor::PrepareRunError,
+    output: &OutputArgs,
+) -> i32 {
+    print_error(output.format, output.quiet, &error.to_string());
+    match error {
+        arazzo_exec::executor::PrepareRunError::Store(_) => crate::exit_codes::RUNTIME_ERROR,
+        _ => crate::exit_codes::VALIDATION_FAILED,
+    }
+}
+
+/// Load `--openapi NAME=PATH` overrides so the compiler uses them instead of fetching.
+pub async fn load_openapi_sources(
+    openapi: &OpenApiArgs,
+    output: &OutputArgs,
+) -> Option<arazzo_exec::openapi::ResolvedSources> {
+    use arazzo_exec::openapi::Loader;
+
+    let loader = arazzo_exec::openapi::HttpLoader::default();
+    let mut sources = arazzo_exec::openapi::ResolvedSources::default();
+    for entry in &openapi.openapi_sources {
+        let Some((name, path)) = entry.split_once('=') else {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("invalid --openapi value '{entry}', expected NAME=PATH"),
+            );
+            return None;
+        };
+        match loader.load(path).await {
+            Ok(raw) => {
+                sources.openapi_docs.insert(
+                    name.to_string(),
+                    arazzo_exec::openapi::OpenApiDoc {
+                        source_url: path.to_string(),
+                        raw,
+                        base_url: None,
+                    },
+                );
+            }
+            Err(e) => {
+                print_error(
+                    output.format,
+                    output.quiet,
+                    &format!("failed to load OpenAPI for source '{name}' from {path}: {e}"),
+                );
+                return None;
+            }
+        }
+    }
+    Some(sources)
+}
+
+/// Validation options for the document at `document`, with the rule overrides of
+/// the validate config applied.
+pub fn load_validation_options(
+    args: &ValidateConfigArgs,
+    document: &Path,
+    output: &OutputArgs,
+) -> Option<ValidationOptions> {
+    let path = match &args.validate_config {
+        Some(path) => path.clone(),
+        None => {
+            let default = document
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(VALIDATE_CONFIG_FILE);
+            if !default.is_file() {
+                return Some(ValidationOptions::default());
+            }
+            default
+        }
+    };
+    let content = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to read validate config {}: {e}", path.display()),
+            );
+            return None;
+        }
+    };
+    let config: ValidateConfig = match serde_yaml::from_str(&content) {
+        Ok(c) => c,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("invalid validate config {}: {e}", path.display()),
+            );
+            return None;
+        }
+    };
+    Some(ValidationOptions {
+        overrides: config.rules,
+        ..ValidationOptions::default()
+    })
+}
+
+/// Parse options for commands that take `--env`.
+pub fn parse_options(env: &EnvArgs) -> ParseOptions {
+    ParseOptions {
+        interpolate_env: env.env,
+        ..ParseOptions::default()
+    }
+}
+
+/// Print the variables `--env` could not expand; `false` when there are any.
+pub fn check_env(report: Option<&EnvReport>, output: &OutputArgs) -> bool {
+    let Some(report) = report.filter(|r| !r.is_complete()) else {
+        return true;
+    };
+    let missing: Vec<String> = report
+        .missing
+        .iter()
+        .map(|v| format!("{} (at {})", v.name, v.path))
+        .collect();
+    print_error(
+        output.format,
+        output.quiet,
+        &format!("environment variables not set: {}", missing.join(", ")),
+    );
+    false
+}
+
+/// The text of every `--overlay`, in order.
+pub fn read_overlays(args: &OverlayArgs, output: &OutputArgs) -> Option<Vec<String>> {
+    let mut contents = Vec::with_capacity(args.overlays.len());
+    for path in &args.overlays {
+        match std::fs::read_to_string(path) {
+            Ok(c) => contents.push(c),
+            Err(e) => {
+                print_error(
+                    output.format,
+                    output.quiet,
+                    &format!("failed to read overlay {}: {e}", path.display()),
+                );
+                return None;
+            }
+        }
+    }
+    Some(contents)
+}
+
+/// `document` with every `--overlay` applied in order.
+pub fn apply_overlays(
+    args: &OverlayArgs,
+    document: ArazzoDocument,
+    output: &OutputArgs,
+) -> Option<ArazzoDocument> {
+    let contents = read_overlays(args, output)?;
+    let mut document = document;
+    for (path, content) in args.overlays.iter().zip(&contents) {
+        let applied =
+            parse_overlay_str(content).and_then(|overlay| apply_overlay(&document, &overlay));
+        document = match applied {
+            Ok(d) => d,
+            Err(e) => {
+                print_error(
+                    output.format,
+                    output.quiet,
+                    &format!("overlay {}: {e}", path.display()),
+                );
+                return None;
+            }
+        };
+    }
+    Some(document)
+}
+
+/// Resolver whose relative source URLs are resolved next to the workflow document at `path`.
+pub fn document_resolver(path: &Path) -> arazzo_exec::openapi::OpenApiResolver {
+    let resolver = arazzo_exec::openapi::OpenApiResolver::default();
+    match path.parent() {
+        Some(dir) => resolver.with_base_dir(dir),
+        None => resolver,
+    }
+}
+
+/// Load the `--sources-override` file: a JSON/YAML map of source name to either a URL
+/// string or an object with `url` and/or `base_url`.
+pub fn load_source_overrides(
+    openapi: &OpenApiArgs,
+    output: &OutputArgs,
+) -> Option<BTreeMap<String, arazzo_exec::openapi::SourceOverride>> {
+    let Some(path) = &openapi.sources_override else {
+        return Some(BTreeMap::new());
+    };
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to read sources override {}: {e}", path.display()),
+            );
+            return None;
+        }
+    };
+    let value: serde_json::Value = match serde_yaml::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("invalid sources override {}: {e}", path.display()),
+            );
+            return None;
+        }
+    };
+    let Some(entries) = value.as_object() else {
+        print_error(
+            output.format,
+            output.quiet,
+            "sources override must map source names to URLs",
+        );
+        return None;
+    };
+
+    let mut overrides = BTreeMap::new();
+    for (name, entry) in entries {
+        let parsed = match entry {
+            serde_json::Value::String(url) => Ok(arazzo_exec::openapi::SourceOverride {
+                url: Some(url.clone()),
+                base_url: None,
+            }),
+            other => serde_json::from_value(other.clone()),
+        };
+        match parsed {
+            Ok(o) => {
+                overrides.insert(name.clone(), o);
+            }
+            Err(e) => {
+                print_error(
+                    output.format,
+                    output.quiet,
+                    &format!("invalid sources override for '{name}': {e}"),
+                );
+                return None;
+            }
+        }
+    }
+    Some(overrides)
+}
+
+/// Overlay `--inputs`/`--set` values onto the inputs stored with a run.
+/// Top-level keys from `overrides` replace the stored ones; other stored keys are kept.
+/// The merge is shallow: a nested object or array is replaced as a whole, and `null`
+/// is stored as the input's value rather than removing it.
+pub fn overlay_inputs(
+    stored: &serde_json::Value,
+    overrides: Option<serde_json::Value>,
+) -> serde_json::Value {
+    let Some(overrides) = overrides else {
+        return stored.clone();
+    };
+    match (stored, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(extra)) => {
+            let mut merged = base.clone();
+            merged.extend(extra);
+            serde_json::Value::Object(merged)
+        }
+        (_, overrides) => overrides,
+    }
+}
+
+/// The inputs a resumed run should use: `--inputs`/`--set` loaded like for `execute`
+/// and overlaid onto the run's stored inputs. `Some(None)` when neither was given, so
+/// there is nothing to persist; `None` when the inputs file could not be loaded.
+pub fn resume_inputs(
+    stored: &serde_json::Value,
+    inputs_path: Option<&Path>,
+    set_inputs: &[String],
+    output: &OutputArgs,
+) -> Option<Option<serde_json::Value>> {
+    let mut overrides = load_inputs(inputs_path, output);
+    if overrides.is_none() && inputs_path.is_some() {
+        return None;
+    }
+    merge_set_inputs(&mut overrides, set_inputs);
+    if overrides.is_none() {
+        return Some(None);
+    }
+    Some(Some(overlay_inputs(stored, overrides)))
+}
+
 pub fn build_executor_config(
     concurrency: &ConcurrencyArgs,
     retry: &RetryArgs,
@@ -70,10 +401,15 @@ pub fn build_executor_config(
             max_delay: Duration::from_millis(retry.retry_max_delay.unwrap_or(60_000)),
             ..Default::default()
         },
+        debug_requests: false,
+        rng: arazzo_exec::executor::RngSource::default(),
     }
 }
 
-pub fn build_policy_config(policy: &PolicyArgs) -> arazzo_exec::policy::PolicyConfig {
+pub fn build_policy_config(
+    policy: &PolicyArgs,
+    output: &OutputArgs,
+) -> Option<arazzo_exec::policy::PolicyConfig> {
     let mut hosts: BTreeSet<String> = policy.allow_hosts.iter().cloned().collect();
     if let Some(file) = &policy.allow_hosts_file {
         if let Ok(content) = std::fs::read_to_string(file) {
@@ -92,14 +428,33 @@ pub fn build_policy_config(policy: &PolicyArgs) -> arazzo_exec::policy::PolicyCo
         ["https"].into_iter().map(String::from).collect()
     };
 
-    arazzo_exec::policy::PolicyConfig {
+    let mut per_source: BTreeMap<String, arazzo_exec::policy::SourcePolicyConfig> = policy
+        .allow_url_secrets
+        .iter()
+        .map(|source| {
+            (
+                source.clone(),
+                arazzo_exec::policy::SourcePolicyConfig {
+                    allow_secrets_in_url: Some(true),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+    for (source, auth) in load_auth_file(policy, output)? {
+        per_source.entry(source).or_default().auth = Some(auth);
+    }
+
+    Some(arazzo_exec::policy::PolicyConfig {
         network: arazzo_exec::policy::NetworkConfig {
             allowed_schemes: schemes,
             allowed_hosts: hosts,
+            allowed_methods: BTreeSet::new(),
             allowed_base_urls: BTreeSet::new(),
             redirects: arazzo_exec::policy::RedirectPolicy {
                 follow: policy.follow_redirects,
                 max_redirects: policy.max_redirects,
+                success_statuses: policy.redirect_success.iter().copied().collect(),
             },
             deny_private_ip_literals: true,
         },
@@ -108,6 +463,7 @@ pub fn build_policy_config(policy: &PolicyArgs) -> arazzo_exec::policy::PolicyCo
                 max_body_bytes: policy.max_request_bytes,
                 max_headers_count: policy.max_headers_count,
                 max_headers_bytes: 16 * 1024, // Keep reasonable default for header size
+                ..Default::default()
             },
             response: arazzo_exec::policy::ResponseLimits {
                 max_body_bytes: policy.max_response_bytes,
@@ -120,10 +476,59 @@ pub fn build_policy_config(policy: &PolicyArgs) -> arazzo_exec::policy::PolicyCo
                 max_total_run_time: Some(Duration::from_secs(policy.max_run_time_seconds)),
             },
         },
+        per_source,
+        strict_secrets: policy.strict_secrets,
         ..Default::default()
+    })
+}
+
+/// Load the `--auth-file` map of source name to credentials.
+fn load_auth_file(
+    policy: &PolicyArgs,
+    output: &OutputArgs,
+) -> Option<BTreeMap<String, arazzo_exec::policy::SourceAuth>> {
+    let Some(path) = &policy.auth_file else {
+        return Some(BTreeMap::new());
+    };
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to read auth file {}: {e}", path.display()),
+            );
+            return None;
+        }
+    };
+    match arazzo_exec::policy::parse_auth_config(&content) {
+        Ok(auth) => Some(auth),
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("invalid auth file {}: {e}", path.display()),
+            );
+            None
+        }
     }
 }
 
+/// Env secrets, preceded by stdin secrets when `--secrets-stdin` is given.
+pub fn build_secrets_provider(
+    secrets: &SecretsArgs,
+) -> std::sync::Arc<dyn arazzo_exec::secrets::SecretsProvider> {
+    use arazzo_exec::secrets::{CompositeProvider, EnvSecretsProvider, StdinSecretsProvider};
+
+    let env = EnvSecretsProvider::default();
+    let stdin = match secrets.secrets_stdin.as_deref() {
+        None => return std::sync::Arc::new(env),
+        Some("-") => StdinSecretsProvider::key_values(),
+        Some(id) => StdinSecretsProvider::single(id),
+    };
+    std::sync::Arc::new(CompositeProvider::new(vec![Box::new(stdin), Box::new(env)]))
+}
+
 pub fn get_database_url(store_arg: Option<String>, output: &OutputArgs) -> Option<String> {
     let url = store_arg
         .or_else(|| std::env::var("ARAZZO_DATABASE_URL").ok())
@@ -133,3 +538,101 @@ pub fn get_database_url(store_arg: Option<String>, output: &OutputArgs) -> Optio
     }
     url
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn overlay_inputs_replaces_top_level_keys_and_keeps_the_rest() {
+        let stored = json!({ "user": "alice", "limit": 10 });
+        let merged = overlay_inputs(&stored, Some(json!({ "limit": 20 })));
+        assert_eq!(merged, json!({ "user": "alice", "limit": 20 }));
+        assert_eq!(overlay_inputs(&stored, None), stored);
+    }
+
+    #[test]
+    fn overlay_inputs_replaces_nested_objects_and_arrays_whole() {
+        let stored = json!({
+            "filter": { "status": "open", "owner": "alice" },
+            "tags": ["a", "b", "c"]
+        });
+        let merged = overlay_inputs(
+            &stored,
+            Some(json!({ "filter": { "status": "closed" }, "tags": ["d"] })),
+        );
+        assert_eq!(
+            merged,
+            json!({ "filter": { "status": "closed" }, "tags": ["d"] })
+        );
+    }
+
+    #[test]
+    fn overlay_inputs_stores_null_instead_of_removing_the_key() {
+        let stored = json!({ "user": "alice", "limit": 10 });
+        let merged = overlay_inputs(&stored, Some(json!({ "limit": null })));
+        assert_eq!(merged, json!({ "user": "alice", "limit": null }));
+    }
+
+    #[test]
+    fn overlay_inputs_with_non_object_sides_takes_the_overrides() {
+        assert_eq!(
+            overlay_inputs(&json!(null), Some(json!({ "user": "bob" }))),
+            json!({ "user": "bob" })
+        );
+        assert_eq!(
+            overlay_inputs(&json!({ "user": "alice" }), Some(json!(["bob"]))),
+            json!(["bob"])
+        );
+    }
+
+    #[test]
+    fn build_provenance_rejects_entries_without_a_value() {
+        let path = Path::new("workflow.yaml");
+        let provenance =
+            build_provenance(path, &["commit=abc123".to_string()]).expect("valid entries");
+        assert_eq!(provenance["commit"], json!("abc123"));
+        assert_eq!(provenance["source_path"], json!("workflow.yaml"));
+
+        let err = build_provenance(path, &["commit".to_string()]).unwrap_err();
+        assert!(err.contains("'commit'"), "{err}");
+    }
+
+    #[test]
+    fn resume_inputs_overlays_the_inputs_file_and_set_values_on_the_stored_inputs() {
+        let output = OutputArgs {
+            format: crate::output::OutputFormat::Json,
+            quiet: true,
+        };
+        let stored = json!({ "user": "alice", "limit": 10, "tag": "a" });
+        let dir = tempfile::TempDir::new().unwrap();
+        let inputs_path = dir.path().join("inputs.json");
+        std::fs::write(&inputs_path, r#"{ "limit": 20, "user": "carol" }"#).unwrap();
+
+        let merged = resume_inputs(
+            &stored,
+            Some(&inputs_path),
+            &["user=bob".to_string()],
+            &output,
+        );
+        assert_eq!(
+            merged,
+            Some(Some(json!({ "user": "bob", "limit": 20, "tag": "a" })))
+        );
+        assert_eq!(
+            resume_inputs(&stored, None, &["tag=b".to_string()], &output),
+            Some(Some(json!({ "user": "alice", "limit": 10, "tag": "b" })))
+        );
+        assert_eq!(resume_inputs(&stored, None, &[], &output), Some(None));
+        assert_eq!(
+            resume_inputs(
+                &stored,
+                Some(&dir.path().join("missing.json")),
+                &[],
+                &output
+            ),
+            None
+        );
+    }
+}
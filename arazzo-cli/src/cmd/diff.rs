@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use arazzo_core::{diff_documents, parse_document_str, ArazzoDocument, Change};
+use serde::Serialize;
+
+use crate::exit_codes;
+use crate::output::{print_error, print_result, OutputFormat};
+use crate::utils::document_format;
+use crate::OutputArgs;
+
+#[derive(Serialize)]
+struct DiffReport {
+    identical: bool,
+    changes: Vec<Change>,
+}
+
+/// Compare two versions of a document by workflow and step id. Differences are not
+/// failures: the command exits 0 whether or not anything changed.
+pub async fn diff_cmd(old: &Path, new: &Path, output: OutputArgs) -> i32 {
+    let old_doc = match load(old, &output) {
+        Ok(doc) => doc,
+        Err(code) => return code,
+    };
+    let new_doc = match load(new, &output) {
+        Ok(doc) => doc,
+        Err(code) => return code,
+    };
+
+    let changes = diff_documents(&old_doc, &new_doc);
+    let report = DiffReport {
+        identical: changes.is_empty(),
+        changes,
+    };
+    if output.format == OutputFormat::Text && !output.quiet {
+        for change in &report.changes {
+            println!("{change}");
+        }
+        if report.identical {
+            println!("no changes");
+        }
+    } else {
+        print_result(output.format, output.quiet, &report);
+    }
+    exit_codes::SUCCESS
+}
+
+fn load(path: &Path, output: &OutputArgs) -> Result<ArazzoDocument, i32> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        print_error(
+            output.format,
+            output.quiet,
+            &format!("failed to read {}: {e}", path.display()),
+        );
+        exit_codes::RUNTIME_ERROR
+    })?;
+    parse_document_str(&content, document_format(path))
+        .map(|p| p.document)
+        .map_err(|e| {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("{}: {e}", path.display()),
+            );
+            exit_codes::VALIDATION_FAILED
+        })
+}
@@ -1,4 +1,4 @@
-use arazzo_store::StateStore;
+use arazzo_store::{RunStatus, StateStore};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -82,7 +82,11 @@ pub async fn events_cmd(run_id: &str, follow: bool, output: OutputArgs, store: S
                 break;
             }
             if let Ok(Some(run)) = pg.get_run(run_uuid).await {
-                if matches!(run.status.as_str(), "succeeded" | "failed" | "canceled") {
+                if run
+                    .status
+                    .parse::<RunStatus>()
+                    .is_ok_and(|status| status.is_terminal())
+                {
                     tokio::time::sleep(std::time::Duration::from_millis(200)).await;
                     let final_events = pg
                         .get_events_after(run_uuid, last_id, 100)
@@ -1,11 +1,12 @@
 use std::path::Path;
 
-use arazzo_core::{parse_document_str, DocumentFormat};
+use arazzo_core::parse_document_str;
 use serde::Serialize;
 
 use crate::exit_codes;
 use crate::output::{print_error, print_result, OutputFormat};
-use crate::OutputArgs;
+use crate::utils::document_format;
+use crate::{OpenApiArgs, OutputArgs};
 
 #[derive(Serialize)]
 struct InputInfo {
@@ -28,6 +29,18 @@ struct StepInfo {
     depends_on: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     output_keys: Vec<String>,
+    /// Set by `--resolve` when the step's operation was found in its OpenAPI source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    http: Option<HttpInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_parameters: Vec<arazzo_exec::MissingParameter>,
+}
+
+#[derive(Serialize)]
+struct HttpInfo {
+    method: String,
+    path: String,
+    base_url: String,
 }
 
 #[derive(Serialize)]
@@ -47,9 +60,18 @@ struct InspectResult {
     output_keys: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     sources: Vec<SourceInfo>,
+    /// OpenAPI resolution problems, from `--resolve`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    diagnostics: Vec<String>,
 }
 
-pub async fn inspect_cmd(path: &Path, workflow_id: Option<&str>, output: OutputArgs) -> i32 {
+pub async fn inspect_cmd(
+    path: &Path,
+    workflow_id: Option<&str>,
+    resolve: bool,
+    output: OutputArgs,
+    openapi: OpenApiArgs,
+) -> i32 {
     let content = match std::fs::read_to_string(path) {
         Ok(v) => v,
         Err(e) => {
@@ -62,7 +84,7 @@ pub async fn inspect_cmd(path: &Path, workflow_id: Option<&str>, output: OutputA
         }
     };
 
-    let parsed = match parse_document_str(&content, DocumentFormat::Auto) {
+    let parsed = match parse_document_str(&content, document_format(path)) {
         Ok(p) => p,
         Err(e) => {
             print_error(output.format, output.quiet, &format!("{e}"));
@@ -122,7 +144,7 @@ pub async fn inspect_cmd(path: &Path, workflow_id: Option<&str>, output: OutputA
         })
         .unwrap_or_default();
 
-    let steps: Vec<StepInfo> = wf
+    let mut steps: Vec<StepInfo> = wf
         .steps
         .iter()
         .map(|s| StepInfo {
@@ -136,9 +158,46 @@ pub async fn inspect_cmd(path: &Path, workflow_id: Option<&str>, output: OutputA
                 .as_ref()
                 .map(|o| o.keys().cloned().collect())
                 .unwrap_or_default(),
+            http: None,
+            missing_parameters: vec![],
         })
         .collect();
 
+    let mut diagnostics = Vec::new();
+    if resolve {
+        let Some(preloaded) = super::config::load_openapi_sources(&openapi, &output).await else {
+            return exit_codes::RUNTIME_ERROR;
+        };
+        let Some(source_overrides) = super::config::load_source_overrides(&openapi, &output) else {
+            return exit_codes::RUNTIME_ERROR;
+        };
+        let compiled = arazzo_exec::Compiler::default()
+            .with_resolver(
+                super::config::document_resolver(path).with_source_overrides(source_overrides),
+            )
+            .with_sources(preloaded)
+            .compile_workflow(&parsed.document, wf)
+            .await;
+
+        diagnostics.extend(compiled.diagnostics.iter().map(|d| d.message.clone()));
+        for c in compiled.steps {
+            diagnostics.extend(
+                c.diagnostics
+                    .iter()
+                    .map(|d| format!("{}: {}", c.step_id, d.message)),
+            );
+            let Some(step) = steps.iter_mut().find(|s| s.step_id == c.step_id) else {
+                continue;
+            };
+            step.http = c.operation.map(|op| HttpInfo {
+                method: op.method,
+                path: op.path,
+                base_url: op.base_url,
+            });
+            step.missing_parameters = c.missing_required_parameters;
+        }
+    }
+
     let output_keys: Vec<String> = wf
         .outputs
         .as_ref()
@@ -166,6 +225,7 @@ pub async fn inspect_cmd(path: &Path, workflow_id: Option<&str>, output: OutputA
         steps,
         output_keys,
         sources,
+        diagnostics,
     };
 
     if output.format == OutputFormat::Text && !output.quiet {
@@ -185,6 +245,23 @@ pub async fn inspect_cmd(path: &Path, workflow_id: Option<&str>, output: OutputA
                 .or(s.workflow_id.as_deref())
                 .unwrap_or("?");
             println!("  - {} -> {}", s.step_id, op);
+            if let Some(http) = &s.http {
+                println!("      {} {}{}", http.method, http.base_url, http.path);
+            }
+            if !s.missing_parameters.is_empty() {
+                let missing: Vec<String> = s
+                    .missing_parameters
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            "{} (in {})",
+                            p.name,
+                            format!("{:?}", p.location).to_lowercase()
+                        )
+                    })
+                    .collect();
+                println!("      missing parameters: {}", missing.join(", "));
+            }
         }
         if !result.output_keys.is_empty() {
             println!("\nOutputs: {}", result.output_keys.join(", "));
@@ -195,6 +272,12 @@ pub async fn inspect_cmd(path: &Path, workflow_id: Option<&str>, output: OutputA
                 println!("  - {}: {}", s.name, s.url);
             }
         }
+        if !result.diagnostics.is_empty() {
+            println!("\nResolution problems:");
+            for d in &result.diagnostics {
+                println!("  - {d}");
+            }
+        }
     } else {
         print_result(output.format, output.quiet, &result);
     }
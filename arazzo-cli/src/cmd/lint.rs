@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use arazzo_core::{lint_document, parse_document_str, LintDiagnostic, Severity};
+use serde::Serialize;
+
+use crate::exit_codes;
+use crate::output::{print_error, print_result, OutputFormat};
+use crate::utils::document_format;
+use crate::OutputArgs;
+
+#[derive(Serialize)]
+struct LintReport {
+    ok: bool,
+    errors: usize,
+    warnings: usize,
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+/// Run the lint rules over a document. Lint errors (not warnings) fail the command;
+/// the document is not validated.
+pub async fn lint_cmd(path: &Path, output: OutputArgs) -> i32 {
+    let content = match std::fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to read {}: {e}", path.display()),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+    let parsed = match parse_document_str(&content, document_format(path)) {
+        Ok(p) => p,
+        Err(e) => {
+            print_error(output.format, output.quiet, &e.to_string());
+            return exit_codes::VALIDATION_FAILED;
+        }
+    };
+
+    let diagnostics = lint_document(&parsed.document);
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let report = LintReport {
+        ok: errors == 0,
+        errors,
+        warnings: diagnostics.len() - errors,
+        diagnostics,
+    };
+    if output.format == OutputFormat::Text && !output.quiet {
+        for d in &report.diagnostics {
+            println!("{} [{}] {}: {}", d.severity, d.rule, d.path, d.message);
+        }
+        println!("{} errors, {} warnings", report.errors, report.warnings);
+    } else {
+        print_result(output.format, output.quiet, &report);
+    }
+
+    if report.ok {
+        exit_codes::SUCCESS
+    } else {
+        exit_codes::VALIDATION_FAILED
+    }
+}
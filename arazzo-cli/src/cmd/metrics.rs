@@ -1,4 +1,4 @@
-use arazzo_store::StateStore;
+use arazzo_store::{RunStepStatus, StateStore};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -102,9 +102,9 @@ pub async fn metrics_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) ->
     let mut policy_denials = 0;
 
     for step in &steps {
-        match step.status.as_str() {
-            "succeeded" => succeeded += 1,
-            "failed" => failed += 1,
+        match step.status.parse::<RunStepStatus>() {
+            Ok(RunStepStatus::Succeeded) => succeeded += 1,
+            Ok(RunStepStatus::Failed) => failed += 1,
             _ => {}
         }
     }
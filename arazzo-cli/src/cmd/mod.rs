@@ -1,15 +1,23 @@
+pub mod bundle;
 pub mod cancel;
+pub mod check;
 pub mod config;
+pub mod diff;
 pub mod doctor;
 pub mod events;
 pub mod execute;
 pub mod inspect;
+pub mod lint;
 pub mod metrics;
 pub mod migrate;
 pub mod openapi;
+pub mod patch;
 pub mod plan;
+pub mod policy_check;
 pub mod progress;
+pub mod replay_events;
 pub mod resume;
+pub mod schema;
 pub mod start;
 pub mod status;
 pub mod trace;
@@ -1,10 +1,11 @@
 use std::path::Path;
 
-use arazzo_core::{parse_document_str, DocumentFormat};
+use arazzo_core::parse_document_str;
 use serde::Serialize;
 
 use crate::exit_codes;
 use crate::output::{print_error, print_result, OutputFormat};
+use crate::utils::document_format;
 use crate::{OpenApiArgs, OutputArgs};
 
 #[derive(Serialize)]
@@ -38,7 +39,7 @@ pub async fn openapi_cmd(path: &Path, output: OutputArgs, _openapi: OpenApiArgs)
         }
     };
 
-    let parsed = match parse_document_str(&content, DocumentFormat::Auto) {
+    let parsed = match parse_document_str(&content, document_format(path)) {
         Ok(p) => p,
         Err(e) => {
             print_error(output.format, output.quiet, &format!("{e}"));
@@ -51,6 +52,7 @@ pub async fn openapi_cmd(path: &Path, output: OutputArgs, _openapi: OpenApiArgs)
 
     for wf in &parsed.document.workflows {
         let compiled = arazzo_exec::Compiler::default()
+            .with_resolver(super::config::document_resolver(path))
             .compile_workflow(&parsed.document, wf)
             .await;
 
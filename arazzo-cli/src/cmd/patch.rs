@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use arazzo_core::YamlDocument;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::exit_codes;
+use crate::output::{print_error, print_result, OutputFormat};
+use crate::OutputArgs;
+
+#[derive(Serialize)]
+struct PatchResult {
+    path: String,
+    /// Nodes set or removed.
+    changed: usize,
+}
+
+/// Apply `set` (target, value pairs) and then `remove` to the text of a YAML
+/// document. The result must still parse as an Arazzo document; it is printed, or
+/// written back to `path` with `in_place`.
+pub async fn patch_cmd(
+    path: &Path,
+    set: &[String],
+    remove: &[String],
+    in_place: bool,
+    output: OutputArgs,
+) -> i32 {
+    let content = match std::fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to read {}: {e}", path.display()),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+    let fail = |message: String| {
+        print_error(output.format, output.quiet, &message);
+        exit_codes::VALIDATION_FAILED
+    };
+    let mut doc = match YamlDocument::parse(&content) {
+        Ok(doc) => doc,
+        Err(e) => return fail(format!("{}: {e}", path.display())),
+    };
+
+    let mut changed = 0;
+    for pair in set.chunks(2) {
+        let [target, value] = pair else {
+            continue;
+        };
+        let value: JsonValue = match serde_yaml::from_str(value) {
+            Ok(v) => v,
+            Err(e) => return fail(format!("invalid value for {target}: {e}")),
+        };
+        match doc.set(target, &value) {
+            Ok(n) => changed += n,
+            Err(e) => return fail(e.to_string()),
+        }
+    }
+    for target in remove {
+        match doc.remove(target) {
+            Ok(n) => changed += n,
+            Err(e) => return fail(e.to_string()),
+        }
+    }
+    if let Err(e) = doc.document() {
+        return fail(format!(
+            "the patched document is not an Arazzo document: {e}"
+        ));
+    }
+
+    if !in_place {
+        print!("{}", doc.as_str());
+        return exit_codes::SUCCESS;
+    }
+    if let Err(e) = std::fs::write(path, doc.as_str()) {
+        print_error(
+            output.format,
+            output.quiet,
+            &format!("failed to write {}: {e}", path.display()),
+        );
+        return exit_codes::RUNTIME_ERROR;
+    }
+    let result = PatchResult {
+        path: path.display().to_string(),
+        changed,
+    };
+    if output.format == OutputFormat::Text && !output.quiet {
+        println!("Patched {}: {} nodes changed", result.path, result.changed);
+    } else {
+        print_result(output.format, output.quiet, &result);
+    }
+    exit_codes::SUCCESS
+}
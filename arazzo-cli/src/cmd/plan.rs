@@ -1,15 +1,16 @@
 use std::path::Path;
 
 use arazzo_core::{
-    parse_document_str, plan_document, DocumentFormat, PlanOperationRef, PlanOptions,
-    PlanningOutcome,
+    parse_document_str_with, plan_document_with, PlanOperationRef, PlanOptions, PlanningOutcome,
 };
 use serde::Serialize;
 
 use crate::exit_codes;
 use crate::output::{print_error, OutputFormat};
-use crate::{OpenApiArgs, OutputArgs};
+use crate::utils::document_format;
+use crate::{EnvArgs, OpenApiArgs, OutputArgs, OverlayArgs, ValidateConfigArgs};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn plan_cmd(
     path: &Path,
     workflow_id: Option<&str>,
@@ -17,6 +18,9 @@ pub async fn plan_cmd(
     compile: bool,
     output: OutputArgs,
     _openapi: OpenApiArgs,
+    validate_config: ValidateConfigArgs,
+    overlay: OverlayArgs,
+    env: EnvArgs,
 ) -> i32 {
     let content = match std::fs::read_to_string(path) {
         Ok(v) => v,
@@ -35,20 +39,33 @@ pub async fn plan_cmd(
         return exit_codes::RUNTIME_ERROR;
     }
 
-    let parsed = match parse_document_str(&content, DocumentFormat::Auto) {
+    let options = super::config::parse_options(&env);
+    let mut parsed = match parse_document_str_with(&content, document_format(path), &options) {
         Ok(p) => p,
         Err(e) => {
             print_error(output.format, output.quiet, &format!("{e}"));
             return exit_codes::VALIDATION_FAILED;
         }
     };
+    if !super::config::check_env(parsed.env.as_ref(), &output) {
+        return exit_codes::VALIDATION_FAILED;
+    }
+    let Some(document) = super::config::apply_overlays(&overlay, parsed.document, &output) else {
+        return exit_codes::VALIDATION_FAILED;
+    };
+    parsed.document = document;
 
-    let outcome = match plan_document(
+    let Some(validation) = super::config::load_validation_options(&validate_config, path, &output)
+    else {
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let outcome = match plan_document_with(
         &parsed.document,
         PlanOptions {
             workflow_id: workflow_id.map(String::from),
             inputs: inputs.clone(),
         },
+        &validation,
     ) {
         Ok(o) => o,
         Err(e) => {
@@ -57,7 +74,8 @@ pub async fn plan_cmd(
         }
     };
 
-    let compiled = if compile && outcome.validation.is_valid {
+    // Warnings don't block compilation; only validation errors do.
+    let compiled = if compile && !outcome.validation.has_errors() {
         match &outcome.plan {
             None => None,
             Some(plan) => {
@@ -83,6 +101,7 @@ pub async fn plan_cmd(
 
                 Some(
                     arazzo_exec::Compiler::default()
+                        .with_resolver(super::config::document_resolver(path))
                         .compile_workflow(&parsed.document, wf)
                         .await,
                 )
@@ -95,7 +114,7 @@ pub async fn plan_cmd(
     match output.format {
         OutputFormat::Json => print_json(&outcome, compiled.as_ref(), output.quiet),
         OutputFormat::Text => print_text(&outcome, compiled.as_ref(), output.quiet),
-        OutputFormat::Dot => print_dot(&outcome, output.quiet),
+        OutputFormat::Dot => print_dot(&outcome, compiled.as_ref(), output.quiet),
     }
 }
 
@@ -152,6 +171,12 @@ fn print_text(
 
     if outcome.validation.is_valid {
         println!("validation: valid");
+        if !outcome.validation.warnings.is_empty() {
+            println!("warnings: {}", outcome.validation.warnings.len());
+            for w in &outcome.validation.warnings {
+                println!("- {w}");
+            }
+        }
     } else {
         println!("validation: invalid");
         println!("errors: {}", outcome.validation.errors.len());
@@ -171,6 +196,12 @@ fn print_text(
             plan.summary.workflow_depends_on.join(", ")
         );
     }
+    if !plan.summary.workflow_prerequisites.is_empty() {
+        println!(
+            "runs after: {}",
+            plan.summary.workflow_prerequisites.join(", ")
+        );
+    }
     if !plan.summary.missing_inputs.is_empty() {
         println!(
             "missing inputs: {}",
@@ -246,7 +277,11 @@ fn print_text(
     exit_codes::SUCCESS
 }
 
-fn print_dot(outcome: &PlanningOutcome, quiet: bool) -> i32 {
+fn print_dot(
+    outcome: &PlanningOutcome,
+    compiled: Option<&arazzo_exec::CompiledPlan>,
+    quiet: bool,
+) -> i32 {
     if quiet {
         return if outcome.validation.is_valid {
             exit_codes::SUCCESS
@@ -265,7 +300,12 @@ fn print_dot(outcome: &PlanningOutcome, quiet: bool) -> i32 {
         return exit_codes::VALIDATION_FAILED;
     };
 
-    println!("{}", plan.graph.to_dot(&plan.summary.workflow_id));
+    let labels = compiled.map(|c| c.dot_labels()).unwrap_or_default();
+    println!(
+        "{}",
+        plan.graph
+            .to_dot_with_labels(&plan.summary.workflow_id, &labels)
+    );
     exit_codes::SUCCESS
 }
 
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use arazzo_core::{parse_document_str, plan_document, PlanOptions};
+use arazzo_exec::executor::{check_policy, PolicyDecision};
+use serde::Serialize;
+
+use crate::exit_codes;
+use crate::output::{print_error, print_result, OutputFormat};
+use crate::utils::document_format;
+use crate::{OpenApiArgs, OutputArgs, PolicyArgs};
+
+use super::config::{
+    build_policy_config, document_resolver, load_inputs, load_openapi_sources,
+    load_source_overrides, merge_set_inputs,
+};
+
+#[derive(Serialize)]
+struct PolicyCheckResult {
+    workflow_id: String,
+    steps: Vec<arazzo_exec::executor::StepPolicyCheck>,
+}
+
+/// Build each step's request and report whether the policy would let it through.
+/// Nothing is sent. Exits with `VALIDATION_FAILED` when any request would be denied.
+#[allow(clippy::too_many_arguments)]
+pub async fn policy_check_cmd(
+    path: &Path,
+    workflow_id: Option<&str>,
+    inputs_path: Option<&Path>,
+    set_inputs: &[String],
+    output: OutputArgs,
+    openapi: OpenApiArgs,
+    policy: PolicyArgs,
+) -> i32 {
+    let content = match std::fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to read {}: {e}", path.display()),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let parsed = match parse_document_str(&content, document_format(path)) {
+        Ok(p) => p,
+        Err(e) => {
+            print_error(output.format, output.quiet, &format!("{e}"));
+            return exit_codes::VALIDATION_FAILED;
+        }
+    };
+
+    let mut inputs = load_inputs(inputs_path, &output);
+    if inputs.is_none() && inputs_path.is_some() {
+        return exit_codes::RUNTIME_ERROR;
+    }
+    merge_set_inputs(&mut inputs, set_inputs);
+
+    let outcome = match plan_document(
+        &parsed.document,
+        PlanOptions {
+            workflow_id: workflow_id.map(String::from),
+            inputs: inputs.clone(),
+        },
+    ) {
+        Ok(o) => o,
+        Err(e) => {
+            print_error(output.format, output.quiet, &format!("{e}"));
+            return exit_codes::VALIDATION_FAILED;
+        }
+    };
+    if !outcome.validation.is_valid {
+        print_error(output.format, output.quiet, "workflow validation failed");
+        return exit_codes::VALIDATION_FAILED;
+    }
+    let Some(plan) = &outcome.plan else {
+        print_error(output.format, output.quiet, "no plan generated");
+        return exit_codes::VALIDATION_FAILED;
+    };
+    let Some(wf) = parsed
+        .document
+        .workflows
+        .iter()
+        .find(|w| w.workflow_id == plan.summary.workflow_id)
+    else {
+        print_error(output.format, output.quiet, "workflow not found");
+        return exit_codes::VALIDATION_FAILED;
+    };
+
+    let Some(preloaded) = load_openapi_sources(&openapi, &output).await else {
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let Some(source_overrides) = load_source_overrides(&openapi, &output) else {
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let compiled = arazzo_exec::Compiler::default()
+        .with_resolver(document_resolver(path).with_source_overrides(source_overrides))
+        .with_sources(preloaded)
+        .compile_workflow(&parsed.document, wf)
+        .await;
+
+    let Some(policy_config) = build_policy_config(&policy, &output) else {
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let policy_gate = arazzo_exec::policy::PolicyGate::new(policy_config);
+    let inputs = inputs.unwrap_or_else(|| serde_json::json!({}));
+    let steps = check_policy(&policy_gate, wf, &compiled, &inputs, Some(&parsed.document)).await;
+
+    let denied = steps
+        .iter()
+        .any(|s| matches!(s.decision, PolicyDecision::Denied { .. }));
+    let result = PolicyCheckResult {
+        workflow_id: wf.workflow_id.clone(),
+        steps,
+    };
+
+    if output.format == OutputFormat::Text && !output.quiet {
+        println!("Workflow: {}", result.workflow_id);
+        for s in &result.steps {
+            match &s.decision {
+                PolicyDecision::Allowed { method, url } => {
+                    println!("  - {}: allowed ({method} {url})", s.step_id)
+                }
+                PolicyDecision::Denied { reason, .. } => {
+                    println!("  - {}: denied ({reason})", s.step_id)
+                }
+                PolicyDecision::Skipped { reason } => {
+                    println!("  - {}: skipped ({reason})", s.step_id)
+                }
+            }
+        }
+    } else {
+        print_result(output.format, output.quiet, &result);
+    }
+
+    if denied {
+        exit_codes::VALIDATION_FAILED
+    } else {
+        exit_codes::SUCCESS
+    }
+}
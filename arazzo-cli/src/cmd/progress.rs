@@ -26,7 +26,7 @@ impl ProgressEventSink {
         let running = self.running.load(Ordering::Relaxed);
         let total = self.total_steps;
         let done = completed + failed;
-        let percent = if total > 0 { (done * 100) / total } else { 0 };
+        let percent = (done * 100).checked_div(total).unwrap_or(0);
         eprint!(
             "\rProgress: [{}/{}] {}% (✓{} ✗{} →{})",
             done, total, percent, completed, failed, running
@@ -0,0 +1,151 @@
+use arazzo_exec::executor::{replay_events, ReplayedRun, StepDiscrepancy};
+use arazzo_store::{RunStatus, StateStore};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::exit_codes;
+use crate::output::{print_error, print_result, OutputFormat};
+use crate::utils::redact_url_password;
+use crate::{OutputArgs, StoreArgs};
+
+#[derive(Serialize)]
+struct ReplayResult {
+    run_id: String,
+    stored_status: String,
+    #[serde(flatten)]
+    replayed: ReplayedRun,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    discrepancies: Vec<StepDiscrepancy>,
+}
+
+/// Rebuild a run's state from its event log and compare it with the stored run.
+/// Exits with `VALIDATION_FAILED` when the two disagree.
+pub async fn replay_events_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i32 {
+    let run_uuid = match Uuid::parse_str(run_id) {
+        Ok(u) => u,
+        Err(e) => {
+            print_error(output.format, output.quiet, &format!("invalid run_id: {e}"));
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let database_url = match store
+        .store
+        .or_else(|| std::env::var("ARAZZO_DATABASE_URL").ok())
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+    {
+        Some(v) => v,
+        None => {
+            print_error(output.format, output.quiet, "missing database URL");
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let pg = match arazzo_store::PostgresStore::connect(&database_url, 5).await {
+        Ok(s) => s,
+        Err(e) => {
+            let safe_url = redact_url_password(&database_url);
+            print_error(output.format, output.quiet, &format!("database connection failed to {}: {e}. Check your DATABASE_URL and ensure Postgres is running.", safe_url));
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let run = match pg.get_run(run_uuid).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            print_error(output.format, output.quiet, "run not found");
+            return exit_codes::RUNTIME_ERROR;
+        }
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to get run {run_uuid}: {e}"),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let steps = match pg.get_run_steps(run_uuid).await {
+        Ok(s) => s,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to get steps: {e}"),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let replayed = match replay_events(&pg, run_uuid).await {
+        Ok(r) => r,
+        Err(e) => {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to get events: {e}"),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+
+    let discrepancies = replayed.discrepancies(&steps);
+    // A queued run has no events yet; any other stored status must be the last one logged.
+    let stored_status = run.status.parse::<RunStatus>();
+    let run_consistent = match replayed.status.as_deref().map(str::parse::<RunStatus>) {
+        Some(Ok(replayed_status)) => stored_status == Ok(replayed_status),
+        Some(Err(_)) => false,
+        None => stored_status == Ok(RunStatus::Queued),
+    };
+    let result = ReplayResult {
+        run_id: run_uuid.to_string(),
+        stored_status: run.status.clone(),
+        replayed,
+        discrepancies,
+    };
+
+    if output.format == OutputFormat::Text && !output.quiet {
+        println!(
+            "Run: {} (stored: {}, replayed: {})",
+            result.run_id,
+            result.stored_status,
+            result.replayed.status.as_deref().unwrap_or("none")
+        );
+        println!("Events replayed: {}", result.replayed.events_replayed);
+        for (step_id, s) in &result.replayed.steps {
+            let retries = if s.retries_scheduled > 0 {
+                format!(", {} retries", s.retries_scheduled)
+            } else {
+                String::new()
+            };
+            println!(
+                "  {step_id}: {} ({} attempts{retries})",
+                s.status,
+                s.attempts.len()
+            );
+        }
+        if !result.replayed.unknown_events.is_empty() {
+            println!(
+                "Unknown event types: {}",
+                result.replayed.unknown_events.join(", ")
+            );
+        }
+        for d in &result.discrepancies {
+            println!(
+                "Mismatch {}: stored {}, replayed {}",
+                d.step_id,
+                d.stored,
+                d.replayed.as_deref().unwrap_or("no events")
+            );
+        }
+    } else {
+        print_result(output.format, output.quiet, &result);
+    }
+
+    if run_consistent && result.discrepancies.is_empty() {
+        exit_codes::SUCCESS
+    } else {
+        exit_codes::VALIDATION_FAILED
+    }
+}
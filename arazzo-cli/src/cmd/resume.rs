@@ -1,8 +1,9 @@
+use std::path::Path;
 use std::sync::Arc;
 
-use arazzo_core::{parse_document_str, plan_document, DocumentFormat, PlanOptions};
+use arazzo_core::{plan_document, PlanOptions};
 #[allow(unused_imports)]
-use arazzo_store::StateStore;
+use arazzo_store::{RunStatus, StateStore};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -10,7 +11,10 @@ use crate::exit_codes;
 use crate::output::{print_error, print_result, OutputFormat};
 use crate::{ConcurrencyArgs, OutputArgs, PolicyArgs, RetryArgs, SecretsArgs, StoreArgs};
 
-use super::config::{build_executor_config, build_policy_config, get_database_url};
+use super::config::{
+    build_executor_config, build_policy_config, build_secrets_provider, get_database_url,
+    resume_inputs,
+};
 use crate::utils::redact_url_password;
 
 #[derive(Serialize)]
@@ -23,11 +27,16 @@ struct ResumeResult {
     steps_failed: usize,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn resume_cmd(
     run_id: &str,
+    inputs_path: Option<&Path>,
+    set_inputs: &[String],
+    persist_inputs: bool,
+    verify_hash: Option<&Path>,
     output: OutputArgs,
     store: StoreArgs,
-    _secrets: SecretsArgs,
+    secrets: SecretsArgs,
     policy: PolicyArgs,
     concurrency: ConcurrencyArgs,
     retry: RetryArgs,
@@ -75,7 +84,11 @@ pub async fn resume_cmd(
         }
     };
 
-    if run.status == "succeeded" || run.status == "failed" || run.status == "canceled" {
+    if run
+        .status
+        .parse::<RunStatus>()
+        .is_ok_and(|status| status.is_terminal())
+    {
         let result = ResumeResult {
             run_id: run_uuid.to_string(),
             status: run.status.clone(),
@@ -107,30 +120,72 @@ pub async fn resume_cmd(
         }
     };
 
-    let format = match workflow_doc.format.as_str() {
-        "json" => DocumentFormat::Json,
-        _ => DocumentFormat::Yaml,
-    };
-    let parsed = match parse_document_str(&workflow_doc.raw, format) {
-        Ok(p) => p,
+    if let Some(path) = verify_hash {
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                print_error(
+                    output.format,
+                    output.quiet,
+                    &format!("failed to read {}: {e}", path.display()),
+                );
+                return exit_codes::RUNTIME_ERROR;
+            }
+        };
+        if !arazzo_store::doc_hash_matches(&workflow_doc.doc_hash, &content) {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!(
+                    "refusing to resume: {} differs from the document stored for run {run_uuid} (doc_hash {})",
+                    path.display(),
+                    workflow_doc.doc_hash
+                ),
+            );
+            return exit_codes::VALIDATION_FAILED;
+        }
+    }
+
+    // The stored document as written, with the run's `--env` variables and overlays
+    // applied again.
+    let loaded = arazzo_exec::executor::run_document(&workflow_doc, &run).and_then(|document| {
+        Ok((
+            document,
+            arazzo_exec::executor::DocumentOverrides::of_run(&run)?,
+        ))
+    });
+    let (document, document_overrides) = match loaded {
+        Ok(loaded) => loaded,
         Err(e) => {
             print_error(
                 output.format,
                 output.quiet,
-                &format!("failed to parse workflow: {e}"),
+                &format!("failed to load workflow: {e}"),
             );
             return exit_codes::RUNTIME_ERROR;
         }
     };
 
-    let inputs: Option<serde_json::Value> = if run.inputs.is_null() {
-        None
-    } else {
-        Some(run.inputs.clone())
+    // Only steps that have not yet succeeded run again, so their stored outputs are untouched.
+    let Some(updated) = resume_inputs(&run.inputs, inputs_path, set_inputs, &output) else {
+        return exit_codes::RUNTIME_ERROR;
     };
+    if let Some(merged) = updated.as_ref().filter(|_| persist_inputs) {
+        if let Err(e) = store_arc.update_run_inputs(run_uuid, merged.clone()).await {
+            print_error(
+                output.format,
+                output.quiet,
+                &format!("failed to persist updated inputs: {e}"),
+            );
+            return exit_codes::RUNTIME_ERROR;
+        }
+    }
+    let merged = updated.unwrap_or_else(|| run.inputs.clone());
+
+    let inputs: Option<serde_json::Value> = if merged.is_null() { None } else { Some(merged) };
 
     let outcome = match plan_document(
-        &parsed.document,
+        &document,
         PlanOptions {
             workflow_id: Some(run.workflow_id.clone()),
             inputs: inputs.clone(),
@@ -156,8 +211,7 @@ pub async fn resume_cmd(
         }
     };
 
-    let wf = match parsed
-        .document
+    let wf = match document
         .workflows
         .iter()
         .find(|w| w.workflow_id == plan.summary.workflow_id)
@@ -173,9 +227,16 @@ pub async fn resume_cmd(
         }
     };
 
+    // Relative source URLs resolve next to the file the run was started from, when known.
+    let resolver = match arazzo_exec::executor::source_dir(&run.provenance) {
+        Some(dir) => arazzo_exec::openapi::OpenApiResolver::default().with_base_dir(dir),
+        None => arazzo_exec::openapi::OpenApiResolver::default(),
+    };
     let compiled = arazzo_exec::Compiler::default()
-        .compile_workflow(&parsed.document, wf)
-        .await;
+        .with_resolver(resolver)
+        .compile_workflow(&document, wf)
+        .await
+        .with_parameter_defaults(document_overrides.fill_parameter_defaults);
     if compiled
         .diagnostics
         .iter()
@@ -186,11 +247,11 @@ pub async fn resume_cmd(
     }
 
     let exec_config = build_executor_config(&concurrency, &retry);
-    let secrets_provider: Arc<dyn arazzo_exec::secrets::SecretsProvider> =
-        Arc::new(arazzo_exec::secrets::EnvSecretsProvider::default());
-    let policy_gate = Arc::new(arazzo_exec::policy::PolicyGate::new(build_policy_config(
-        &policy,
-    )));
+    let secrets_provider = build_secrets_provider(&secrets);
+    let Some(policy_config) = build_policy_config(&policy, &output) else {
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let policy_gate = Arc::new(arazzo_exec::policy::PolicyGate::new(policy_config));
     let http_client: Arc<dyn arazzo_exec::executor::HttpClient> =
         Arc::new(arazzo_exec::executor::http::ReqwestHttpClient::default());
     let event_sink: Arc<dyn arazzo_exec::executor::EventSink> = Arc::new(
@@ -210,10 +271,8 @@ pub async fn resume_cmd(
 
     // Reset any steps stuck in 'running' state from a previous crash
     match store_arc.reset_stale_running_steps(run_uuid).await {
-        Ok(count) if count > 0 => {
-            if output.format == OutputFormat::Text && !output.quiet {
-                println!("Reset {} stale running step(s)", count);
-            }
+        Ok(count) if count > 0 && output.format == OutputFormat::Text && !output.quiet => {
+            println!("Reset {} stale running step(s)", count);
         }
         Err(e) => {
             print_error(
@@ -231,20 +290,25 @@ pub async fn resume_cmd(
     }
 
     let result = executor
-        .execute_run(run_uuid, wf, &compiled, &run_inputs, Some(&parsed.document))
+        .execute_run(run_uuid, wf, &compiled, &run_inputs, Some(&document))
         .await;
 
     match result {
         Ok(exec_result) => {
+            let (status, verb) = if exec_result.canceled {
+                ("canceled", "canceled")
+            } else {
+                ("succeeded", "completed")
+            };
             let res = ResumeResult {
                 run_id: run_uuid.to_string(),
-                status: "succeeded".to_string(),
+                status: status.to_string(),
                 error: None,
                 steps_succeeded: exec_result.succeeded_steps,
                 steps_failed: exec_result.failed_steps,
             };
             if output.format == OutputFormat::Text && !output.quiet {
-                println!("Run {} completed", run_uuid);
+                println!("Run {} {}", run_uuid, verb);
                 println!("  Steps succeeded: {}", res.steps_succeeded);
                 println!("  Steps failed: {}", res.steps_failed);
             } else {
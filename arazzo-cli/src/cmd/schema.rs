@@ -0,0 +1,10 @@
+use arazzo_core::document_json_schema;
+
+use crate::exit_codes;
+use crate::output::print_result;
+use crate::OutputArgs;
+
+pub async fn schema_cmd(output: OutputArgs) -> i32 {
+    print_result(output.format, output.quiet, &document_json_schema());
+    exit_codes::SUCCESS
+}
@@ -1,4 +1,5 @@
-use arazzo_store::StateStore;
+use arazzo_store::{RunStep, RunStepStatus, StateStore};
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -12,6 +13,8 @@ struct StepSummary {
     step_id: String,
     status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    next_run_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
 }
 
@@ -27,6 +30,11 @@ struct StatusResult {
     steps_skipped: usize,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     failed_steps: Vec<StepSummary>,
+    /// Pending steps waiting out a retry backoff.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    retrying_steps: Vec<StepSummary>,
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    provenance: serde_json::Map<String, serde_json::Value>,
 }
 
 pub async fn status_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i32 {
@@ -78,6 +86,8 @@ pub async fn status_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i
         }
     };
 
+    let provenance = run.provenance.as_object().cloned().unwrap_or_default();
+
     let steps = match pg.get_run_steps(run_uuid).await {
         Ok(s) => s,
         Err(e) => {
@@ -96,25 +106,31 @@ pub async fn status_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i
     let mut failed = 0;
     let mut skipped = 0;
     let mut failed_steps = Vec::new();
+    let mut retrying_steps = Vec::new();
+    let now = Utc::now();
 
     for step in &steps {
-        match step.status.as_str() {
-            "pending" => pending += 1,
-            "running" => running += 1,
-            "succeeded" => succeeded += 1,
-            "failed" => {
+        match step.status.parse() {
+            Ok(RunStepStatus::Pending) => {
+                pending += 1;
+                retrying_steps.extend(scheduled_retry(step, now));
+            }
+            Ok(RunStepStatus::Running) => running += 1,
+            Ok(RunStepStatus::Succeeded) => succeeded += 1,
+            Ok(RunStepStatus::Failed) => {
                 failed += 1;
                 failed_steps.push(StepSummary {
                     step_id: step.step_id.clone(),
                     status: step.status.clone(),
+                    next_run_at: None,
                     error: step
                         .error
                         .as_ref()
                         .and_then(|e| e.get("message").and_then(|m| m.as_str()).map(String::from)),
                 });
             }
-            "skipped" => skipped += 1,
-            _ => {}
+            Ok(RunStepStatus::Skipped) => skipped += 1,
+            Err(_) => {}
         }
     }
 
@@ -128,12 +144,20 @@ pub async fn status_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i
         steps_failed: failed,
         steps_skipped: skipped,
         failed_steps,
+        retrying_steps,
+        provenance,
     };
 
     if output.format == OutputFormat::Text && !output.quiet {
         println!("Run: {}", result.run_id);
         println!("Workflow: {}", result.workflow_id);
         println!("Status: {}", result.status);
+        for (k, v) in &result.provenance {
+            match v.as_str() {
+                Some(s) => println!("Provenance {k}: {s}"),
+                None => println!("Provenance {k}: {v}"),
+            }
+        }
         println!();
         println!("Steps:");
         println!("  Pending:   {}", result.steps_pending);
@@ -152,9 +176,70 @@ pub async fn status_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i
                 println!();
             }
         }
+        if !result.retrying_steps.is_empty() {
+            println!();
+            println!("Retrying steps:");
+            for rs in &result.retrying_steps {
+                if let Some(at) = &rs.next_run_at {
+                    println!("  - {}: next run at {at}", rs.step_id);
+                }
+            }
+        }
     } else {
         print_result(output.format, output.quiet, &result);
     }
 
     exit_codes::SUCCESS
 }
+
+/// A pending step whose next attempt is scheduled in the future, i.e. in retry backoff.
+fn scheduled_retry(step: &RunStep, now: DateTime<Utc>) -> Option<StepSummary> {
+    let next_run_at = step.next_run_at.filter(|at| *at > now)?;
+    Some(StepSummary {
+        step_id: step.step_id.clone(),
+        status: step.status.clone(),
+        next_run_at: Some(next_run_at.to_rfc3339()),
+        error: step
+            .error
+            .as_ref()
+            .and_then(|e| e.get("message").and_then(|m| m.as_str()).map(String::from)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_step(next_run_at: Option<DateTime<Utc>>) -> RunStep {
+        RunStep {
+            id: Uuid::new_v4(),
+            run_id: Uuid::new_v4(),
+            step_id: "charge".to_string(),
+            step_index: 0,
+            status: "pending".to_string(),
+            source_name: None,
+            operation_id: None,
+            depends_on: Vec::new(),
+            deps_remaining: 0,
+            next_run_at,
+            outputs: serde_json::json!({}),
+            error: Some(serde_json::json!({"message": "HTTP 503"})),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    #[test]
+    fn pending_step_with_future_next_run_at_is_reported_as_retrying() {
+        let now = Utc::now();
+        let at = now + chrono::Duration::seconds(30);
+
+        let summary = scheduled_retry(&pending_step(Some(at)), now).expect("retrying");
+        assert_eq!(summary.next_run_at, Some(at.to_rfc3339()));
+        assert_eq!(summary.error.as_deref(), Some("HTTP 503"));
+
+        assert!(scheduled_retry(&pending_step(None), now).is_none());
+        let due = now - chrono::Duration::seconds(1);
+        assert!(scheduled_retry(&pending_step(Some(due)), now).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use arazzo_store::StateStore;
+use arazzo_store::{RunStepStatus, StateStore};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -24,6 +24,9 @@ struct StepTrace {
     status: String,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     depends_on: Vec<String>,
+    /// When a pending step's next (retry) attempt is scheduled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_run_at: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     attempts: Vec<AttemptInfo>,
 }
@@ -34,6 +37,8 @@ struct TraceResult {
     workflow_id: String,
     status: String,
     steps: Vec<StepTrace>,
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    provenance: serde_json::Map<String, serde_json::Value>,
 }
 
 pub async fn trace_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i32 {
@@ -85,6 +90,8 @@ pub async fn trace_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i3
         }
     };
 
+    let provenance = run.provenance.as_object().cloned().unwrap_or_default();
+
     let steps = match pg.get_run_steps(run_uuid).await {
         Ok(s) => s,
         Err(e) => {
@@ -119,6 +126,10 @@ pub async fn trace_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i3
             step_index: step.step_index,
             status: step.status.clone(),
             depends_on: step.depends_on.clone(),
+            next_run_at: step
+                .next_run_at
+                .filter(|_| step.status.parse() == Ok(RunStepStatus::Pending))
+                .map(|at| at.to_rfc3339()),
             attempts: attempt_infos,
         });
     }
@@ -130,11 +141,18 @@ pub async fn trace_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i3
         workflow_id: run.workflow_id.clone(),
         status: run.status.clone(),
         steps: step_traces,
+        provenance,
     };
 
     if output.format == OutputFormat::Text && !output.quiet {
         println!("Run: {} ({})", result.run_id, result.status);
         println!("Workflow: {}", result.workflow_id);
+        for (k, v) in &result.provenance {
+            match v.as_str() {
+                Some(s) => println!("Provenance {k}: {s}"),
+                None => println!("Provenance {k}: {v}"),
+            }
+        }
         println!();
         for s in &result.steps {
             let deps = if s.depends_on.is_empty() {
@@ -158,6 +176,9 @@ pub async fn trace_cmd(run_id: &str, output: OutputArgs, store: StoreArgs) -> i3
                     .unwrap_or_default();
                 println!("  Attempt {}: {}{}{}", a.attempt_no, a.status, dur, err);
             }
+            if let Some(at) = &s.next_run_at {
+                println!("  Next attempt at {at}");
+            }
         }
     } else {
         print_result(output.format, output.quiet, &result);
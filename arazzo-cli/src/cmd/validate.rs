@@ -1,21 +1,41 @@
 use std::path::Path;
 
-use arazzo_core::{parse_document_str, DocumentFormat, ParseError, Validate};
+use arazzo_core::{
+    parse_document_str_with, parse_value_str, validation_report, validation_report_with_schema,
+    EnvReport, ErrorLocation, ParseError, ParseOptions, Violation,
+};
 use serde::Serialize;
 
 use crate::exit_codes;
 use crate::output::{print_error, print_result, OutputFormat};
-use crate::OutputArgs;
+use crate::utils::document_format;
+use crate::{EnvArgs, OutputArgs, ValidateConfigArgs};
 
 #[derive(Serialize)]
 struct ValidateResult {
     valid: bool,
-    format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    /// The declared `arazzo` version, when it parses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spec_version: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     errors: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    /// Present with `--env`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env: Option<EnvReport>,
 }
 
-pub async fn validate_cmd(path: &Path, output: OutputArgs) -> i32 {
+pub async fn validate_cmd(
+    path: &Path,
+    strict: bool,
+    schema: bool,
+    output: OutputArgs,
+    validate_config: ValidateConfigArgs,
+    env: EnvArgs,
+) -> i32 {
     let content = match std::fs::read_to_string(path) {
         Ok(v) => v,
         Err(e) => {
@@ -28,68 +48,153 @@ pub async fn validate_cmd(path: &Path, output: OutputArgs) -> i32 {
         }
     };
 
-    let parsed = match parse_document_str(&content, DocumentFormat::Auto) {
+    let options = ParseOptions {
+        strict,
+        interpolate_env: env.env,
+    };
+    let parsed = match parse_document_str_with(&content, document_format(path), &options) {
         Ok(p) => p,
-        Err(ParseError::Json(e)) => {
-            print_error(
-                output.format,
-                output.quiet,
-                &format!("JSON parse failed: {e}"),
-            );
+        Err(ParseError::UnknownFields(unknown)) => {
+            let result = ValidateResult {
+                valid: false,
+                format: None,
+                spec_version: None,
+                errors: unknown
+                    .iter()
+                    .map(|v| format!("{}: {}", v.path, v.message))
+                    .collect(),
+                warnings: vec![],
+                env: None,
+            };
+            if output.format == OutputFormat::Text && !output.quiet {
+                eprintln!("error: strict parsing failed");
+                for e in &result.errors {
+                    eprintln!("- {e}");
+                }
+            } else {
+                print_result(output.format, output.quiet, &result);
+            }
             return exit_codes::VALIDATION_FAILED;
         }
-        Err(ParseError::Yaml(e)) => {
-            print_error(
-                output.format,
-                output.quiet,
-                &format!("YAML parse failed: {e}"),
-            );
+        Err(e) => {
+            print_parse_error(path, &content, &e, &output);
             return exit_codes::VALIDATION_FAILED;
         }
-        Err(ParseError::UnknownFormat) => {
-            print_error(
-                output.format,
-                output.quiet,
-                "input is neither valid JSON nor valid YAML",
-            );
-            return exit_codes::VALIDATION_FAILED;
+    };
+
+    let Some(options) = super::config::load_validation_options(&validate_config, path, &output)
+    else {
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let report = if schema {
+        match parse_value_str(&content, parsed.format) {
+            Ok(raw) => validation_report_with_schema(&parsed.document, &raw, &options),
+            Err(e) => {
+                print_parse_error(path, &content, &e, &output);
+                return exit_codes::VALIDATION_FAILED;
+            }
         }
+    } else {
+        validation_report(&parsed.document, &options)
     };
+    let describe = |v: &Violation| format!("{}: {}", v.path, v.message);
+    let warnings: Vec<String> = report.warnings().map(describe).collect();
+    let env_errors: Vec<String> = parsed
+        .env
+        .iter()
+        .flat_map(|r| &r.missing)
+        .map(|v| format!("{}: environment variable '{}' is not set", v.path, v.name))
+        .collect();
+    if output.format == OutputFormat::Text && !output.quiet {
+        for v in parsed.env.iter().flat_map(|r| &r.substituted) {
+            eprintln!("env: substituted {} at {}", v.name, v.path);
+        }
+    }
 
-    match parsed.document.validate() {
-        Ok(()) => {
-            let result = ValidateResult {
-                valid: true,
-                format: format!("{:?}", parsed.format),
-                errors: vec![],
-            };
-            if output.format == OutputFormat::Text && !output.quiet {
-                println!("ok: valid Arazzo document ({:?})", parsed.format);
-            } else {
-                print_result(output.format, output.quiet, &result);
+    if report.is_valid() && env_errors.is_empty() {
+        let result = ValidateResult {
+            valid: true,
+            format: Some(format!("{:?}", parsed.format)),
+            spec_version: parsed.spec_version.map(|v| v.to_string()),
+            errors: vec![],
+            warnings: warnings.clone(),
+            env: parsed.env,
+        };
+        if output.format == OutputFormat::Text && !output.quiet {
+            println!("ok: valid Arazzo document ({:?})", parsed.format);
+            for w in &warnings {
+                eprintln!("warning: {w}");
             }
-            exit_codes::SUCCESS
+        } else {
+            print_result(output.format, output.quiet, &result);
         }
-        Err(err) => {
-            let errors: Vec<String> = err
-                .violations
-                .iter()
-                .map(|v| format!("{}: {}", v.path, v.message))
-                .collect();
-            let result = ValidateResult {
-                valid: false,
-                format: format!("{:?}", parsed.format),
-                errors: errors.clone(),
-            };
-            if output.format == OutputFormat::Text && !output.quiet {
-                eprintln!("error: validation failed");
-                for e in &errors {
-                    eprintln!("- {e}");
-                }
-            } else {
-                print_result(output.format, output.quiet, &result);
+        exit_codes::SUCCESS
+    } else {
+        let errors: Vec<String> = env_errors
+            .into_iter()
+            .chain(report.errors().map(describe))
+            .collect();
+        let result = ValidateResult {
+            valid: false,
+            format: Some(format!("{:?}", parsed.format)),
+            spec_version: parsed.spec_version.map(|v| v.to_string()),
+            errors: errors.clone(),
+            warnings,
+            env: parsed.env,
+        };
+        if output.format == OutputFormat::Text && !output.quiet {
+            eprintln!("error: validation failed");
+            for e in &errors {
+                eprintln!("- {e}");
             }
-            exit_codes::VALIDATION_FAILED
+        } else {
+            print_result(output.format, output.quiet, &result);
         }
+        exit_codes::VALIDATION_FAILED
     }
 }
+
+/// Report a parse error. Text output points at the offending line with a caret;
+/// JSON output adds the location next to the message.
+fn print_parse_error(path: &Path, content: &str, err: &ParseError, output: &OutputArgs) {
+    let message = match err {
+        ParseError::Json { source, .. } => format!("JSON parse failed: {source}"),
+        ParseError::Yaml { source, .. } => format!("YAML parse failed: {source}"),
+        ParseError::Toml { source, .. } => format!("TOML parse failed: {source}"),
+        ParseError::UnknownFormat => "input is neither valid JSON nor valid YAML".to_string(),
+        ParseError::UnknownFields(_) => err.to_string(),
+    };
+    let Some(location) = err.location() else {
+        print_error(output.format, output.quiet, &message);
+        return;
+    };
+
+    if output.format == OutputFormat::Text && !output.quiet {
+        eprintln!("error: {message}");
+        eprintln!("{}", annotated_snippet(path, content, location));
+    } else {
+        let err = serde_json::json!({"error": message, "location": location});
+        eprintln!("{}", serde_json::to_string(&err).unwrap_or_default());
+    }
+}
+
+fn annotated_snippet(path: &Path, content: &str, location: &ErrorLocation) -> String {
+    let line = content
+        .lines()
+        .nth(location.line.saturating_sub(1))
+        .unwrap_or_default();
+    let gutter = " ".repeat(location.line.to_string().len());
+    let indent: String = line
+        .chars()
+        .take(location.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    format!(
+        "{gutter}--> {}:{}:{}\n{gutter} |\n{} | {line}\n{gutter} | {indent}^ {}",
+        path.display(),
+        location.line,
+        location.column,
+        location.line,
+        location.path
+    )
+}
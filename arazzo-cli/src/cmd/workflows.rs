@@ -1,10 +1,11 @@
 use std::path::Path;
 
-use arazzo_core::{parse_document_str, DocumentFormat};
+use arazzo_core::parse_workflow_summaries;
 use serde::Serialize;
 
 use crate::exit_codes;
 use crate::output::{print_error, print_result, OutputFormat};
+use crate::utils::document_format;
 use crate::OutputArgs;
 
 #[derive(Serialize)]
@@ -35,23 +36,21 @@ pub async fn workflows_cmd(path: &Path, output: OutputArgs) -> i32 {
         }
     };
 
-    let parsed = match parse_document_str(&content, DocumentFormat::Auto) {
-        Ok(p) => p,
+    let summaries = match parse_workflow_summaries(&content, document_format(path)) {
+        Ok(s) => s,
         Err(e) => {
             print_error(output.format, output.quiet, &format!("{e}"));
             return exit_codes::VALIDATION_FAILED;
         }
     };
 
-    let workflows: Vec<WorkflowInfo> = parsed
-        .document
-        .workflows
-        .iter()
+    let workflows: Vec<WorkflowInfo> = summaries
+        .into_iter()
         .map(|w| WorkflowInfo {
-            workflow_id: w.workflow_id.clone(),
-            summary: w.summary.clone(),
-            description: w.description.clone(),
-            step_count: w.steps.len(),
+            workflow_id: w.workflow_id,
+            summary: w.summary,
+            description: w.description,
+            step_count: w.step_count,
         })
         .collect();
 
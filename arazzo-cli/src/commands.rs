@@ -14,12 +14,21 @@ pub enum Command {
         inputs: Option<PathBuf>,
         #[arg(long = "set", value_name = "KEY=VALUE")]
         set_inputs: Vec<String>,
+        #[arg(long = "provenance", value_name = "KEY=VALUE")]
+        provenance: Vec<String>,
         #[arg(long)]
         run_id: Option<String>,
         #[arg(long)]
         idempotency_key: Option<String>,
         #[arg(long, default_value = "postgres")]
         events: String,
+        /// Emit a step.debug event per attempt with the resolved parameters and body
+        /// (secrets redacted).
+        #[arg(long)]
+        debug_requests: bool,
+        /// Send OpenAPI defaults for optional parameters a step does not set.
+        #[arg(long)]
+        fill_parameter_defaults: bool,
         #[command(flatten)]
         output: OutputArgs,
         #[command(flatten)]
@@ -27,6 +36,8 @@ pub enum Command {
         #[command(flatten)]
         openapi: OpenApiArgs,
         #[command(flatten)]
+        validate_config: ValidateConfigArgs,
+        #[command(flatten)]
         secrets: SecretsArgs,
         #[command(flatten)]
         webhook: WebhookArgs,
@@ -36,6 +47,10 @@ pub enum Command {
         concurrency: ConcurrencyArgs,
         #[command(flatten)]
         retry: RetryArgs,
+        #[command(flatten)]
+        overlay: OverlayArgs,
+        #[command(flatten)]
+        env: EnvArgs,
     },
     Start {
         path: PathBuf,
@@ -45,8 +60,13 @@ pub enum Command {
         inputs: Option<PathBuf>,
         #[arg(long = "set", value_name = "KEY=VALUE")]
         set_inputs: Vec<String>,
+        #[arg(long = "provenance", value_name = "KEY=VALUE")]
+        provenance: Vec<String>,
         #[arg(long)]
         idempotency_key: Option<String>,
+        /// Send OpenAPI defaults for optional parameters a step does not set.
+        #[arg(long)]
+        fill_parameter_defaults: bool,
         #[command(flatten)]
         output: OutputArgs,
         #[command(flatten)]
@@ -54,6 +74,8 @@ pub enum Command {
         #[command(flatten)]
         openapi: OpenApiArgs,
         #[command(flatten)]
+        validate_config: ValidateConfigArgs,
+        #[command(flatten)]
         secrets: SecretsArgs,
         #[command(flatten)]
         policy: PolicyArgs,
@@ -64,6 +86,15 @@ pub enum Command {
     },
     Resume {
         run_id: String,
+        #[arg(long)]
+        inputs: Option<PathBuf>,
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set_inputs: Vec<String>,
+        #[arg(long)]
+        persist_inputs: bool,
+        /// Abort if the stored workflow document differs from this file.
+        #[arg(long, value_name = "PATH")]
+        verify_hash: Option<PathBuf>,
         #[command(flatten)]
         output: OutputArgs,
         #[command(flatten)]
@@ -107,8 +138,40 @@ pub enum Command {
         #[command(flatten)]
         store: StoreArgs,
     },
+    /// Rebuild a run's state from its event log and compare it with the stored run.
+    ReplayEvents {
+        run_id: String,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        store: StoreArgs,
+    },
     Validate {
         path: PathBuf,
+        /// Reject keys that are neither Arazzo fields nor `x-` extensions.
+        #[arg(long)]
+        strict: bool,
+        /// Also validate against the Arazzo JSON Schema.
+        #[arg(long)]
+        schema: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        validate_config: ValidateConfigArgs,
+        #[command(flatten)]
+        env: EnvArgs,
+    },
+    /// Report unused components, outputs and dependencies and unreachable steps.
+    Lint {
+        path: PathBuf,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Compare two versions of a document: added, removed and changed workflows,
+    /// steps, parameters, criteria, actions and outputs.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
         #[command(flatten)]
         output: OutputArgs,
     },
@@ -124,6 +187,12 @@ pub enum Command {
         output: OutputArgs,
         #[command(flatten)]
         openapi: OpenApiArgs,
+        #[command(flatten)]
+        validate_config: ValidateConfigArgs,
+        #[command(flatten)]
+        overlay: OverlayArgs,
+        #[command(flatten)]
+        env: EnvArgs,
     },
     Workflows {
         path: PathBuf,
@@ -134,8 +203,71 @@ pub enum Command {
         path: PathBuf,
         #[arg(long)]
         workflow: Option<String>,
+        /// Load the OpenAPI sources and show each step's resolved HTTP operation.
+        #[arg(long)]
+        resolve: bool,
         #[command(flatten)]
         output: OutputArgs,
+        #[command(flatten)]
+        openapi: OpenApiArgs,
+    },
+    Schema {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Inline local file references into a single document.
+    Bundle {
+        path: PathBuf,
+        /// Write the bundled document here instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Edit a YAML document's text, keeping comments and formatting outside the edited
+    /// nodes. Every `--set` applies before any `--remove`.
+    Patch {
+        path: PathBuf,
+        /// Set the nodes TARGET selects (an overlay-style JSONPath) to VALUE, parsed
+        /// as YAML. A missing final key is added.
+        #[arg(long, num_args = 2, value_names = ["TARGET", "VALUE"])]
+        set: Vec<String>,
+        /// Remove the nodes TARGET selects.
+        #[arg(long, value_name = "TARGET")]
+        remove: Vec<String>,
+        /// Write the result back to the file instead of stdout.
+        #[arg(long)]
+        in_place: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Parse, validate, plan and compile in one pass and report every diagnostic.
+    Check {
+        path: PathBuf,
+        #[arg(long)]
+        workflow: Option<String>,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        openapi: OpenApiArgs,
+        #[command(flatten)]
+        validate_config: ValidateConfigArgs,
+    },
+    /// Report which step requests the policy would deny, without sending them.
+    PolicyCheck {
+        path: PathBuf,
+        #[arg(long)]
+        workflow: Option<String>,
+        #[arg(long)]
+        inputs: Option<PathBuf>,
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set_inputs: Vec<String>,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        openapi: OpenApiArgs,
+        #[command(flatten)]
+        policy: PolicyArgs,
     },
     Openapi {
         path: PathBuf,
@@ -19,6 +19,11 @@ struct Cli {
 
 fn main() {
     let cli = Cli::parse();
+    // Warnings from the engine (e.g. an event the store rejected) go to stderr.
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(tracing_subscriber::filter::LevelFilter::WARN)
+        .init();
 
     let rt = match tokio::runtime::Runtime::new() {
         Ok(rt) => rt,
@@ -39,34 +44,46 @@ async fn run_command(command: Command) -> i32 {
             workflow,
             inputs,
             set_inputs,
+            provenance,
             run_id,
             idempotency_key,
             events,
+            debug_requests,
+            fill_parameter_defaults,
             output,
             store,
             openapi,
+            validate_config,
             secrets,
             webhook,
             policy,
             concurrency,
             retry,
+            overlay,
+            env,
         } => {
             cmd::execute::execute_cmd(
                 &path,
                 workflow.as_deref(),
                 inputs.as_deref(),
                 &set_inputs,
+                &provenance,
                 run_id.as_deref(),
                 idempotency_key.as_deref(),
                 &events,
+                debug_requests,
+                fill_parameter_defaults,
                 output,
                 store,
                 openapi,
+                validate_config,
                 secrets,
                 webhook,
                 policy,
                 concurrency,
                 retry,
+                overlay,
+                env,
             )
             .await
         }
@@ -75,10 +92,13 @@ async fn run_command(command: Command) -> i32 {
             workflow,
             inputs,
             set_inputs,
+            provenance,
             idempotency_key,
+            fill_parameter_defaults,
             output,
             store,
             openapi,
+            validate_config,
             secrets,
             policy,
             concurrency,
@@ -89,10 +109,13 @@ async fn run_command(command: Command) -> i32 {
                 workflow.as_deref(),
                 inputs.as_deref(),
                 &set_inputs,
+                &provenance,
                 idempotency_key.as_deref(),
+                fill_parameter_defaults,
                 output,
                 store,
                 openapi,
+                validate_config,
                 secrets,
                 policy,
                 concurrency,
@@ -102,6 +125,10 @@ async fn run_command(command: Command) -> i32 {
         }
         Command::Resume {
             run_id,
+            inputs,
+            set_inputs,
+            persist_inputs,
+            verify_hash,
             output,
             store,
             secrets,
@@ -109,8 +136,20 @@ async fn run_command(command: Command) -> i32 {
             concurrency,
             retry,
         } => {
-            cmd::resume::resume_cmd(&run_id, output, store, secrets, policy, concurrency, retry)
-                .await
+            cmd::resume::resume_cmd(
+                &run_id,
+                inputs.as_deref(),
+                &set_inputs,
+                persist_inputs,
+                verify_hash.as_deref(),
+                output,
+                store,
+                secrets,
+                policy,
+                concurrency,
+                retry,
+            )
+            .await
         }
         Command::Cancel {
             run_id,
@@ -133,7 +172,21 @@ async fn run_command(command: Command) -> i32 {
             output,
             store,
         } => cmd::events::events_cmd(&run_id, follow, output, store).await,
-        Command::Validate { path, output } => cmd::validate::validate_cmd(&path, output).await,
+        Command::ReplayEvents {
+            run_id,
+            output,
+            store,
+        } => cmd::replay_events::replay_events_cmd(&run_id, output, store).await,
+        Command::Validate {
+            path,
+            strict,
+            schema,
+            output,
+            validate_config,
+            env,
+        } => cmd::validate::validate_cmd(&path, strict, schema, output, validate_config, env).await,
+        Command::Lint { path, output } => cmd::lint::lint_cmd(&path, output).await,
+        Command::Diff { old, new, output } => cmd::diff::diff_cmd(&old, &new, output).await,
         Command::Plan {
             path,
             workflow,
@@ -141,6 +194,9 @@ async fn run_command(command: Command) -> i32 {
             compile,
             output,
             openapi,
+            validate_config,
+            overlay,
+            env,
         } => {
             cmd::plan::plan_cmd(
                 &path,
@@ -149,6 +205,9 @@ async fn run_command(command: Command) -> i32 {
                 compile,
                 output,
                 openapi,
+                validate_config,
+                overlay,
+                env,
             )
             .await
         }
@@ -156,8 +215,51 @@ async fn run_command(command: Command) -> i32 {
         Command::Inspect {
             path,
             workflow,
+            resolve,
             output,
-        } => cmd::inspect::inspect_cmd(&path, workflow.as_deref(), output).await,
+            openapi,
+        } => cmd::inspect::inspect_cmd(&path, workflow.as_deref(), resolve, output, openapi).await,
+        Command::Schema { output } => cmd::schema::schema_cmd(output).await,
+        Command::Bundle { path, out, output } => {
+            cmd::bundle::bundle_cmd(&path, out.as_deref(), output).await
+        }
+        Command::Patch {
+            path,
+            set,
+            remove,
+            in_place,
+            output,
+        } => cmd::patch::patch_cmd(&path, &set, &remove, in_place, output).await,
+        Command::Check {
+            path,
+            workflow,
+            output,
+            openapi,
+            validate_config,
+        } => {
+            cmd::check::check_cmd(&path, workflow.as_deref(), output, openapi, validate_config)
+                .await
+        }
+        Command::PolicyCheck {
+            path,
+            workflow,
+            inputs,
+            set_inputs,
+            output,
+            openapi,
+            policy,
+        } => {
+            cmd::policy_check::policy_check_cmd(
+                &path,
+                workflow.as_deref(),
+                inputs.as_deref(),
+                &set_inputs,
+                output,
+                openapi,
+                policy,
+            )
+            .await
+        }
         Command::Openapi {
             path,
             output,
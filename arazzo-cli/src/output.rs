@@ -7,8 +7,13 @@ pub enum OutputFormat {
     Dot,
 }
 
+/// Print a command's final result on stdout. In quiet mode the result is always a
+/// single compact JSON line, whatever `format` is, so it can be piped.
 pub fn print_result<T: Serialize>(format: OutputFormat, quiet: bool, result: &T) {
     if quiet {
+        if let Ok(json) = serde_json::to_string(result) {
+            println!("{json}");
+        }
         return;
     }
     match format {
@@ -32,10 +37,9 @@ pub fn print_result<T: Serialize>(format: OutputFormat, quiet: bool, result: &T)
     }
 }
 
+/// Print a diagnostic on stderr, never stdout. In quiet mode it is a JSON object line.
 pub fn print_error(format: OutputFormat, quiet: bool, message: &str) {
-    if quiet {
-        return;
-    }
+    let format = if quiet { OutputFormat::Json } else { format };
     match format {
         OutputFormat::Text => eprintln!("error: {message}"),
         OutputFormat::Json => {
@@ -1,10 +1,22 @@
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use std::fs;
 use tempfile::TempDir;
 
+/// A one-operation OpenAPI document for tests that compile against a local source.
+const USERS_OPENAPI: &str = r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "Users", "version": "1.0.0" },
+  "servers": [{ "url": "https://api.example.com" }],
+  "paths": {
+    "/users": {
+      "get": { "operationId": "getUsers", "responses": { "200": { "description": "ok" } } }
+    }
+  }
+}"#;
+
 #[test]
 fn test_validate_command() {
-    let mut cmd = Command::cargo_bin("arazzo").unwrap();
+    let mut cmd = cargo_bin_cmd!("arazzo");
 
     // Create a minimal valid workflow
     let workflow = r#"
@@ -27,28 +39,625 @@ workflows:
     let workflow_path = tmp_dir.path().join("test.yaml");
     fs::write(&workflow_path, workflow).unwrap();
 
-    cmd.args(&["validate", workflow_path.to_str().unwrap()])
+    cmd.args(["validate", workflow_path.to_str().unwrap()])
         .assert()
         .success();
 }
 
 #[test]
 fn test_validate_invalid_workflow() {
-    let mut cmd = Command::cargo_bin("arazzo").unwrap();
+    let mut cmd = cargo_bin_cmd!("arazzo");
 
     let tmp_dir = TempDir::new().unwrap();
     let workflow_path = tmp_dir.path().join("invalid.yaml");
     fs::write(&workflow_path, "invalid: yaml: content").unwrap();
 
-    cmd.args(&["validate", workflow_path.to_str().unwrap()])
+    cmd.args(["validate", workflow_path.to_str().unwrap()])
         .assert()
         .failure()
         .code(2); // VALIDATION_FAILED
 }
 
+#[test]
+fn test_validate_parse_error_points_at_the_offending_line() {
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("bad.yaml");
+    fs::write(
+        &workflow_path,
+        "arazzo: 1.0.1\ninfo:\n  title: Test\n  version: [1]\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args(["validate", workflow_path.to_str().unwrap()])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("bad.yaml:4:12"), "stderr: {stderr}");
+    assert!(stderr.contains("4 |   version: [1]"), "stderr: {stderr}");
+    assert!(stderr.contains("^ $.info.version"), "stderr: {stderr}");
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "validate",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let err: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(err["location"]["line"], 4);
+    assert_eq!(err["location"]["path"], "$.info.version");
+}
+
+#[test]
+fn test_validate_strict_reports_unknown_fields() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+        succesCriteria:
+          - condition: $statusCode == 200
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "validate",
+            "--strict",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["valid"], false);
+    assert_eq!(
+        result["errors"][0],
+        "$.workflows[0].steps[0].succesCriteria: unknown field (only x-* specification extensions are allowed)"
+    );
+}
+
+#[test]
+fn test_validate_applies_rule_overrides_from_config() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+        timeout: 30
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    cargo_bin_cmd!("arazzo")
+        .args(["validate", workflow_path.to_str().unwrap()])
+        .assert()
+        .code(2);
+
+    fs::write(
+        tmp_dir.path().join(".arazzo-validate.yaml"),
+        "rules:\n  - path: $.workflows[*].steps[*].timeout\n    level: warning\n",
+    )
+    .unwrap();
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "validate",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["valid"], true);
+    assert_eq!(result["warnings"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_validate_schema_reports_schema_violations() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+        successCriteria: []
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    cargo_bin_cmd!("arazzo")
+        .args(["validate", workflow_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "validate",
+            "--schema",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        result["errors"][0],
+        "$.workflows[0].steps[0].successCriteria: does not match the Arazzo JSON Schema: [] has less than 1 item"
+    );
+}
+
+#[test]
+fn test_bundle_inlines_component_files() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+        parameters:
+          - reference: $components.parameters.limit
+components:
+  parameters:
+    limit:
+      $ref: parameters.yaml#/limit
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+    fs::write(
+        tmp_dir.path().join("parameters.yaml"),
+        "limit:\n  name: limit\n  in: query\n  value: 10\n",
+    )
+    .unwrap();
+    let out_path = tmp_dir.path().join("bundled.json");
+
+    cargo_bin_cmd!("arazzo")
+        .args([
+            "bundle",
+            "--format",
+            "json",
+            "--out",
+            out_path.to_str().unwrap(),
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    let bundled: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out_path).unwrap()).unwrap();
+    assert_eq!(
+        bundled["components"]["parameters"]["limit"],
+        serde_json::json!({"name": "limit", "in": "query", "value": 10})
+    );
+}
+
+#[test]
+fn test_diff_reports_semantic_changes() {
+    let old = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+      - stepId: step2
+        operationId: getUser
+"#;
+    let new = old.replace("getUser\n", "getUserById\n");
+    let tmp_dir = TempDir::new().unwrap();
+    let old_path = tmp_dir.path().join("old.yaml");
+    let new_path = tmp_dir.path().join("new.yaml");
+    fs::write(&old_path, old).unwrap();
+    fs::write(&new_path, new).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "diff",
+            "--format",
+            "json",
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["identical"], false);
+    assert_eq!(
+        report["changes"],
+        serde_json::json!([{
+            "kind": "changed",
+            "subject": "operationId",
+            "workflow_id": "test",
+            "step_id": "step2",
+            "old": "getUser",
+            "new": "getUserById",
+        }])
+    );
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "diff",
+            old_path.to_str().unwrap(),
+            old_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(String::from_utf8(output).unwrap(), "no changes\n");
+}
+
+#[test]
+fn test_validate_env_expands_placeholders() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://${ARAZZO_TEST_API_HOST}/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+        parameters:
+          - name: tenant
+            in: query
+            value: ${ARAZZO_TEST_UNSET_TENANT}
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .env("ARAZZO_TEST_API_HOST", "api.example.com")
+        .env_remove("ARAZZO_TEST_UNSET_TENANT")
+        .args([
+            "validate",
+            "--env",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .code(2)
+        .get_output()
+        .stdout
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(result["valid"], false);
+    assert_eq!(
+        result["errors"][0],
+        "$.workflows[0].steps[0].parameters[0].value: environment variable 'ARAZZO_TEST_UNSET_TENANT' is not set"
+    );
+    assert_eq!(
+        result["env"]["substituted"],
+        serde_json::json!([{"name": "ARAZZO_TEST_API_HOST", "path": "$.sourceDescriptions[0].url"}])
+    );
+}
+
+#[test]
+fn test_validate_reads_toml_by_extension() {
+    let workflow = r#"arazzo = "1.0.1"
+
+[info]
+title = "Test"
+version = "1.0.0"
+
+[[sourceDescriptions]]
+name = "api"
+url = "https://api.example.com/openapi.json"
+
+[[workflows]]
+workflowId = "test"
+
+[[workflows.steps]]
+stepId = "step1"
+operationId = "getUsers"
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("workflow.toml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "validate",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(result["valid"], true);
+    assert_eq!(result["format"], "Toml");
+}
+
+#[test]
+fn test_patch_keeps_comments_and_writes_in_place() {
+    let workflow = r#"arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  # Point at staging until launch.
+  - name: api
+    url: https://staging.example.com/openapi.json # pinned
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+      - stepId: step2
+        operationId: getUser
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "patch",
+            workflow_path.to_str().unwrap(),
+            "--set",
+            "$.sourceDescriptions[?(@.name == 'api')].url",
+            "https://example.com/openapi.json",
+            "--remove",
+            "$.workflows[0].steps[1]",
+            "--in-place",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(result["changed"], 2);
+    let expected = workflow
+        .replace("staging.example.com", "example.com")
+        .replace("      - stepId: step2\n        operationId: getUser\n", "");
+    assert_eq!(fs::read_to_string(&workflow_path).unwrap(), expected);
+
+    // An edit that breaks the document is rejected and nothing is written.
+    cargo_bin_cmd!("arazzo")
+        .args([
+            "patch",
+            workflow_path.to_str().unwrap(),
+            "--remove",
+            "$.workflows[0].steps[0].stepId",
+            "--in-place",
+        ])
+        .assert()
+        .code(2);
+    assert_eq!(fs::read_to_string(&workflow_path).unwrap(), expected);
+}
+
+#[test]
+fn test_workflows_lists_without_parsing_steps() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://api.example.com/openapi.json
+workflows:
+  - workflowId: users
+    summary: List users
+    steps:
+      - stepId: list
+        operationId: getUsers
+        parameters: not-a-list
+      - stepId: show
+        operationId: getUser
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("workflow.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "workflows",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        result["workflows"],
+        serde_json::json!([
+            {"workflow_id": "users", "summary": "List users", "step_count": 2}
+        ])
+    );
+}
+
+#[test]
+fn test_lint_reports_rule_ids_and_fails_on_errors() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+        onSuccess:
+          - name: stop
+            type: end
+        outputs:
+          ids: $response.body#/ids
+      - stepId: step2
+        operationId: getUser
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args(["lint", "--format", "json", workflow_path.to_str().unwrap()])
+        .assert()
+        .code(2)
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["errors"], 1);
+    assert_eq!(report["warnings"], 1);
+    let rules: Vec<&str> = report["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["rule"].as_str().unwrap())
+        .collect();
+    assert_eq!(rules, ["unreachable-step", "unused-step-output"]);
+}
+
+#[test]
+fn test_plan_applies_overlays() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    type: openapi
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+      - stepId: step2
+        operationId: getAuditLog
+"#;
+    let overlay = r#"
+overlay: 1.0.0
+info:
+  title: No audit
+  version: 1.0.0
+actions:
+  - target: $.workflows[0].steps[?(@.stepId == 'step2')]
+    remove: true
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    let overlay_path = tmp_dir.path().join("overlay.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+    fs::write(&overlay_path, overlay).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "plan",
+            "--format",
+            "json",
+            "--overlay",
+            overlay_path.to_str().unwrap(),
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let plan: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let steps = plan["logical"]["plan"]["steps"].as_array().unwrap();
+    assert_eq!(steps.len(), 1);
+    assert_eq!(steps[0]["step_id"], "step1");
+
+    fs::write(&overlay_path, overlay.replace("step2", "missing")).unwrap();
+    cargo_bin_cmd!("arazzo")
+        .args([
+            "plan",
+            "--overlay",
+            overlay_path.to_str().unwrap(),
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .code(2);
+}
+
 #[test]
 fn test_plan_command() {
-    let mut cmd = Command::cargo_bin("arazzo").unwrap();
+    let mut cmd = cargo_bin_cmd!("arazzo");
 
     let workflow = r#"
 arazzo: 1.0.1
@@ -70,14 +679,14 @@ workflows:
     let workflow_path = tmp_dir.path().join("test.yaml");
     fs::write(&workflow_path, workflow).unwrap();
 
-    cmd.args(&["plan", workflow_path.to_str().unwrap()])
+    cmd.args(["plan", workflow_path.to_str().unwrap()])
         .assert()
         .success();
 }
 
 #[test]
 fn test_plan_dot_format() {
-    let mut cmd = Command::cargo_bin("arazzo").unwrap();
+    let mut cmd = cargo_bin_cmd!("arazzo");
 
     // Use a simple workflow that doesn't require OpenAPI resolution
     let workflow = r#"
@@ -101,7 +710,7 @@ workflows:
     fs::write(&workflow_path, workflow).unwrap();
 
     let assert = cmd
-        .args(&["plan", "--format", "dot", workflow_path.to_str().unwrap()])
+        .args(["plan", "--format", "dot", workflow_path.to_str().unwrap()])
         .assert()
         .success();
 
@@ -110,3 +719,349 @@ workflows:
     assert!(stdout.contains("digraph"));
     assert!(stdout.contains("test"));
 }
+
+#[test]
+fn test_schema_command_lists_required_top_level_fields() {
+    let mut cmd = cargo_bin_cmd!("arazzo");
+
+    let out = cmd
+        .args(["schema", "--format", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let schema: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|v| v.as_str())
+        .collect();
+    for field in ["arazzo", "info", "workflows"] {
+        assert!(required.contains(&field), "missing {field} in {required:?}");
+    }
+}
+
+#[test]
+fn quiet_mode_prints_only_the_json_result_on_stdout() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    type: openapi
+    url: https://example.com/openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args(["validate", "--quiet", workflow_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1, "stdout: {stdout}");
+    let result: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(result["valid"], true);
+    assert_eq!(result["spec_version"], "1.0.1");
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args(["status", "--quiet", "not-a-uuid"])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    assert!(output.stdout.is_empty());
+    let diagnostic: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert!(diagnostic["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("invalid run_id"));
+}
+
+#[test]
+fn plan_compiles_a_document_that_only_has_warnings() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    type: openapi
+    url: openapi.json
+  - name: billing
+    type: openapi
+    url: openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: $sourceDescriptions.api.getUsers
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+    fs::write(tmp_dir.path().join("openapi.json"), USERS_OPENAPI).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "plan",
+            "--compile",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let warnings = result["logical"]["validation"]["warnings"]
+        .as_array()
+        .unwrap();
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert!(warnings[0].as_str().unwrap().contains("billing"));
+    let steps = result["compiled"]["steps"].as_array().unwrap();
+    assert_eq!(steps[0]["operation"]["path"], "/users");
+}
+
+#[test]
+fn compiled_dot_labels_nodes_with_the_http_operation() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    type: openapi
+    url: openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+    fs::write(tmp_dir.path().join("openapi.json"), USERS_OPENAPI).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "plan",
+            "--compile",
+            "--format",
+            "dot",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(r#""step1" [label="step1\nGET /users\napi.example.com"];"#),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn inspect_resolve_shows_each_steps_http_operation() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    type: openapi
+    url: openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+    fs::write(tmp_dir.path().join("openapi.json"), USERS_OPENAPI).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "inspect",
+            "--resolve",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let step = &result["steps"][0];
+    assert_eq!(step["operation_id"], "getUsers");
+    assert_eq!(step["http"]["method"], "GET");
+    assert_eq!(step["http"]["path"], "/users");
+    assert_eq!(step["http"]["base_url"], "https://api.example.com");
+
+    let text = cargo_bin_cmd!("arazzo")
+        .args(["inspect", "--resolve", workflow_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert!(String::from_utf8(text)
+        .unwrap()
+        .contains("GET https://api.example.com/users"));
+}
+
+#[test]
+fn policy_check_reports_a_disallowed_host_as_denied() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    type: openapi
+    url: openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: getUsers
+"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+    fs::write(tmp_dir.path().join("openapi.json"), USERS_OPENAPI).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "policy-check",
+            "--allow-host",
+            "other.example.com",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let step = &result["steps"][0];
+    assert_eq!(step["step_id"], "step1");
+    assert_eq!(step["decision"], "denied");
+    assert_eq!(step["kind"], "host");
+    assert!(step["reason"].as_str().unwrap().contains("api.example.com"));
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args([
+            "policy-check",
+            "--allow-host",
+            "api.example.com",
+            "--format",
+            "json",
+            workflow_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["steps"][0]["decision"], "allowed");
+    assert!(result["steps"][0]["url"]
+        .as_str()
+        .unwrap()
+        .starts_with("https://api.example.com/users"));
+}
+
+#[test]
+fn check_merges_validation_errors_and_compile_warnings() {
+    let workflow = r#"
+arazzo: 1.0.1
+info:
+  title: Test
+  version: 1.0.0
+sourceDescriptions:
+  - name: api
+    type: openapi
+    url: openapi.json
+workflows:
+  - workflowId: test
+    steps:
+      - stepId: step1
+        operationId: listUsers
+        x-ignore-missing-params: warn
+        outputs:
+          broken: $bogus
+"#;
+    let openapi = r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "Users", "version": "1.0.0" },
+  "servers": [{ "url": "https://api.example.com" }],
+  "paths": {
+    "/users": {
+      "get": {
+        "operationId": "listUsers",
+        "parameters": [{ "name": "limit", "in": "query", "required": true }],
+        "responses": { "200": { "description": "ok" } }
+      }
+    }
+  }
+}"#;
+    let tmp_dir = TempDir::new().unwrap();
+    let workflow_path = tmp_dir.path().join("test.yaml");
+    fs::write(&workflow_path, workflow).unwrap();
+    fs::write(tmp_dir.path().join("openapi.json"), openapi).unwrap();
+
+    let output = cargo_bin_cmd!("arazzo")
+        .args(["check", "--format", "json", workflow_path.to_str().unwrap()])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["ok"], false);
+    let diagnostics = report["diagnostics"].as_array().unwrap();
+
+    let error = diagnostics
+        .iter()
+        .find(|d| d["severity"] == "error")
+        .unwrap();
+    assert_eq!(error["source"], "validator");
+    assert!(error["message"]
+        .as_str()
+        .unwrap()
+        .contains("outputs.broken"));
+
+    let warning = diagnostics
+        .iter()
+        .find(|d| d["severity"] == "warning")
+        .unwrap();
+    assert_eq!(warning["source"], "compiler");
+    assert_eq!(warning["workflow_id"], "test");
+    assert_eq!(warning["step_id"], "step1");
+    assert!(warning["message"].as_str().unwrap().contains("limit"));
+    assert_eq!(report["errors"], 1);
+    assert_eq!(report["warnings"], 1);
+}
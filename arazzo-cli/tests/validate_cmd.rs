@@ -1,4 +1,4 @@
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use tempfile::NamedTempFile;
 
 fn write_temp(contents: &str) -> NamedTempFile {
@@ -25,8 +25,7 @@ workflows:
 "#;
     let f = write_temp(doc);
 
-    Command::cargo_bin("arazzo")
-        .unwrap()
+    cargo_bin_cmd!("arazzo")
         .args(["validate", f.path().to_string_lossy().as_ref()])
         .assert()
         .success();
@@ -50,8 +49,7 @@ workflows:
 "#;
     let f = write_temp(doc);
 
-    Command::cargo_bin("arazzo")
-        .unwrap()
+    cargo_bin_cmd!("arazzo")
         .args(["validate", f.path().to_string_lossy().as_ref()])
         .assert()
         .code(2); // VALIDATION_FAILED
@@ -75,8 +73,7 @@ workflows:
 "#;
     let f = write_temp(doc);
 
-    Command::cargo_bin("arazzo")
-        .unwrap()
+    cargo_bin_cmd!("arazzo")
         .args([
             "plan",
             f.path().to_string_lossy().as_ref(),
@@ -144,8 +141,7 @@ workflows:
     );
     let arazzo_file = write_temp(&arazzo);
 
-    Command::cargo_bin("arazzo")
-        .unwrap()
+    cargo_bin_cmd!("arazzo")
         .args([
             "plan",
             arazzo_file.path().to_string_lossy().as_ref(),
@@ -0,0 +1,416 @@
+//! Bundling a document split across several files into one [`ArazzoDocument`].
+//!
+//! Two kinds of cross-file references are resolved:
+//! - inside `components`, an object whose only key is [`FILE_REF_KEY`] is replaced by
+//!   the file (or the part of it named by a `#/json/pointer` fragment) it points at;
+//! - a source description of type `arazzo` with a relative `url` is inlined: its
+//!   workflows, components and source descriptions move into the root document, and
+//!   `$sourceDescriptions.<name>.<workflowId>` references become plain workflow ids.
+//!
+//! Paths are resolved against the directory of the file that contains them. Remote
+//! (`scheme://`) references are left alone.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use crate::error::ParseError;
+use crate::parser::{from_value, parse_value};
+use crate::types::{
+    ArazzoDocument, Components, FailureActionOrReusable, SourceDescriptionType,
+    SuccessActionOrReusable,
+};
+
+/// Key of an object that stands for the content of another file, e.g.
+/// `{"$ref": "./parameters.yaml#/pageSize"}`.
+pub const FILE_REF_KEY: &str = "$ref";
+
+#[derive(Debug, Clone)]
+pub struct BundledDocument {
+    pub document: ArazzoDocument,
+    /// Every file that was read, the root document first.
+    pub files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Error)]
+pub enum BundleError {
+    #[error("failed to read {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{}: {source}", .path.display())]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: ParseError,
+    },
+    #[error("{}: {node}: {source}", .path.display())]
+    Document {
+        path: PathBuf,
+        /// Path to the offending node, e.g. `$.workflows[0].steps`.
+        node: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("{}: cannot resolve '{reference}': {reason}", .path.display())]
+    Reference {
+        path: PathBuf,
+        reference: String,
+        reason: String,
+    },
+    #[error("reference cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("{kind} '{name}' from {} is already defined", .path.display())]
+    Conflict {
+        kind: &'static str,
+        name: String,
+        path: PathBuf,
+    },
+}
+
+/// Bundle the document at `path` and every local file it references.
+pub fn bundle_document(path: &Path) -> Result<BundledDocument, BundleError> {
+    bundle_document_with(path, |p| std::fs::read_to_string(p))
+}
+
+/// Like [`bundle_document`], reading files through `read` instead of the file system.
+pub fn bundle_document_with<F>(path: &Path, read: F) -> Result<BundledDocument, BundleError>
+where
+    F: FnMut(&Path) -> io::Result<String>,
+{
+    let mut bundler = Bundler {
+        read,
+        stack: Vec::new(),
+        files: Vec::new(),
+    };
+    let document = bundler.document(&normalize(path))?;
+    Ok(BundledDocument {
+        document,
+        files: bundler.files,
+    })
+}
+
+struct Bundler<F> {
+    read: F,
+    /// The references currently being resolved, outermost first.
+    stack: Vec<String>,
+    files: Vec<PathBuf>,
+}
+
+impl<F: FnMut(&Path) -> io::Result<String>> Bundler<F> {
+    fn document(&mut self, path: &Path) -> Result<ArazzoDocument, BundleError> {
+        self.enter(path.display().to_string())?;
+        let mut value = self.load(path)?;
+        if let Some(components) = value.get_mut("components") {
+            self.resolve_refs(components, path)?;
+        }
+        let mut doc: ArazzoDocument =
+            from_value(value).map_err(|(source, node)| BundleError::Document {
+                path: path.to_path_buf(),
+                node,
+                source,
+            })?;
+        self.inline_arazzo_sources(&mut doc, path)?;
+        self.stack.pop();
+        Ok(doc)
+    }
+
+    fn load(&mut self, path: &Path) -> Result<JsonValue, BundleError> {
+        let content = (self.read)(path).map_err(|source| BundleError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if !self.files.iter().any(|f| f == path) {
+            self.files.push(path.to_path_buf());
+        }
+        parse_value(&content).map_err(|source| BundleError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    fn enter(&mut self, key: String) -> Result<(), BundleError> {
+        if let Some(start) = self.stack.iter().position(|k| *k == key) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(key);
+            return Err(BundleError::Cycle(cycle));
+        }
+        self.stack.push(key);
+        Ok(())
+    }
+
+    /// Replace every file reference under `value`, which was read from `file`.
+    fn resolve_refs(&mut self, value: &mut JsonValue, file: &Path) -> Result<(), BundleError> {
+        let reference = match value {
+            JsonValue::Object(map) => file_ref(map),
+            _ => None,
+        };
+        if let Some(reference) = reference {
+            let (target, pointer) = reference.split_once('#').unwrap_or((&reference, ""));
+            if is_remote(target) {
+                return Err(BundleError::Reference {
+                    path: file.to_path_buf(),
+                    reference: reference.clone(),
+                    reason: "only local files can be bundled".to_string(),
+                });
+            }
+            let target = normalize(&base_dir(file).join(target));
+            self.enter(format!("{}#{pointer}", target.display()))?;
+            let loaded = self.load(&target)?;
+            let mut resolved = if pointer.is_empty() {
+                loaded
+            } else {
+                loaded
+                    .pointer(pointer)
+                    .cloned()
+                    .ok_or_else(|| BundleError::Reference {
+                        path: file.to_path_buf(),
+                        reference: reference.clone(),
+                        reason: format!("{} has nothing at {pointer}", target.display()),
+                    })?
+            };
+            self.resolve_refs(&mut resolved, &target)?;
+            self.stack.pop();
+            *value = resolved;
+            return Ok(());
+        }
+
+        match value {
+            JsonValue::Object(map) => {
+                for v in map.values_mut() {
+                    self.resolve_refs(v, file)?;
+                }
+            }
+            JsonValue::Array(items) => {
+                for v in items {
+                    self.resolve_refs(v, file)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Move every local `arazzo` source description of `doc` (read from `path`) into it.
+    fn inline_arazzo_sources(
+        &mut self,
+        doc: &mut ArazzoDocument,
+        path: &Path,
+    ) -> Result<(), BundleError> {
+        let (local, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut doc.source_descriptions)
+            .into_iter()
+            .partition(|s| {
+                s.source_type == Some(SourceDescriptionType::Arazzo) && !is_remote(&s.url)
+            });
+        doc.source_descriptions = kept;
+
+        for source in local {
+            let sub_path = normalize(&base_dir(path).join(&source.url));
+            let sub = self.document(&sub_path)?;
+            // The included file's relative urls are relative to its own directory.
+            let sub_dir = base_dir(Path::new(&source.url));
+
+            for mut sd in sub.source_descriptions {
+                if !is_remote(&sd.url) {
+                    sd.url = path_to_url(&normalize(&sub_dir.join(&sd.url)));
+                }
+                match doc.source_descriptions.iter().find(|s| s.name == sd.name) {
+                    Some(existing) if existing.url == sd.url => {}
+                    Some(_) => return Err(conflict("sourceDescription", &sd.name, &sub_path)),
+                    None => doc.source_descriptions.push(sd),
+                }
+            }
+            for wf in sub.workflows {
+                if doc
+                    .workflows
+                    .iter()
+                    .any(|w| w.workflow_id == wf.workflow_id)
+                {
+                    return Err(conflict("workflow", &wf.workflow_id, &sub_path));
+                }
+                doc.workflows.push(wf);
+            }
+            if let Some(components) = sub.components {
+                merge_components(
+                    doc.components.get_or_insert_with(Components::default),
+                    components,
+                    &sub_path,
+                )?;
+            }
+
+            localize_workflow_refs(doc, &format!("$sourceDescriptions.{}.", source.name));
+        }
+        Ok(())
+    }
+}
+
+fn conflict(kind: &'static str, name: &str, path: &Path) -> BundleError {
+    BundleError::Conflict {
+        kind,
+        name: name.to_string(),
+        path: path.to_path_buf(),
+    }
+}
+
+fn merge_components(
+    into: &mut Components,
+    from: Components,
+    path: &Path,
+) -> Result<(), BundleError> {
+    merge_map(&mut into.inputs, from.inputs, "components.inputs", path)?;
+    merge_map(
+        &mut into.parameters,
+        from.parameters,
+        "components.parameters",
+        path,
+    )?;
+    merge_map(
+        &mut into.success_actions,
+        from.success_actions,
+        "components.successActions",
+        path,
+    )?;
+    merge_map(
+        &mut into.failure_actions,
+        from.failure_actions,
+        "components.failureActions",
+        path,
+    )
+}
+
+/// Identical entries defined in both maps are fine; differing ones conflict.
+fn merge_map<V: PartialEq>(
+    into: &mut Option<BTreeMap<String, V>>,
+    from: Option<BTreeMap<String, V>>,
+    kind: &'static str,
+    path: &Path,
+) -> Result<(), BundleError> {
+    let Some(from) = from else {
+        return Ok(());
+    };
+    let into = into.get_or_insert_with(BTreeMap::new);
+    for (name, value) in from {
+        match into.get(&name) {
+            Some(existing) if *existing == value => {}
+            Some(_) => return Err(conflict(kind, &name, path)),
+            None => {
+                into.insert(name, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Strip `prefix` (`$sourceDescriptions.<name>.`) from every workflow reference, now
+/// that the workflows it points at are part of `doc`.
+fn localize_workflow_refs(doc: &mut ArazzoDocument, prefix: &str) {
+    let localize = |id: &mut String| {
+        if let Some(local) = id.strip_prefix(prefix) {
+            *id = local.to_string();
+        }
+    };
+    let localize_opt = |id: &mut Option<String>| {
+        if let Some(id) = id {
+            localize(id);
+        }
+    };
+    let success = |actions: &mut [SuccessActionOrReusable]| {
+        for action in actions {
+            if let SuccessActionOrReusable::Action(a) = action {
+                localize_opt(&mut a.workflow_id);
+            }
+        }
+    };
+    let failure = |actions: &mut [FailureActionOrReusable]| {
+        for action in actions {
+            if let FailureActionOrReusable::Action(a) = action {
+                localize_opt(&mut a.workflow_id);
+            }
+        }
+    };
+
+    for wf in &mut doc.workflows {
+        wf.depends_on.iter_mut().flatten().for_each(&localize);
+        if let Some(actions) = &mut wf.success_actions {
+            success(actions);
+        }
+        if let Some(actions) = &mut wf.failure_actions {
+            failure(actions);
+        }
+        for step in &mut wf.steps {
+            localize_opt(&mut step.workflow_id);
+            if let Some(actions) = &mut step.on_success {
+                success(actions);
+            }
+            if let Some(actions) = &mut step.on_failure {
+                failure(actions);
+            }
+        }
+    }
+    if let Some(components) = &mut doc.components {
+        for a in components
+            .success_actions
+            .iter_mut()
+            .flatten()
+            .map(|(_, a)| a)
+        {
+            localize_opt(&mut a.workflow_id);
+        }
+        for a in components
+            .failure_actions
+            .iter_mut()
+            .flatten()
+            .map(|(_, a)| a)
+        {
+            localize_opt(&mut a.workflow_id);
+        }
+    }
+}
+
+/// The target of a file reference object, unless it only points within the same file.
+fn file_ref(map: &serde_json::Map<String, JsonValue>) -> Option<String> {
+    if map.len() != 1 {
+        return None;
+    }
+    map.get(FILE_REF_KEY)
+        .and_then(|v| v.as_str())
+        .filter(|r| !r.starts_with('#'))
+        .map(String::from)
+}
+
+fn is_remote(reference: &str) -> bool {
+    reference.contains("://")
+}
+
+fn base_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
+/// Resolve `.` and `..` components lexically, without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(out.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn path_to_url(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,174 @@
+//! Inlining reusable objects: every `{reference: $components...}` parameter and
+//! action is replaced by a copy of the component it names, so consumers only ever
+//! see [`ParameterOrReusable::Parameter`] and the `Action` variants. A reusable
+//! parameter's `value` overrides the component's value. `components` itself is
+//! kept as is.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::types::{
+    ArazzoDocument, Components, FailureActionOrReusable, ParameterOrReusable, ReusableObject,
+    SuccessActionOrReusable, Workflow,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{path}: cannot resolve '{reference}': {reason}")]
+pub struct DereferenceError {
+    /// Path to the reusable object, e.g. `$.workflows[0].steps[1].parameters[0]`.
+    pub path: String,
+    pub reference: String,
+    pub reason: String,
+}
+
+/// A copy of `doc` with every reusable object in its workflows inlined.
+pub fn dereference(doc: &ArazzoDocument) -> Result<ArazzoDocument, DereferenceError> {
+    let mut resolver = Resolver::new(doc.components.as_ref());
+    let mut out = doc.clone();
+    for (wi, wf) in out.workflows.iter_mut().enumerate() {
+        resolver.workflow(wf, &format!("$.workflows[{wi}]"));
+    }
+    resolver.into_result().map(|()| out)
+}
+
+/// A copy of `workflow`, which belongs to `doc`, with its reusable objects inlined.
+pub fn dereference_workflow(
+    doc: &ArazzoDocument,
+    workflow: &Workflow,
+) -> Result<Workflow, DereferenceError> {
+    let (out, errors) = dereference_workflow_best_effort(doc, workflow);
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(out),
+    }
+}
+
+/// Like [`dereference_workflow`], but a reference that does not resolve is kept as
+/// written, and reported, while every other one is still inlined.
+pub fn dereference_workflow_best_effort(
+    doc: &ArazzoDocument,
+    workflow: &Workflow,
+) -> (Workflow, Vec<DereferenceError>) {
+    let base = match doc
+        .workflows
+        .iter()
+        .position(|w| w.workflow_id == workflow.workflow_id)
+    {
+        Some(wi) => format!("$.workflows[{wi}]"),
+        None => format!("workflow '{}'", workflow.workflow_id),
+    };
+    let mut resolver = Resolver::new(doc.components.as_ref());
+    let mut out = workflow.clone();
+    resolver.workflow(&mut out, &base);
+    (out, resolver.errors)
+}
+
+/// Inlines what resolves and collects an error for every reference that does not.
+struct Resolver<'d> {
+    components: Option<&'d Components>,
+    errors: Vec<DereferenceError>,
+}
+
+impl<'d> Resolver<'d> {
+    fn new(components: Option<&'d Components>) -> Self {
+        Self {
+            components,
+            errors: Vec::new(),
+        }
+    }
+
+    fn into_result(self) -> Result<(), DereferenceError> {
+        match self.errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn workflow(&mut self, wf: &mut Workflow, base: &str) {
+        self.parameters(wf.parameters.as_mut(), &format!("{base}.parameters"));
+        self.success_actions(
+            wf.success_actions.as_mut(),
+            &format!("{base}.successActions"),
+        );
+        self.failure_actions(
+            wf.failure_actions.as_mut(),
+            &format!("{base}.failureActions"),
+        );
+        for (si, step) in wf.steps.iter_mut().enumerate() {
+            let base = format!("{base}.steps[{si}]");
+            self.parameters(step.parameters.as_mut(), &format!("{base}.parameters"));
+            self.success_actions(step.on_success.as_mut(), &format!("{base}.onSuccess"));
+            self.failure_actions(step.on_failure.as_mut(), &format!("{base}.onFailure"));
+        }
+    }
+
+    fn parameters(&mut self, items: Option<&mut Vec<ParameterOrReusable>>, base: &str) {
+        for (i, item) in items.into_iter().flatten().enumerate() {
+            if let ParameterOrReusable::Reusable(r) = item {
+                let path = format!("{base}[{i}]");
+                let Some(param) = self.lookup(r, &path, "parameters", |c| c.parameters.as_ref())
+                else {
+                    continue;
+                };
+                let mut param = param.clone();
+                if let Some(value) = &r.value {
+                    param.value = value.clone();
+                }
+                *item = ParameterOrReusable::Parameter(param);
+            }
+        }
+    }
+
+    fn success_actions(&mut self, items: Option<&mut Vec<SuccessActionOrReusable>>, base: &str) {
+        for (i, item) in items.into_iter().flatten().enumerate() {
+            if let SuccessActionOrReusable::Reusable(r) = item {
+                let path = format!("{base}[{i}]");
+                if let Some(action) =
+                    self.lookup(r, &path, "successActions", |c| c.success_actions.as_ref())
+                {
+                    *item = SuccessActionOrReusable::Action(action.clone());
+                }
+            }
+        }
+    }
+
+    fn failure_actions(&mut self, items: Option<&mut Vec<FailureActionOrReusable>>, base: &str) {
+        for (i, item) in items.into_iter().flatten().enumerate() {
+            if let FailureActionOrReusable::Reusable(r) = item {
+                let path = format!("{base}[{i}]");
+                if let Some(action) =
+                    self.lookup(r, &path, "failureActions", |c| c.failure_actions.as_ref())
+                {
+                    *item = FailureActionOrReusable::Action(action.clone());
+                }
+            }
+        }
+    }
+
+    /// The component `reference` names, which must be in the `kind` map. When it is
+    /// not, the error is recorded and `None` returned.
+    fn lookup<T>(
+        &mut self,
+        reusable: &ReusableObject,
+        path: &str,
+        kind: &str,
+        map: impl Fn(&'d Components) -> Option<&'d BTreeMap<String, T>>,
+    ) -> Option<&'d T> {
+        let error = |reason: String| DereferenceError {
+            path: path.to_string(),
+            reference: reusable.reference.clone(),
+            reason,
+        };
+        let prefix = format!("$components.{kind}.");
+        let found = match reusable.reference.trim().strip_prefix(&prefix) {
+            None => Err(error(format!("expected a {prefix}<name> reference"))),
+            Some(name) => self
+                .components
+                .and_then(map)
+                .and_then(|m| m.get(name))
+                .ok_or_else(|| error(format!("no {kind} component named '{name}'"))),
+        };
+        found.map_err(|e| self.errors.push(e)).ok()
+    }
+}
@@ -0,0 +1,465 @@
+//! Semantic differences between two versions of a document, for reviewing workflow
+//! changes. Workflows, steps, parameters, actions and sources are matched by their
+//! ids rather than their position, so reordering alone is reported once as a change
+//! of step order instead of as a cascade of edits.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::types::{
+    ArazzoDocument, Extensions, FailureActionOrReusable, ParameterOrReusable, Step,
+    SuccessActionOrReusable, Workflow,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    fn sign(self) -> char {
+        match self {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Changed => '~',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// What changed within its workflow or step, e.g. `step`,
+    /// `parameter query:limit` or `successCriteria`.
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<JsonValue>,
+}
+
+/// One line per change, e.g. `~ pets.list: parameter query:limit (10 -> 20)`.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.kind.sign())?;
+        match (&self.workflow_id, &self.step_id) {
+            (Some(wf), Some(step)) => write!(f, "{wf}.{step}: ")?,
+            (Some(wf), None) => write!(f, "{wf}: ")?,
+            _ => {}
+        }
+        f.write_str(&self.subject)?;
+        if let (Some(old), Some(new)) = (scalar(&self.old), scalar(&self.new)) {
+            write!(f, " ({old} -> {new})")?;
+        }
+        Ok(())
+    }
+}
+
+fn scalar(value: &Option<JsonValue>) -> Option<String> {
+    match value.as_ref()? {
+        JsonValue::String(s) => Some(s.clone()),
+        v @ (JsonValue::Number(_) | JsonValue::Bool(_) | JsonValue::Null) => Some(v.to_string()),
+        JsonValue::Array(_) | JsonValue::Object(_) => None,
+    }
+}
+
+/// Every change from `old` to `new`, document-level first, then per workflow in the
+/// order of `new`.
+pub fn diff_documents(old: &ArazzoDocument, new: &ArazzoDocument) -> Vec<Change> {
+    let mut d = Differ::default();
+    let doc = Scope::default();
+
+    d.field(doc, "arazzo", Some(&old.arazzo), Some(&new.arazzo));
+    d.field(
+        doc,
+        "info.title",
+        Some(&old.info.title),
+        Some(&new.info.title),
+    );
+    d.field(
+        doc,
+        "info.version",
+        Some(&old.info.version),
+        Some(&new.info.version),
+    );
+    d.field(
+        doc,
+        "info.summary",
+        old.info.summary.as_ref(),
+        new.info.summary.as_ref(),
+    );
+    d.field(
+        doc,
+        "info.description",
+        old.info.description.as_ref(),
+        new.info.description.as_ref(),
+    );
+    d.keyed(
+        doc,
+        "sourceDescription",
+        keyed(&old.source_descriptions, |s| s.name.clone()),
+        keyed(&new.source_descriptions, |s| s.name.clone()),
+    );
+    d.extensions(doc, &old.extensions, &new.extensions);
+
+    for wf in &old.workflows {
+        if !new
+            .workflows
+            .iter()
+            .any(|w| w.workflow_id == wf.workflow_id)
+        {
+            d.push(
+                ChangeKind::Removed,
+                Scope::workflow(wf),
+                "workflow",
+                None,
+                None,
+            );
+        }
+    }
+    for wf in &new.workflows {
+        match old
+            .workflows
+            .iter()
+            .find(|w| w.workflow_id == wf.workflow_id)
+        {
+            Some(prev) => d.workflow(prev, wf),
+            None => d.push(
+                ChangeKind::Added,
+                Scope::workflow(wf),
+                "workflow",
+                None,
+                None,
+            ),
+        }
+    }
+
+    let empty = Default::default();
+    let old_c = old.components.as_ref().unwrap_or(&empty);
+    let new_c = new.components.as_ref().unwrap_or(&empty);
+    d.keyed(
+        doc,
+        "component input",
+        entries(old_c.inputs.iter().flatten()),
+        entries(new_c.inputs.iter().flatten()),
+    );
+    d.keyed(
+        doc,
+        "component parameter",
+        entries(old_c.parameters.iter().flatten()),
+        entries(new_c.parameters.iter().flatten()),
+    );
+    d.keyed(
+        doc,
+        "component successAction",
+        entries(old_c.success_actions.iter().flatten()),
+        entries(new_c.success_actions.iter().flatten()),
+    );
+    d.keyed(
+        doc,
+        "component failureAction",
+        entries(old_c.failure_actions.iter().flatten()),
+        entries(new_c.failure_actions.iter().flatten()),
+    );
+    d.changes
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Scope<'a> {
+    workflow_id: Option<&'a str>,
+    step_id: Option<&'a str>,
+}
+
+impl<'a> Scope<'a> {
+    fn workflow(wf: &'a Workflow) -> Self {
+        Self {
+            workflow_id: Some(&wf.workflow_id),
+            step_id: None,
+        }
+    }
+
+    fn step(self, step: &'a Step) -> Self {
+        Self {
+            step_id: Some(&step.step_id),
+            ..self
+        }
+    }
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn push(
+        &mut self,
+        kind: ChangeKind,
+        scope: Scope<'_>,
+        subject: &str,
+        old: Option<JsonValue>,
+        new: Option<JsonValue>,
+    ) {
+        self.changes.push(Change {
+            kind,
+            subject: subject.to_string(),
+            workflow_id: scope.workflow_id.map(String::from),
+            step_id: scope.step_id.map(String::from),
+            old,
+            new,
+        });
+    }
+
+    fn field<T: Serialize>(
+        &mut self,
+        scope: Scope<'_>,
+        subject: &str,
+        old: Option<&T>,
+        new: Option<&T>,
+    ) {
+        let (old, new) = (old.map(to_value), new.map(to_value));
+        let kind = match (&old, &new) {
+            (None, Some(_)) => ChangeKind::Added,
+            (Some(_), None) => ChangeKind::Removed,
+            (Some(a), Some(b)) if a != b => ChangeKind::Changed,
+            _ => return,
+        };
+        self.push(kind, scope, subject, old, new);
+    }
+
+    /// Compare two id-keyed lists; removed entries come first, then the entries of
+    /// `new` in order.
+    fn keyed(
+        &mut self,
+        scope: Scope<'_>,
+        what: &str,
+        old: Vec<(String, JsonValue)>,
+        new: Vec<(String, JsonValue)>,
+    ) {
+        for (key, value) in &old {
+            if !new.iter().any(|(k, _)| k == key) {
+                let subject = format!("{what} {key}");
+                self.push(
+                    ChangeKind::Removed,
+                    scope,
+                    &subject,
+                    Some(value.clone()),
+                    None,
+                );
+            }
+        }
+        for (key, value) in new {
+            let subject = format!("{what} {key}");
+            match old.iter().find(|(k, _)| *k == key) {
+                Some((_, prev)) if *prev == value => {}
+                Some((_, prev)) => {
+                    self.push(
+                        ChangeKind::Changed,
+                        scope,
+                        &subject,
+                        Some(prev.clone()),
+                        Some(value),
+                    );
+                }
+                None => self.push(ChangeKind::Added, scope, &subject, None, Some(value)),
+            }
+        }
+    }
+
+    fn extensions(&mut self, scope: Scope<'_>, old: &Extensions, new: &Extensions) {
+        self.keyed(scope, "extension", entries(old), entries(new));
+    }
+
+    fn workflow(&mut self, old: &Workflow, new: &Workflow) {
+        let scope = Scope::workflow(new);
+        self.field(scope, "summary", old.summary.as_ref(), new.summary.as_ref());
+        self.field(
+            scope,
+            "description",
+            old.description.as_ref(),
+            new.description.as_ref(),
+        );
+        self.field(scope, "inputs", old.inputs.as_ref(), new.inputs.as_ref());
+        self.field(
+            scope,
+            "dependsOn",
+            old.depends_on.as_ref(),
+            new.depends_on.as_ref(),
+        );
+        self.keyed(
+            scope,
+            "parameter",
+            parameters(old.parameters.as_deref()),
+            parameters(new.parameters.as_deref()),
+        );
+        self.keyed(
+            scope,
+            "successAction",
+            success_actions(old.success_actions.as_deref()),
+            success_actions(new.success_actions.as_deref()),
+        );
+        self.keyed(
+            scope,
+            "failureAction",
+            failure_actions(old.failure_actions.as_deref()),
+            failure_actions(new.failure_actions.as_deref()),
+        );
+        self.keyed(
+            scope,
+            "output",
+            entries(old.outputs.iter().flatten()),
+            entries(new.outputs.iter().flatten()),
+        );
+        self.extensions(scope, &old.extensions, &new.extensions);
+
+        for step in &old.steps {
+            if !new.steps.iter().any(|s| s.step_id == step.step_id) {
+                self.push(ChangeKind::Removed, scope.step(step), "step", None, None);
+            }
+        }
+        for step in &new.steps {
+            match old.steps.iter().find(|s| s.step_id == step.step_id) {
+                Some(prev) => self.step(scope.step(step), prev, step),
+                None => self.push(ChangeKind::Added, scope.step(step), "step", None, None),
+            }
+        }
+
+        // Steps kept in both versions but in a different relative order.
+        let kept = |steps: &[Step], other: &[Step]| -> Vec<String> {
+            steps
+                .iter()
+                .filter(|s| other.iter().any(|o| o.step_id == s.step_id))
+                .map(|s| s.step_id.clone())
+                .collect()
+        };
+        let (old_order, new_order) = (kept(&old.steps, &new.steps), kept(&new.steps, &old.steps));
+        if old_order != new_order {
+            self.push(
+                ChangeKind::Changed,
+                scope,
+                "step order",
+                Some(to_value(&old_order)),
+                Some(to_value(&new_order)),
+            );
+        }
+    }
+
+    fn step(&mut self, scope: Scope<'_>, old: &Step, new: &Step) {
+        self.field(
+            scope,
+            "description",
+            old.description.as_ref(),
+            new.description.as_ref(),
+        );
+        self.field(
+            scope,
+            "operationId",
+            old.operation_id.as_ref(),
+            new.operation_id.as_ref(),
+        );
+        self.field(
+            scope,
+            "operationPath",
+            old.operation_path.as_ref(),
+            new.operation_path.as_ref(),
+        );
+        self.field(
+            scope,
+            "workflowId",
+            old.workflow_id.as_ref(),
+            new.workflow_id.as_ref(),
+        );
+        self.keyed(
+            scope,
+            "parameter",
+            parameters(old.parameters.as_deref()),
+            parameters(new.parameters.as_deref()),
+        );
+        self.field(
+            scope,
+            "requestBody",
+            old.request_body.as_ref(),
+            new.request_body.as_ref(),
+        );
+        self.field(
+            scope,
+            "successCriteria",
+            old.success_criteria.as_ref(),
+            new.success_criteria.as_ref(),
+        );
+        self.keyed(
+            scope,
+            "onSuccess",
+            success_actions(old.on_success.as_deref()),
+            success_actions(new.on_success.as_deref()),
+        );
+        self.keyed(
+            scope,
+            "onFailure",
+            failure_actions(old.on_failure.as_deref()),
+            failure_actions(new.on_failure.as_deref()),
+        );
+        self.keyed(
+            scope,
+            "output",
+            entries(old.outputs.iter().flatten()),
+            entries(new.outputs.iter().flatten()),
+        );
+        self.extensions(scope, &old.extensions, &new.extensions);
+    }
+}
+
+fn to_value<T: Serialize + ?Sized>(value: &T) -> JsonValue {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+fn keyed<T: Serialize>(items: &[T], key: impl Fn(&T) -> String) -> Vec<(String, JsonValue)> {
+    items.iter().map(|i| (key(i), to_value(i))).collect()
+}
+
+fn entries<'a, T: Serialize + 'a>(
+    map: impl IntoIterator<Item = (&'a String, &'a T)>,
+) -> Vec<(String, JsonValue)> {
+    map.into_iter()
+        .map(|(k, v)| (k.clone(), to_value(v)))
+        .collect()
+}
+
+/// Parameters are identified by location and name (`query:limit`), reusable ones by
+/// their reference.
+fn parameters(params: Option<&[ParameterOrReusable]>) -> Vec<(String, JsonValue)> {
+    keyed(params.unwrap_or_default(), |p| match p {
+        ParameterOrReusable::Parameter(p) => match &p.r#in {
+            Some(location) => format!(
+                "{}:{}",
+                to_value(location).as_str().unwrap_or_default(),
+                p.name
+            ),
+            None => p.name.clone(),
+        },
+        ParameterOrReusable::Reusable(r) => r.reference.clone(),
+    })
+}
+
+fn success_actions(actions: Option<&[SuccessActionOrReusable]>) -> Vec<(String, JsonValue)> {
+    keyed(actions.unwrap_or_default(), |a| match a {
+        SuccessActionOrReusable::Action(a) => a.name.clone(),
+        SuccessActionOrReusable::Reusable(r) => r.reference.clone(),
+    })
+}
+
+fn failure_actions(actions: Option<&[FailureActionOrReusable]>) -> Vec<(String, JsonValue)> {
+    keyed(actions.unwrap_or_default(), |a| match a {
+        FailureActionOrReusable::Action(a) => a.name.clone(),
+        FailureActionOrReusable::Reusable(r) => r.reference.clone(),
+    })
+}
@@ -0,0 +1,515 @@
+//! Comment-preserving edits to YAML documents. A [`YamlDocument`] keeps the
+//! original text and rewrites only the nodes an edit touches, so comments, anchors,
+//! quoting and blank lines elsewhere survive, which a parse and
+//! [`ArazzoDocument::to_yaml`](crate::ArazzoDocument::to_yaml) round trip loses.
+//!
+//! ```
+//! use arazzo_core::YamlDocument;
+//!
+//! let mut doc = YamlDocument::parse(
+//!     "sourceDescriptions:\n  # staging until launch\n  - name: pets\n    url: https://staging.example.com/openapi.yaml\n",
+//! )?;
+//! doc.set(
+//!     "$.sourceDescriptions[?(@.name == 'pets')].url",
+//!     &"https://example.com/openapi.yaml".into(),
+//! )?;
+//! assert_eq!(
+//!     doc.as_str(),
+//!     "sourceDescriptions:\n  # staging until launch\n  - name: pets\n    url: https://example.com/openapi.yaml\n",
+//! );
+//! # Ok::<(), arazzo_core::EditError>(())
+//! ```
+//!
+//! Targets use the JSONPath subset of overlay targets. Block mappings and
+//! sequences are edited in place; a `{...}` or `[...]` collection is rewritten as a
+//! whole when something inside it changes.
+
+mod tree;
+
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use crate::error::ParseError;
+use crate::overlay::target::{Key, Target};
+use crate::parser::{parse_document_str, parse_value, DocumentFormat, ParsedDocument};
+
+use self::tree::{line_start, Member, Node, NodeKind};
+
+#[derive(Debug, Error)]
+pub enum EditError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// Valid YAML in a shape the editor does not handle, e.g. explicit `?` keys or
+    /// several documents in one file.
+    #[error("unsupported YAML layout: {0}")]
+    Layout(String),
+    #[error("invalid target '{target}': {reason}")]
+    Target { target: String, reason: String },
+    #[error("target '{0}' selects nothing")]
+    NoMatch(String),
+    #[error("cannot edit {path}: {reason}")]
+    Edit { path: String, reason: String },
+}
+
+/// The text of a YAML document, edited in place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YamlDocument {
+    text: String,
+}
+
+impl YamlDocument {
+    /// Fails unless `text` is a single YAML document whose top level is a block
+    /// mapping.
+    pub fn parse(text: &str) -> Result<Self, EditError> {
+        let doc = Self {
+            text: text.to_string(),
+        };
+        doc.value()?;
+        match doc.tree()?.kind {
+            NodeKind::Mapping(_) => Ok(doc),
+            _ => Err(EditError::Layout(
+                "the top level must be a block mapping".to_string(),
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// The current text parsed as an Arazzo document.
+    pub fn document(&self) -> Result<ParsedDocument, ParseError> {
+        parse_document_str(&self.text, DocumentFormat::Yaml)
+    }
+
+    /// Set every node `target` selects to `value`. When nothing matches, a target
+    /// ending in a child name adds that child to each mapping the rest of the target
+    /// selects, and one ending in `[n]` appends to each sequence of length `n`.
+    /// Returns the number of nodes set.
+    pub fn set(&mut self, target: &str, value: &JsonValue) -> Result<usize, EditError> {
+        let parsed = parse_target(target)?;
+        let root = self.value()?;
+        let mut paths = parsed.select(&root);
+        if paths.is_empty() {
+            if let Some((parent, key)) = parsed.split_child() {
+                paths = parent
+                    .select(&root)
+                    .into_iter()
+                    .filter(|path| match (node_at(&root, path), &key) {
+                        (Some(JsonValue::Object(_)), Key::Name(_)) => true,
+                        (Some(JsonValue::Array(items)), Key::Index(i)) => items.len() == *i,
+                        _ => false,
+                    })
+                    .map(|mut path| {
+                        path.push(key.clone());
+                        path
+                    })
+                    .collect();
+            }
+        }
+        if paths.is_empty() {
+            return Err(EditError::NoMatch(target.to_string()));
+        }
+        for path in &paths {
+            self.set_path(path, value)?;
+        }
+        Ok(paths.len())
+    }
+
+    /// Remove every node `target` selects. Returns the number of nodes removed.
+    pub fn remove(&mut self, target: &str) -> Result<usize, EditError> {
+        let parsed = parse_target(target)?;
+        let mut paths = parsed.select(&self.value()?);
+        if paths.is_empty() {
+            return Err(EditError::NoMatch(target.to_string()));
+        }
+        // Later siblings first, so removing one does not shift the indices of the rest.
+        paths.sort();
+        for path in paths.iter().rev() {
+            self.remove_path(path)?;
+        }
+        Ok(paths.len())
+    }
+
+    fn value(&self) -> Result<JsonValue, ParseError> {
+        parse_value(&self.text)
+    }
+
+    fn tree(&self) -> Result<Node, EditError> {
+        tree::parse(&self.text).map_err(EditError::Layout)
+    }
+
+    fn set_path(&mut self, path: &[Key], value: &JsonValue) -> Result<(), EditError> {
+        let tree = self.tree()?;
+        let reached = descend(&tree, path);
+        let rest = &path[reached.depth..];
+        let Some(first) = rest.first() else {
+            self.replace(reached.node, reached.owner, value);
+            return Ok(());
+        };
+        match (&reached.node.kind, first) {
+            (NodeKind::Flow, _) => {
+                self.rewrite_flow(reached.node, path, |v| set_in(v, rest, value.clone()))
+            }
+            (NodeKind::Mapping(members), Key::Name(name)) => {
+                let value = nest(&rest[1..], value.clone()).ok_or_else(|| missing(path))?;
+                self.append(members, Some(name), &value);
+                Ok(())
+            }
+            (NodeKind::Sequence(members), Key::Index(i))
+                if *i == members.len() && rest.len() == 1 =>
+            {
+                self.append(members, None, value);
+                Ok(())
+            }
+            (NodeKind::Scalar, _) if reached.node.span.is_empty() => {
+                let value = nest(rest, value.clone()).ok_or_else(|| missing(path))?;
+                self.replace(reached.node, reached.owner, &value);
+                Ok(())
+            }
+            _ => Err(missing(path)),
+        }
+    }
+
+    fn remove_path(&mut self, path: &[Key]) -> Result<(), EditError> {
+        let Some((last, parent)) = path.split_last() else {
+            return Err(EditError::Edit {
+                path: "$".to_string(),
+                reason: "cannot remove the document root".to_string(),
+            });
+        };
+        let tree = self.tree()?;
+        let reached = descend(&tree, parent);
+        if reached.depth < parent.len() {
+            return match reached.node.kind {
+                NodeKind::Flow => {
+                    let rest = &path[reached.depth..];
+                    self.rewrite_flow(reached.node, path, |v| remove_in(v, rest))
+                }
+                _ => Err(missing(path)),
+            };
+        }
+        let (members, index) = match (&reached.node.kind, last) {
+            (NodeKind::Mapping(members), Key::Name(name)) => (
+                members,
+                members
+                    .iter()
+                    .position(|m| m.key.as_deref() == Some(name.as_str())),
+            ),
+            (NodeKind::Sequence(members), Key::Index(i)) => {
+                (members, Some(*i).filter(|i| *i < members.len()))
+            }
+            (NodeKind::Flow, _) => {
+                return self.rewrite_flow(reached.node, path, |v| {
+                    remove_in(v, std::slice::from_ref(last))
+                })
+            }
+            _ => return Err(missing(path)),
+        };
+        let Some(index) = index else {
+            return Err(missing(path));
+        };
+
+        if members.len() == 1 {
+            let empty = match reached.node.kind {
+                NodeKind::Mapping(_) => JsonValue::Object(Default::default()),
+                _ => JsonValue::Array(Vec::new()),
+            };
+            self.replace(reached.node, reached.owner, &empty);
+            return Ok(());
+        }
+        let member = &members[index];
+        let line = line_start(&self.text, member.start);
+        if self.text[line..member.start].trim().is_empty() {
+            self.text.replace_range(line..member.end, "");
+        } else {
+            // The first member shares its line with the parent's `-`: pull the next
+            // member up onto that line.
+            self.text.replace_range(member.start..members[1].start, "");
+        }
+        Ok(())
+    }
+
+    /// Replace `node`, the value of `owner` (the root when `None`), with `value`.
+    fn replace(&mut self, node: &Node, owner: Option<&Member>, value: &JsonValue) {
+        let Some(owner) = owner else {
+            let column = node.span.start - line_start(&self.text, node.span.start);
+            let text = render_block(value, column);
+            self.text
+                .replace_range(node.span.clone(), text.trim_start());
+            return;
+        };
+        let column = owner.start - line_start(&self.text, owner.start);
+        // Scalars and flow collections are swapped where they stand, which keeps a
+        // comment after them on the line; an empty `[]` or `{}` gives way to a block.
+        let in_place = match node.kind {
+            NodeKind::Scalar => !node.span.is_empty(),
+            NodeKind::Flow => {
+                let source = &self.text[node.span.clone()];
+                is_inline(value) || source.chars().filter(|c| !c.is_whitespace()).count() > 2
+            }
+            NodeKind::Mapping(_) | NodeKind::Sequence(_) => false,
+        };
+        let whole = owner.value_start..node.span.end;
+        let (range, text) = if in_place && is_inline(value) {
+            let quote = self.text[node.span.clone()].chars().next();
+            (node.span.clone(), render_scalar(value, quote, column))
+        } else if in_place && matches!(node.kind, NodeKind::Flow) {
+            (node.span.clone(), render_flow(value))
+        } else if is_inline(value) {
+            (whole, format!(" {}", render_inline(value, column)))
+        } else if owner.key.is_some() {
+            (whole, format!("\n{}", render_block(value, column + 2)))
+        } else {
+            let block = render_block(value, column + 2);
+            (whole, format!(" {}", block.trim_start()))
+        };
+        self.text.replace_range(range, &text);
+    }
+
+    /// Add a member after the last of `members`: an entry named `key`, or a
+    /// sequence item when `key` is `None`.
+    fn append(&mut self, members: &[Member], key: Option<&str>, value: &JsonValue) {
+        let column = members[0].start - line_start(&self.text, members[0].start);
+        let at = members.last().expect("block collections are not empty").end;
+        let mut text = String::new();
+        let newline = !self.text[..at].ends_with('\n');
+        if newline {
+            text.push('\n');
+        }
+        text.push_str(&" ".repeat(column));
+        match key {
+            Some(key) => {
+                text.push_str(&render_inline(&JsonValue::String(key.to_string()), column));
+                text.push(':');
+                if is_inline(value) {
+                    text.push(' ');
+                    text.push_str(&render_inline(value, column));
+                } else {
+                    text.push('\n');
+                    text.push_str(&render_block(value, column + 2));
+                }
+            }
+            None => {
+                text.push_str("- ");
+                text.push_str(render_block(value, column + 2).trim_start());
+            }
+        }
+        if !newline {
+            text.push('\n');
+        }
+        self.text.insert_str(at, &text);
+    }
+
+    /// Re-render the flow collection `node` after `edit` changes its value.
+    fn rewrite_flow(
+        &mut self,
+        node: &Node,
+        path: &[Key],
+        edit: impl FnOnce(&mut JsonValue) -> bool,
+    ) -> Result<(), EditError> {
+        let mut value: JsonValue = serde_yaml::from_str(&self.text[node.span.clone()])
+            .map_err(|e| EditError::Layout(e.to_string()))?;
+        if !edit(&mut value) {
+            return Err(missing(path));
+        }
+        self.text
+            .replace_range(node.span.clone(), &render_flow(&value));
+        Ok(())
+    }
+}
+
+fn parse_target(target: &str) -> Result<Target, EditError> {
+    Target::parse(target).map_err(|reason| EditError::Target {
+        target: target.to_string(),
+        reason,
+    })
+}
+
+fn missing(path: &[Key]) -> EditError {
+    EditError::Edit {
+        path: display(path),
+        reason: "no such node in the document text".to_string(),
+    }
+}
+
+fn display(path: &[Key]) -> String {
+    path.iter().fold("$".to_string(), |out, key| match key {
+        Key::Name(name) => format!("{out}.{name}"),
+        Key::Index(i) => format!("{out}[{i}]"),
+    })
+}
+
+struct Reached<'t> {
+    node: &'t Node,
+    /// The member `node` is the value of; `None` for the root.
+    owner: Option<&'t Member>,
+    /// How many keys of the path led to `node`.
+    depth: usize,
+}
+
+/// Follow `path` from `root` through block collections as far as it goes.
+fn descend<'t>(root: &'t Node, path: &[Key]) -> Reached<'t> {
+    let mut reached = Reached {
+        node: root,
+        owner: None,
+        depth: 0,
+    };
+    for key in path {
+        let member = match (&reached.node.kind, key) {
+            (NodeKind::Mapping(members), Key::Name(name)) => members
+                .iter()
+                .find(|m| m.key.as_deref() == Some(name.as_str())),
+            (NodeKind::Sequence(members), Key::Index(i)) => members.get(*i),
+            _ => None,
+        };
+        let Some(member) = member else { break };
+        reached = Reached {
+            node: &member.value,
+            owner: Some(member),
+            depth: reached.depth + 1,
+        };
+    }
+    reached
+}
+
+fn node_at<'v>(root: &'v JsonValue, path: &[Key]) -> Option<&'v JsonValue> {
+    path.iter().try_fold(root, |node, key| match key {
+        Key::Name(name) => node.get(name),
+        Key::Index(i) => node.get(i),
+    })
+}
+
+/// `value` under the keys of `path`, for adding a path that does not exist yet.
+fn nest(path: &[Key], value: JsonValue) -> Option<JsonValue> {
+    path.iter().rev().try_fold(value, |value, key| match key {
+        Key::Name(name) => Some(JsonValue::Object(
+            [(name.clone(), value)].into_iter().collect(),
+        )),
+        Key::Index(0) => Some(JsonValue::Array(vec![value])),
+        Key::Index(_) => None,
+    })
+}
+
+fn set_in(root: &mut JsonValue, path: &[Key], value: JsonValue) -> bool {
+    let Some((last, parent)) = path.split_last() else {
+        *root = value;
+        return true;
+    };
+    let parent = parent.iter().try_fold(root, |node, key| match key {
+        Key::Name(name) => node.get_mut(name),
+        Key::Index(i) => node.get_mut(i),
+    });
+    match (parent, last) {
+        (Some(JsonValue::Object(map)), Key::Name(name)) => {
+            map.insert(name.clone(), value);
+            true
+        }
+        (Some(JsonValue::Array(items)), Key::Index(i)) if *i < items.len() => {
+            items[*i] = value;
+            true
+        }
+        (Some(JsonValue::Array(items)), Key::Index(i)) if *i == items.len() => {
+            items.push(value);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn remove_in(root: &mut JsonValue, path: &[Key]) -> bool {
+    let Some((last, parent)) = path.split_last() else {
+        return false;
+    };
+    let parent = parent.iter().try_fold(root, |node, key| match key {
+        Key::Name(name) => node.get_mut(name),
+        Key::Index(i) => node.get_mut(i),
+    });
+    match (parent, last) {
+        (Some(JsonValue::Object(map)), Key::Name(name)) => map.remove(name).is_some(),
+        (Some(JsonValue::Array(items)), Key::Index(i)) if *i < items.len() => {
+            items.remove(*i);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Scalars and empty collections, which fit on the line of their key or `-`.
+fn is_inline(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Object(map) => map.is_empty(),
+        JsonValue::Array(items) => items.is_empty(),
+        _ => true,
+    }
+}
+
+/// `value` as YAML with every line but the first indented by `indent`.
+fn render_inline(value: &JsonValue, indent: usize) -> String {
+    let text = serde_yaml::to_string(value).unwrap_or_default();
+    let mut lines = text.trim_end_matches('\n').lines();
+    let first = lines.next().unwrap_or_default().to_string();
+    lines.fold(first, |mut out, line| {
+        out.push('\n');
+        if !line.is_empty() {
+            out.push_str(&" ".repeat(indent));
+            out.push_str(line);
+        }
+        out
+    })
+}
+
+/// Like [`render_inline`], but a single-line string keeps the quote style of the
+/// scalar it replaces.
+fn render_scalar(value: &JsonValue, quote: Option<char>, indent: usize) -> String {
+    match (value, quote) {
+        (JsonValue::String(s), Some('"')) if !s.contains('\n') => {
+            serde_json::to_string(s).unwrap_or_default()
+        }
+        (JsonValue::String(s), Some('\'')) if !s.contains('\n') => {
+            format!("'{}'", s.replace('\'', "''"))
+        }
+        _ => render_inline(value, indent),
+    }
+}
+
+/// `value` as block YAML with every line indented by `indent`.
+fn render_block(value: &JsonValue, indent: usize) -> String {
+    format!("{}{}", " ".repeat(indent), render_inline(value, indent))
+}
+
+/// `value` as a single-line flow collection or scalar.
+fn render_flow(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Object(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{}: {}",
+                        render_flow(&JsonValue::String(k.clone())),
+                        render_flow(v)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        JsonValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(render_flow).collect();
+            format!("[{}]", items.join(", "))
+        }
+        JsonValue::String(s) => {
+            let plain = render_inline(value, 0);
+            if plain.contains(['\n', ',', '[', ']', '{', '}']) {
+                serde_json::to_string(s).unwrap_or_default()
+            } else {
+                plain
+            }
+        }
+        _ => render_inline(value, 0),
+    }
+}
@@ -0,0 +1,392 @@
+//! A lossless view of block-style YAML. Every mapping entry, sequence item and
+//! scalar is located by byte offsets into the original text; nothing is copied
+//! except mapping keys.
+
+use std::ops::Range;
+
+#[derive(Debug)]
+pub(super) struct Node {
+    pub(super) kind: NodeKind,
+    /// The node's text without trailing comments. Empty for a missing value, at the
+    /// position just past its `:` or `-`.
+    pub(super) span: Range<usize>,
+}
+
+#[derive(Debug)]
+pub(super) enum NodeKind {
+    Mapping(Vec<Member>),
+    Sequence(Vec<Member>),
+    /// A plain, quoted or block scalar, or an alias.
+    Scalar,
+    /// A `{...}` or `[...]` collection, which is only ever edited as a whole.
+    Flow,
+}
+
+#[derive(Debug)]
+pub(super) struct Member {
+    /// The mapping key; `None` for a sequence item.
+    pub(super) key: Option<String>,
+    /// Offset of the key or the `-`.
+    pub(super) start: usize,
+    /// Just past the `:` or `-` and any anchor or tag on the value.
+    pub(super) value_start: usize,
+    /// Just past the newline that ends the member's last line.
+    pub(super) end: usize,
+    pub(super) value: Node,
+}
+
+impl Node {
+    fn empty(at: usize) -> Self {
+        Node {
+            kind: NodeKind::Scalar,
+            span: at..at,
+        }
+    }
+}
+
+/// Offset of the start of the line containing `offset`.
+pub(super) fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Build the tree of a single-document YAML text. Errors name the line of a
+/// construct this view does not support, such as explicit `?` keys or a second
+/// document.
+pub(super) fn parse(text: &str) -> Result<Node, String> {
+    let parser = Parser {
+        text,
+        lines: split_lines(text),
+    };
+    let mut first = parser.next_content(0);
+    while let Some(li) = first {
+        let rest = parser.rest(li);
+        if !(rest.starts_with('%') || rest == "---") {
+            break;
+        }
+        first = parser.next_content(li + 1);
+    }
+    let Some(first) = first else {
+        return Ok(Node::empty(text.len()));
+    };
+    let (root, last) = parser.node(first, parser.lines[first].indent, -1)?;
+    match parser.next_content(last + 1) {
+        Some(li) if parser.rest(li) != "..." => {
+            Err(parser.error(li, "expected the end of the document"))
+        }
+        _ => Ok(root),
+    }
+}
+
+struct Line {
+    start: usize,
+    /// End of the line's text, before `\r\n` or `\n`.
+    end: usize,
+    /// Start of the next line.
+    next: usize,
+    indent: usize,
+    /// Neither blank nor only a comment.
+    content: bool,
+}
+
+fn split_lines(text: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let (end, next) = match text[start..].find('\n') {
+            Some(i) => (start + i, start + i + 1),
+            None => (text.len(), text.len()),
+        };
+        let end = if text[start..end].ends_with('\r') {
+            end - 1
+        } else {
+            end
+        };
+        let line = &text[start..end];
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let body = line.trim_start();
+        lines.push(Line {
+            start,
+            end,
+            next,
+            indent,
+            content: !(body.is_empty() || body.starts_with('#')),
+        });
+        start = next;
+    }
+    lines
+}
+
+struct Parser<'t> {
+    text: &'t str,
+    lines: Vec<Line>,
+}
+
+impl Parser<'_> {
+    fn error(&self, li: usize, message: &str) -> String {
+        format!("line {}: {message}", li + 1)
+    }
+
+    fn next_content(&self, from: usize) -> Option<usize> {
+        (from..self.lines.len()).find(|&li| self.lines[li].content)
+    }
+
+    /// The text of line `li` after its indentation.
+    fn rest(&self, li: usize) -> &str {
+        let line = &self.lines[li];
+        &self.text[line.start + line.indent..line.end]
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        self.lines.partition_point(|l| l.start <= offset) - 1
+    }
+
+    /// The node starting at column `col` of line `li`, whose lines must be indented
+    /// deeper than `parent`. Returns it with the index of its last line.
+    fn node(&self, li: usize, col: usize, parent: isize) -> Result<(Node, usize), String> {
+        let line = &self.lines[li];
+        let rest = &self.text[line.start + col..line.end];
+        if is_item(rest) {
+            self.block(li, col, false)
+        } else if mapping_key(rest).is_some() {
+            self.block(li, col, true)
+        } else {
+            self.inline(li, line.start + col, parent)
+        }
+    }
+
+    /// A block mapping or sequence whose first member starts at column `col` of
+    /// line `li`; later members start their own lines at that indentation.
+    fn block(&self, mut li: usize, col: usize, mapping: bool) -> Result<(Node, usize), String> {
+        let mut members = Vec::new();
+        let mut at = self.lines[li].start + col;
+        let mut last_line;
+        loop {
+            let line = &self.lines[li];
+            let rest = &self.text[at..line.end];
+            let (key, after) = if mapping {
+                let (key, colon) =
+                    mapping_key(rest).ok_or_else(|| self.error(li, "expected a mapping key"))?;
+                (Some(key), at + colon + 1)
+            } else if is_item(rest) {
+                (None, at + 1)
+            } else {
+                return Err(self.error(li, "expected a sequence item"));
+            };
+            let (value_start, value, last) = self.value(li, after, col, mapping)?;
+            members.push(Member {
+                key,
+                start: at,
+                value_start,
+                end: self.lines[last].next,
+                value,
+            });
+            last_line = last;
+            match self.next_content(last + 1) {
+                Some(next)
+                    if self.lines[next].indent == col && (mapping || is_item(self.rest(next))) =>
+                {
+                    li = next;
+                    at = self.lines[next].start + col;
+                }
+                Some(next) if self.lines[next].indent > col => {
+                    return Err(self.error(next, "unexpected indentation"));
+                }
+                _ => break,
+            }
+        }
+        let start = members[0].start;
+        let end = members.last().expect("at least one member").value.span.end;
+        let kind = if mapping {
+            NodeKind::Mapping(members)
+        } else {
+            NodeKind::Sequence(members)
+        };
+        Ok((
+            Node {
+                kind,
+                span: start..end,
+            },
+            last_line,
+        ))
+    }
+
+    /// The value of a member at column `col` whose `:` or `-` ends at `after`.
+    fn value(
+        &self,
+        li: usize,
+        after: usize,
+        col: usize,
+        mapping: bool,
+    ) -> Result<(usize, Node, usize), String> {
+        let end = self.lines[li].end;
+        let skip =
+            |pos: usize| pos + self.text[pos..end].len() - self.text[pos..end].trim_start().len();
+        let mut value_start = after;
+        let mut pos = skip(after);
+        while self.text[pos..end].starts_with(['&', '!']) {
+            value_start = self.text[pos..end]
+                .find([' ', '\t'])
+                .map_or(end, |i| pos + i);
+            pos = skip(value_start);
+        }
+
+        let rest = &self.text[pos..end];
+        if rest.is_empty() || rest.starts_with('#') {
+            let nested = self.next_content(li + 1).filter(|&next| {
+                let indent = self.lines[next].indent;
+                indent > col || (mapping && indent == col && is_item(self.rest(next)))
+            });
+            return match nested {
+                Some(next) => {
+                    let (node, last) = self.node(next, self.lines[next].indent, col as isize)?;
+                    Ok((value_start, node, last))
+                }
+                None => Ok((value_start, Node::empty(value_start), li)),
+            };
+        }
+        let (node, last) = self.node(li, pos - self.lines[li].start, col as isize)?;
+        Ok((value_start, node, last))
+    }
+
+    /// A scalar or flow collection starting at `at` on line `li`.
+    fn inline(&self, li: usize, at: usize, parent: isize) -> Result<(Node, usize), String> {
+        let line = &self.lines[li];
+        let rest = &self.text[at..line.end];
+        let deeper = |l: &Line| l.indent as isize > parent;
+
+        let (kind, end) = match rest.as_bytes()[0] {
+            b'|' | b'>' => {
+                let mut last = li;
+                for (n, l) in self.lines.iter().enumerate().skip(li + 1) {
+                    if self.text[l.start..l.end].trim().is_empty() {
+                        continue;
+                    }
+                    if !deeper(l) {
+                        break;
+                    }
+                    last = n;
+                }
+                (NodeKind::Scalar, self.lines[last].end)
+            }
+            b'[' | b'{' => {
+                let end = flow_end(self.text, at)
+                    .ok_or_else(|| self.error(li, "unclosed flow collection"))?;
+                (NodeKind::Flow, end)
+            }
+            b'"' | b'\'' => {
+                let end = quoted_end(&self.text[at..])
+                    .ok_or_else(|| self.error(li, "unclosed quoted scalar"))?;
+                (NodeKind::Scalar, at + end)
+            }
+            _ => {
+                let mut end = at + plain_len(rest);
+                for l in &self.lines[li + 1..] {
+                    let body = self.text[l.start..l.end].trim();
+                    if body.is_empty() {
+                        continue;
+                    }
+                    if body.starts_with('#') || !deeper(l) {
+                        break;
+                    }
+                    end = l.start + l.indent + plain_len(&self.text[l.start + l.indent..l.end]);
+                }
+                (NodeKind::Scalar, end)
+            }
+        };
+        Ok((
+            Node {
+                kind,
+                span: at..end,
+            },
+            self.line_of(end.max(1) - 1),
+        ))
+    }
+}
+
+fn is_item(rest: &str) -> bool {
+    rest == "-" || rest.starts_with("- ") || rest.starts_with("-\t")
+}
+
+/// The key of a `key: value` line and the offset of its `:`.
+fn mapping_key(rest: &str) -> Option<(String, usize)> {
+    let ends_key = |after: &str| after.is_empty() || after.starts_with([' ', '\t']);
+    if rest.starts_with(['"', '\'']) {
+        let close = quoted_end(rest)?;
+        let after = rest[close..].trim_start();
+        let colon = rest.len() - after.len();
+        if !(after.starts_with(':') && ends_key(&after[1..])) {
+            return None;
+        }
+        let key = serde_yaml::from_str(&rest[..close]).ok()?;
+        return Some((key, colon));
+    }
+    if rest.starts_with(['[', '{', '&', '*', '!', '|', '>', '?', '#', '%', '@', '`']) {
+        return None;
+    }
+    for (i, c) in rest.char_indices() {
+        match c {
+            ':' if ends_key(&rest[i + 1..]) => return Some((rest[..i].trim_end().to_string(), i)),
+            '#' if rest[..i].ends_with([' ', '\t']) => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Length of a plain scalar line, without a trailing comment or whitespace.
+fn plain_len(rest: &str) -> usize {
+    let body = rest
+        .match_indices('#')
+        .find(|&(i, _)| rest[..i].ends_with([' ', '\t']))
+        .map_or(rest, |(i, _)| &rest[..i]);
+    body.trim_end().len()
+}
+
+/// Offset just past the quote closing the quoted scalar `s` starts with.
+fn quoted_end(s: &str) -> Option<usize> {
+    let quote = s.chars().next()?;
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            // `''` is an escaped quote inside a single-quoted scalar.
+            '\'' if quote == '\'' && chars.peek().is_some_and(|&(_, n)| n == '\'') => {
+                chars.next();
+            }
+            c if c == quote => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Offset just past the bracket closing the flow collection at `at`.
+fn flow_end(text: &str, at: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut pos = at;
+    while pos < text.len() {
+        let c = text[pos..].chars().next()?;
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            '"' | '\'' => {
+                pos += quoted_end(&text[pos..])?;
+                continue;
+            }
+            '#' if text[..pos].ends_with([' ', '\t', '\n']) => {
+                pos = text[pos..].find('\n').map_or(text.len(), |i| pos + i);
+                continue;
+            }
+            _ => {}
+        }
+        pos += c.len_utf8();
+    }
+    None
+}
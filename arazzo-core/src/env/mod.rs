@@ -0,0 +1,134 @@
+//! `${VAR}` placeholders in source description URLs and parameter values, expanded
+//! from the environment when a document is loaded. Expansion is opt-in (see
+//! [`ParseOptions::interpolate_env`](crate::ParseOptions::interpolate_env)); `$${VAR}`
+//! stays a literal `${VAR}`.
+
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::types::{ArazzoDocument, ParameterOrReusable};
+
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$?\$\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("valid"));
+
+/// One placeholder and the node it appeared in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnvVariable {
+    pub name: String,
+    /// e.g. `$.sourceDescriptions[0].url`.
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EnvReport {
+    pub substituted: Vec<EnvVariable>,
+    /// Placeholders left in place because the variable is not set.
+    pub missing: Vec<EnvVariable>,
+}
+
+impl EnvReport {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Expand placeholders in `doc` from the process environment.
+pub fn interpolate_env(doc: &mut ArazzoDocument) -> EnvReport {
+    interpolate_env_with(doc, |name| std::env::var(name).ok())
+}
+
+/// Like [`interpolate_env`], looking variables up with `lookup`.
+pub fn interpolate_env_with(
+    doc: &mut ArazzoDocument,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> EnvReport {
+    let mut pass = Pass {
+        lookup,
+        report: EnvReport::default(),
+    };
+    for (i, source) in doc.source_descriptions.iter_mut().enumerate() {
+        pass.string(&mut source.url, &format!("$.sourceDescriptions[{i}].url"));
+    }
+    for (wi, wf) in doc.workflows.iter_mut().enumerate() {
+        let base = format!("$.workflows[{wi}]");
+        pass.parameters(wf.parameters.as_mut(), &base);
+        for (si, step) in wf.steps.iter_mut().enumerate() {
+            pass.parameters(step.parameters.as_mut(), &format!("{base}.steps[{si}]"));
+        }
+    }
+    let components = doc.components.as_mut().and_then(|c| c.parameters.as_mut());
+    for (name, param) in components.into_iter().flatten() {
+        pass.value(
+            &mut param.value,
+            &format!("$.components.parameters.{name}.value"),
+        );
+    }
+    pass.report
+}
+
+struct Pass<F> {
+    lookup: F,
+    report: EnvReport,
+}
+
+impl<F: Fn(&str) -> Option<String>> Pass<F> {
+    fn parameters(&mut self, params: Option<&mut Vec<ParameterOrReusable>>, base: &str) {
+        for (i, param) in params.into_iter().flatten().enumerate() {
+            let value = match param {
+                ParameterOrReusable::Parameter(p) => &mut p.value,
+                ParameterOrReusable::Reusable(r) => match &mut r.value {
+                    Some(v) => v,
+                    None => continue,
+                },
+            };
+            self.value(value, &format!("{base}.parameters[{i}].value"));
+        }
+    }
+
+    fn value(&mut self, value: &mut JsonValue, path: &str) {
+        match value {
+            JsonValue::String(s) => self.string(s, path),
+            JsonValue::Array(items) => {
+                for (i, v) in items.iter_mut().enumerate() {
+                    self.value(v, &format!("{path}[{i}]"));
+                }
+            }
+            JsonValue::Object(map) => {
+                for (k, v) in map.iter_mut() {
+                    self.value(v, &format!("{path}.{k}"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn string(&mut self, s: &mut String, path: &str) {
+        if !s.contains("${") {
+            return;
+        }
+        let expanded = PLACEHOLDER_RE.replace_all(s, |caps: &Captures<'_>| {
+            let whole = &caps[0];
+            if let Some(escaped) = whole.strip_prefix("$$") {
+                return format!("${escaped}");
+            }
+            let variable = EnvVariable {
+                name: caps[1].to_string(),
+                path: path.to_string(),
+            };
+            match (self.lookup)(&caps[1]) {
+                Some(v) => {
+                    self.report.substituted.push(variable);
+                    v
+                }
+                None => {
+                    self.report.missing.push(variable);
+                    whole.to_string()
+                }
+            }
+        });
+        *s = expanded.into_owned();
+    }
+}
@@ -10,12 +10,60 @@ pub enum ArazzoError {
 
 #[derive(Debug, Error)]
 pub enum ParseError {
-    #[error("failed to parse as JSON: {0}")]
-    Json(#[from] serde_json::Error),
-    #[error("failed to parse as YAML: {0}")]
-    Yaml(#[from] serde_yaml::Error),
+    #[error("failed to parse as JSON: {source}")]
+    Json {
+        source: serde_json::Error,
+        location: Option<ErrorLocation>,
+    },
+    #[error("failed to parse as YAML: {source}")]
+    Yaml {
+        source: serde_yaml::Error,
+        location: Option<ErrorLocation>,
+    },
+    #[error("failed to parse as TOML: {source}")]
+    Toml {
+        // Boxed: the TOML error is several times larger than the others.
+        source: Box<toml::de::Error>,
+        location: Option<ErrorLocation>,
+    },
     #[error("unable to auto-detect document format (neither valid JSON nor valid YAML)")]
     UnknownFormat,
+    /// Strict parsing found keys that are neither fields nor `x-` extensions.
+    #[error("document has {} unknown fields", .0.len())]
+    UnknownFields(Vec<Violation>),
+}
+
+impl ParseError {
+    /// Where in the input the error was raised, when the parser reported a position.
+    pub fn location(&self) -> Option<&ErrorLocation> {
+        match self {
+            ParseError::Json { location, .. }
+            | ParseError::Yaml { location, .. }
+            | ParseError::Toml { location, .. } => location.as_ref(),
+            ParseError::UnknownFormat | ParseError::UnknownFields(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WriteError {
+    #[error("failed to write JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to write YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+}
+
+/// Position of a [`ParseError`] in the original input.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ErrorLocation {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number.
+    pub column: usize,
+    /// Path to the node being read when the error was raised, in the same form as
+    /// [`Violation::path`], e.g. `$.workflows[0].steps[1].stepId`. Syntax errors
+    /// point at the enclosing node.
+    pub path: String,
 }
 
 #[derive(Debug, Error)]
@@ -33,19 +81,73 @@ impl ValidationError {
             violations_len,
         }
     }
+
+    /// The violations of one severity, in the order they were found.
+    pub fn by_severity(&self, severity: Severity) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(move |v| v.severity == severity)
+    }
+}
+
+/// How serious a [`Violation`] is. Only errors make a document invalid.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Violation {
     pub path: String,
     pub message: String,
+    pub severity: Severity,
 }
 
 impl Violation {
+    /// An error-level violation.
     pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             path: path.into(),
             message: message.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    /// A warning-level violation; it is reported but does not fail validation.
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(path, message)
         }
     }
 }
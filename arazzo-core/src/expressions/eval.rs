@@ -0,0 +1,419 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value as JsonValue;
+use serde_json_path::JsonPath;
+
+use super::json_pointer::JsonPointer;
+use super::pipeline::{parse_pipeline, Argument, Function, Pipeline};
+use super::runtime::{parse_runtime_expr, RuntimeExpr, RuntimeExprError, Source};
+use super::template::{parse_template_with, Segment, TemplateDelimiters, TemplateError};
+
+/// Separates the values of a repeated header inside a single [`ResponseContext`] or
+/// [`RequestContext`] header entry. It cannot appear inside a header value, so it is
+/// only used to hand headers to the evaluator; headers that are stored or emitted
+/// are joined with `", "` instead.
+pub const HEADER_VALUE_SEPARATOR: char = '\n';
+
+/// Everything an expression can read while it is evaluated.
+#[derive(Debug, Clone)]
+pub struct EvalContext<'a> {
+    pub inputs: &'a JsonValue,
+    /// Outputs of earlier steps by step id, each an object of output name to value.
+    pub steps: &'a BTreeMap<String, JsonValue>,
+    /// The response being handled, for `$statusCode` and `$response.*`.
+    pub response: Option<ResponseContext<'a>>,
+    /// Embedded-expression delimiters of the document the value comes from.
+    pub delimiters: &'a TemplateDelimiters,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseContext<'a> {
+    pub status: u16,
+    /// Header names as received; repeated headers are joined with
+    /// [`HEADER_VALUE_SEPARATOR`].
+    pub headers: &'a BTreeMap<String, String>,
+    pub body: &'a [u8],
+    pub body_json: Option<JsonValue>,
+    /// The request that produced the response, for `$url`, `$method` and
+    /// `$request.*`.
+    pub request: Option<RequestContext<'a>>,
+}
+
+/// An outgoing request as expressions see it. Executors pass the sanitized request,
+/// so redacted secrets stay redacted in outputs.
+#[derive(Debug, Clone)]
+pub struct RequestContext<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    /// Header names as sent; repeated headers are joined with
+    /// [`HEADER_VALUE_SEPARATOR`].
+    pub headers: &'a BTreeMap<String, String>,
+    /// Query parameter values by name.
+    pub query: &'a BTreeMap<String, String>,
+    /// Path parameter values by name.
+    pub path: &'a BTreeMap<String, String>,
+    pub body: &'a [u8],
+    pub body_json: Option<JsonValue>,
+}
+
+impl RequestContext<'_> {
+    /// The value `$request.<source>` selects. An absent query or path parameter is
+    /// `null`.
+    pub fn value(&self, source: &Source) -> Result<JsonValue, EvalError> {
+        let param = |values: &BTreeMap<String, String>, name: &str| {
+            values
+                .get(name)
+                .map_or(JsonValue::Null, |v| JsonValue::String(v.clone()))
+        };
+        match source {
+            Source::Header(h) => Ok(header_value(self.headers, h, false)),
+            Source::HeaderAll(h) => Ok(header_value(self.headers, h, true)),
+            Source::Query(name) => Ok(param(self.query, name)),
+            Source::Path(name) => Ok(param(self.path, name)),
+            Source::Body { pointer } => {
+                body_value(self.body, self.body_json.as_ref(), pointer.as_ref())
+            }
+            Source::BodyPath(query) => {
+                let json = self.body_json.as_ref().ok_or(EvalError::BodyNotJson)?;
+                body_path_matches(json, query)
+            }
+        }
+    }
+}
+
+/// The body, or the node `pointer` selects in it (`null` when absent). An empty body
+/// is `null`; any other body must be JSON.
+fn body_value(
+    body: &[u8],
+    body_json: Option<&JsonValue>,
+    pointer: Option<&JsonPointer>,
+) -> Result<JsonValue, EvalError> {
+    if body.is_empty() {
+        return Ok(JsonValue::Null);
+    }
+    let json = body_json.ok_or(EvalError::BodyNotJson)?;
+    Ok(match pointer {
+        Some(ptr) => json
+            .pointer(ptr.as_str())
+            .cloned()
+            .unwrap_or(JsonValue::Null),
+        None => json.clone(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EvalError {
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+    #[error(transparent)]
+    RuntimeExpr(#[from] RuntimeExprError),
+    #[error("missing input: {0}")]
+    MissingInput(String),
+    #[error("missing input path: {0}")]
+    MissingInputPath(String),
+    #[error("only $steps.<id>.outputs.* is supported")]
+    UnsupportedStepPath,
+    #[error("missing output name")]
+    MissingOutputName,
+    #[error("missing step output: {0}")]
+    MissingStepOutput(String),
+    #[error("no response context")]
+    NoResponse,
+    #[error("no request context")]
+    NoRequest,
+    #[error("response body is not JSON")]
+    BodyNotJson,
+    #[error("invalid JSONPath '{query}': {reason}")]
+    InvalidJsonPath { query: String, reason: String },
+    #[error("unsupported response source")]
+    UnsupportedSource,
+    #[error("unsupported runtime expression")]
+    UnsupportedExpression,
+    #[error("{function}() cannot convert {value}")]
+    Conversion {
+        function: &'static str,
+        value: JsonValue,
+    },
+}
+
+impl EvalError {
+    /// The value does not exist, as opposed to the expression being unusable.
+    pub fn is_missing(&self) -> bool {
+        matches!(
+            self,
+            Self::MissingInput(_) | Self::MissingInputPath(_) | Self::MissingStepOutput(_)
+        )
+    }
+}
+
+/// Evaluate every expression in `value`. A string starting with `$` is replaced by the
+/// value of its runtime expression; any other string is a template whose embedded
+/// expressions are rendered into it. Arrays and objects are evaluated element-wise.
+pub fn eval_value(value: &JsonValue, ctx: &EvalContext<'_>) -> Result<JsonValue, EvalError> {
+    match value {
+        JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_) => Ok(value.clone()),
+        JsonValue::String(s) => eval_string(s, ctx),
+        JsonValue::Array(arr) => arr
+            .iter()
+            .map(|v| eval_value(v, ctx))
+            .collect::<Result<_, _>>()
+            .map(JsonValue::Array),
+        JsonValue::Object(map) => map
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), eval_value(v, ctx)?)))
+            .collect::<Result<_, _>>()
+            .map(JsonValue::Object),
+    }
+}
+
+fn eval_string(s: &str, ctx: &EvalContext<'_>) -> Result<JsonValue, EvalError> {
+    let trimmed = s.trim();
+    if trimmed.starts_with('$') {
+        return eval_expression(trimmed, ctx);
+    }
+
+    let tpl = parse_template_with(s, ctx.delimiters)?;
+    match tpl.segments.as_slice() {
+        [Segment::Literal(lit)] => return Ok(JsonValue::String(lit.clone())),
+        // A template that is a single expression with functions keeps the type of its
+        // result, so `{ $inputs.port | number }` is a number.
+        [Segment::Expr(e)] => {
+            let pipeline = parse_pipeline(e).map_err(TemplateError::from)?;
+            if !pipeline.functions.is_empty() {
+                return eval_pipeline(&pipeline, ctx);
+            }
+        }
+        _ => {}
+    }
+
+    let mut out = String::new();
+    for seg in tpl.segments {
+        match seg {
+            Segment::Literal(l) => out.push_str(&l),
+            Segment::Expr(e) => {
+                let pipeline = parse_pipeline(&e).map_err(TemplateError::from)?;
+                out.push_str(&render(&eval_pipeline(&pipeline, ctx)?));
+            }
+        }
+    }
+    Ok(JsonValue::String(out))
+}
+
+/// A value as it appears inside a rendered template: strings unquoted, `null` empty.
+fn render(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Evaluate an embedded expression and pass its value through its functions in order.
+pub fn eval_pipeline(pipeline: &Pipeline, ctx: &EvalContext<'_>) -> Result<JsonValue, EvalError> {
+    let mut value = eval_runtime_expr(&pipeline.expr, ctx);
+    for call in &pipeline.functions {
+        let mut args = Vec::with_capacity(call.args.len());
+        for arg in &call.args {
+            args.push(match arg {
+                Argument::Literal(v) => v.clone(),
+                Argument::Expr(e) => eval_runtime_expr(e, ctx)?,
+            });
+        }
+        value = match (call.function, value) {
+            (Function::Default, Ok(JsonValue::Null)) => Ok(args.swap_remove(0)),
+            (Function::Default, Err(e)) if e.is_missing() => Ok(args.swap_remove(0)),
+            (Function::Default, other) => other,
+            (function, Ok(v)) => apply(function, v, &args),
+            (_, Err(e)) => Err(e),
+        };
+    }
+    value
+}
+
+fn apply(function: Function, value: JsonValue, args: &[JsonValue]) -> Result<JsonValue, EvalError> {
+    let text = |f: fn(&str) -> String| match &value {
+        JsonValue::Null => JsonValue::Null,
+        v => JsonValue::String(f(&render(v))),
+    };
+    Ok(match function {
+        Function::Default => value,
+        Function::Concat => JsonValue::String(
+            std::iter::once(&value)
+                .chain(args)
+                .map(render)
+                .collect::<String>(),
+        ),
+        Function::ToUpper => text(str::to_uppercase),
+        Function::ToLower => text(str::to_lowercase),
+        Function::String => JsonValue::String(render(&value)),
+        Function::Number => match &value {
+            JsonValue::Null | JsonValue::Number(_) => value,
+            JsonValue::String(s) => parse_number(s.trim()).ok_or(EvalError::Conversion {
+                function: function.name(),
+                value,
+            })?,
+            _ => {
+                return Err(EvalError::Conversion {
+                    function: function.name(),
+                    value,
+                })
+            }
+        },
+    })
+}
+
+fn parse_number(s: &str) -> Option<JsonValue> {
+    if let Ok(i) = s.parse::<i64>() {
+        return Some(i.into());
+    }
+    s.parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(JsonValue::Number)
+}
+
+/// Evaluate a single runtime expression such as `$steps.login.outputs.token`.
+pub fn eval_expression(expr: &str, ctx: &EvalContext<'_>) -> Result<JsonValue, EvalError> {
+    eval_runtime_expr(&parse_runtime_expr(expr)?, ctx)
+}
+
+fn eval_runtime_expr(expr: &RuntimeExpr, ctx: &EvalContext<'_>) -> Result<JsonValue, EvalError> {
+    match expr {
+        RuntimeExpr::Inputs(np) => {
+            // An explicitly provided `null` is a value like any other; only absent keys are missing.
+            let mut cur = ctx
+                .inputs
+                .get(&np.root)
+                .ok_or_else(|| EvalError::MissingInput(np.root.clone()))?;
+            for seg in &np.rest {
+                cur = cur
+                    .get(seg)
+                    .ok_or_else(|| EvalError::MissingInputPath(seg.clone()))?;
+            }
+            Ok(cur.clone())
+        }
+        RuntimeExpr::Steps(np) => {
+            // Only `$steps.<stepId>.outputs.<name>` plus optional pointer.
+            if np.rest.first().map(String::as_str) != Some("outputs") {
+                return Err(EvalError::UnsupportedStepPath);
+            }
+            let out_name = np.rest.get(1).ok_or(EvalError::MissingOutputName)?;
+            let mut cur = ctx
+                .steps
+                .get(&np.root)
+                .and_then(|outputs| outputs.get(out_name))
+                .ok_or_else(|| EvalError::MissingStepOutput(out_name.clone()))?
+                .clone();
+            if let Some(ptr) = &np.pointer {
+                if let Some(v) = cur.pointer(ptr.as_str()) {
+                    cur = v.clone();
+                }
+            }
+            Ok(cur)
+        }
+        RuntimeExpr::StatusCode => Ok(JsonValue::Number(
+            ctx.response.as_ref().map_or(0, |r| r.status).into(),
+        )),
+        RuntimeExpr::Response(source) => {
+            let r = ctx.response.as_ref().ok_or(EvalError::NoResponse)?;
+            match source {
+                Source::Header(h) => Ok(header_value(r.headers, h, false)),
+                Source::HeaderAll(h) => Ok(header_value(r.headers, h, true)),
+                Source::Body { pointer } => {
+                    body_value(r.body, r.body_json.as_ref(), pointer.as_ref())
+                }
+                Source::BodyPath(query) => {
+                    let json = r.body_json.as_ref().ok_or(EvalError::BodyNotJson)?;
+                    body_path_matches(json, query)
+                }
+                _ => Err(EvalError::UnsupportedSource),
+            }
+        }
+        RuntimeExpr::Url => Ok(JsonValue::String(request(ctx)?.url.to_string())),
+        RuntimeExpr::Method => Ok(JsonValue::String(request(ctx)?.method.to_string())),
+        RuntimeExpr::Request(source) => request(ctx)?.value(source),
+        _ => Err(EvalError::UnsupportedExpression),
+    }
+}
+
+fn request<'c>(ctx: &'c EvalContext<'_>) -> Result<&'c RequestContext<'c>, EvalError> {
+    ctx.response
+        .as_ref()
+        .and_then(|r| r.request.as_ref())
+        .ok_or(EvalError::NoRequest)
+}
+
+/// Ids of the steps whose outputs the expressions in `value` read, so a caller that
+/// keeps outputs elsewhere can load just those into [`EvalContext::steps`].
+/// Malformed expressions are skipped; evaluating them reports the error.
+pub fn referenced_steps(value: &JsonValue, delimiters: &TemplateDelimiters) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    collect_steps(value, delimiters, &mut ids);
+    ids
+}
+
+fn collect_steps(value: &JsonValue, delimiters: &TemplateDelimiters, ids: &mut BTreeSet<String>) {
+    let mut add = |expr: &RuntimeExpr| {
+        if let RuntimeExpr::Steps(np) = expr {
+            if np.rest.first().map(String::as_str) == Some("outputs") {
+                ids.insert(np.root.clone());
+            }
+        }
+    };
+    match value {
+        JsonValue::String(s) if s.trim().starts_with('$') => {
+            if let Ok(expr) = parse_runtime_expr(s.trim()) {
+                add(&expr);
+            }
+        }
+        JsonValue::String(s) => {
+            if let Ok(tpl) = parse_template_with(s, delimiters) {
+                for seg in tpl.segments {
+                    if let Segment::Expr(e) = seg {
+                        parse_pipeline(&e)
+                            .iter()
+                            .flat_map(Pipeline::expressions)
+                            .for_each(&mut add);
+                    }
+                }
+            }
+        }
+        JsonValue::Array(arr) => arr.iter().for_each(|v| collect_steps(v, delimiters, ids)),
+        JsonValue::Object(map) => map.values().for_each(|v| collect_steps(v, delimiters, ids)),
+        _ => {}
+    }
+}
+
+/// Look up a header case-insensitively. Repeated headers are joined with `", "`, or
+/// returned as an array of values when `all` is set. An absent header is `null`, or an
+/// empty array when `all` is set.
+pub fn header_value(headers: &BTreeMap<String, String>, name: &str, all: bool) -> JsonValue {
+    let values: Vec<&str> = headers
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case(name))
+        .flat_map(|(_, v)| v.split(HEADER_VALUE_SEPARATOR))
+        .collect();
+    if all {
+        JsonValue::Array(
+            values
+                .into_iter()
+                .map(|v| JsonValue::String(v.to_string()))
+                .collect(),
+        )
+    } else if values.is_empty() {
+        JsonValue::Null
+    } else {
+        JsonValue::String(values.join(", "))
+    }
+}
+
+/// Every node `query` matches in `json`, as an array (empty when nothing matches).
+pub fn body_path_matches(json: &JsonValue, query: &str) -> Result<JsonValue, EvalError> {
+    let path = JsonPath::parse(query).map_err(|e| EvalError::InvalidJsonPath {
+        query: query.to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(JsonValue::Array(
+        path.query(json).all().into_iter().cloned().collect(),
+    ))
+}
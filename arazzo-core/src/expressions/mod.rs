@@ -1,17 +1,30 @@
+mod eval;
 mod json_pointer;
+mod pipeline;
 mod runtime;
 mod template;
 
+pub use eval::{
+    body_path_matches, eval_expression, eval_pipeline, eval_value, header_value, referenced_steps,
+    EvalContext, EvalError, RequestContext, ResponseContext, HEADER_VALUE_SEPARATOR,
+};
 pub use json_pointer::{JsonPointer, JsonPointerError};
+pub use pipeline::{parse_pipeline, Argument, Function, FunctionCall, FunctionError, Pipeline};
 pub use runtime::{parse_runtime_expr, RuntimeExpr, RuntimeExprError, Source};
-pub use template::{parse_template, Segment, Template, TemplateError};
+pub use template::{
+    parse_template, parse_template_with, Segment, Template, TemplateDelimiters, TemplateError,
+};
 
 use crate::types::AnyValue;
 
 /// Validate that any expression-like strings inside a value are syntactically valid.
 ///
 /// - If a string starts with `$`, it must be a valid runtime expression.
-/// - If a string contains embedded `{ $... }` expressions, each embedded expression must be valid.
-pub fn validate_value_expressions(value: &AnyValue) -> Result<(), TemplateError> {
-    template::validate_value_expressions(value)
+/// - If a string contains embedded `{ $... }` expressions (or the configured `delimiters`),
+///   each embedded expression must be valid.
+pub fn validate_value_expressions(
+    value: &AnyValue,
+    delimiters: &TemplateDelimiters,
+) -> Result<(), TemplateError> {
+    template::validate_value_expressions(value, delimiters)
 }
@@ -0,0 +1,215 @@
+//! Functions applied to an embedded expression, e.g.
+//! `{ $inputs.host | default('api.example.com') | toUpper }`. Each ` | ` passes the
+//! value on the left to the function on the right.
+
+use serde_json::Value as JsonValue;
+
+use super::runtime::{parse_runtime_expr, RuntimeExpr, RuntimeExprError};
+
+/// An embedded expression: a runtime expression followed by function calls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub expr: RuntimeExpr,
+    pub functions: Vec<FunctionCall>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCall {
+    pub function: Function,
+    pub args: Vec<Argument>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    /// `default(value)`: `value` when the input is `null` or does not exist.
+    Default,
+    /// `concat(value, ...)`: the input followed by each value, as a string.
+    Concat,
+    ToUpper,
+    ToLower,
+    /// Parse a string as a number; numbers pass through.
+    Number,
+    /// Render the value as it would appear in a template.
+    String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Argument {
+    /// A quoted string, number, boolean or `null`.
+    Literal(JsonValue),
+    Expr(RuntimeExpr),
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "default" => Self::Default,
+            "concat" => Self::Concat,
+            "toUpper" => Self::ToUpper,
+            "toLower" => Self::ToLower,
+            "number" => Self::Number,
+            "string" => Self::String,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Concat => "concat",
+            Self::ToUpper => "toUpper",
+            Self::ToLower => "toLower",
+            Self::Number => "number",
+            Self::String => "string",
+        }
+    }
+
+    fn accepts(self, count: usize) -> bool {
+        match self {
+            Self::Default => count == 1,
+            Self::Concat => count >= 1,
+            Self::ToUpper | Self::ToLower | Self::Number | Self::String => count == 0,
+        }
+    }
+}
+
+impl Pipeline {
+    /// The runtime expressions the pipeline reads: its own, then those passed as
+    /// arguments.
+    pub fn expressions(&self) -> impl Iterator<Item = &RuntimeExpr> {
+        std::iter::once(&self.expr).chain(self.functions.iter().flat_map(|call| {
+            call.args.iter().filter_map(|arg| match arg {
+                Argument::Expr(e) => Some(e),
+                Argument::Literal(_) => None,
+            })
+        }))
+    }
+
+    /// Whether a missing value is replaced by `default(...)`.
+    pub fn has_default(&self) -> bool {
+        self.functions
+            .iter()
+            .any(|call| call.function == Function::Default)
+    }
+}
+
+/// Parse the text between embedded-expression delimiters. Functions follow the
+/// expression after a `|` preceded by whitespace, so a `|` inside the expression
+/// itself (in a JSON pointer, say) is left alone.
+pub fn parse_pipeline(input: &str) -> Result<Pipeline, FunctionError> {
+    let mut parts = split_top_level(input.trim(), Split::Pipe).into_iter();
+    let head = parts.next().unwrap_or_default();
+    let expr = parse_runtime_expr(head.trim()).map_err(FunctionError::InvalidRuntimeExpr)?;
+    let functions = parts
+        .map(|part| parse_call(part.trim()))
+        .collect::<Result<_, _>>()?;
+    Ok(Pipeline { expr, functions })
+}
+
+fn parse_call(text: &str) -> Result<FunctionCall, FunctionError> {
+    let (name, args) = match text.split_once('(') {
+        Some((name, rest)) => {
+            let inner = rest
+                .strip_suffix(')')
+                .ok_or_else(|| FunctionError::Syntax(text.to_string()))?;
+            let args = if inner.trim().is_empty() {
+                Vec::new()
+            } else {
+                split_top_level(inner, Split::Comma)
+                    .into_iter()
+                    .map(|arg| parse_argument(arg.trim()))
+                    .collect::<Result<_, _>>()?
+            };
+            (name.trim(), args)
+        }
+        None => (text, Vec::new()),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(FunctionError::Syntax(text.to_string()));
+    }
+    let function =
+        Function::from_name(name).ok_or_else(|| FunctionError::Unknown(name.to_string()))?;
+    if !function.accepts(args.len()) {
+        return Err(FunctionError::Arity {
+            function: function.name(),
+            found: args.len(),
+        });
+    }
+    Ok(FunctionCall { function, args })
+}
+
+fn parse_argument(text: &str) -> Result<Argument, FunctionError> {
+    if text.starts_with('$') {
+        return parse_runtime_expr(text)
+            .map(Argument::Expr)
+            .map_err(FunctionError::InvalidRuntimeExpr);
+    }
+    for quote in ['\'', '"'] {
+        if let Some(inner) = text.strip_prefix(quote).and_then(|t| t.strip_suffix(quote)) {
+            if !inner.contains(quote) {
+                return Ok(Argument::Literal(JsonValue::String(inner.to_string())));
+            }
+        }
+    }
+    match serde_json::from_str(text) {
+        Ok(v @ (JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_))) => {
+            Ok(Argument::Literal(v))
+        }
+        _ => Err(FunctionError::InvalidArgument(text.to_string())),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Split {
+    Pipe,
+    Comma,
+}
+
+/// Split `text` at `|` or `,` outside quotes and brackets.
+fn split_top_level(text: &str, split: Split) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth = depth.saturating_sub(1),
+            (None, '|')
+                if depth == 0
+                    && split == Split::Pipe
+                    && text[..i].ends_with(char::is_whitespace) =>
+            {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            (None, ',') if depth == 0 && split == Split::Comma => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FunctionError {
+    #[error(transparent)]
+    InvalidRuntimeExpr(RuntimeExprError),
+    #[error("unknown function: {0}")]
+    Unknown(String),
+    #[error("expected a function call such as name(arg, ...), found '{0}'")]
+    Syntax(String),
+    #[error("invalid argument '{0}': expected a quoted string, number, boolean, null or runtime expression")]
+    InvalidArgument(String),
+    #[error("{function}() does not take {found} argument(s)")]
+    Arity {
+        function: &'static str,
+        found: usize,
+    },
+}
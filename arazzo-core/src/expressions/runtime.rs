@@ -29,9 +29,16 @@ pub enum RuntimeExpr {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Source {
     Header(String),
+    /// `header.<name>#all`: every value of a repeated header.
+    HeaderAll(String),
     Query(String),
     Path(String),
-    Body { pointer: Option<JsonPointer> },
+    Body {
+        pointer: Option<JsonPointer>,
+    },
+    /// `body$<jsonpath>`: every match of a JSONPath query on the body, e.g.
+    /// `$response.body$.data[*].id`. Holds the query including its leading `$`.
+    BodyPath(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,6 +54,32 @@ pub fn parse_runtime_expr(input: &str) -> Result<RuntimeExpr, RuntimeExprError>
         return Err(RuntimeExprError::MissingDollarPrefix);
     }
 
+    // `#all` on a header selects all of its values; it is not a JSON pointer.
+    if let Some(head) = s[1..].strip_suffix("#all") {
+        if let Some(token) = head.strip_prefix("request.header.") {
+            return Ok(RuntimeExpr::Request(Source::HeaderAll(parse_header_token(
+                token,
+            )?)));
+        }
+        if let Some(token) = head.strip_prefix("response.header.") {
+            return Ok(RuntimeExpr::Response(Source::HeaderAll(
+                parse_header_token(token)?,
+            )));
+        }
+    }
+
+    // A JSONPath query may itself contain `#`, so split it off before the pointer.
+    if let Some(query) = s[1..].strip_prefix("request.body$") {
+        return Ok(RuntimeExpr::Request(Source::BodyPath(parse_body_path(
+            query,
+        )?)));
+    }
+    if let Some(query) = s[1..].strip_prefix("response.body$") {
+        return Ok(RuntimeExpr::Response(Source::BodyPath(parse_body_path(
+            query,
+        )?)));
+    }
+
     // Split optional `#<json-pointer>` suffix.
     let (head, pointer) = split_pointer_suffix(&s[1..])?;
 
@@ -61,10 +94,10 @@ pub fn parse_runtime_expr(input: &str) -> Result<RuntimeExpr, RuntimeExprError>
     }
 
     if let Some(rest) = head.strip_prefix("request.") {
-        return Ok(RuntimeExpr::Request(parse_source(rest)?));
+        return Ok(RuntimeExpr::Request(parse_source(rest, pointer)?));
     }
     if let Some(rest) = head.strip_prefix("response.") {
-        return Ok(RuntimeExpr::Response(parse_source(rest)?));
+        return Ok(RuntimeExpr::Response(parse_source(rest, pointer)?));
     }
     if let Some(rest) = head.strip_prefix("inputs.") {
         return Ok(RuntimeExpr::Inputs(parse_name_path(rest, pointer)?));
@@ -105,6 +138,17 @@ pub fn parse_runtime_expr(input: &str) -> Result<RuntimeExpr, RuntimeExprError>
     Err(RuntimeExprError::UnknownExpression(head.to_string()))
 }
 
+/// The `$`-prefixed JSONPath query following `body$`, checked so a malformed query
+/// is rejected with the expression rather than when it is evaluated.
+fn parse_body_path(query: &str) -> Result<String, RuntimeExprError> {
+    let query = format!("${query}");
+    serde_json_path::JsonPath::parse(&query).map_err(|e| RuntimeExprError::InvalidJsonPath {
+        query: query.clone(),
+        reason: e.to_string(),
+    })?;
+    Ok(query)
+}
+
 fn split_pointer_suffix(s: &str) -> Result<(String, Option<JsonPointer>), RuntimeExprError> {
     if let Some((head, frag)) = s.split_once('#') {
         let ptr = JsonPointer::parse(frag).map_err(RuntimeExprError::InvalidJsonPointer)?;
@@ -114,15 +158,20 @@ fn split_pointer_suffix(s: &str) -> Result<(String, Option<JsonPointer>), Runtim
     }
 }
 
-fn parse_source(rest: &str) -> Result<Source, RuntimeExprError> {
+fn parse_header_token(token: &str) -> Result<String, RuntimeExprError> {
+    if token.is_empty() {
+        return Err(RuntimeExprError::EmptyName);
+    }
+    if !TCHAR_RE.is_match(token) {
+        return Err(RuntimeExprError::InvalidHeaderToken(token.to_string()));
+    }
+    Ok(token.to_string())
+}
+
+/// `pointer` is the `#` suffix already split off the expression; only a body takes one.
+fn parse_source(rest: &str, pointer: Option<JsonPointer>) -> Result<Source, RuntimeExprError> {
     if let Some(token) = rest.strip_prefix("header.") {
-        if token.is_empty() {
-            return Err(RuntimeExprError::EmptyName);
-        }
-        if !TCHAR_RE.is_match(token) {
-            return Err(RuntimeExprError::InvalidHeaderToken(token.to_string()));
-        }
-        return Ok(Source::Header(token.to_string()));
+        return Ok(Source::Header(parse_header_token(token)?));
     }
     if let Some(name) = rest.strip_prefix("query.") {
         validate_name(name)?;
@@ -133,13 +182,7 @@ fn parse_source(rest: &str) -> Result<Source, RuntimeExprError> {
         return Ok(Source::Path(name.to_string()));
     }
     if rest == "body" {
-        return Ok(Source::Body { pointer: None });
-    }
-    if let Some(ptr) = rest.strip_prefix("body#") {
-        let pointer = JsonPointer::parse(ptr).map_err(RuntimeExprError::InvalidJsonPointer)?;
-        return Ok(Source::Body {
-            pointer: Some(pointer),
-        });
+        return Ok(Source::Body { pointer });
     }
 
     Err(RuntimeExprError::InvalidSource(rest.to_string()))
@@ -198,4 +241,6 @@ pub enum RuntimeExprError {
     InvalidJsonPointer(#[from] JsonPointerError),
     #[error("json pointer is not allowed on this runtime expression")]
     PointerNotAllowed,
+    #[error("invalid JSONPath query {query}: {reason}")]
+    InvalidJsonPath { query: String, reason: String },
 }
@@ -1,5 +1,6 @@
+use super::pipeline::{parse_pipeline, FunctionError};
 use super::runtime::{parse_runtime_expr, RuntimeExprError};
-use crate::types::AnyValue;
+use crate::types::{AnyValue, Extensions};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Segment {
@@ -12,56 +13,92 @@ pub struct Template {
     pub segments: Vec<Segment>,
 }
 
+/// Markers around embedded expressions in template strings. The spec uses `{` / `}`;
+/// documents whose payloads are full of literal braces can switch to e.g. `{{` / `}}`
+/// through the [`TemplateDelimiters::EXTENSION`] document extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateDelimiters {
+    pub open: String,
+    pub close: String,
+}
+
+impl Default for TemplateDelimiters {
+    fn default() -> Self {
+        Self::new("{", "}")
+    }
+}
+
+impl TemplateDelimiters {
+    /// Document-level extension holding `{ open: <str>, close: <str> }`.
+    pub const EXTENSION: &'static str = "x-template-delimiters";
+
+    pub fn new(open: impl Into<String>, close: impl Into<String>) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into(),
+        }
+    }
+
+    /// Delimiters configured by [`Self::EXTENSION`], or the defaults when it is absent
+    /// or malformed (the validator reports malformed values).
+    pub fn from_extensions(extensions: &Extensions) -> Self {
+        extensions
+            .get(Self::EXTENSION)
+            .and_then(Self::from_value)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn from_value(value: &AnyValue) -> Option<Self> {
+        let open = value.get("open")?.as_str()?;
+        let close = value.get("close")?.as_str()?;
+        if open.is_empty() || close.is_empty() {
+            return None;
+        }
+        Some(Self::new(open, close))
+    }
+}
+
 pub fn parse_template(input: &str) -> Result<Template, TemplateError> {
+    parse_template_with(input, &TemplateDelimiters::default())
+}
+
+pub fn parse_template_with(
+    input: &str,
+    delimiters: &TemplateDelimiters,
+) -> Result<Template, TemplateError> {
+    let open = delimiters.open.as_str();
+    let close = delimiters.close.as_str();
     let mut segments = Vec::new();
     let mut buf = String::new();
-    let mut chars = input.chars().peekable();
-
-    while let Some(ch) = chars.next() {
-        if ch == '{' {
-            // Only treat `{ ... }` as an embedded expression if it looks like `{ $... }`.
-            // Otherwise, keep scanning; this avoids swallowing JSON objects in templated payload strings.
-            let mut lookahead = chars.clone();
-            while let Some(ws) = lookahead.peek() {
-                if ws.is_whitespace() {
-                    lookahead.next();
-                } else {
-                    break;
-                }
-            }
-            let is_expr = matches!(lookahead.peek(), Some('$'));
-            if !is_expr {
-                buf.push('{');
-                continue;
-            }
+    let mut rest = input;
 
-            // Find matching } (no nesting support).
-            let mut inner = String::new();
-            let mut found = false;
-            for n in chars.by_ref() {
-                if n == '}' {
-                    found = true;
-                    break;
-                }
-                inner.push(n);
-            }
+    while let Some(idx) = rest.find(open) {
+        buf.push_str(&rest[..idx]);
+        let after_open = &rest[idx + open.len()..];
 
-            if !found {
-                // Unclosed expression-like start; treat as a hard error.
-                return Err(TemplateError::UnclosedExpression);
-            }
+        // Only treat `<open> ... <close>` as an embedded expression if it looks like `<open> $...`.
+        // Otherwise, keep scanning; this avoids swallowing JSON objects in templated payload strings.
+        if !after_open.trim_start().starts_with('$') {
+            buf.push_str(open);
+            rest = after_open;
+            continue;
+        }
 
-            let inner_trimmed = inner.trim();
-            // At this point, it should start with '$' due to lookahead.
-            parse_runtime_expr(inner_trimmed).map_err(TemplateError::InvalidRuntimeExpr)?;
-            if !buf.is_empty() {
-                segments.push(Segment::Literal(std::mem::take(&mut buf)));
-            }
-            segments.push(Segment::Expr(inner_trimmed.to_string()));
-        } else {
-            buf.push(ch);
+        // Find the closing delimiter (no nesting support).
+        let Some(end) = after_open.find(close) else {
+            // Unclosed expression-like start; treat as a hard error.
+            return Err(TemplateError::UnclosedExpression);
+        };
+
+        let inner_trimmed = after_open[..end].trim();
+        parse_pipeline(inner_trimmed)?;
+        if !buf.is_empty() {
+            segments.push(Segment::Literal(std::mem::take(&mut buf)));
         }
+        segments.push(Segment::Expr(inner_trimmed.to_string()));
+        rest = &after_open[end + close.len()..];
     }
+    buf.push_str(rest);
 
     if !buf.is_empty() {
         segments.push(Segment::Literal(buf));
@@ -70,26 +107,32 @@ pub fn parse_template(input: &str) -> Result<Template, TemplateError> {
     Ok(Template { segments })
 }
 
-pub fn validate_value_expressions(value: &AnyValue) -> Result<(), TemplateError> {
+pub fn validate_value_expressions(
+    value: &AnyValue,
+    delimiters: &TemplateDelimiters,
+) -> Result<(), TemplateError> {
     match value {
         AnyValue::Null | AnyValue::Bool(_) | AnyValue::Number(_) => Ok(()),
-        AnyValue::String(s) => validate_string_expressions(s),
+        AnyValue::String(s) => validate_string_expressions(s, delimiters),
         AnyValue::Array(arr) => {
             for v in arr {
-                validate_value_expressions(v)?;
+                validate_value_expressions(v, delimiters)?;
             }
             Ok(())
         }
         AnyValue::Object(map) => {
             for (_k, v) in map {
-                validate_value_expressions(v)?;
+                validate_value_expressions(v, delimiters)?;
             }
             Ok(())
         }
     }
 }
 
-fn validate_string_expressions(s: &str) -> Result<(), TemplateError> {
+fn validate_string_expressions(
+    s: &str,
+    delimiters: &TemplateDelimiters,
+) -> Result<(), TemplateError> {
     let trimmed = s.trim();
     if trimmed.starts_with('$') {
         parse_runtime_expr(trimmed).map_err(TemplateError::InvalidRuntimeExpr)?;
@@ -97,7 +140,7 @@ fn validate_string_expressions(s: &str) -> Result<(), TemplateError> {
     }
 
     // Validate embedded expressions in templates.
-    let _ = parse_template(s)?;
+    let _ = parse_template_with(s, delimiters)?;
     Ok(())
 }
 
@@ -105,6 +148,17 @@ fn validate_string_expressions(s: &str) -> Result<(), TemplateError> {
 pub enum TemplateError {
     #[error("invalid runtime expression: {0}")]
     InvalidRuntimeExpr(#[from] RuntimeExprError),
-    #[error("unclosed embedded expression (missing '}}')")]
+    #[error("unclosed embedded expression (missing closing delimiter)")]
     UnclosedExpression,
+    #[error("invalid function call: {0}")]
+    InvalidFunction(FunctionError),
+}
+
+impl From<FunctionError> for TemplateError {
+    fn from(e: FunctionError) -> Self {
+        match e {
+            FunctionError::InvalidRuntimeExpr(e) => Self::InvalidRuntimeExpr(e),
+            other => Self::InvalidFunction(other),
+        }
+    }
 }
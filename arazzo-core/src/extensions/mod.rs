@@ -0,0 +1,155 @@
+//! Typed access to `x-*` specification extensions.
+//!
+//! Extensions are kept as raw JSON on every object. An [`ExtensionRegistry`] maps
+//! extension keys to Rust types: [`ExtensionRegistry::extract`] decodes every
+//! registered extension in a document, and setting
+//! [`ValidationOptions::extensions`](crate::ValidationOptions::extensions) makes
+//! validation reject extensions that do not decode.
+
+mod walk;
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::Violation;
+use crate::types::ArazzoDocument;
+
+pub(crate) use walk::for_each_extensions;
+
+type Decoded = Box<dyn Any + Send + Sync>;
+type Decoder = dyn Fn(&serde_json::Value) -> Result<Decoded, String> + Send + Sync;
+
+/// Extension keys with the type their value must decode into.
+#[derive(Clone, Default)]
+pub struct ExtensionRegistry {
+    decoders: BTreeMap<String, Arc<Decoder>>,
+}
+
+impl ExtensionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode `key` (e.g. `x-timeout`) into `T` wherever it appears.
+    pub fn with_extension<T>(mut self, key: impl Into<String>) -> Self
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        let decoder: Arc<Decoder> = Arc::new(|value: &serde_json::Value| {
+            T::deserialize(value)
+                .map(|v| Box::new(v) as Decoded)
+                .map_err(|e| e.to_string())
+        });
+        self.decoders.insert(key.into(), decoder);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decoders.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.decoders.keys().map(String::as_str)
+    }
+
+    /// Why `value` is not a valid `key` extension, or `None` when it is valid or `key`
+    /// is not registered.
+    pub(crate) fn check(&self, key: &str, value: &serde_json::Value) -> Option<String> {
+        let decoder = self.decoders.get(key)?;
+        decoder(value).err().map(|e| invalid(key, &e))
+    }
+
+    /// Decode every registered extension in `doc`. Values that fail to decode are
+    /// left out and reported in [`ExtensionValues::diagnostics`].
+    pub fn extract(&self, doc: &ArazzoDocument) -> ExtensionValues {
+        let mut out = ExtensionValues::default();
+        if self.is_empty() {
+            return out;
+        }
+        for_each_extensions(doc, |path, ext| {
+            for (key, value) in ext {
+                let Some(decoder) = self.decoders.get(key) else {
+                    continue;
+                };
+                match decoder(value) {
+                    Ok(decoded) => {
+                        out.values.insert((path.to_string(), key.clone()), decoded);
+                    }
+                    Err(e) => out
+                        .diagnostics
+                        .push(Violation::new(format!("{path}.{key}"), invalid(key, &e))),
+                }
+            }
+        });
+        out
+    }
+}
+
+fn invalid(key: &str, error: &str) -> String {
+    format!("invalid {key} extension: {error}")
+}
+
+impl fmt::Debug for ExtensionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.decoders.keys()).finish()
+    }
+}
+
+/// Registries are equal when they register the same keys with the same decoders.
+impl PartialEq for ExtensionRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.decoders.len() == other.decoders.len()
+            && self
+                .decoders
+                .iter()
+                .zip(&other.decoders)
+                .all(|((a, da), (b, db))| a == b && Arc::ptr_eq(da, db))
+    }
+}
+
+/// Decoded extensions of one document, keyed by the path of the object that holds
+/// them (e.g. `$.workflows[0].steps[1]`) and the extension key.
+#[derive(Default)]
+pub struct ExtensionValues {
+    values: BTreeMap<(String, String), Decoded>,
+    /// Extensions that did not decode, as error-level violations.
+    pub diagnostics: Vec<Violation>,
+}
+
+impl ExtensionValues {
+    /// The `key` extension of the object at `path`, if it was registered as a `T`.
+    pub fn get<T: 'static>(&self, path: &str, key: &str) -> Option<&T> {
+        self.values
+            .get(&(path.to_string(), key.to_string()))
+            .and_then(|v| v.downcast_ref())
+    }
+
+    /// Every decoded `key` extension with the path of the object holding it.
+    pub fn all<'a, T: 'static>(&'a self, key: &'a str) -> impl Iterator<Item = (&'a str, &'a T)> {
+        self.values
+            .iter()
+            .filter(move |((_, k), _)| k == key)
+            .filter_map(|((path, _), v)| Some((path.as_str(), v.downcast_ref()?)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl fmt::Debug for ExtensionValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtensionValues")
+            .field("keys", &self.values.keys().collect::<Vec<_>>())
+            .field("diagnostics", &self.diagnostics)
+            .finish()
+    }
+}
@@ -0,0 +1,130 @@
+use crate::types::{
+    ArazzoDocument, Criterion, CriterionType, Extensions, FailureActionOrReusable,
+    ParameterOrReusable, SuccessActionOrReusable,
+};
+
+type Visit<'a, 'd> = dyn FnMut(&str, &'d Extensions) + 'a;
+
+/// Call `visit` with the path and extensions map of every object in `doc` that has
+/// one. Paths use the same form as validation violations, e.g. `$.workflows[0]`.
+pub(crate) fn for_each_extensions<'d>(
+    doc: &'d ArazzoDocument,
+    mut visit: impl FnMut(&str, &'d Extensions),
+) {
+    let visit: &mut Visit<'_, 'd> = &mut visit;
+    visit("$", &doc.extensions);
+    visit("$.info", &doc.info.extensions);
+    for (idx, src) in doc.source_descriptions.iter().enumerate() {
+        visit(&format!("$.sourceDescriptions[{idx}]"), &src.extensions);
+    }
+
+    for (idx, wf) in doc.workflows.iter().enumerate() {
+        let path = format!("$.workflows[{idx}]");
+        visit(&path, &wf.extensions);
+        if let Some(params) = &wf.parameters {
+            parameters(visit, &format!("{path}.parameters"), params);
+        }
+        if let Some(actions) = &wf.success_actions {
+            success_actions(visit, &format!("{path}.successActions"), actions);
+        }
+        if let Some(actions) = &wf.failure_actions {
+            failure_actions(visit, &format!("{path}.failureActions"), actions);
+        }
+
+        for (sidx, step) in wf.steps.iter().enumerate() {
+            let spath = format!("{path}.steps[{sidx}]");
+            visit(&spath, &step.extensions);
+            if let Some(params) = &step.parameters {
+                parameters(visit, &format!("{spath}.parameters"), params);
+            }
+            if let Some(rb) = &step.request_body {
+                let rb_path = format!("{spath}.requestBody");
+                visit(&rb_path, &rb.extensions);
+                for (ridx, rep) in rb.replacements.iter().flatten().enumerate() {
+                    visit(&format!("{rb_path}.replacements[{ridx}]"), &rep.extensions);
+                }
+            }
+            if let Some(criteria) = &step.success_criteria {
+                criteria_list(visit, &format!("{spath}.successCriteria"), criteria);
+            }
+            if let Some(actions) = &step.on_success {
+                success_actions(visit, &format!("{spath}.onSuccess"), actions);
+            }
+            if let Some(actions) = &step.on_failure {
+                failure_actions(visit, &format!("{spath}.onFailure"), actions);
+            }
+        }
+    }
+
+    if let Some(components) = &doc.components {
+        let path = "$.components";
+        visit(path, &components.extensions);
+        for (k, p) in components.parameters.iter().flatten() {
+            visit(&format!("{path}.parameters.{k}"), &p.extensions);
+        }
+        for (k, a) in components.success_actions.iter().flatten() {
+            let apath = format!("{path}.successActions.{k}");
+            visit(&apath, &a.extensions);
+            if let Some(criteria) = &a.criteria {
+                criteria_list(visit, &format!("{apath}.criteria"), criteria);
+            }
+        }
+        for (k, a) in components.failure_actions.iter().flatten() {
+            let apath = format!("{path}.failureActions.{k}");
+            visit(&apath, &a.extensions);
+            if let Some(criteria) = &a.criteria {
+                criteria_list(visit, &format!("{apath}.criteria"), criteria);
+            }
+        }
+    }
+}
+
+fn parameters<'d>(visit: &mut Visit<'_, 'd>, path: &str, params: &'d [ParameterOrReusable]) {
+    for (idx, item) in params.iter().enumerate() {
+        if let ParameterOrReusable::Parameter(p) = item {
+            visit(&format!("{path}[{idx}]"), &p.extensions);
+        }
+    }
+}
+
+fn success_actions<'d>(
+    visit: &mut Visit<'_, 'd>,
+    path: &str,
+    actions: &'d [SuccessActionOrReusable],
+) {
+    for (idx, item) in actions.iter().enumerate() {
+        if let SuccessActionOrReusable::Action(a) = item {
+            let ipath = format!("{path}[{idx}]");
+            visit(&ipath, &a.extensions);
+            if let Some(criteria) = &a.criteria {
+                criteria_list(visit, &format!("{ipath}.criteria"), criteria);
+            }
+        }
+    }
+}
+
+fn failure_actions<'d>(
+    visit: &mut Visit<'_, 'd>,
+    path: &str,
+    actions: &'d [FailureActionOrReusable],
+) {
+    for (idx, item) in actions.iter().enumerate() {
+        if let FailureActionOrReusable::Action(a) = item {
+            let ipath = format!("{path}[{idx}]");
+            visit(&ipath, &a.extensions);
+            if let Some(criteria) = &a.criteria {
+                criteria_list(visit, &format!("{ipath}.criteria"), criteria);
+            }
+        }
+    }
+}
+
+fn criteria_list<'d>(visit: &mut Visit<'_, 'd>, path: &str, criteria: &'d [Criterion]) {
+    for (idx, c) in criteria.iter().enumerate() {
+        let ipath = format!("{path}[{idx}]");
+        visit(&ipath, &c.extensions);
+        if let Some(CriterionType::Custom(custom)) = &c.r#type {
+            visit(&format!("{ipath}.type"), &custom.extensions);
+        }
+    }
+}
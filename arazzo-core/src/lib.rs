@@ -1,17 +1,56 @@
 #![forbid(unsafe_code)]
 
+pub mod bundle;
+pub mod dereference;
+pub mod diff;
+pub mod edit;
+pub mod env;
 pub mod error;
 pub mod expressions;
+pub mod extensions;
+pub mod lint;
+pub mod overlay;
 pub mod parser;
 pub mod planner;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod types;
 pub mod validate;
+pub mod version;
+pub mod writer;
 
-pub use crate::error::{ArazzoError, ParseError, ValidationError};
-pub use crate::parser::{parse_document_str, DocumentFormat, ParsedDocument};
+pub use crate::bundle::{bundle_document, bundle_document_with, BundleError, BundledDocument};
+pub use crate::dereference::{
+    dereference, dereference_workflow, dereference_workflow_best_effort, DereferenceError,
+};
+pub use crate::diff::{diff_documents, Change, ChangeKind};
+pub use crate::edit::{EditError, YamlDocument};
+pub use crate::env::{interpolate_env, interpolate_env_with, EnvReport, EnvVariable};
+pub use crate::error::{
+    ArazzoError, ErrorLocation, ParseError, Severity, ValidationError, Violation, WriteError,
+};
+pub use crate::extensions::{ExtensionRegistry, ExtensionValues};
+pub use crate::lint::{lint_document, LintDiagnostic, LintRule};
+pub use crate::overlay::{apply_overlay, parse_overlay_str, Overlay, OverlayError};
+pub use crate::parser::{
+    parse_document_str, parse_document_str_with, parse_value_str, parse_workflow_summaries,
+    DocumentFormat, ParseOptions, ParsedDocument, WorkflowSummary,
+};
 pub use crate::planner::{
-    plan_document, plan_from_str, DependencyGraph, Plan, PlanFormat, PlanIntentStep,
-    PlanOperationRef, PlanOptions, PlanSummary, PlanningOutcome, ValidationSummary,
+    apply_input_defaults, missing_inputs, plan_document, plan_document_with, plan_from_str,
+    workflow_dependency_graph, DependencyGraph, Plan, PlanFormat, PlanIntentStep, PlanOperationRef,
+    PlanOptions, PlanSummary, PlanningOutcome, ValidationSummary,
 };
+#[cfg(feature = "schema")]
+pub use crate::schema::document_json_schema;
 pub use crate::types::ArazzoDocument;
-pub use crate::validate::{validate_document, Validate};
+pub use crate::validate::{
+    document_warnings, document_warnings_with, validate_document, validate_document_with,
+    validation_report, DeprecatedField, RuleLevel, RuleOverride, Validate, ValidationOptions,
+    ValidationReport, VersionedField,
+};
+#[cfg(feature = "json-schema-validation")]
+pub use crate::validate::{
+    json_schema_violations, validation_report_with_schema, ARAZZO_JSON_SCHEMA,
+};
+pub use crate::version::{SpecVersion, VersionError};
@@ -0,0 +1,106 @@
+//! Lint rules: findings about documents that are valid but probably not what the
+//! author meant, such as outputs nobody reads. Linting is separate from validation
+//! and never makes a document invalid; each finding names the [`LintRule`] behind it.
+
+mod rules;
+
+use serde::Serialize;
+
+use crate::error::Severity;
+use crate::expressions::TemplateDelimiters;
+use crate::types::ArazzoDocument;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// A parameter in `components.parameters` that no step or workflow references.
+    UnusedComponentParameter,
+    /// A step output that no expression in its workflow reads.
+    UnusedStepOutput,
+    /// A step that control flow never reaches: every path to it ends the workflow
+    /// or jumps past it with an unconditional `end` or `goto` action.
+    UnreachableStep,
+    /// A `dependsOn` workflow whose outputs the depending workflow never reads.
+    UnusedDependsOn,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 4] = [
+        LintRule::UnusedComponentParameter,
+        LintRule::UnusedStepOutput,
+        LintRule::UnreachableStep,
+        LintRule::UnusedDependsOn,
+    ];
+
+    /// The rule id, e.g. `unused-step-output`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            LintRule::UnusedComponentParameter => "unused-component-parameter",
+            LintRule::UnusedStepOutput => "unused-step-output",
+            LintRule::UnreachableStep => "unreachable-step",
+            LintRule::UnusedDependsOn => "unused-depends-on",
+        }
+    }
+
+    /// Unreachable steps are errors; everything else is a warning.
+    pub fn severity(&self) -> Severity {
+        match self {
+            LintRule::UnreachableStep => Severity::Error,
+            LintRule::UnusedComponentParameter
+            | LintRule::UnusedStepOutput
+            | LintRule::UnusedDependsOn => Severity::Warning,
+        }
+    }
+}
+
+impl std::fmt::Display for LintRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl std::str::FromStr for LintRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LintRule::ALL
+            .into_iter()
+            .find(|r| r.id() == s)
+            .ok_or_else(|| format!("unknown lint rule '{s}'"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintDiagnostic {
+    pub rule: LintRule,
+    pub severity: Severity,
+    /// Path to the offending node, e.g. `$.workflows[0].steps[1].outputs.id`.
+    pub path: String,
+    pub message: String,
+}
+
+impl LintDiagnostic {
+    fn new(rule: LintRule, path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            severity: rule.severity(),
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Run every lint rule over `doc`. Diagnostics come in document order, components
+/// last.
+pub fn lint_document(doc: &ArazzoDocument) -> Vec<LintDiagnostic> {
+    let delimiters = TemplateDelimiters::from_extensions(&doc.extensions);
+    let mut out = Vec::new();
+    for (wi, wf) in doc.workflows.iter().enumerate() {
+        let base = format!("$.workflows[{wi}]");
+        rules::unused_depends_on(wf, &delimiters, &base, &mut out);
+        rules::unreachable_steps(doc, wf, &base, &mut out);
+        rules::unused_step_outputs(wf, &delimiters, &base, &mut out);
+    }
+    rules::unused_component_parameters(doc, &delimiters, &mut out);
+    out
+}
@@ -0,0 +1,251 @@
+use std::collections::BTreeSet;
+
+use super::{LintDiagnostic, LintRule};
+use crate::expressions::{RuntimeExpr, TemplateDelimiters};
+use crate::planner::for_each_referenced_expression;
+use crate::types::{
+    ArazzoDocument, FailureActionOrReusable, FailureActionType, SuccessActionOrReusable,
+    SuccessActionType, Workflow,
+};
+
+pub(super) fn unused_depends_on(
+    wf: &Workflow,
+    delimiters: &TemplateDelimiters,
+    base: &str,
+    out: &mut Vec<LintDiagnostic>,
+) {
+    let Some(depends_on) = &wf.depends_on else {
+        return;
+    };
+    let mut referenced = BTreeSet::new();
+    for_each_expression(wf, delimiters, &mut |expr| {
+        if let RuntimeExpr::Workflows(np) = expr {
+            referenced.insert(np.root.clone());
+        }
+    });
+    for (i, id) in depends_on.iter().enumerate() {
+        // `$sourceDescriptions.<name>.<workflowId>` dependencies live in another document.
+        if id.starts_with('$') || referenced.contains(id) {
+            continue;
+        }
+        out.push(LintDiagnostic::new(
+            LintRule::UnusedDependsOn,
+            format!("{base}.dependsOn[{i}]"),
+            format!("workflow '{id}' is a dependency but $workflows.{id} is never referenced"),
+        ));
+    }
+}
+
+pub(super) fn unused_step_outputs(
+    wf: &Workflow,
+    delimiters: &TemplateDelimiters,
+    base: &str,
+    out: &mut Vec<LintDiagnostic>,
+) {
+    // Per step id: `None` when all its outputs are read at once (`$steps.x.outputs`).
+    let mut referenced: Vec<(String, Option<String>)> = Vec::new();
+    for_each_expression(wf, delimiters, &mut |expr| {
+        let RuntimeExpr::Steps(np) = expr else {
+            return;
+        };
+        if let Some((field, tail)) = np.rest.split_first() {
+            if field == "outputs" {
+                let tail = (!tail.is_empty()).then(|| tail.join("."));
+                referenced.push((np.root.clone(), tail));
+            }
+        }
+    });
+
+    for (si, step) in wf.steps.iter().enumerate() {
+        for name in step.outputs.iter().flatten().map(|(name, _)| name) {
+            let used = referenced.iter().any(|(id, tail)| {
+                *id == step.step_id && tail.as_deref().map_or(true, |t| names(t, name))
+            });
+            if !used {
+                out.push(LintDiagnostic::new(
+                    LintRule::UnusedStepOutput,
+                    format!("{base}.steps[{si}].outputs.{name}"),
+                    format!(
+                        "output '{name}' of step '{}' is never referenced",
+                        step.step_id
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+pub(super) fn unused_component_parameters(
+    doc: &ArazzoDocument,
+    delimiters: &TemplateDelimiters,
+    out: &mut Vec<LintDiagnostic>,
+) {
+    let Some(parameters) = doc.components.as_ref().and_then(|c| c.parameters.as_ref()) else {
+        return;
+    };
+    let mut referenced = Vec::new();
+    for wf in &doc.workflows {
+        for_each_expression(wf, delimiters, &mut |expr| {
+            if let RuntimeExpr::ComponentsParameters(name) = expr {
+                referenced.push(name.clone());
+            }
+        });
+    }
+    for name in parameters.keys() {
+        if !referenced.iter().any(|r| names(r, name)) {
+            out.push(LintDiagnostic::new(
+                LintRule::UnusedComponentParameter,
+                format!("$.components.parameters.{name}"),
+                format!("component parameter '{name}' is never referenced"),
+            ));
+        }
+    }
+}
+
+/// Control flow follows the spec: a step falls through to the next one unless an
+/// unconditional success action ends the workflow or jumps elsewhere, and `goto`
+/// actions make their target reachable. Workflow-level actions apply to every step
+/// without `onSuccess`/`onFailure` actions of its own.
+pub(super) fn unreachable_steps(
+    doc: &ArazzoDocument,
+    wf: &Workflow,
+    base: &str,
+    out: &mut Vec<LintDiagnostic>,
+) {
+    let index = |id: &str| wf.steps.iter().position(|s| s.step_id == id);
+    let mut reached = vec![false; wf.steps.len()];
+    let mut queue = Vec::new();
+    if !wf.steps.is_empty() {
+        reached[0] = true;
+        queue.push(0);
+    }
+
+    while let Some(i) = queue.pop() {
+        let step = &wf.steps[i];
+        let mut next = Vec::new();
+        let mut falls_through = true;
+
+        let on_success = match step.on_success.as_deref() {
+            Some(actions) if !actions.is_empty() => actions,
+            _ => wf.success_actions.as_deref().unwrap_or_default(),
+        };
+        for action in on_success {
+            let Some((action_type, step_id, unconditional)) = resolve_success(doc, action) else {
+                continue;
+            };
+            if let Some(target) = step_id.filter(|_| action_type == SuccessActionType::Goto) {
+                next.extend(index(target));
+            }
+            if unconditional {
+                falls_through = false;
+            }
+        }
+
+        let on_failure = match step.on_failure.as_deref() {
+            Some(actions) if !actions.is_empty() => actions,
+            _ => wf.failure_actions.as_deref().unwrap_or_default(),
+        };
+        for action in on_failure {
+            if let Some(target) = resolve_failure_goto(doc, action) {
+                next.extend(index(target));
+            }
+        }
+
+        if falls_through && i + 1 < wf.steps.len() {
+            next.push(i + 1);
+        }
+        for j in next {
+            if !reached[j] {
+                reached[j] = true;
+                queue.push(j);
+            }
+        }
+    }
+
+    for (si, step) in wf.steps.iter().enumerate() {
+        if !reached[si] {
+            out.push(LintDiagnostic::new(
+                LintRule::UnreachableStep,
+                format!("{base}.steps[{si}]"),
+                format!(
+                    "step '{}' is never reached: every step before it ends the workflow or jumps past it",
+                    step.step_id
+                ),
+            ));
+        }
+    }
+}
+
+/// Type, target step and whether the action has no criteria. `None` for a reusable
+/// action that does not resolve.
+fn resolve_success<'d>(
+    doc: &'d ArazzoDocument,
+    action: &'d SuccessActionOrReusable,
+) -> Option<(SuccessActionType, Option<&'d str>, bool)> {
+    let action = match action {
+        SuccessActionOrReusable::Action(a) => a,
+        SuccessActionOrReusable::Reusable(r) => {
+            let name = r.reference.strip_prefix("$components.successActions.")?;
+            doc.components
+                .as_ref()?
+                .success_actions
+                .as_ref()?
+                .get(name)?
+        }
+    };
+    let unconditional = action.criteria.as_ref().map_or(true, Vec::is_empty);
+    Some((
+        action.action_type.clone(),
+        action.step_id.as_deref(),
+        unconditional,
+    ))
+}
+
+fn resolve_failure_goto<'d>(
+    doc: &'d ArazzoDocument,
+    action: &'d FailureActionOrReusable,
+) -> Option<&'d str> {
+    let action = match action {
+        FailureActionOrReusable::Action(a) => a,
+        FailureActionOrReusable::Reusable(r) => {
+            let name = r.reference.strip_prefix("$components.failureActions.")?;
+            doc.components
+                .as_ref()?
+                .failure_actions
+                .as_ref()?
+                .get(name)?
+        }
+    };
+    if action.action_type != FailureActionType::Goto {
+        return None;
+    }
+    action.step_id.as_deref()
+}
+
+/// Whether the reference tail `tail` (which may continue into the value, as in
+/// `token.value`) names `name`.
+fn names(tail: &str, name: &str) -> bool {
+    tail.strip_prefix(name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Call `f` with every runtime expression in the strings of `wf`.
+fn for_each_expression(
+    wf: &Workflow,
+    delimiters: &TemplateDelimiters,
+    f: &mut impl FnMut(&RuntimeExpr),
+) {
+    let value = serde_json::to_value(wf).unwrap_or_default();
+    for_each_string(&value, &mut |s| {
+        for_each_referenced_expression(s, delimiters, f)
+    });
+}
+
+fn for_each_string(value: &serde_json::Value, f: &mut impl FnMut(&str)) {
+    match value {
+        serde_json::Value::String(s) => f(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| for_each_string(v, f)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| for_each_string(v, f)),
+        _ => {}
+    }
+}
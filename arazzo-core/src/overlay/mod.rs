@@ -0,0 +1,232 @@
+//! Overlays: patches that derive an environment-specific variant of a document, in
+//! the shape of OpenAPI Overlay 1.0 documents.
+//!
+//! ```yaml
+//! overlay: 1.0.0
+//! info:
+//!   title: Staging
+//!   version: 1.0.0
+//! actions:
+//!   - target: $.sourceDescriptions[?(@.name == 'petStore')]
+//!     update:
+//!       url: https://staging.example.com/openapi.yaml
+//!   - target: $.workflows[?(@.workflowId == 'pets')].steps[?(@.stepId == 'audit')]
+//!     remove: true
+//! ```
+//!
+//! Actions apply in order. An `update` is merged into every node the target selects:
+//! objects are merged recursively, a selected array gets the value appended, and any
+//! other node is replaced. `remove: true` deletes the selected nodes. Unlike OpenAPI Overlays,
+//! a target that selects nothing is an error, so a stale overlay cannot silently stop
+//! applying.
+
+pub(crate) mod target;
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use crate::error::ParseError;
+use crate::parser::{from_value, parse_value};
+use crate::types::{ArazzoDocument, Extensions};
+
+use self::target::{Key, Target};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Overlay {
+    /// Overlay specification version; only `1.0.x` is supported.
+    pub overlay: String,
+    pub info: OverlayInfo,
+    /// The document the overlay was written for. Informational only.
+    #[serde(default)]
+    pub extends: Option<String>,
+    pub actions: Vec<OverlayAction>,
+    #[serde(flatten, default)]
+    pub extensions: Extensions,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OverlayInfo {
+    pub title: String,
+    pub version: String,
+    #[serde(flatten, default)]
+    pub extensions: Extensions,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OverlayAction {
+    /// JSONPath selecting the nodes to change, e.g.
+    /// `$.workflows[?(@.workflowId == 'pets')].inputs`.
+    pub target: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub update: Option<JsonValue>,
+    #[serde(default)]
+    pub remove: bool,
+    #[serde(flatten, default)]
+    pub extensions: Extensions,
+}
+
+#[derive(Debug, Error)]
+pub enum OverlayError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("invalid overlay: {node}: {source}")]
+    Overlay {
+        node: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A key that is neither an overlay field nor an `x-` extension.
+    #[error("invalid overlay: unknown field {0}")]
+    UnknownField(String),
+    #[error("unsupported overlay version '{0}' (expected 1.0.x)")]
+    Version(String),
+    #[error("actions[{index}]: invalid target '{target}': {reason}")]
+    Target {
+        index: usize,
+        target: String,
+        reason: String,
+    },
+    #[error("actions[{index}]: target '{target}' selects nothing")]
+    NoMatch { index: usize, target: String },
+    #[error("actions[{index}]: {reason}")]
+    Action { index: usize, reason: String },
+    /// The patched document is no longer an Arazzo document.
+    #[error("overlay produced an invalid document: {node}: {source}")]
+    Document {
+        node: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Parse an overlay document (JSON or YAML).
+pub fn parse_overlay_str(input: &str) -> Result<Overlay, OverlayError> {
+    let overlay: Overlay = from_value(parse_value(input)?)
+        .map_err(|(source, node)| OverlayError::Overlay { node, source })?;
+    if !(overlay.overlay == "1.0" || overlay.overlay.starts_with("1.0.")) {
+        return Err(OverlayError::Version(overlay.overlay));
+    }
+    let objects = [
+        ("$".to_string(), &overlay.extensions),
+        ("$.info".to_string(), &overlay.info.extensions),
+    ]
+    .into_iter()
+    .chain(
+        overlay
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (format!("$.actions[{i}]"), &a.extensions)),
+    );
+    for (path, extensions) in objects {
+        if let Some(key) = extensions.keys().find(|k| !k.starts_with("x-")) {
+            return Err(OverlayError::UnknownField(format!("{path}.{key}")));
+        }
+    }
+    Ok(overlay)
+}
+
+/// Apply every action of `overlay` to a copy of `doc`.
+pub fn apply_overlay(
+    doc: &ArazzoDocument,
+    overlay: &Overlay,
+) -> Result<ArazzoDocument, OverlayError> {
+    let mut value = serde_json::to_value(doc).map_err(|source| OverlayError::Document {
+        node: "$".to_string(),
+        source,
+    })?;
+    for (index, action) in overlay.actions.iter().enumerate() {
+        apply_action(&mut value, index, action)?;
+    }
+    from_value(value).map_err(|(source, node)| OverlayError::Document { node, source })
+}
+
+fn apply_action(
+    root: &mut JsonValue,
+    index: usize,
+    action: &OverlayAction,
+) -> Result<(), OverlayError> {
+    let target = Target::parse(&action.target).map_err(|reason| OverlayError::Target {
+        index,
+        target: action.target.clone(),
+        reason,
+    })?;
+    let mut paths = target.select(root);
+    if paths.is_empty() {
+        return Err(OverlayError::NoMatch {
+            index,
+            target: action.target.clone(),
+        });
+    }
+
+    match (&action.update, action.remove) {
+        (None, true) => {
+            // Later siblings first, so removing one does not shift the indices of the rest.
+            paths.sort();
+            for path in paths.iter().rev() {
+                let Some((last, parent)) = path.split_last() else {
+                    return Err(OverlayError::Action {
+                        index,
+                        reason: "cannot remove the document root".to_string(),
+                    });
+                };
+                match (node_mut(root, parent), last) {
+                    (Some(JsonValue::Object(map)), Key::Name(name)) => {
+                        map.remove(name);
+                    }
+                    (Some(JsonValue::Array(items)), Key::Index(i)) => {
+                        items.remove(*i);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (Some(update), false) => {
+            for path in &paths {
+                if let Some(node) = node_mut(root, path) {
+                    merge(node, update);
+                }
+            }
+        }
+        (Some(_), true) => {
+            return Err(OverlayError::Action {
+                index,
+                reason: "update and remove are mutually exclusive".to_string(),
+            })
+        }
+        (None, false) => {
+            return Err(OverlayError::Action {
+                index,
+                reason: "needs either update or remove: true".to_string(),
+            })
+        }
+    }
+    Ok(())
+}
+
+fn node_mut<'a>(root: &'a mut JsonValue, path: &[Key]) -> Option<&'a mut JsonValue> {
+    path.iter().try_fold(root, |node, key| match key {
+        Key::Name(name) => node.get_mut(name),
+        Key::Index(i) => node.get_mut(i),
+    })
+}
+
+fn merge(node: &mut JsonValue, update: &JsonValue) {
+    match (node, update) {
+        (JsonValue::Object(map), JsonValue::Object(patch)) => {
+            for (key, value) in patch {
+                match map.get_mut(key) {
+                    Some(existing @ JsonValue::Object(_)) => merge(existing, value),
+                    _ => {
+                        map.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (JsonValue::Array(items), value) => items.push(value.clone()),
+        (node, value) => *node = value.clone(),
+    }
+}
@@ -0,0 +1,193 @@
+//! The JSONPath subset accepted in overlay targets: `$`, child names (`.name`,
+//! `['name']`), indices (`[0]`), wildcards (`.*`, `[*]`) and equality filters on a
+//! child field (`[?(@.stepId == 'audit')]`, `!=` also works).
+
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Name(String),
+    Index(usize),
+    Wildcard,
+    Filter {
+        field: Vec<String>,
+        equal: bool,
+        value: JsonValue,
+    },
+}
+
+/// A step from a node to one of its children.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Key {
+    Name(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Target {
+    segments: Vec<Segment>,
+}
+
+impl Target {
+    pub(crate) fn parse(input: &str) -> Result<Self, String> {
+        let mut rest = input
+            .trim()
+            .strip_prefix('$')
+            .ok_or("must start with '$'")?;
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix('.') {
+                let end = tail.find(['.', '[']).unwrap_or(tail.len());
+                let name = &tail[..end];
+                segments.push(match name {
+                    "" => return Err("empty name after '.'".into()),
+                    "*" => Segment::Wildcard,
+                    _ => Segment::Name(name.to_string()),
+                });
+                rest = &tail[end..];
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let (inner, after) = split_bracket(tail)?;
+                segments.push(bracket(inner.trim())?);
+                rest = after;
+            } else {
+                return Err(format!("unexpected '{rest}'"));
+            }
+        }
+        Ok(Self { segments })
+    }
+
+    /// The target without its last segment, when that segment is a child name or
+    /// index, and the key it names.
+    pub(crate) fn split_child(&self) -> Option<(Target, Key)> {
+        let (last, parent) = self.segments.split_last()?;
+        let key = match last {
+            Segment::Name(name) => Key::Name(name.clone()),
+            Segment::Index(i) => Key::Index(*i),
+            _ => return None,
+        };
+        let parent = Target {
+            segments: parent.to_vec(),
+        };
+        Some((parent, key))
+    }
+
+    /// Paths of every node of `root` the target selects, in document order.
+    pub(crate) fn select(&self, root: &JsonValue) -> Vec<Vec<Key>> {
+        let mut current = vec![(Vec::new(), root)];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for (path, node) in current {
+                for (key, child) in children(node) {
+                    if segment.matches(&key, child) {
+                        let mut path = path.clone();
+                        path.push(key);
+                        next.push((path, child));
+                    }
+                }
+            }
+            current = next;
+        }
+        current.into_iter().map(|(path, _)| path).collect()
+    }
+}
+
+impl Segment {
+    fn matches(&self, key: &Key, child: &JsonValue) -> bool {
+        match (self, key) {
+            (Segment::Name(name), Key::Name(k)) => name == k,
+            (Segment::Index(i), Key::Index(k)) => i == k,
+            (Segment::Wildcard, _) => true,
+            (
+                Segment::Filter {
+                    field,
+                    equal,
+                    value,
+                },
+                _,
+            ) => {
+                let found = field.iter().try_fold(child, |node, name| node.get(name));
+                (found == Some(value)) == *equal
+            }
+            _ => false,
+        }
+    }
+}
+
+fn children(node: &JsonValue) -> Vec<(Key, &JsonValue)> {
+    match node {
+        JsonValue::Object(map) => map.iter().map(|(k, v)| (Key::Name(k.clone()), v)).collect(),
+        JsonValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (Key::Index(i), v))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Split `tail` (just past a `[`) at its closing `]`, skipping brackets inside quotes.
+fn split_bracket(tail: &str) -> Result<(&str, &str), String> {
+    let mut quote = None;
+    for (i, c) in tail.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, ']') => return Ok((&tail[..i], &tail[i + 1..])),
+            _ => {}
+        }
+    }
+    Err("unclosed '['".into())
+}
+
+fn bracket(inner: &str) -> Result<Segment, String> {
+    if inner == "*" {
+        return Ok(Segment::Wildcard);
+    }
+    if let Some(filter) = inner.strip_prefix('?') {
+        return parse_filter(filter.trim());
+    }
+    if let Some(name) = unquote(inner) {
+        return Ok(Segment::Name(name.to_string()));
+    }
+    inner
+        .parse()
+        .map(Segment::Index)
+        .map_err(|_| format!("unsupported selector '[{inner}]'"))
+}
+
+fn parse_filter(filter: &str) -> Result<Segment, String> {
+    let expr = filter
+        .strip_prefix('(')
+        .and_then(|f| f.strip_suffix(')'))
+        .unwrap_or(filter)
+        .trim();
+    let (lhs, rhs, equal) = if let Some((l, r)) = expr.split_once("==") {
+        (l, r, true)
+    } else if let Some((l, r)) = expr.split_once("!=") {
+        (l, r, false)
+    } else {
+        return Err(format!(
+            "unsupported filter '{filter}': expected @.field == value"
+        ));
+    };
+    let field = lhs
+        .trim()
+        .strip_prefix("@.")
+        .ok_or_else(|| format!("unsupported filter '{filter}': expected @.field == value"))?;
+    let rhs = rhs.trim();
+    let value = match unquote(rhs) {
+        Some(s) => JsonValue::String(s.to_string()),
+        None => serde_json::from_str(rhs).map_err(|_| format!("invalid filter value '{rhs}'"))?,
+    };
+    Ok(Segment::Filter {
+        field: field.split('.').map(String::from).collect(),
+        equal,
+        value,
+    })
+}
+
+fn unquote(s: &str) -> Option<&str> {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| s.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+}
@@ -1,10 +1,22 @@
-use crate::error::ParseError;
+mod strict;
+mod summary;
+mod track;
+
+use serde::de::DeserializeOwned;
+
+use crate::env::{interpolate_env, EnvReport};
+use crate::error::{ErrorLocation, ParseError};
 use crate::types::ArazzoDocument;
+use crate::version::SpecVersion;
+
+pub use summary::{parse_workflow_summaries, WorkflowSummary};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DocumentFormat {
     Json,
     Yaml,
+    /// Never auto-detected: callers choose it, e.g. from a `.toml` file extension.
+    Toml,
     Auto,
 }
 
@@ -12,70 +24,193 @@ pub enum DocumentFormat {
 pub struct ParsedDocument {
     pub document: ArazzoDocument,
     pub format: DocumentFormat,
+    /// The declared `arazzo` version, or `None` when it is not a valid version.
+    /// Validation reports why.
+    pub spec_version: Option<SpecVersion>,
+    /// Environment placeholders expanded while loading, when
+    /// [`ParseOptions::interpolate_env`] is set.
+    pub env: Option<EnvReport>,
+}
+
+impl ParsedDocument {
+    fn new(document: ArazzoDocument, format: DocumentFormat) -> Self {
+        Self {
+            spec_version: document.spec_version(),
+            document,
+            format,
+            env: None,
+        }
+    }
+}
+
+/// Options for [`parse_document_str_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Reject keys that are neither fields of their object nor `x-` extensions,
+    /// e.g. a misspelled `succesCriteria`. Without it they are kept as extensions
+    /// and only reported by validation.
+    pub strict: bool,
+    /// Expand `${VAR}` placeholders in source description URLs and parameter values
+    /// from the process environment. Unset variables are left in place and listed in
+    /// [`ParsedDocument::env`].
+    pub interpolate_env: bool,
 }
 
 pub fn parse_document_str(
     input: &str,
     format: DocumentFormat,
 ) -> Result<ParsedDocument, ParseError> {
+    parse_document_str_with(input, format, &ParseOptions::default())
+}
+
+/// Like [`parse_document_str`], with [`ParseOptions`]. In strict mode every unknown
+/// key is returned in [`ParseError::UnknownFields`].
+pub fn parse_document_str_with(
+    input: &str,
+    format: DocumentFormat,
+    options: &ParseOptions,
+) -> Result<ParsedDocument, ParseError> {
+    let mut parsed = parse_format(input, format)?;
+    if options.interpolate_env {
+        parsed.env = Some(interpolate_env(&mut parsed.document));
+    }
+    if options.strict {
+        let unknown = strict::unknown_fields(&parsed.document);
+        if !unknown.is_empty() {
+            return Err(ParseError::UnknownFields(unknown));
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_format(input: &str, format: DocumentFormat) -> Result<ParsedDocument, ParseError> {
     match format {
-        DocumentFormat::Json => Ok(ParsedDocument {
-            document: serde_json::from_str::<ArazzoDocument>(input)?,
-            format,
-        }),
-        DocumentFormat::Yaml => Ok(ParsedDocument {
-            document: serde_yaml::from_str::<ArazzoDocument>(input)?,
-            format,
-        }),
+        DocumentFormat::Json => Ok(ParsedDocument::new(from_json(input, "")?, format)),
+        DocumentFormat::Yaml => Ok(ParsedDocument::new(from_yaml(input)?, format)),
+        DocumentFormat::Toml => Ok(ParsedDocument::new(from_toml(input)?, format)),
         DocumentFormat::Auto => parse_document_auto(input),
     }
 }
 
 fn parse_document_auto(input: &str) -> Result<ParsedDocument, ParseError> {
-    // Heuristic: JSON always starts with `{` or `[` after trimming.
-    let trimmed = input.trim_start();
-    if trimmed.starts_with('{') || trimmed.starts_with('[') {
-        match serde_json::from_str::<ArazzoDocument>(input) {
-            Ok(doc) => {
-                return Ok(ParsedDocument {
-                    document: doc,
-                    format: DocumentFormat::Json,
-                });
-            }
-            Err(e) => {
-                // If JSON parsing fails, try YAML as fallback
-                match serde_yaml::from_str::<ArazzoDocument>(input) {
-                    Ok(doc) => {
-                        return Ok(ParsedDocument {
-                            document: doc,
-                            format: DocumentFormat::Yaml,
-                        });
-                    }
-                    Err(_) => {
-                        // Return JSON error since we tried JSON first
-                        return Err(ParseError::Json(e));
-                    }
-                }
-            }
-        }
+    let (document, format) = parse_auto(input)?;
+    Ok(ParsedDocument::new(document, format))
+}
+
+/// Parse `input` as a plain value in `format`, without reading it into the document
+/// model. This is the input [`crate::validation_report_with_schema`] checks.
+pub fn parse_value_str(
+    input: &str,
+    format: DocumentFormat,
+) -> Result<serde_json::Value, ParseError> {
+    match format {
+        DocumentFormat::Json => from_json(input, ""),
+        DocumentFormat::Yaml => from_yaml(input),
+        DocumentFormat::Toml => from_toml(input),
+        DocumentFormat::Auto => parse_value(input),
+    }
+}
+
+/// Parse `input` as any JSON or YAML value, detecting the format like
+/// [`DocumentFormat::Auto`].
+pub(crate) fn parse_value(input: &str) -> Result<serde_json::Value, ParseError> {
+    parse_auto(input).map(|(value, _)| value)
+}
+
+/// Deserialize an already parsed value, returning the path of the offending node
+/// on error.
+pub(crate) fn from_value<T: DeserializeOwned>(
+    value: serde_json::Value,
+) -> Result<T, (serde_json::Error, String)> {
+    track::deserialize(value)
+}
+
+fn parse_auto<T: DeserializeOwned>(input: &str) -> Result<(T, DocumentFormat), ParseError> {
+    // Heuristic: JSON always starts with `{` or `[` once leading comments are skipped.
+    let content = skip_leading_comments(input);
+    if content.starts_with('{') || content.starts_with('[') {
+        let skipped = &input[..input.len() - content.len()];
+        return match from_json(content, skipped) {
+            Ok(doc) => Ok((doc, DocumentFormat::Json)),
+            // If JSON parsing fails, try YAML as fallback, but report the JSON error
+            // since we tried JSON first.
+            Err(e) => from_yaml(content)
+                .map(|doc| (doc, DocumentFormat::Yaml))
+                .map_err(|_| e),
+        };
+    }
+
+    // Try YAML first for non-JSON-looking input, falling back to JSON; a failure
+    // reports the YAML error.
+    match from_yaml(input) {
+        Ok(doc) => Ok((doc, DocumentFormat::Yaml)),
+        Err(e) => from_json(input, "")
+            .map(|doc| (doc, DocumentFormat::Json))
+            .map_err(|_| e),
     }
+}
+
+/// Parse JSON `input` that followed `skipped` in the original text, so reported
+/// positions refer to the original text.
+fn from_json<T: DeserializeOwned>(input: &str, skipped: &str) -> Result<T, ParseError> {
+    let mut de = serde_json::Deserializer::from_str(input);
+    track::deserialize(&mut de)
+        .and_then(|doc| de.end().map(|()| doc).map_err(|e| (e, "$".to_string())))
+        .map_err(|(source, path)| {
+            // serde_json reports line 0 when it has no position (I/O errors only).
+            let location = (source.line() > 0).then(|| {
+                let first_line = skipped.rfind('\n').map_or(skipped, |i| &skipped[i + 1..]);
+                ErrorLocation {
+                    line: source.line() + skipped.matches('\n').count(),
+                    column: if source.line() == 1 {
+                        source.column() + first_line.len()
+                    } else {
+                        source.column()
+                    },
+                    path,
+                }
+            });
+            ParseError::Json { source, location }
+        })
+}
+
+fn from_yaml<T: DeserializeOwned>(input: &str) -> Result<T, ParseError> {
+    track::deserialize(serde_yaml::Deserializer::from_str(input)).map_err(|(source, path)| {
+        let location = source.location().map(|l| ErrorLocation {
+            line: l.line(),
+            column: l.column(),
+            path,
+        });
+        ParseError::Yaml { source, location }
+    })
+}
 
-    // Try YAML first for non-JSON-looking input
-    match serde_yaml::from_str::<ArazzoDocument>(input) {
-        Ok(doc) => Ok(ParsedDocument {
-            document: doc,
-            format: DocumentFormat::Yaml,
-        }),
-        Err(e) => {
-            // If YAML fails, try JSON as fallback
-            if let Ok(doc) = serde_json::from_str::<ArazzoDocument>(input) {
-                return Ok(ParsedDocument {
-                    document: doc,
-                    format: DocumentFormat::Json,
-                });
+fn from_toml<T: DeserializeOwned>(input: &str) -> Result<T, ParseError> {
+    track::deserialize(toml::Deserializer::new(input)).map_err(|(source, path)| {
+        let location = source.span().map(|span| {
+            let before = &input[..span.start];
+            ErrorLocation {
+                line: before.matches('\n').count() + 1,
+                column: before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1,
+                path,
             }
-            // Return YAML error since we tried YAML first
-            Err(ParseError::Yaml(e))
+        });
+        ParseError::Toml {
+            source: Box::new(source),
+            location,
         }
+    })
+}
+
+/// Skips leading whitespace and full-line `#` (including a `#!` shebang) or `//` comments,
+/// returning the input from the first line of actual content.
+fn skip_leading_comments(input: &str) -> &str {
+    let mut rest = input.trim_start();
+    while rest.starts_with('#') || rest.starts_with("//") {
+        rest = match rest.find('\n') {
+            Some(idx) => rest[idx + 1..].trim_start(),
+            None => "",
+        };
     }
+    rest
 }
@@ -0,0 +1,18 @@
+use crate::error::Violation;
+use crate::extensions::for_each_extensions;
+use crate::types::ArazzoDocument;
+use crate::validate::UNKNOWN_FIELD_MESSAGE;
+
+/// Every key that landed in an extensions map without the `x-` prefix, i.e. a field
+/// the object does not define. Paths use the same form as validation violations.
+pub(crate) fn unknown_fields(doc: &ArazzoDocument) -> Vec<Violation> {
+    let mut out = Vec::new();
+    for_each_extensions(doc, |path, ext| {
+        out.extend(
+            ext.keys()
+                .filter(|key| !key.starts_with("x-"))
+                .map(|key| Violation::new(format!("{path}.{key}"), UNKNOWN_FIELD_MESSAGE)),
+        );
+    });
+    out
+}
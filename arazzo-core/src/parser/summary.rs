@@ -0,0 +1,69 @@
+//! A partial parse that reads only what a workflow listing needs. Steps are counted
+//! and skipped without being deserialized, and every other field is ignored.
+
+use std::fmt;
+
+use serde::de::{Deserializer, IgnoredAny, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use super::{from_json, from_toml, from_yaml, parse_auto, DocumentFormat};
+use crate::error::ParseError;
+
+/// The identity of one workflow, as read by [`parse_workflow_summaries`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WorkflowSummary {
+    #[serde(rename = "workflowId")]
+    pub workflow_id: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "steps", default, deserialize_with = "count_items")]
+    pub step_count: usize,
+}
+
+#[derive(Deserialize)]
+struct Outline {
+    #[serde(default)]
+    workflows: Vec<WorkflowSummary>,
+}
+
+/// Read the id, summary, description and step count of every workflow in `input`
+/// without building the full document. Much cheaper than
+/// [`parse_document_str`](super::parse_document_str) on large documents, but it does
+/// not check anything beyond those fields: a document with malformed steps still
+/// lists.
+pub fn parse_workflow_summaries(
+    input: &str,
+    format: DocumentFormat,
+) -> Result<Vec<WorkflowSummary>, ParseError> {
+    let outline: Outline = match format {
+        DocumentFormat::Json => from_json(input, "")?,
+        DocumentFormat::Yaml => from_yaml(input)?,
+        DocumentFormat::Toml => from_toml(input)?,
+        DocumentFormat::Auto => parse_auto(input)?.0,
+    };
+    Ok(outline.workflows)
+}
+
+fn count_items<'de, D: Deserializer<'de>>(de: D) -> Result<usize, D::Error> {
+    struct Count;
+
+    impl<'de> Visitor<'de> for Count {
+        type Value = usize;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a sequence")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<usize, A::Error> {
+            let mut count = 0;
+            while seq.next_element::<IgnoredAny>()?.is_some() {
+                count += 1;
+            }
+            Ok(count)
+        }
+    }
+
+    de.deserialize_seq(Count)
+}
@@ -0,0 +1,498 @@
+//! A deserializer wrapper that remembers where in the document an error was raised,
+//! as a violation-style path such as `$.workflows[0].steps[1].stepId`.
+//!
+//! Every wrapped deserializer, visitor and access type carries the path of the node it
+//! is positioned on. The first (innermost) error to pass through records that path;
+//! outer nodes see the error afterwards and leave the recorded path alone.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use serde::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+
+/// Deserialize a `T` from `de`, returning the path of the node that failed alongside
+/// the error.
+pub(crate) fn deserialize<'de, D, T>(de: D) -> Result<T, (D::Error, String)>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let track = Track::default();
+    T::deserialize(Wrap::new(de, &Chain::Root, &track)).map_err(|e| {
+        let path = track
+            .path
+            .into_inner()
+            .unwrap_or_else(|| Chain::Root.render());
+        (e, path)
+    })
+}
+
+enum Chain<'a> {
+    Root,
+    Index { parent: &'a Chain<'a>, index: usize },
+    Key { parent: &'a Chain<'a>, key: String },
+}
+
+impl Chain<'_> {
+    fn render(&self) -> String {
+        match self {
+            Chain::Root => "$".to_string(),
+            Chain::Index { parent, index } => format!("{}[{index}]", parent.render()),
+            Chain::Key { parent, key } => format!("{}.{key}", parent.render()),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Track {
+    path: RefCell<Option<String>>,
+}
+
+impl Track {
+    fn trigger<E>(&self, chain: &Chain<'_>, error: E) -> E {
+        let mut path = self.path.borrow_mut();
+        if path.is_none() {
+            *path = Some(chain.render());
+        }
+        error
+    }
+}
+
+/// Wraps a deserializer, visitor, seed or enum access positioned at `chain`.
+struct Wrap<'a, 'b, X> {
+    delegate: X,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+}
+
+impl<'a, 'b, X> Wrap<'a, 'b, X> {
+    fn new(delegate: X, chain: &'a Chain<'a>, track: &'b Track) -> Self {
+        Self {
+            delegate,
+            chain,
+            track,
+        }
+    }
+}
+
+/// Forward every `Deserializer` method to `self.delegate` through `$call`, which
+/// wraps the visitor.
+macro_rules! forward_deserializer {
+    ($call:ident) => {
+        forward_deserializer! {
+            $call;
+            deserialize_any();
+            deserialize_bool();
+            deserialize_i8();
+            deserialize_i16();
+            deserialize_i32();
+            deserialize_i64();
+            deserialize_i128();
+            deserialize_u8();
+            deserialize_u16();
+            deserialize_u32();
+            deserialize_u64();
+            deserialize_u128();
+            deserialize_f32();
+            deserialize_f64();
+            deserialize_char();
+            deserialize_str();
+            deserialize_string();
+            deserialize_bytes();
+            deserialize_byte_buf();
+            deserialize_option();
+            deserialize_unit();
+            deserialize_unit_struct(name: &'static str);
+            deserialize_newtype_struct(name: &'static str);
+            deserialize_seq();
+            deserialize_tuple(len: usize);
+            deserialize_tuple_struct(name: &'static str, len: usize);
+            deserialize_map();
+            deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+            deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+            deserialize_identifier();
+            deserialize_ignored_any();
+        }
+
+        fn is_human_readable(&self) -> bool {
+            self.delegate.is_human_readable()
+        }
+    };
+    ($call:ident; $($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                $call!(self, $method($($arg),*), visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! tracked_call {
+    ($self:ident, $method:ident($($arg:ident),*), $visitor:ident) => {{
+        let (chain, track) = ($self.chain, $self.track);
+        $self
+            .delegate
+            .$method($($arg,)* Wrap::new($visitor, chain, track))
+            .map_err(|e| track.trigger(chain, e))
+    }};
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Wrap<'_, '_, D> {
+    type Error = D::Error;
+
+    forward_deserializer!(tracked_call);
+}
+
+/// Forward `Visitor` methods that carry plain values.
+macro_rules! forward_visits {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+                self.delegate.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Wrap<'_, '_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.delegate.expecting(f)
+    }
+
+    forward_visits! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.delegate.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.delegate.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.delegate
+            .visit_some(Wrap::new(de, self.chain, self.track))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.delegate
+            .visit_newtype_struct(Wrap::new(de, self.chain, self.track))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.delegate.visit_seq(Seq {
+            delegate: seq,
+            chain: self.chain,
+            track: self.track,
+            index: 0,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.delegate.visit_map(Map {
+            delegate: map,
+            chain: self.chain,
+            track: self.track,
+            key: None,
+        })
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.delegate
+            .visit_enum(Wrap::new(data, self.chain, self.track))
+    }
+}
+
+impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for Wrap<'_, '_, T> {
+    type Value = T::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.delegate
+            .deserialize(Wrap::new(de, self.chain, self.track))
+    }
+}
+
+impl<'a, 'b, 'de, A: EnumAccess<'de>> EnumAccess<'de> for Wrap<'a, 'b, A> {
+    type Error = A::Error;
+    type Variant = Wrap<'a, 'b, A::Variant>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let (chain, track) = (self.chain, self.track);
+        match self.delegate.variant_seed(seed) {
+            Ok((value, variant)) => Ok((value, Wrap::new(variant, chain, track))),
+            Err(e) => Err(track.trigger(chain, e)),
+        }
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for Wrap<'_, '_, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        let (chain, track) = (self.chain, self.track);
+        self.delegate
+            .unit_variant()
+            .map_err(|e| track.trigger(chain, e))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        let (chain, track) = (self.chain, self.track);
+        self.delegate
+            .newtype_variant_seed(Wrap::new(seed, chain, track))
+            .map_err(|e| track.trigger(chain, e))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (chain, track) = (self.chain, self.track);
+        self.delegate
+            .tuple_variant(len, Wrap::new(visitor, chain, track))
+            .map_err(|e| track.trigger(chain, e))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (chain, track) = (self.chain, self.track);
+        self.delegate
+            .struct_variant(fields, Wrap::new(visitor, chain, track))
+            .map_err(|e| track.trigger(chain, e))
+    }
+}
+
+struct Seq<'a, 'b, A> {
+    delegate: A,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+    index: usize,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Seq<'_, '_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let chain = Chain::Index {
+            parent: self.chain,
+            index: self.index,
+        };
+        let track = self.track;
+        self.index += 1;
+        self.delegate
+            .next_element_seed(Wrap::new(seed, &chain, track))
+            .map_err(|e| track.trigger(&chain, e))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.delegate.size_hint()
+    }
+}
+
+struct Map<'a, 'b, A> {
+    delegate: A,
+    chain: &'a Chain<'a>,
+    track: &'b Track,
+    /// The last key read, used to name the value that follows it.
+    key: Option<String>,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Map<'_, '_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.key = None;
+        let (chain, track) = (self.chain, self.track);
+        self.delegate
+            .next_key_seed(CaptureKey {
+                delegate: seed,
+                key: &mut self.key,
+            })
+            .map_err(|e| track.trigger(chain, e))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let track = self.track;
+        let keyed;
+        // Keys that are not scalars have no name to put in the path.
+        let chain = match self.key.take() {
+            Some(key) => {
+                keyed = Chain::Key {
+                    parent: self.chain,
+                    key,
+                };
+                &keyed
+            }
+            None => self.chain,
+        };
+        self.delegate
+            .next_value_seed(Wrap::new(seed, chain, track))
+            .map_err(|e| track.trigger(chain, e))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.delegate.size_hint()
+    }
+}
+
+/// Records the text of a scalar map key while deserializing it.
+struct CaptureKey<'k, X> {
+    delegate: X,
+    key: &'k mut Option<String>,
+}
+
+impl<'de, K: DeserializeSeed<'de>> DeserializeSeed<'de> for CaptureKey<'_, K> {
+    type Value = K::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.delegate.deserialize(CaptureKey {
+            delegate: de,
+            key: self.key,
+        })
+    }
+}
+
+macro_rules! capturing_call {
+    ($self:ident, $method:ident($($arg:ident),*), $visitor:ident) => {
+        $self.delegate.$method(
+            $($arg,)*
+            CaptureKey {
+                delegate: $visitor,
+                key: $self.key,
+            },
+        )
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for CaptureKey<'_, D> {
+    type Error = D::Error;
+
+    forward_deserializer!(capturing_call);
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for CaptureKey<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.delegate.expecting(f)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        *self.key = Some(v.to_string());
+        self.delegate.visit_bool(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        *self.key = Some(v.to_string());
+        self.delegate.visit_i64(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        *self.key = Some(v.to_string());
+        self.delegate.visit_u64(v)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        *self.key = Some(v.to_string());
+        self.delegate.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        *self.key = Some(v.to_string());
+        self.delegate.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        *self.key = Some(v.clone());
+        self.delegate.visit_string(v)
+    }
+
+    forward_visits! {
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.delegate.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.delegate.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.delegate.visit_some(de)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        self.delegate.visit_newtype_struct(de)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.delegate.visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.delegate.visit_map(map)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.delegate.visit_enum(data)
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 use crate::planner::model::DependencyGraph;
-use crate::types::Workflow;
+use crate::types::{ArazzoDocument, Workflow};
 
 pub(crate) fn build_step_dependency_graph(
     workflow: &Workflow,
@@ -32,6 +32,123 @@ pub(crate) fn build_step_dependency_graph(
     })
 }
 
+/// The graph of `dependsOn` edges between the workflows of `doc`. References to
+/// other documents and to unknown workflows are left out; cycles are an error
+/// naming each one, e.g. `["a", "b", "a"]`.
+pub(crate) fn build_workflow_dependency_graph(
+    doc: &ArazzoDocument,
+) -> Result<DependencyGraph, Vec<Vec<String>>> {
+    let depends_on = workflow_edges(doc);
+    let ids: BTreeSet<String> = depends_on.keys().cloned().collect();
+    let topo_order = topo_sort(&ids, &depends_on).map_err(|_| dependency_cycles(&depends_on))?;
+    let levels = compute_levels(&topo_order, &depends_on);
+    Ok(DependencyGraph {
+        depends_on,
+        levels,
+        topo_order,
+    })
+}
+
+/// The workflows `workflow_id` waits for, directly or not, in topological order.
+/// Only the workflows reachable from it are considered, so a cycle elsewhere in the
+/// document does not matter; one among them is the error.
+pub(crate) fn workflow_prerequisites(
+    doc: &ArazzoDocument,
+    workflow_id: &str,
+) -> Result<Vec<String>, Vec<String>> {
+    let edges = workflow_edges(doc);
+    let mut reachable = BTreeSet::from([workflow_id.to_string()]);
+    let mut pending = vec![workflow_id];
+    while let Some(id) = pending.pop() {
+        for dep in edges.get(id).into_iter().flatten() {
+            if reachable.insert(dep.clone()) {
+                pending.push(dep);
+            }
+        }
+    }
+    let depends_on: BTreeMap<String, Vec<String>> = edges
+        .into_iter()
+        .filter(|(id, _)| reachable.contains(id))
+        .collect();
+    let order = topo_sort(&reachable, &depends_on)
+        .map_err(|_| dependency_cycles(&depends_on).swap_remove(0))?;
+    Ok(order.into_iter().filter(|id| id != workflow_id).collect())
+}
+
+/// Each workflow's `dependsOn` entries that name a local workflow, sorted.
+fn workflow_edges(doc: &ArazzoDocument) -> BTreeMap<String, Vec<String>> {
+    let ids: BTreeSet<&str> = doc
+        .workflows
+        .iter()
+        .map(|w| w.workflow_id.as_str())
+        .collect();
+    let mut depends_on: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for wf in &doc.workflows {
+        let mut d: Vec<String> = wf
+            .depends_on
+            .iter()
+            .flatten()
+            .filter(|x| ids.contains(x.as_str()))
+            .cloned()
+            .collect();
+        d.sort();
+        d.dedup();
+        depends_on
+            .entry(wf.workflow_id.clone())
+            .or_default()
+            .extend(d);
+    }
+    depends_on
+}
+
+/// Every cycle in `depends_on`, each starting and ending at its smallest node. A
+/// node is reported in at most one cycle.
+fn dependency_cycles(depends_on: &BTreeMap<String, Vec<String>>) -> Vec<Vec<String>> {
+    let mut reported = BTreeSet::new();
+    let mut cycles = Vec::new();
+    for start in depends_on.keys() {
+        if reported.contains(start) {
+            continue;
+        }
+        if let Some(cycle) = path_back(start, depends_on, &reported) {
+            reported.extend(cycle.iter().cloned());
+            cycles.push(cycle);
+        }
+    }
+    cycles
+}
+
+/// The shortest dependency path from `start` back to itself that avoids `skip`.
+fn path_back(
+    start: &str,
+    depends_on: &BTreeMap<String, Vec<String>>,
+    skip: &BTreeSet<String>,
+) -> Option<Vec<String>> {
+    let mut parent: BTreeMap<&str, &str> = BTreeMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for dep in depends_on.get(node).into_iter().flatten() {
+            if dep == start {
+                let mut path = vec![start.to_string()];
+                let mut at = node;
+                while at != start {
+                    path.push(at.to_string());
+                    at = parent[at];
+                }
+                path[1..].reverse();
+                path.push(start.to_string());
+                return Some(path);
+            }
+            if skip.contains(dep) || parent.contains_key(dep.as_str()) {
+                continue;
+            }
+            parent.insert(dep, node);
+            queue.push_back(dep);
+        }
+    }
+    None
+}
+
 fn topo_sort(
     nodes: &BTreeSet<String>,
     depends_on: &BTreeMap<String, Vec<String>>,
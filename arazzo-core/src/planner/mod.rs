@@ -3,16 +3,21 @@ mod format;
 mod model;
 mod scan;
 
+use std::collections::BTreeSet;
+
 use crate::error::ParseError;
+use crate::expressions::TemplateDelimiters;
 use crate::parser::{parse_document_str, DocumentFormat};
 use crate::types::{ArazzoDocument, Workflow};
-use crate::validate::validate_document;
+use crate::validate::{validation_report, ValidationOptions};
 
+pub(crate) use dependency::build_workflow_dependency_graph;
 pub use format::PlanFormat;
 pub use model::{
     DependencyGraph, Plan, PlanIntentStep, PlanOperationRef, PlanSummary, PlanningOutcome,
     ValidationSummary,
 };
+pub(crate) use scan::for_each_referenced_expression;
 
 #[derive(Debug, Clone, Default)]
 pub struct PlanOptions {
@@ -35,10 +40,17 @@ pub fn plan_document(
     doc: &ArazzoDocument,
     options: PlanOptions,
 ) -> Result<PlanningOutcome, PlannerError> {
-    let validation = match validate_document(doc) {
-        Ok(()) => ValidationSummary::valid(),
-        Err(e) => ValidationSummary::invalid_from(e),
-    };
+    plan_document_with(doc, options, &ValidationOptions::default())
+}
+
+/// Like [`plan_document`], validating with `validation` (e.g. to apply
+/// [`RuleOverride`](crate::validate::RuleOverride)s) before planning.
+pub fn plan_document_with(
+    doc: &ArazzoDocument,
+    options: PlanOptions,
+    validation: &ValidationOptions,
+) -> Result<PlanningOutcome, PlannerError> {
+    let validation = ValidationSummary::from_report(&validation_report(doc, validation));
 
     if !validation.is_valid {
         return Ok(PlanningOutcome {
@@ -55,6 +67,44 @@ pub fn plan_document(
     })
 }
 
+/// The graph of `dependsOn` edges between the workflows of `doc`: which local
+/// workflows each one waits for, in levels and a topological order. References to
+/// other documents (`$sourceDescriptions.*`) and to unknown workflow ids are left out.
+pub fn workflow_dependency_graph(doc: &ArazzoDocument) -> Result<DependencyGraph, PlannerError> {
+    dependency::build_workflow_dependency_graph(doc)
+        .map_err(|mut cycles| PlannerError::WorkflowCycle(cycles.swap_remove(0)))
+}
+
+/// `inputs` with the `default` of every property in `workflow`'s `inputs` schema
+/// filled in where the key is absent, including inside nested objects. Keys that
+/// are present, even as `null`, are kept.
+pub fn apply_input_defaults(workflow: &Workflow, inputs: &serde_json::Value) -> serde_json::Value {
+    let mut effective = inputs.clone();
+    if let Some(schema) = &workflow.inputs {
+        scan::fill_defaults(schema, &mut effective);
+    }
+    effective
+}
+
+/// Inputs `workflow` needs that `inputs` does not provide: those its steps reference
+/// through `$inputs.*` and those its `inputs` schema lists as `required`. Inputs
+/// with a schema `default` are never missing.
+pub fn missing_inputs(
+    workflow: &Workflow,
+    inputs: &serde_json::Value,
+    delimiters: &TemplateDelimiters,
+) -> BTreeSet<String> {
+    let inputs = &apply_input_defaults(workflow, inputs);
+    let scan = scan::scan_workflow(workflow, Some(inputs), delimiters);
+    let mut missing = scan.missing_inputs_all;
+    missing.extend(
+        scan::required_inputs(workflow)
+            .into_iter()
+            .filter(|name| !scan::input_present(inputs, name)),
+    );
+    missing
+}
+
 fn select_workflow<'a>(
     doc: &'a ArazzoDocument,
     workflow_id: Option<&str>,
@@ -78,7 +128,11 @@ fn build_plan(
     workflow: &Workflow,
     inputs: Option<serde_json::Value>,
 ) -> Result<Plan, PlannerError> {
-    let scan = scan::scan_workflow(workflow, inputs.as_ref());
+    let delimiters = TemplateDelimiters::from_extensions(&doc.extensions);
+    let prerequisites = dependency::workflow_prerequisites(doc, &workflow.workflow_id)
+        .map_err(PlannerError::WorkflowCycle)?;
+    let inputs = apply_input_defaults(workflow, &inputs.unwrap_or_else(|| serde_json::json!({})));
+    let scan = scan::scan_workflow(workflow, Some(&inputs), &delimiters);
     let graph = dependency::build_step_dependency_graph(workflow, &scan.step_dependencies)
         .map_err(PlannerError::DependencyGraph)?;
 
@@ -131,7 +185,9 @@ fn build_plan(
         summary: PlanSummary {
             workflow_id: workflow.workflow_id.clone(),
             workflow_depends_on: workflow.depends_on.clone().unwrap_or_default(),
+            workflow_prerequisites: prerequisites,
             missing_inputs: scan.missing_inputs_all,
+            effective_inputs: inputs,
         },
         graph,
         steps,
@@ -151,4 +207,8 @@ pub enum PlannerError {
 
     #[error("unable to build dependency graph: {0}")]
     DependencyGraph(String),
+
+    /// Workflows whose `dependsOn` lead back to themselves, e.g. `["a", "b", "a"]`.
+    #[error("workflow dependsOn cycle: {}", .0.join(" -> "))]
+    WorkflowCycle(Vec<String>),
 }
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use crate::error::ValidationError;
+use crate::error::{Severity, ValidationError, Violation};
+use crate::validate::ValidationReport;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PlanningOutcome {
@@ -25,10 +26,24 @@ impl ValidationSummary {
         }
     }
 
+    /// Whether any hard errors were found; warnings alone never count.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Errors and warnings of `report`, kept apart; only errors make it invalid.
+    pub fn from_report(report: &ValidationReport) -> Self {
+        let describe = |v: &Violation| format!("{}: {}", v.path, v.message);
+        Self {
+            is_valid: report.is_valid(),
+            errors: report.errors().map(describe).collect(),
+            warnings: report.warnings().map(describe).collect(),
+        }
+    }
+
     pub fn invalid_from(err: ValidationError) -> Self {
         let errors = err
-            .violations
-            .into_iter()
+            .by_severity(Severity::Error)
             .map(|v| format!("{}: {}", v.path, v.message))
             .collect();
         Self {
@@ -50,7 +65,14 @@ pub struct Plan {
 pub struct PlanSummary {
     pub workflow_id: String,
     pub workflow_depends_on: Vec<String>,
+    /// Local workflows that must finish first, directly or through their own
+    /// `dependsOn`, in an order they can run in.
+    #[serde(default)]
+    pub workflow_prerequisites: Vec<String>,
     pub missing_inputs: BTreeSet<String>,
+    /// The inputs a run starts with: those given, plus schema defaults for the rest.
+    #[serde(default)]
+    pub effective_inputs: serde_json::Value,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -65,12 +87,34 @@ pub struct DependencyGraph {
 
 impl DependencyGraph {
     pub fn to_dot(&self, workflow_id: &str) -> String {
+        self.to_dot_with_labels(workflow_id, &BTreeMap::new())
+    }
+
+    /// Like [`to_dot`](Self::to_dot), with extra lines shown under a step's id in its
+    /// node label (e.g. the HTTP operation it resolves to).
+    pub fn to_dot_with_labels(
+        &self,
+        workflow_id: &str,
+        labels: &BTreeMap<String, Vec<String>>,
+    ) -> String {
         let mut out = String::new();
         out.push_str("digraph arazzo {\n");
         out.push_str(&format!("  label=\"workflow: {workflow_id}\";\n"));
         out.push_str("  labelloc=t;\n");
         out.push_str("  rankdir=LR;\n");
 
+        for (step, lines) in labels {
+            if lines.is_empty() || !self.depends_on.contains_key(step) {
+                continue;
+            }
+            let label = std::iter::once(step.as_str())
+                .chain(lines.iter().map(String::as_str))
+                .map(|l| l.replace('\\', "\\\\").replace('"', "\\\""))
+                .collect::<Vec<_>>()
+                .join("\\n");
+            out.push_str(&format!("  \"{step}\" [label=\"{label}\"];\n"));
+        }
+
         for (step, deps) in &self.depends_on {
             if deps.is_empty() {
                 out.push_str(&format!("  \"{step}\";\n"));
@@ -174,8 +218,10 @@ fn extract_source_from_templated_op_path(operation_path: &str) -> Option<String>
     if let Ok(tpl) = crate::expressions::parse_template(operation_path) {
         for seg in tpl.segments {
             if let crate::expressions::Segment::Expr(e) = seg {
-                if let Ok(crate::expressions::RuntimeExpr::SourceDescriptions(np)) =
-                    crate::expressions::parse_runtime_expr(&e)
+                if let Ok(crate::expressions::Pipeline {
+                    expr: crate::expressions::RuntimeExpr::SourceDescriptions(np),
+                    ..
+                }) = crate::expressions::parse_pipeline(&e)
                 {
                     return Some(np.root);
                 }
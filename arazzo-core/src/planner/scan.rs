@@ -1,16 +1,11 @@
 use std::collections::{BTreeMap, BTreeSet};
-use std::sync::LazyLock;
 
-use regex::Regex;
-
-use crate::expressions::{parse_runtime_expr, parse_template, Segment};
+use crate::expressions::{
+    parse_pipeline, parse_runtime_expr, parse_template_with, RuntimeExpr, Segment,
+    TemplateDelimiters,
+};
 use crate::types::{AnyValue, Step, Workflow};
 
-static STEPS_REF_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\\$steps\\.([A-Za-z0-9_\\-]+)").expect("valid"));
-static INPUTS_REF_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\\$inputs\\.([a-zA-Z0-9\\.\\-_]+)").expect("valid"));
-
 #[derive(Debug, Default)]
 pub(crate) struct ScanResult {
     pub step_dependencies: BTreeMap<String, BTreeSet<String>>,
@@ -19,13 +14,17 @@ pub(crate) struct ScanResult {
     pub missing_inputs_all: BTreeSet<String>,
 }
 
-pub(crate) fn scan_workflow(workflow: &Workflow, inputs: Option<&serde_json::Value>) -> ScanResult {
+pub(crate) fn scan_workflow(
+    workflow: &Workflow,
+    inputs: Option<&serde_json::Value>,
+    delimiters: &TemplateDelimiters,
+) -> ScanResult {
     let mut out = ScanResult::default();
     for step in &workflow.steps {
         let mut deps = BTreeSet::<String>::new();
         let mut inputs_ref = BTreeSet::<String>::new();
 
-        scan_step(step, &mut deps, &mut inputs_ref);
+        scan_step(step, &mut deps, &mut inputs_ref, delimiters);
 
         out.step_dependencies.insert(step.step_id.clone(), deps);
         out.referenced_inputs_by_step
@@ -41,18 +40,23 @@ pub(crate) fn scan_workflow(workflow: &Workflow, inputs: Option<&serde_json::Val
     out
 }
 
-fn scan_step(step: &Step, deps: &mut BTreeSet<String>, inputs_ref: &mut BTreeSet<String>) {
+fn scan_step(
+    step: &Step,
+    deps: &mut BTreeSet<String>,
+    inputs_ref: &mut BTreeSet<String>,
+    delimiters: &TemplateDelimiters,
+) {
     // parameters
     if let Some(params) = &step.parameters {
         for p in params {
             match p {
                 crate::types::ParameterOrReusable::Parameter(p) => {
-                    scan_value(&p.value, deps, inputs_ref)
+                    scan_value(&p.value, deps, inputs_ref, delimiters)
                 }
                 crate::types::ParameterOrReusable::Reusable(r) => {
-                    scan_string(&r.reference, deps, inputs_ref);
+                    scan_string(&r.reference, deps, inputs_ref, delimiters);
                     if let Some(v) = &r.value {
-                        scan_value(v, deps, inputs_ref);
+                        scan_value(v, deps, inputs_ref, delimiters);
                     }
                 }
             }
@@ -62,30 +66,30 @@ fn scan_step(step: &Step, deps: &mut BTreeSet<String>, inputs_ref: &mut BTreeSet
     // outputs values are runtime expressions
     if let Some(outputs) = &step.outputs {
         for v in outputs.values() {
-            scan_string(v, deps, inputs_ref);
+            scan_string(v, deps, inputs_ref, delimiters);
         }
     }
 
     // operationId / workflowId / operationPath
     if let Some(op_id) = &step.operation_id {
-        scan_string(op_id, deps, inputs_ref);
+        scan_string(op_id, deps, inputs_ref, delimiters);
     }
     if let Some(wf_id) = &step.workflow_id {
-        scan_string(wf_id, deps, inputs_ref);
+        scan_string(wf_id, deps, inputs_ref, delimiters);
     }
     if let Some(op_path) = &step.operation_path {
-        scan_string(op_path, deps, inputs_ref);
+        scan_string(op_path, deps, inputs_ref, delimiters);
     }
 
     // request body
     if let Some(rb) = &step.request_body {
         if let Some(payload) = &rb.payload {
-            scan_value(payload, deps, inputs_ref);
+            scan_value(payload, deps, inputs_ref, delimiters);
         }
         if let Some(reps) = &rb.replacements {
             for r in reps {
-                scan_string(&r.target, deps, inputs_ref);
-                scan_value(&r.value, deps, inputs_ref);
+                scan_string(&r.target, deps, inputs_ref, delimiters);
+                scan_value(&r.value, deps, inputs_ref, delimiters);
             }
         }
     }
@@ -94,77 +98,112 @@ fn scan_step(step: &Step, deps: &mut BTreeSet<String>, inputs_ref: &mut BTreeSet
     if let Some(criteria) = &step.success_criteria {
         for c in criteria {
             if let Some(ctx) = &c.context {
-                scan_string(ctx, deps, inputs_ref);
+                scan_string(ctx, deps, inputs_ref, delimiters);
             }
-            scan_string(&c.condition, deps, inputs_ref);
+            scan_string(&c.condition, deps, inputs_ref, delimiters);
         }
     }
 }
 
-fn scan_value(value: &AnyValue, deps: &mut BTreeSet<String>, inputs_ref: &mut BTreeSet<String>) {
+fn scan_value(
+    value: &AnyValue,
+    deps: &mut BTreeSet<String>,
+    inputs_ref: &mut BTreeSet<String>,
+    delimiters: &TemplateDelimiters,
+) {
     match value {
         AnyValue::Null | AnyValue::Bool(_) | AnyValue::Number(_) => {}
-        AnyValue::String(s) => scan_string(s, deps, inputs_ref),
+        AnyValue::String(s) => scan_string(s, deps, inputs_ref, delimiters),
         AnyValue::Array(arr) => {
             for v in arr {
-                scan_value(v, deps, inputs_ref);
+                scan_value(v, deps, inputs_ref, delimiters);
             }
         }
         AnyValue::Object(map) => {
             for (_k, v) in map {
-                scan_value(v, deps, inputs_ref);
+                scan_value(v, deps, inputs_ref, delimiters);
             }
         }
     }
 }
 
-fn scan_string(s: &str, deps: &mut BTreeSet<String>, inputs_ref: &mut BTreeSet<String>) {
-    // Full runtime expression
-    if let Ok(expr) = parse_runtime_expr(s.trim()) {
-        match expr {
-            crate::expressions::RuntimeExpr::Steps(np) => {
-                deps.insert(np.root);
-            }
-            crate::expressions::RuntimeExpr::Inputs(np) => {
-                inputs_ref.insert(np.root);
-            }
-            _ => {}
+fn scan_string(
+    s: &str,
+    deps: &mut BTreeSet<String>,
+    inputs_ref: &mut BTreeSet<String>,
+    delimiters: &TemplateDelimiters,
+) {
+    // Inputs of an expression with a `default(...)` fallback are not required.
+    for_each_expression(s, delimiters, &mut |expr, has_default| match expr {
+        RuntimeExpr::Steps(np) => {
+            deps.insert(np.root.clone());
         }
+        RuntimeExpr::Inputs(np) if !has_default => {
+            inputs_ref.insert(np.root.clone());
+        }
+        _ => {}
+    });
+}
+
+/// Call `f` with each runtime expression in `s`: the whole string when it is one,
+/// otherwise every expression embedded in its templates, along with whether the
+/// expression's pipeline has a `default(...)` fallback.
+pub(crate) fn for_each_expression(
+    s: &str,
+    delimiters: &TemplateDelimiters,
+    f: &mut impl FnMut(&RuntimeExpr, bool),
+) {
+    if let Ok(expr) = parse_runtime_expr(s.trim()) {
+        f(&expr, false);
         return;
     }
 
-    // Embedded templates
-    if let Ok(tpl) = parse_template(s) {
-        for seg in tpl.segments {
-            if let Segment::Expr(e) = seg {
-                if let Ok(expr) = parse_runtime_expr(&e) {
-                    match expr {
-                        crate::expressions::RuntimeExpr::Steps(np) => {
-                            deps.insert(np.root);
-                        }
-                        crate::expressions::RuntimeExpr::Inputs(np) => {
-                            inputs_ref.insert(np.root);
-                        }
-                        _ => {}
-                    }
-                }
-            }
+    let Ok(tpl) = parse_template_with(s, delimiters) else {
+        return;
+    };
+    for seg in tpl.segments {
+        let Segment::Expr(e) = seg else {
+            continue;
+        };
+        let Ok(pipeline) = parse_pipeline(&e) else {
+            continue;
+        };
+        for expr in pipeline.expressions() {
+            f(expr, pipeline.has_default());
         }
     }
+}
 
-    // Best-effort raw regex scan (for Criterion.condition etc.).
-    for cap in STEPS_REF_RE.captures_iter(s) {
-        if let Some(m) = cap.get(1) {
-            deps.insert(m.as_str().to_string());
-        }
+/// Like [`for_each_expression`], but a string without any also has each of its
+/// `$`-prefixed tokens parsed, which finds the expressions in criterion conditions
+/// such as `$steps.login.outputs.ok == true`.
+pub(crate) fn for_each_referenced_expression(
+    s: &str,
+    delimiters: &TemplateDelimiters,
+    f: &mut impl FnMut(&RuntimeExpr),
+) {
+    let mut found = false;
+    for_each_expression(s, delimiters, &mut |expr, _| {
+        found = true;
+        f(expr);
+    });
+    if found {
+        return;
     }
-    for cap in INPUTS_REF_RE.captures_iter(s) {
-        if let Some(m) = cap.get(1) {
-            inputs_ref.insert(m.as_str().to_string());
+    for (start, _) in s.match_indices('$') {
+        let token = s[start..]
+            .split(|c: char| c.is_whitespace() || "=!<>()&|,'\"".contains(c))
+            .next()
+            .unwrap_or_default();
+        if let Ok(expr) = parse_runtime_expr(token) {
+            f(&expr);
         }
     }
 }
 
+/// An input counts as provided when its key is present, even if the value is `null`;
+/// only absent keys are reported missing. This matches evaluation, where `$inputs.x`
+/// resolves to JSON `null` for an explicit null and fails for an absent key.
 fn compute_missing_inputs(
     referenced: &BTreeSet<String>,
     inputs: Option<&serde_json::Value>,
@@ -180,7 +219,44 @@ fn compute_missing_inputs(
         .collect()
 }
 
-fn input_present(inputs: &serde_json::Value, name: &str) -> bool {
+/// Names listed in the `required` array of the workflow's `inputs` schema.
+pub(crate) fn required_inputs(workflow: &Workflow) -> BTreeSet<String> {
+    workflow
+        .inputs
+        .as_ref()
+        .and_then(|schema| schema.get("required"))
+        .and_then(|r| r.as_array())
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|n| n.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fill the properties `value` lacks from the `default`s in `schema.properties`,
+/// descending into nested objects that are present or defaulted.
+pub(crate) fn fill_defaults(schema: &serde_json::Value, value: &mut serde_json::Value) {
+    let properties = schema.get("properties").and_then(|p| p.as_object());
+    let (Some(properties), Some(obj)) = (properties, value.as_object_mut()) else {
+        return;
+    };
+    for (name, property) in properties {
+        match obj.get_mut(name) {
+            Some(present) => fill_defaults(property, present),
+            None => {
+                if let Some(default) = property.get("default") {
+                    let mut default = default.clone();
+                    fill_defaults(property, &mut default);
+                    obj.insert(name.clone(), default);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn input_present(inputs: &serde_json::Value, name: &str) -> bool {
     // First attempt: direct key in top-level object.
     if let Some(obj) = inputs.as_object() {
         if obj.contains_key(name) {
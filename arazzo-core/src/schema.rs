@@ -0,0 +1,8 @@
+//! JSON Schema export for Arazzo documents, derived from the [`crate::types`] structs.
+
+use crate::types::ArazzoDocument;
+
+/// Generate the JSON Schema describing an [`ArazzoDocument`].
+pub fn document_json_schema() -> serde_json::Value {
+    schemars::schema_for!(ArazzoDocument).to_value()
+}
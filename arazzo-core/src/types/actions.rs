@@ -1,6 +1,7 @@
 use crate::types::{Criterion, Extensions, ReusableObject};
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum SuccessActionType {
     End,
@@ -8,6 +9,7 @@ pub enum SuccessActionType {
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SuccessAction {
     pub name: String,
 
@@ -30,6 +32,7 @@ pub struct SuccessAction {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum FailureActionType {
     End,
@@ -38,6 +41,7 @@ pub enum FailureActionType {
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FailureAction {
     pub name: String,
 
@@ -68,6 +72,7 @@ pub struct FailureAction {
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum SuccessActionOrReusable {
     Action(SuccessAction),
@@ -75,6 +80,7 @@ pub enum SuccessActionOrReusable {
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum FailureActionOrReusable {
     Action(FailureAction),
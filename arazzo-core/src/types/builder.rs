@@ -0,0 +1,357 @@
+use std::collections::BTreeMap;
+
+use crate::error::ValidationError;
+use crate::types::{
+    AnyValue, ArazzoDocument, Components, Criterion, Extensions, FailureAction,
+    FailureActionOrReusable, Info, JsonSchema, Parameter, ParameterLocation, ParameterOrReusable,
+    RequestBody, RuntimeExpression, SourceDescription, SourceDescriptionType, Step, SuccessAction,
+    SuccessActionOrReusable, Workflow,
+};
+use crate::validate::validate_document;
+
+/// Spec version written by [`DocumentBuilder::new`].
+pub const DEFAULT_ARAZZO_VERSION: &str = "1.0.1";
+
+/// Builds an [`ArazzoDocument`]; [`build`](Self::build) validates it.
+///
+/// ```
+/// use arazzo_core::types::{DocumentBuilder, StepBuilder, WorkflowBuilder};
+///
+/// let doc = DocumentBuilder::new("Pets", "1.0.0")
+///     .with_openapi_source("petStore", "https://example.com/openapi.yaml")
+///     .with_workflow(
+///         WorkflowBuilder::new("listPets")
+///             .with_step(StepBuilder::operation("list", "listPets").with_success_criterion("$statusCode == 200")),
+///     )
+///     .build()
+///     .unwrap();
+/// assert_eq!(doc.workflows[0].steps[0].step_id, "list");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DocumentBuilder {
+    doc: ArazzoDocument,
+}
+
+impl DocumentBuilder {
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            doc: ArazzoDocument {
+                arazzo: DEFAULT_ARAZZO_VERSION.to_string(),
+                info: Info {
+                    title: title.into(),
+                    summary: None,
+                    description: None,
+                    version: version.into(),
+                    extensions: Extensions::new(),
+                },
+                source_descriptions: Vec::new(),
+                workflows: Vec::new(),
+                components: None,
+                extensions: Extensions::new(),
+            },
+        }
+    }
+
+    pub fn with_arazzo_version(mut self, version: impl Into<String>) -> Self {
+        self.doc.arazzo = version.into();
+        self
+    }
+
+    pub fn with_summary(mut self, summary: impl Into<String>) -> Self {
+        self.doc.info.summary = Some(summary.into());
+        self
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.doc.info.description = Some(description.into());
+        self
+    }
+
+    pub fn with_source(mut self, source: SourceDescription) -> Self {
+        self.doc.source_descriptions.push(source);
+        self
+    }
+
+    /// Add a source description of type `openapi`.
+    pub fn with_openapi_source(self, name: impl Into<String>, url: impl Into<String>) -> Self {
+        self.with_source(SourceDescription {
+            name: name.into(),
+            url: url.into(),
+            source_type: Some(SourceDescriptionType::Openapi),
+            extensions: Extensions::new(),
+        })
+    }
+
+    pub fn with_workflow(mut self, workflow: impl Into<Workflow>) -> Self {
+        self.doc.workflows.push(workflow.into());
+        self
+    }
+
+    pub fn with_components(mut self, components: Components) -> Self {
+        self.doc.components = Some(components);
+        self
+    }
+
+    /// Add a specification extension; `key` should start with `x-`.
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<AnyValue>) -> Self {
+        self.doc.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    /// Validate and return the document.
+    pub fn build(self) -> Result<ArazzoDocument, ValidationError> {
+        validate_document(&self.doc)?;
+        Ok(self.doc)
+    }
+
+    /// Return the document without validating it.
+    pub fn build_unchecked(self) -> ArazzoDocument {
+        self.doc
+    }
+}
+
+/// Builds a [`Workflow`]. It is validated as part of the document it is added to.
+#[derive(Debug, Clone)]
+pub struct WorkflowBuilder {
+    workflow: Workflow,
+}
+
+impl WorkflowBuilder {
+    pub fn new(workflow_id: impl Into<String>) -> Self {
+        Self {
+            workflow: Workflow {
+                workflow_id: workflow_id.into(),
+                summary: None,
+                description: None,
+                inputs: None,
+                depends_on: None,
+                steps: Vec::new(),
+                success_actions: None,
+                failure_actions: None,
+                outputs: None,
+                parameters: None,
+                extensions: Extensions::new(),
+            },
+        }
+    }
+
+    pub fn with_summary(mut self, summary: impl Into<String>) -> Self {
+        self.workflow.summary = Some(summary.into());
+        self
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.workflow.description = Some(description.into());
+        self
+    }
+
+    /// JSON Schema for the workflow inputs.
+    pub fn with_inputs(mut self, schema: JsonSchema) -> Self {
+        self.workflow.inputs = Some(schema);
+        self
+    }
+
+    pub fn with_depends_on(mut self, workflow_id: impl Into<String>) -> Self {
+        self.workflow
+            .depends_on
+            .get_or_insert_with(Vec::new)
+            .push(workflow_id.into());
+        self
+    }
+
+    pub fn with_step(mut self, step: impl Into<Step>) -> Self {
+        self.workflow.steps.push(step.into());
+        self
+    }
+
+    /// A parameter applied to every step of the workflow.
+    pub fn with_parameter(mut self, parameter: Parameter) -> Self {
+        self.workflow
+            .parameters
+            .get_or_insert_with(Vec::new)
+            .push(ParameterOrReusable::Parameter(parameter));
+        self
+    }
+
+    pub fn with_success_action(mut self, action: SuccessAction) -> Self {
+        self.workflow
+            .success_actions
+            .get_or_insert_with(Vec::new)
+            .push(SuccessActionOrReusable::Action(action));
+        self
+    }
+
+    pub fn with_failure_action(mut self, action: FailureAction) -> Self {
+        self.workflow
+            .failure_actions
+            .get_or_insert_with(Vec::new)
+            .push(FailureActionOrReusable::Action(action));
+        self
+    }
+
+    pub fn with_output(
+        mut self,
+        name: impl Into<String>,
+        expression: impl Into<RuntimeExpression>,
+    ) -> Self {
+        self.workflow
+            .outputs
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.into(), expression.into());
+        self
+    }
+
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<AnyValue>) -> Self {
+        self.workflow.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn build(self) -> Workflow {
+        self.workflow
+    }
+}
+
+impl From<WorkflowBuilder> for Workflow {
+    fn from(builder: WorkflowBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builds a [`Step`]. Start from the thing the step runs: an OpenAPI operation by id
+/// or path, or another workflow.
+#[derive(Debug, Clone)]
+pub struct StepBuilder {
+    step: Step,
+}
+
+impl StepBuilder {
+    fn new(step_id: impl Into<String>) -> Self {
+        Self {
+            step: Step {
+                step_id: step_id.into(),
+                description: None,
+                operation_id: None,
+                operation_path: None,
+                workflow_id: None,
+                parameters: None,
+                request_body: None,
+                success_criteria: None,
+                on_success: None,
+                on_failure: None,
+                outputs: None,
+                extensions: Extensions::new(),
+            },
+        }
+    }
+
+    /// A step calling the operation with this `operationId`.
+    pub fn operation(step_id: impl Into<String>, operation_id: impl Into<String>) -> Self {
+        let mut builder = Self::new(step_id);
+        builder.step.operation_id = Some(operation_id.into());
+        builder
+    }
+
+    /// A step calling the operation at this `operationPath`, e.g.
+    /// `{$sourceDescriptions.petStore.url}#/paths/~1pets/get`.
+    pub fn operation_path(step_id: impl Into<String>, operation_path: impl Into<String>) -> Self {
+        let mut builder = Self::new(step_id);
+        builder.step.operation_path = Some(operation_path.into());
+        builder
+    }
+
+    /// A step running another workflow.
+    pub fn workflow(step_id: impl Into<String>, workflow_id: impl Into<String>) -> Self {
+        let mut builder = Self::new(step_id);
+        builder.step.workflow_id = Some(workflow_id.into());
+        builder
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.step.description = Some(description.into());
+        self
+    }
+
+    pub fn with_parameter(
+        mut self,
+        name: impl Into<String>,
+        location: ParameterLocation,
+        value: impl Into<AnyValue>,
+    ) -> Self {
+        self.step
+            .parameters
+            .get_or_insert_with(Vec::new)
+            .push(ParameterOrReusable::Parameter(Parameter {
+                name: name.into(),
+                r#in: Some(location),
+                value: value.into(),
+                extensions: Extensions::new(),
+            }));
+        self
+    }
+
+    pub fn with_request_body(mut self, body: RequestBody) -> Self {
+        self.step.request_body = Some(body);
+        self
+    }
+
+    /// Add a `simple` criterion such as `$statusCode == 200`.
+    pub fn with_success_criterion(self, condition: impl Into<String>) -> Self {
+        self.with_criterion(Criterion {
+            context: None,
+            condition: condition.into(),
+            r#type: None,
+            extensions: Extensions::new(),
+        })
+    }
+
+    pub fn with_criterion(mut self, criterion: Criterion) -> Self {
+        self.step
+            .success_criteria
+            .get_or_insert_with(Vec::new)
+            .push(criterion);
+        self
+    }
+
+    pub fn with_on_success(mut self, action: SuccessAction) -> Self {
+        self.step
+            .on_success
+            .get_or_insert_with(Vec::new)
+            .push(SuccessActionOrReusable::Action(action));
+        self
+    }
+
+    pub fn with_on_failure(mut self, action: FailureAction) -> Self {
+        self.step
+            .on_failure
+            .get_or_insert_with(Vec::new)
+            .push(FailureActionOrReusable::Action(action));
+        self
+    }
+
+    pub fn with_output(
+        mut self,
+        name: impl Into<String>,
+        expression: impl Into<RuntimeExpression>,
+    ) -> Self {
+        self.step
+            .outputs
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.into(), expression.into());
+        self
+    }
+
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<AnyValue>) -> Self {
+        self.step.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn build(self) -> Step {
+        self.step
+    }
+}
+
+impl From<StepBuilder> for Step {
+    fn from(builder: StepBuilder) -> Self {
+        builder.build()
+    }
+}
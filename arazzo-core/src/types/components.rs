@@ -2,7 +2,8 @@ use std::collections::BTreeMap;
 
 use crate::types::{Extensions, FailureAction, JsonSchema, Parameter, SuccessAction};
 
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Components {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub inputs: Option<BTreeMap<String, JsonSchema>>,
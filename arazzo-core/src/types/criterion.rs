@@ -1,6 +1,7 @@
 use crate::types::{Extensions, RuntimeExpression};
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum KnownCriterionType {
     Simple,
@@ -10,6 +11,7 @@ pub enum KnownCriterionType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum CriterionExpressionLanguage {
     Jsonpath,
@@ -17,6 +19,7 @@ pub enum CriterionExpressionLanguage {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CriterionExpressionType {
     pub r#type: CriterionExpressionLanguage,
     pub version: String,
@@ -26,6 +29,7 @@ pub struct CriterionExpressionType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum CriterionType {
     Known(KnownCriterionType),
@@ -33,6 +37,7 @@ pub enum CriterionType {
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Criterion {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub context: Option<RuntimeExpression>,
@@ -1,6 +1,7 @@
 use crate::types::{Components, Extensions, Info, SourceDescription, Workflow};
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ArazzoDocument {
     /// The Arazzo Specification version (e.g. "1.0.1").
     pub arazzo: String,
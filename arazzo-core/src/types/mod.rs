@@ -1,4 +1,5 @@
 mod actions;
+mod builder;
 mod common;
 mod components;
 mod criterion;
@@ -12,6 +13,7 @@ mod step;
 mod workflow;
 
 pub use actions::*;
+pub use builder::*;
 pub use common::*;
 pub use components::*;
 pub use criterion::*;
@@ -1,6 +1,7 @@
 use crate::types::{AnyValue, Extensions};
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PayloadReplacement {
     pub target: String,
     pub value: AnyValue,
@@ -10,6 +11,7 @@ pub struct PayloadReplacement {
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct RequestBody {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(rename = "contentType")]
@@ -1,6 +1,7 @@
 use crate::types::{AnyValue, RuntimeExpression};
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct ReusableObject {
     pub reference: RuntimeExpression,
@@ -1,6 +1,7 @@
 use crate::types::Extensions;
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum SourceDescriptionType {
     Openapi,
@@ -8,6 +9,7 @@ pub enum SourceDescriptionType {
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SourceDescription {
     pub name: String,
     pub url: String,
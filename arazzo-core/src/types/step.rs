@@ -6,6 +6,7 @@ use crate::types::{
 };
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Step {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
@@ -6,6 +6,7 @@ use crate::types::{
 };
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Workflow {
     #[serde(rename = "workflowId")]
     pub workflow_id: String,
@@ -0,0 +1,102 @@
+//! Validation against the published Arazzo 1.0.x JSON Schema. It runs on the raw
+//! parsed value, so it also catches input the typed model quietly accepts.
+
+use std::sync::LazyLock;
+
+use jsonschema::error::ValidationErrorKind;
+use serde_json::Value as JsonValue;
+
+use crate::error::Violation;
+use crate::types::ArazzoDocument;
+
+use super::{apply_overrides, validation_report, ValidationOptions, ValidationReport};
+
+/// The Arazzo 1.0.x JSON Schema (2024-08-01), draft 2020-12.
+pub const ARAZZO_JSON_SCHEMA: &str = include_str!("../../schema/arazzo-1.0.schema.json");
+
+static VALIDATOR: LazyLock<jsonschema::Validator> = LazyLock::new(|| {
+    let schema = serde_json::from_str(ARAZZO_JSON_SCHEMA).expect("valid schema JSON");
+    jsonschema::validator_for(&schema).expect("valid JSON Schema")
+});
+
+/// Every place `raw` does not match the Arazzo JSON Schema, as error-level violations.
+pub fn json_schema_violations(raw: &JsonValue) -> Vec<Violation> {
+    let mut found = Vec::new();
+    let mut unevaluated = Vec::new();
+    for e in VALIDATOR.iter_errors(raw) {
+        let path = violation_path(raw, e.instance_path.as_str());
+        match e.kind {
+            ValidationErrorKind::UnevaluatedProperties { unexpected } => {
+                unevaluated.extend(unexpected.into_iter().map(|key| format!("{path}.{key}")));
+            }
+            _ => found.push(Violation::new(path, schema_message(e))),
+        }
+    }
+    // A property whose value failed validation does not count as evaluated, so its
+    // parent also reports it as unexpected; only report properties with no error of
+    // their own.
+    for path in unevaluated {
+        let nested = found.iter().any(|v| {
+            v.path
+                .strip_prefix(path.as_str())
+                .is_some_and(is_child_suffix)
+        });
+        if !nested {
+            found.push(Violation::new(
+                path,
+                schema_message("property is not allowed"),
+            ));
+        }
+    }
+    found
+}
+
+fn schema_message(error: impl std::fmt::Display) -> String {
+    format!("does not match the Arazzo JSON Schema: {error}")
+}
+
+fn is_child_suffix(rest: &str) -> bool {
+    rest.is_empty() || rest.starts_with(['.', '['])
+}
+
+/// Validate `document` with the rules of [`validation_report`] and `raw`, the value it
+/// was read from (see [`crate::parse_value_str`]), with the JSON Schema. A schema
+/// violation is dropped when a rule already reported a problem at the same path;
+/// overrides in `options` apply to both.
+pub fn validation_report_with_schema(
+    document: &ArazzoDocument,
+    raw: &JsonValue,
+    options: &ValidationOptions,
+) -> ValidationReport {
+    let mut report = validation_report(document, options);
+    let schema: Vec<_> = json_schema_violations(raw)
+        .into_iter()
+        .filter(|s| !report.violations.iter().any(|v| v.path == s.path))
+        .collect();
+    report
+        .violations
+        .extend(apply_overrides(schema, &options.overrides));
+    report
+}
+
+/// Turn the JSON Pointer `pointer` into `raw` into a violation path such as
+/// `$.workflows[0].steps`. Numeric segments are indices only inside arrays.
+fn violation_path(raw: &JsonValue, pointer: &str) -> String {
+    let mut path = String::from("$");
+    let mut node = Some(raw);
+    for segment in pointer.split('/').skip(1) {
+        let key = segment.replace("~1", "/").replace("~0", "~");
+        node = match node {
+            Some(JsonValue::Array(items)) => {
+                path.push_str(&format!("[{key}]"));
+                key.parse::<usize>().ok().and_then(|i| items.get(i))
+            }
+            other => {
+                path.push('.');
+                path.push_str(&key);
+                other.and_then(|n| n.get(&key))
+            }
+        };
+    }
+    path
+}
@@ -1,10 +1,21 @@
+#[cfg(feature = "json-schema-validation")]
+mod json_schema;
 mod rules;
 mod validator;
 
-use crate::error::ValidationError;
+use crate::error::{Severity, ValidationError, Violation};
+use crate::extensions::ExtensionRegistry;
 use crate::types::ArazzoDocument;
+use crate::version::SpecVersion;
 use validator::Validator;
 
+#[cfg(feature = "json-schema-validation")]
+pub use json_schema::{json_schema_violations, validation_report_with_schema, ARAZZO_JSON_SCHEMA};
+
+/// Reported for a key that is neither a field of its object nor an `x-` extension.
+pub(crate) const UNKNOWN_FIELD_MESSAGE: &str =
+    "unknown field (only x-* specification extensions are allowed)";
+
 pub trait Validate {
     fn validate(&self) -> Result<(), ValidationError>;
 }
@@ -16,7 +27,229 @@ impl Validate for ArazzoDocument {
 }
 
 pub fn validate_document(doc: &ArazzoDocument) -> Result<(), ValidationError> {
-    let mut v = Validator::new();
+    validate_document_with(doc, &ValidationOptions::default())
+}
+
+/// Like [`validate_document`], with configurable bounds.
+pub fn validate_document_with(
+    doc: &ArazzoDocument,
+    options: &ValidationOptions,
+) -> Result<(), ValidationError> {
+    validation_report(doc, options).into_result()
+}
+
+/// Every violation found in `doc`, warnings included, whether or not it is valid.
+pub fn validation_report(doc: &ArazzoDocument, options: &ValidationOptions) -> ValidationReport {
+    let mut v = Validator::new().with_options(options.clone());
     v.validate_document(doc);
     v.finish()
 }
+
+/// The outcome of validating a document: errors and warnings in the order found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// The violations of one severity, in the order they were found.
+    pub fn by_severity(&self, severity: Severity) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(move |v| v.severity == severity)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Violation> {
+        self.by_severity(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Violation> {
+        self.by_severity(Severity::Warning)
+    }
+
+    /// Whether the document is valid: warnings alone never make it invalid.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// `Err` with every violation when there is at least one error.
+    pub fn into_result(self) -> Result<(), ValidationError> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(ValidationError::new(self.violations))
+        }
+    }
+}
+
+/// Upper bounds checked on retry failure actions (`None` disables a check) and other
+/// knobs for validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationOptions {
+    /// Largest accepted `retryAfter`, in seconds.
+    pub max_retry_after_seconds: Option<f64>,
+    /// Largest accepted `retryLimit`.
+    pub max_retry_limit: Option<u32>,
+    /// Fields reported by [`document_warnings_with`] instead of being rejected as unknown.
+    pub deprecated_fields: Vec<DeprecatedField>,
+    /// Fields added in a 1.0.x release after 1.0.0. They are accepted when the
+    /// document declares that release or a later one. Arazzo 1.0.1 added none, so the
+    /// list starts empty.
+    pub versioned_fields: Vec<VersionedField>,
+    /// Extensions whose values must decode into their registered type.
+    pub extensions: ExtensionRegistry,
+    /// Severity changes for matching violations; when several match, the last wins.
+    pub overrides: Vec<RuleOverride>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            max_retry_after_seconds: Some(86_400.0),
+            max_retry_limit: Some(1_000),
+            deprecated_fields: Vec::new(),
+            versioned_fields: Vec::new(),
+            extensions: ExtensionRegistry::default(),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+/// A field authors should migrate away from. It is still accepted, but reported as a
+/// warning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeprecatedField {
+    /// Violation-style path of the field, with `[*]` for array indices,
+    /// e.g. `$.workflows[*].steps[*].x-timeout`.
+    pub path: String,
+    /// What to use instead.
+    pub message: String,
+}
+
+impl DeprecatedField {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Whether the concrete violation path `path` (e.g. `$.workflows[0].steps[1].x-timeout`)
+    /// names this field.
+    pub fn matches(&self, path: &str) -> bool {
+        rules::deprecations::index_pattern(path) == self.path
+    }
+}
+
+/// A field that only exists from spec version `since` on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionedField {
+    /// Violation-style path with `[*]` for array indices, as in [`DeprecatedField`].
+    pub path: String,
+    pub since: SpecVersion,
+}
+
+impl VersionedField {
+    pub fn new(path: impl Into<String>, since: SpecVersion) -> Self {
+        Self {
+            path: path.into(),
+            since,
+        }
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        rules::deprecations::index_pattern(path) == self.path
+    }
+}
+
+/// How violations matched by a [`RuleOverride`] are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Error,
+    Warning,
+    /// Drop the violation.
+    Off,
+}
+
+/// Downgrades, upgrades or suppresses the violations it matches, so a team can adopt
+/// validation one rule at a time. In a config file:
+///
+/// ```yaml
+/// rules:
+///   - path: $.workflows[*].steps[*].x-timeout
+///     message: unknown field
+///     level: off
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleOverride {
+    /// Violation path with `[*]` for array indices. Violations below it match too,
+    /// so `$.workflows[*].steps` covers everything inside steps.
+    pub path: String,
+    /// Only match violations whose message contains this text.
+    #[serde(default)]
+    pub message: Option<String>,
+    pub level: RuleLevel,
+}
+
+impl RuleOverride {
+    pub fn new(path: impl Into<String>, level: RuleLevel) -> Self {
+        Self {
+            path: path.into(),
+            message: None,
+            level,
+        }
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn matches(&self, violation: &Violation) -> bool {
+        let pattern = rules::deprecations::index_pattern(&violation.path);
+        let below = pattern
+            .strip_prefix(self.path.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']));
+        below
+            && self
+                .message
+                .as_deref()
+                .map_or(true, |m| violation.message.contains(m))
+    }
+}
+
+/// Re-level or drop each violation by the last override matching it.
+pub(crate) fn apply_overrides(
+    violations: Vec<Violation>,
+    overrides: &[RuleOverride],
+) -> Vec<Violation> {
+    violations
+        .into_iter()
+        .filter_map(|mut violation| {
+            let level = overrides.iter().rev().find(|o| o.matches(&violation));
+            match level.map(|o| o.level) {
+                Some(RuleLevel::Off) => return None,
+                Some(RuleLevel::Error) => violation.severity = Severity::Error,
+                Some(RuleLevel::Warning) => violation.severity = Severity::Warning,
+                None => {}
+            }
+            Some(violation)
+        })
+        .collect()
+}
+
+/// Non-fatal findings (e.g. unreferenced `sourceDescriptions`). These never make a
+/// document invalid; callers decide whether to surface them.
+pub fn document_warnings(doc: &ArazzoDocument) -> Vec<Violation> {
+    document_warnings_with(doc, &ValidationOptions::default())
+}
+
+/// Like [`document_warnings`], also reporting the `deprecated_fields` of `options`.
+pub fn document_warnings_with(doc: &ArazzoDocument, options: &ValidationOptions) -> Vec<Violation> {
+    validation_report(doc, options)
+        .warnings()
+        .cloned()
+        .collect()
+}
@@ -155,6 +155,23 @@ pub(crate) fn validate_failure_action_list(
                             if secs < 0.0 {
                                 v.push(format!("{ipath}.retryAfter"), "must be non-negative");
                             }
+                            if let Some(max) = v.options.max_retry_after_seconds {
+                                if secs > max {
+                                    v.push(
+                                        format!("{ipath}.retryAfter"),
+                                        format!("must be at most {max} seconds"),
+                                    );
+                                }
+                            }
+                        }
+                        if let (Some(limit), Some(max)) = (a.retry_limit, v.options.max_retry_limit)
+                        {
+                            if limit > max {
+                                v.push(
+                                    format!("{ipath}.retryLimit"),
+                                    format!("must be at most {max}"),
+                                );
+                            }
                         }
                         let has_workflow = a.workflow_id.is_some();
                         let has_step = a.step_id.is_some();
@@ -29,7 +29,7 @@ pub(crate) fn validate_template_string(v: &mut Validator, path: &str, s: &str) {
 }
 
 pub(crate) fn validate_value_exprs(v: &mut Validator, path: &str, value: &serde_json::Value) {
-    if let Err(e) = validate_value_expressions(value) {
+    if let Err(e) = validate_value_expressions(value, &v.delimiters) {
         v.push(path, format!("invalid expression inside value: {e}"));
     }
 }
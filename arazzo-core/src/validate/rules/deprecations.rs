@@ -0,0 +1,54 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::Value as JsonValue;
+
+use crate::error::Violation;
+use crate::types::ArazzoDocument;
+use crate::validate::DeprecatedField;
+
+static INDEX_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\d+\]").expect("valid"));
+
+/// `path` with every array index replaced by `[*]`, the form deprecation patterns use.
+pub(crate) fn index_pattern(path: &str) -> String {
+    INDEX_RE.replace_all(path, "[*]").into_owned()
+}
+
+/// Warn about every field of `doc` whose path matches one of `deprecated`.
+pub(crate) fn deprecated_fields(
+    doc: &ArazzoDocument,
+    deprecated: &[DeprecatedField],
+) -> Vec<Violation> {
+    if deprecated.is_empty() {
+        return Vec::new();
+    }
+    let Ok(value) = serde_json::to_value(doc) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    walk(&value, "$", deprecated, &mut out);
+    out
+}
+
+fn walk(value: &JsonValue, path: &str, deprecated: &[DeprecatedField], out: &mut Vec<Violation>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                let child_path = format!("{path}.{key}");
+                if let Some(d) = deprecated.iter().find(|d| d.matches(&child_path)) {
+                    out.push(Violation::warning(
+                        child_path.clone(),
+                        format!("deprecated: {}", d.message),
+                    ));
+                }
+                walk(child, &child_path, deprecated, out);
+            }
+        }
+        JsonValue::Array(items) => {
+            for (idx, child) in items.iter().enumerate() {
+                walk(child, &format!("{path}[{idx}]"), deprecated, out);
+            }
+        }
+        _ => {}
+    }
+}
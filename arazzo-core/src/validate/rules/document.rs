@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use crate::expressions::TemplateDelimiters;
+use crate::planner::build_workflow_dependency_graph;
 use crate::types::ArazzoDocument;
 use crate::validate::rules::{common::validate_runtime_expr, components, info, workflow};
 use crate::validate::validator::{Validator, ID_RE};
@@ -8,6 +10,16 @@ pub(crate) fn validate_document(v: &mut Validator, doc: &ArazzoDocument) {
     v.validate_extensions("$", &doc.extensions);
     v.validate_spec_version("$.arazzo", &doc.arazzo);
 
+    if let Some(value) = doc.extensions.get(TemplateDelimiters::EXTENSION) {
+        match TemplateDelimiters::from_value(value) {
+            Some(delimiters) => v.delimiters = delimiters,
+            None => v.push(
+                format!("$.{}", TemplateDelimiters::EXTENSION),
+                "must be an object with non-empty string `open` and `close`",
+            ),
+        }
+    }
+
     info::validate_info(v, &doc.info, "$.info");
 
     if doc.source_descriptions.is_empty() {
@@ -49,7 +61,7 @@ pub(crate) fn validate_document(v: &mut Validator, doc: &ArazzoDocument) {
             v.push(format!("{path}.workflowId"), "must be unique");
         }
 
-        workflow::validate_workflow(v, wf, &path);
+        workflow::validate_workflow(v, wf, &path, doc.components.as_ref());
     }
 
     // dependsOn: validate against local workflowId unless it's an external runtime expression.
@@ -72,6 +84,26 @@ pub(crate) fn validate_document(v: &mut Validator, doc: &ArazzoDocument) {
         }
     }
 
+    // Each cycle is reported once, on the dependsOn entry that starts it.
+    for cycle in build_workflow_dependency_graph(doc)
+        .err()
+        .unwrap_or_default()
+    {
+        let Some(idx) = doc.workflows.iter().position(|w| w.workflow_id == cycle[0]) else {
+            continue;
+        };
+        let didx = doc.workflows[idx]
+            .depends_on
+            .iter()
+            .flatten()
+            .position(|d| *d == cycle[1])
+            .unwrap_or_default();
+        v.push(
+            format!("$.workflows[{idx}].dependsOn[{didx}]"),
+            format!("workflow dependsOn cycle: {}", cycle.join(" -> ")),
+        );
+    }
+
     if let Some(c) = &doc.components {
         components::validate_components(v, c, "$.components");
     }
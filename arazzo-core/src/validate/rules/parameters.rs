@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::types::ParameterOrReusable;
+use crate::types::{Components, Parameter, ParameterOrReusable};
 use crate::validate::rules::common::{validate_runtime_expr, validate_value_exprs};
 use crate::validate::validator::Validator;
 
@@ -15,6 +15,7 @@ pub(crate) fn validate_parameter_list(
     path: &str,
     params: &[ParameterOrReusable],
     context: Option<ParameterContext>,
+    components: Option<&Components>,
 ) {
     let mut seen = HashSet::<String>::new();
     for (idx, item) in params.iter().enumerate() {
@@ -27,26 +28,21 @@ pub(crate) fn validate_parameter_list(
                 }
                 validate_value_exprs(v, &format!("{ipath}.value"), &p.value);
                 match context {
-                    Some(ParameterContext::WorkflowStep) => {
-                        if p.r#in.is_some() {
-                            v.push(
-                                format!("{ipath}.in"),
-                                "must be omitted when the step specifies workflowId (parameters map to workflow inputs)",
-                            );
-                        }
+                    Some(ParameterContext::WorkflowStep) if p.r#in.is_some() => {
+                        v.push(
+                            format!("{ipath}.in"),
+                            "must be omitted when the step specifies workflowId (parameters map to workflow inputs)",
+                        );
                     }
-                    Some(ParameterContext::OperationStep) => {
-                        if p.r#in.is_none() {
-                            v.push(
-                                format!("{ipath}.in"),
-                                "must be provided when the step targets an operationId/operationPath",
-                            );
-                        }
+                    Some(ParameterContext::OperationStep) if p.r#in.is_none() => {
+                        v.push(
+                            format!("{ipath}.in"),
+                            "must be provided when the step targets an operationId/operationPath",
+                        );
                     }
-                    None => {}
+                    _ => {}
                 }
-                let key = format!("param:{}:{:?}", p.name, p.r#in);
-                if !seen.insert(key) {
+                if !seen.insert(param_key(p)) {
                     v.push(ipath, "duplicate parameter (unique by name + in)");
                 }
             }
@@ -54,6 +50,17 @@ pub(crate) fn validate_parameter_list(
                 let key = format!("ref:{}", r.reference);
                 if !seen.insert(key) {
                     v.push(ipath.as_str(), "duplicate reusable reference");
+                } else if let Some(p) = resolve_component_parameter(components, &r.reference) {
+                    // A reference clashes with any parameter sharing its effective name + in.
+                    if !seen.insert(param_key(p)) {
+                        v.push(
+                            ipath.as_str(),
+                            format!(
+                                "duplicate parameter (unique by name + in): {} resolves to '{}'",
+                                r.reference, p.name
+                            ),
+                        );
+                    }
                 }
                 validate_runtime_expr(v, &format!("{ipath}.reference"), &r.reference);
                 if !r.reference.starts_with("$components.parameters.") {
@@ -69,3 +76,15 @@ pub(crate) fn validate_parameter_list(
         }
     }
 }
+
+fn param_key(p: &Parameter) -> String {
+    format!("param:{}:{:?}", p.name, p.r#in)
+}
+
+fn resolve_component_parameter<'a>(
+    components: Option<&'a Components>,
+    reference: &str,
+) -> Option<&'a Parameter> {
+    let name = reference.strip_prefix("$components.parameters.")?;
+    components?.parameters.as_ref()?.get(name)
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeSet;
+
+use crate::error::Violation;
+use crate::expressions::{
+    parse_pipeline, parse_runtime_expr, parse_template, Pipeline, RuntimeExpr, Segment,
+};
+use crate::types::{ArazzoDocument, SourceDescriptionType};
+
+/// Warn about `sourceDescriptions` that no step reaches through its
+/// operationId, operationPath or workflowId.
+pub(crate) fn unreferenced_sources(doc: &ArazzoDocument) -> Vec<Violation> {
+    let mut referenced = BTreeSet::<String>::new();
+    // An unqualified operationId is only allowed with a single OpenAPI source,
+    // so it counts as a reference to every OpenAPI source.
+    let mut unqualified_operation_id = false;
+
+    for wf in &doc.workflows {
+        for step in &wf.steps {
+            if let Some(op_id) = &step.operation_id {
+                if op_id.trim().starts_with('$') {
+                    collect_source(op_id, &mut referenced);
+                } else {
+                    unqualified_operation_id = true;
+                }
+            }
+            if let Some(op_path) = &step.operation_path {
+                collect_source(op_path, &mut referenced);
+            }
+            if let Some(wf_id) = &step.workflow_id {
+                collect_source(wf_id, &mut referenced);
+            }
+        }
+    }
+
+    doc.source_descriptions
+        .iter()
+        .enumerate()
+        .filter(|(_, src)| {
+            let is_openapi = !matches!(src.source_type, Some(SourceDescriptionType::Arazzo));
+            let implied = is_openapi && unqualified_operation_id;
+            !implied && !referenced.contains(&src.name)
+        })
+        .map(|(idx, src)| {
+            Violation::warning(
+                format!("$.sourceDescriptions[{idx}]"),
+                format!("source '{}' is not referenced by any step", src.name),
+            )
+        })
+        .collect()
+}
+
+fn collect_source(s: &str, out: &mut BTreeSet<String>) {
+    let trimmed = s.trim();
+    if let Ok(RuntimeExpr::SourceDescriptions(np)) = parse_runtime_expr(trimmed) {
+        out.insert(np.root);
+        return;
+    }
+    if let Ok(tpl) = parse_template(s) {
+        for seg in tpl.segments {
+            if let Segment::Expr(e) = seg {
+                for expr in parse_pipeline(&e).iter().flat_map(Pipeline::expressions) {
+                    if let RuntimeExpr::SourceDescriptions(np) = expr {
+                        out.insert(np.root.clone());
+                    }
+                }
+            }
+        }
+    }
+}
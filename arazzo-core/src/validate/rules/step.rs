@@ -1,4 +1,4 @@
-use crate::types::Step;
+use crate::types::{Components, Step};
 use crate::validate::rules::{
     actions,
     common::{validate_map_keys, validate_runtime_expr, validate_template_string},
@@ -11,21 +11,30 @@ pub(crate) fn validate_step(
     step: &Step,
     path: &str,
     step_ids: &std::collections::HashSet<String>,
+    components: Option<&Components>,
 ) {
-    let op_fields = [
-        step.operation_id.is_some(),
-        step.operation_path.is_some(),
-        step.workflow_id.is_some(),
+    let targets: Vec<&str> = [
+        ("operationId", step.operation_id.is_some()),
+        ("operationPath", step.operation_path.is_some()),
+        ("workflowId", step.workflow_id.is_some()),
     ]
     .into_iter()
-    .filter(|v| *v)
-    .count();
+    .filter_map(|(name, set)| set.then_some(name))
+    .collect();
 
-    if op_fields != 1 {
-        v.push(
+    match targets.len() {
+        1 => {}
+        0 => v.push(
             path,
-            "exactly one of operationId, operationPath, workflowId must be provided",
-        );
+            "exactly one of operationId, operationPath, workflowId must be provided; none is set",
+        ),
+        _ => v.push(
+            path,
+            format!(
+                "exactly one of operationId, operationPath, workflowId must be provided; found {}",
+                targets.join(", ")
+            ),
+        ),
     }
 
     if let Some(operation_path) = &step.operation_path {
@@ -59,7 +68,13 @@ pub(crate) fn validate_step(
     };
 
     if let Some(parameters) = &step.parameters {
-        parameters::validate_parameter_list(v, &format!("{path}.parameters"), parameters, context);
+        parameters::validate_parameter_list(
+            v,
+            &format!("{path}.parameters"),
+            parameters,
+            context,
+            components,
+        );
     }
 
     if let Some(rb) = &step.request_body {
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::types::Workflow;
+use crate::types::{Components, Workflow};
 use crate::validate::rules::{
     actions,
     common::{validate_map_keys, validate_runtime_expr},
@@ -8,7 +8,12 @@ use crate::validate::rules::{
 };
 use crate::validate::validator::{Validator, ID_RE};
 
-pub(crate) fn validate_workflow(v: &mut Validator, wf: &Workflow, path: &str) {
+pub(crate) fn validate_workflow(
+    v: &mut Validator,
+    wf: &Workflow,
+    path: &str,
+    components: Option<&Components>,
+) {
     if wf.steps.is_empty() {
         v.push(format!("{path}.steps"), "must have at least one entry");
     }
@@ -21,7 +26,13 @@ pub(crate) fn validate_workflow(v: &mut Validator, wf: &Workflow, path: &str) {
     }
 
     if let Some(parameters) = &wf.parameters {
-        parameters::validate_parameter_list(v, &format!("{path}.parameters"), parameters, None);
+        parameters::validate_parameter_list(
+            v,
+            &format!("{path}.parameters"),
+            parameters,
+            None,
+            components,
+        );
     }
 
     if let Some(success_actions) = &wf.success_actions {
@@ -59,6 +70,6 @@ pub(crate) fn validate_workflow(v: &mut Validator, wf: &Workflow, path: &str) {
             );
         }
 
-        step::validate_step(v, s, &spath, &step_ids);
+        step::validate_step(v, s, &spath, &step_ids, components);
     }
 }
@@ -2,10 +2,13 @@ use std::sync::LazyLock;
 
 use regex::Regex;
 
-use crate::error::{ValidationError, Violation};
+use crate::error::Violation;
+use crate::expressions::TemplateDelimiters;
 use crate::types::{ArazzoDocument, Extensions};
+use crate::version::SpecVersion;
 
 use super::rules;
+use super::{apply_overrides, ValidationOptions, ValidationReport, UNKNOWN_FIELD_MESSAGE};
 
 pub(crate) static ID_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^[A-Za-z0-9_\-]+$").expect("valid"));
@@ -14,25 +17,44 @@ pub(crate) static MAP_KEY_RE: LazyLock<Regex> =
 
 pub struct Validator {
     violations: Vec<Violation>,
+    /// Delimiters for embedded expressions in values, taken from the document being validated.
+    pub(crate) delimiters: TemplateDelimiters,
+    pub(crate) options: ValidationOptions,
+    /// The version the document declares, when it parses.
+    pub(crate) spec_version: Option<SpecVersion>,
 }
 
 impl Validator {
     pub fn new() -> Self {
         Self {
             violations: Vec::new(),
+            delimiters: TemplateDelimiters::default(),
+            options: ValidationOptions::default(),
+            spec_version: None,
         }
     }
 
-    pub fn finish(self) -> Result<(), ValidationError> {
-        if self.violations.is_empty() {
-            Ok(())
-        } else {
-            Err(ValidationError::new(self.violations))
+    pub fn with_options(mut self, options: ValidationOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn finish(self) -> ValidationReport {
+        ValidationReport {
+            violations: apply_overrides(self.violations, &self.options.overrides),
         }
     }
 
     pub fn validate_document(&mut self, doc: &ArazzoDocument) {
+        self.spec_version = doc.spec_version();
         rules::document::validate_document(self, doc);
+        self.violations
+            .extend(rules::sources::unreferenced_sources(doc));
+        self.violations
+            .extend(rules::deprecations::deprecated_fields(
+                doc,
+                &self.options.deprecated_fields,
+            ));
     }
 
     pub(crate) fn push(&mut self, path: impl Into<String>, message: impl Into<String>) {
@@ -40,25 +62,58 @@ impl Validator {
     }
 
     pub(crate) fn validate_spec_version(&mut self, path: &str, version: &str) {
-        // Spec says tooling should treat 1.0.0 and 1.0.1 as the same feature-set (major.minor).
-        // We enforce that major.minor == 1.0.
-        let parts: Vec<&str> = version.split('.').collect();
-        if parts.len() < 2 {
-            self.push(path, "must be a semver-like string (major.minor[.patch])");
-            return;
-        }
-        if parts[0] != "1" || parts[1] != "0" {
-            self.push(path, "only Arazzo spec 1.0.x is currently supported");
+        // Spec says tooling should treat every 1.0.x as the same feature-set (major.minor);
+        // fields added by a patch release are gated through `versioned_fields`.
+        match SpecVersion::parse(version.trim()) {
+            Err(_) => self.push(path, "must be a semver-like string (major.minor[.patch])"),
+            Ok(v) if !v.is_supported() => {
+                self.push(path, "only Arazzo spec 1.0.x is currently supported")
+            }
+            Ok(v) if v > SpecVersion::LATEST => self.violations.push(Violation::warning(
+                path,
+                format!(
+                    "Arazzo {v} is newer than {}, the latest version known to this validator",
+                    SpecVersion::LATEST
+                ),
+            )),
+            Ok(_) => {}
         }
     }
 
     pub(crate) fn validate_extensions(&mut self, path: &str, ext: &Extensions) {
-        for key in ext.keys() {
-            if !key.starts_with("x-") {
-                self.push(
-                    format!("{path}.{key}"),
-                    "unknown field (only x-* specification extensions are allowed)",
-                );
+        for (key, value) in ext {
+            let field_path = format!("{path}.{key}");
+            if let Some(message) = self.options.extensions.check(key, value) {
+                self.push(field_path.clone(), message);
+            }
+            if key.starts_with("x-") {
+                continue;
+            }
+            let versioned = self
+                .options
+                .versioned_fields
+                .iter()
+                .find(|f| f.matches(&field_path));
+            if let Some(field) = versioned {
+                if self.spec_version.map_or(true, |v| v < field.since) {
+                    let declared = self
+                        .spec_version
+                        .map_or_else(|| "no valid version".to_string(), |v| v.to_string());
+                    let message = format!(
+                        "requires Arazzo {} or later, but the document declares {declared}",
+                        field.since
+                    );
+                    self.push(field_path, message);
+                }
+                continue;
+            }
+            let deprecated = self
+                .options
+                .deprecated_fields
+                .iter()
+                .any(|d| d.matches(&field_path));
+            if !deprecated {
+                self.push(field_path, UNKNOWN_FIELD_MESSAGE);
             }
         }
     }
@@ -0,0 +1,87 @@
+//! Arazzo specification versions. Every 1.0.x release is accepted; a document's
+//! declared version decides which version-gated fields it may use (see
+//! [`ValidationOptions::versioned_fields`](crate::ValidationOptions::versioned_fields)).
+
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::types::ArazzoDocument;
+
+/// A `major.minor.patch` spec version. A pre-release suffix (`1.0.1-rc1`) is accepted
+/// and ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpecVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("'{0}' is not a version of the form major.minor[.patch]")]
+pub struct VersionError(pub String);
+
+impl SpecVersion {
+    /// The oldest supported version.
+    pub const V1_0_0: SpecVersion = SpecVersion::new(1, 0, 0);
+    /// The newest version this crate knows about.
+    pub const LATEST: SpecVersion = SpecVersion::new(1, 0, 1);
+
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// `major.minor` or `major.minor.patch`; a missing patch is `0`.
+    pub fn parse(version: &str) -> Result<Self, VersionError> {
+        let invalid = || VersionError(version.to_string());
+        let release = version.split_once('-').map_or(version, |(r, _)| r);
+        let mut parts = release.split('.').map(|p| {
+            (!p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| p.parse::<u32>().ok())
+                .flatten()
+        });
+        let major = parts.next().flatten().ok_or_else(invalid)?;
+        let minor = parts.next().flatten().ok_or_else(invalid)?;
+        let patch = match parts.next() {
+            None => 0,
+            Some(p) => p.ok_or_else(invalid)?,
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Self::new(major, minor, patch))
+    }
+
+    /// Whether documents declaring this version can be processed: 1.0.0 to 1.0.x.
+    /// Patch releases newer than [`LATEST`](Self::LATEST) are accepted, since patch
+    /// releases do not change the feature set in incompatible ways.
+    pub fn is_supported(&self) -> bool {
+        self.major == 1 && self.minor == 0
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for SpecVersion {
+    type Err = VersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl ArazzoDocument {
+    /// The declared `arazzo` version, if it parses.
+    pub fn spec_version(&self) -> Option<SpecVersion> {
+        SpecVersion::parse(self.arazzo.trim()).ok()
+    }
+}
@@ -0,0 +1,19 @@
+use crate::error::WriteError;
+use crate::types::ArazzoDocument;
+
+/// Canonical output: fields follow the order the Arazzo specification lists them in,
+/// unset optional fields are omitted, and `x-` extensions come last in each object,
+/// sorted by key. Writing the same document twice gives identical text, and parsing
+/// the text gives back an equal document.
+impl ArazzoDocument {
+    /// Pretty-printed JSON with two-space indentation and a trailing newline.
+    pub fn to_json(&self) -> Result<String, WriteError> {
+        let mut out = serde_json::to_string_pretty(self)?;
+        out.push('\n');
+        Ok(out)
+    }
+
+    pub fn to_yaml(&self) -> Result<String, WriteError> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
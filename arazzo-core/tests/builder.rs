@@ -0,0 +1,60 @@
+use arazzo_core::types::{
+    DocumentBuilder, ParameterLocation, StepBuilder, SuccessAction, SuccessActionType,
+    WorkflowBuilder,
+};
+use arazzo_core::{parse_document_str, DocumentFormat};
+
+#[test]
+fn builders_produce_a_valid_document() {
+    let doc = DocumentBuilder::new("Pets", "1.0.0")
+        .with_openapi_source("petStore", "https://example.com/openapi.yaml")
+        .with_workflow(
+            WorkflowBuilder::new("adoptPet")
+                .with_inputs(serde_json::json!({
+                    "type": "object",
+                    "properties": {"petId": {"type": "string"}}
+                }))
+                .with_step(
+                    StepBuilder::operation("find", "getPet")
+                        .with_parameter("petId", ParameterLocation::Path, "$inputs.petId")
+                        .with_success_criterion("$statusCode == 200")
+                        .with_output("name", "$response.body#/name")
+                        .with_on_success(SuccessAction {
+                            name: "alreadyAdopted".to_string(),
+                            action_type: SuccessActionType::End,
+                            workflow_id: None,
+                            step_id: None,
+                            criteria: None,
+                            extensions: Default::default(),
+                        }),
+                )
+                .with_step(StepBuilder::operation("adopt", "adoptPet"))
+                .with_output("name", "$steps.find.outputs.name"),
+        )
+        .build()
+        .unwrap();
+
+    // The built document round-trips through the canonical writer and parser.
+    let parsed = parse_document_str(&doc.to_yaml().unwrap(), DocumentFormat::Yaml).unwrap();
+    assert_eq!(parsed.document, doc);
+}
+
+#[test]
+fn build_runs_validation() {
+    let err = DocumentBuilder::new("Pets", "1.0.0")
+        .with_openapi_source("petStore", "https://example.com/openapi.yaml")
+        .with_workflow(
+            WorkflowBuilder::new("w")
+                .with_step(StepBuilder::operation("same", "a"))
+                .with_step(StepBuilder::operation("same", "b")),
+        )
+        .build()
+        .unwrap_err();
+    assert!(
+        err.violations
+            .iter()
+            .any(|v| v.path.starts_with("$.workflows[0].steps[1]")),
+        "{:?}",
+        err.violations
+    );
+}
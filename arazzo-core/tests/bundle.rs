@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use arazzo_core::{bundle_document_with, validate_document, BundleError, BundledDocument};
+
+fn bundle(files: &[(&str, &str)], root: &str) -> Result<BundledDocument, BundleError> {
+    let files: HashMap<PathBuf, String> = files
+        .iter()
+        .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+        .collect();
+    bundle_document_with(Path::new(root), |path| {
+        files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such file"))
+    })
+}
+
+const MAIN: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Main
+  version: 1.0.0
+sourceDescriptions:
+  - name: petStore
+    url: ./openapi/pets.yaml
+    type: openapi
+  - name: shared
+    url: ./shared/auth.arazzo.yaml
+    type: arazzo
+workflows:
+  - workflowId: buyPet
+    steps:
+      - stepId: login
+        workflowId: $sourceDescriptions.shared.login
+      - stepId: buy
+        operationId: buyPet
+        parameters:
+          - reference: $components.parameters.pageSize
+components:
+  parameters:
+    pageSize:
+      $ref: ./params.yaml#/pageSize
+"#;
+
+const PARAMS: &str = r#"
+pageSize:
+  name: pageSize
+  in: query
+  value: 20
+"#;
+
+const AUTH: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Auth
+  version: 1.0.0
+sourceDescriptions:
+  - name: authApi
+    url: ../openapi/auth.yaml
+    type: openapi
+workflows:
+  - workflowId: login
+    steps:
+      - stepId: token
+        operationId: createToken
+"#;
+
+#[test]
+fn bundle_inlines_component_files_and_arazzo_sources() {
+    let bundled = bundle(
+        &[
+            ("specs/main.yaml", MAIN),
+            ("specs/params.yaml", PARAMS),
+            ("specs/shared/auth.arazzo.yaml", AUTH),
+        ],
+        "specs/main.yaml",
+    )
+    .unwrap();
+    let doc = &bundled.document;
+
+    assert_eq!(
+        bundled.files,
+        [
+            PathBuf::from("specs/main.yaml"),
+            PathBuf::from("specs/params.yaml"),
+            PathBuf::from("specs/shared/auth.arazzo.yaml"),
+        ]
+    );
+    let sources: Vec<(&str, &str)> = doc
+        .source_descriptions
+        .iter()
+        .map(|s| (s.name.as_str(), s.url.as_str()))
+        .collect();
+    assert_eq!(
+        sources,
+        [
+            ("petStore", "./openapi/pets.yaml"),
+            ("authApi", "openapi/auth.yaml"),
+        ]
+    );
+    let workflows: Vec<&str> = doc
+        .workflows
+        .iter()
+        .map(|w| w.workflow_id.as_str())
+        .collect();
+    assert_eq!(workflows, ["buyPet", "login"]);
+    assert_eq!(
+        doc.workflows[0].steps[0].workflow_id.as_deref(),
+        Some("login")
+    );
+
+    let params = doc
+        .components
+        .as_ref()
+        .unwrap()
+        .parameters
+        .as_ref()
+        .unwrap();
+    assert_eq!(params["pageSize"].value, serde_json::json!(20));
+
+    validate_document(doc).unwrap();
+}
+
+#[test]
+fn bundle_reports_cycles_and_conflicts() {
+    let source = |name: &str, url: &str| {
+        format!(
+            "arazzo: 1.0.1\ninfo: {{title: T, version: 1.0.0}}\nsourceDescriptions:\n  - name: {name}\n    url: {url}\n    type: arazzo\nworkflows:\n  - workflowId: {name}\n    steps:\n      - stepId: s\n        operationId: op\n"
+        )
+    };
+    let a = source("a", "b.yaml");
+    let b = source("b", "a.yaml");
+    let err = bundle(&[("a.yaml", &a), ("b.yaml", &b)], "a.yaml").unwrap_err();
+    assert!(
+        matches!(&err, BundleError::Cycle(chain) if chain == &["a.yaml", "b.yaml", "a.yaml"]),
+        "{err}"
+    );
+
+    // Both files define a workflow called `dup`.
+    let root = source("dup", "other.yaml");
+    let other = "arazzo: 1.0.1\ninfo: {title: T, version: 1.0.0}\nsourceDescriptions: []\nworkflows:\n  - workflowId: dup\n    steps:\n      - stepId: s\n        operationId: op\n";
+    let err = bundle(&[("root.yaml", &root), ("other.yaml", other)], "root.yaml").unwrap_err();
+    assert!(
+        matches!(&err, BundleError::Conflict { kind: "workflow", name, .. } if name == "dup"),
+        "{err}"
+    );
+}
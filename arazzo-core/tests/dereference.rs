@@ -0,0 +1,145 @@
+use arazzo_core::types::{FailureActionOrReusable, ParameterOrReusable, SuccessActionOrReusable};
+use arazzo_core::{
+    dereference, dereference_workflow_best_effort, parse_document_str, DocumentFormat,
+};
+
+const DOC: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Pets
+  version: 1.0.0
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: pets
+    parameters:
+      - reference: $components.parameters.pageSize
+    failureActions:
+      - reference: $components.failureActions.retry
+    steps:
+      - stepId: list
+        operationId: listPets
+        parameters:
+          - reference: $components.parameters.pageSize
+            value: 50
+          - name: sort
+            in: query
+            value: name
+        onSuccess:
+          - reference: $components.successActions.done
+components:
+  parameters:
+    pageSize:
+      name: limit
+      in: query
+      value: 10
+  successActions:
+    done:
+      name: done
+      type: end
+  failureActions:
+    retry:
+      name: retry
+      type: retry
+      retryLimit: 3
+"#;
+
+#[test]
+fn dereference_inlines_every_reusable_object() {
+    let doc = parse_document_str(DOC, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let out = dereference(&doc).unwrap();
+    let wf = &out.workflows[0];
+
+    let ParameterOrReusable::Parameter(default) = &wf.parameters.as_ref().unwrap()[0] else {
+        panic!("workflow parameter was not inlined");
+    };
+    assert_eq!(
+        (default.name.as_str(), &default.value),
+        ("limit", &10.into())
+    );
+    assert!(matches!(
+        &wf.failure_actions.as_ref().unwrap()[0],
+        FailureActionOrReusable::Action(a) if a.name == "retry" && a.retry_limit == Some(3)
+    ));
+
+    let step = &wf.steps[0];
+    let params = step.parameters.as_ref().unwrap();
+    let ParameterOrReusable::Parameter(limit) = &params[0] else {
+        panic!("step parameter was not inlined");
+    };
+    assert_eq!(
+        limit.value, 50,
+        "the reusable value overrides the component"
+    );
+    assert!(matches!(&params[1], ParameterOrReusable::Parameter(p) if p.name == "sort"));
+    assert!(matches!(
+        &step.on_success.as_ref().unwrap()[0],
+        SuccessActionOrReusable::Action(a) if a.name == "done"
+    ));
+
+    assert_eq!(out.components, doc.components);
+}
+
+#[test]
+fn dereference_reports_unresolved_references() {
+    let doc = parse_document_str(
+        &DOC.replace(
+            "$components.successActions.done",
+            "$components.successActions.missing",
+        ),
+        DocumentFormat::Yaml,
+    )
+    .unwrap()
+    .document;
+    let err = dereference(&doc).unwrap_err();
+    assert_eq!(err.path, "$.workflows[0].steps[0].onSuccess[0]");
+    assert_eq!(err.reason, "no successActions component named 'missing'");
+
+    let doc = parse_document_str(
+        &DOC.replace(
+            "$components.successActions.done",
+            "$components.parameters.pageSize",
+        ),
+        DocumentFormat::Yaml,
+    )
+    .unwrap()
+    .document;
+    let err = dereference(&doc).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "$.workflows[0].steps[0].onSuccess[0]: cannot resolve '$components.parameters.pageSize': expected a $components.successActions.<name> reference"
+    );
+}
+
+#[test]
+fn best_effort_inlines_what_resolves_and_keeps_the_rest() {
+    let doc = parse_document_str(
+        &DOC.replace(
+            "$components.successActions.done",
+            "$components.successActions.missing",
+        ),
+        DocumentFormat::Yaml,
+    )
+    .unwrap()
+    .document;
+    let (wf, errors) = dereference_workflow_best_effort(&doc, &doc.workflows[0]);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, "$.workflows[0].steps[0].onSuccess[0]");
+    let step = &wf.steps[0];
+    assert!(matches!(
+        &step.on_success.as_ref().unwrap()[0],
+        SuccessActionOrReusable::Reusable(r) if r.reference == "$components.successActions.missing"
+    ));
+    let ParameterOrReusable::Parameter(page_size) = &step.parameters.as_ref().unwrap()[0] else {
+        panic!("resolvable parameter was not inlined");
+    };
+    assert_eq!(page_size.value, 50);
+    assert!(matches!(
+        &wf.failure_actions.as_ref().unwrap()[0],
+        FailureActionOrReusable::Action(a) if a.name == "retry"
+    ));
+}
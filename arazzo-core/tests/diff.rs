@@ -0,0 +1,88 @@
+use arazzo_core::{diff_documents, parse_document_str, ChangeKind, DocumentFormat};
+
+const OLD: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Pets
+  version: 1.0.0
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: pets
+    steps:
+      - stepId: list
+        operationId: listPets
+        parameters:
+          - name: limit
+            in: query
+            value: 10
+        successCriteria:
+          - condition: $statusCode == 200
+      - stepId: show
+        operationId: getPet
+      - stepId: audit
+        operationId: audit
+"#;
+
+fn diff(old: &str, new: &str) -> Vec<String> {
+    let old = parse_document_str(old, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let new = parse_document_str(new, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    diff_documents(&old, &new)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn identical_documents_have_no_changes() {
+    assert!(diff(OLD, OLD).is_empty());
+}
+
+#[test]
+fn diff_matches_steps_and_parameters_by_id() {
+    let new = OLD
+        .replace("version: 1.0.0", "version: 1.1.0")
+        .replace("value: 10", "value: 20")
+        .replace("== 200", "== 201")
+        .replace(
+            "      - stepId: audit\n        operationId: audit\n",
+            "      - stepId: delete\n        operationId: deletePet\n",
+        );
+    assert_eq!(
+        diff(OLD, &new),
+        [
+            "~ info.version (1.0.0 -> 1.1.0)",
+            "- pets.audit: step",
+            "~ pets.list: parameter query:limit",
+            "~ pets.list: successCriteria",
+            "+ pets.delete: step",
+        ]
+    );
+}
+
+#[test]
+fn reordering_steps_is_one_change() {
+    let new = OLD.replace(
+        "      - stepId: show\n        operationId: getPet\n      - stepId: audit\n        operationId: audit\n",
+        "      - stepId: audit\n        operationId: audit\n      - stepId: show\n        operationId: getPet\n",
+    );
+    let old = parse_document_str(OLD, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let new = parse_document_str(&new, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let changes = diff_documents(&old, &new);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, ChangeKind::Changed);
+    assert_eq!(changes[0].subject, "step order");
+    assert_eq!(
+        changes[0].new,
+        Some(serde_json::json!(["list", "audit", "show"]))
+    );
+}
@@ -0,0 +1,160 @@
+use arazzo_core::{EditError, YamlDocument};
+use serde_json::json;
+
+const BASE: &str = r#"# Pet workflows, maintained by hand.
+arazzo: 1.0.1
+info:
+  title: Pets # shown in the catalog
+  version: "1.0.0"
+sourceDescriptions:
+  - name: petStore
+    url: &store https://example.com/openapi.yaml
+
+workflows:
+  - workflowId: pets
+    # Audit runs before anything is listed.
+    steps:
+      - stepId: audit
+        operationId: audit
+      - stepId: list
+        operationId: listPets
+        successCriteria: [{condition: $statusCode == 200}]
+"#;
+
+fn base() -> YamlDocument {
+    YamlDocument::parse(BASE).unwrap()
+}
+
+#[test]
+fn set_rewrites_only_the_selected_scalars() {
+    let mut doc = base();
+    let url = json!("https://staging.example.com/openapi.yaml");
+    assert_eq!(
+        doc.set("$.sourceDescriptions[?(@.name == 'petStore')].url", &url)
+            .unwrap(),
+        1
+    );
+    doc.set("$.info.title", &json!("Staging pets")).unwrap();
+    doc.set("$.info.version", &json!("1.1.0")).unwrap();
+
+    let expected = BASE
+        .replace(
+            "&store https://example.com/openapi.yaml",
+            "&store https://staging.example.com/openapi.yaml",
+        )
+        .replace("title: Pets #", "title: Staging pets #")
+        .replace("version: \"1.0.0\"", "version: \"1.1.0\"");
+    assert_eq!(doc.as_str(), expected);
+    let parsed = doc.document().unwrap().document;
+    assert_eq!(parsed.info.version, "1.1.0");
+}
+
+#[test]
+fn set_adds_missing_keys_and_sequence_items() {
+    let mut doc = base();
+    doc.set("$.workflows[0].steps[0].description", &json!("Audit first"))
+        .unwrap();
+    doc.set(
+        "$.workflows[0].steps[2]",
+        &json!({"stepId": "show", "operationId": "getPet"}),
+    )
+    .unwrap();
+    doc.set(
+        "$.workflows[0].outputs",
+        &json!({"count": "$steps.list.outputs.count"}),
+    )
+    .unwrap();
+
+    let expected = BASE
+        .replace(
+            "        operationId: audit\n",
+            "        operationId: audit\n        description: Audit first\n",
+        )
+        .replace(
+            "[{condition: $statusCode == 200}]\n",
+            "[{condition: $statusCode == 200}]\n      - operationId: getPet\n        stepId: show\n    outputs:\n      count: $steps.list.outputs.count\n",
+        );
+    assert_eq!(doc.as_str(), expected);
+}
+
+#[test]
+fn set_replaces_collections_and_edits_inside_flow_collections() {
+    let mut doc = base();
+    doc.set(
+        "$.workflows[0].steps[1].successCriteria[1]",
+        &json!({"condition": "$response.body.count > 0"}),
+    )
+    .unwrap();
+    doc.set("$.info", &json!({"title": "Pets", "version": "2.0.0"}))
+        .unwrap();
+
+    let expected = BASE
+        .replace(
+            "[{condition: $statusCode == 200}]",
+            "[{condition: $statusCode == 200}, {condition: $response.body.count > 0}]",
+        )
+        .replace(
+            "info:\n  title: Pets # shown in the catalog\n  version: \"1.0.0\"\n",
+            "info:\n  title: Pets\n  version: 2.0.0\n",
+        );
+    assert_eq!(doc.as_str(), expected);
+    doc.document().unwrap();
+}
+
+#[test]
+fn remove_deletes_entries_and_items_with_their_lines() {
+    let mut doc = base();
+    assert_eq!(
+        doc.remove("$.workflows[0].steps[?(@.stepId == 'audit')]")
+            .unwrap(),
+        1
+    );
+    doc.remove("$.workflows[0].steps[0].successCriteria")
+        .unwrap();
+    doc.remove("$.info.title").unwrap();
+
+    let expected = BASE
+        .replace("  title: Pets # shown in the catalog\n", "")
+        .replace("      - stepId: audit\n        operationId: audit\n", "")
+        .replace(
+            "        successCriteria: [{condition: $statusCode == 200}]\n",
+            "",
+        );
+    assert_eq!(doc.as_str(), expected);
+
+    // Dropping the first key of a step pulls the next one onto the `-` line, and
+    // dropping the last item leaves an empty sequence.
+    doc.remove("$.workflows[0].steps[0].stepId").unwrap();
+    assert!(doc
+        .as_str()
+        .contains("    steps:\n      - operationId: listPets\n"));
+    doc.remove("$.workflows[0].steps[0]").unwrap();
+    assert!(doc.as_str().contains("    steps: []\n"), "{}", doc.as_str());
+}
+
+#[test]
+fn edits_report_unmatched_targets_and_unsupported_layouts() {
+    let mut doc = base();
+    assert!(matches!(
+        doc.remove("$.workflows[0].steps[?(@.stepId == 'missing')]"),
+        Err(EditError::NoMatch(_))
+    ));
+    assert!(matches!(
+        doc.set("$.workflows[0].steps[5]", &json!({})),
+        Err(EditError::NoMatch(_))
+    ));
+    assert!(matches!(
+        doc.set("workflows", &json!([])),
+        Err(EditError::Target { .. })
+    ));
+    assert_eq!(doc.as_str(), BASE);
+
+    assert!(matches!(
+        YamlDocument::parse("{\"arazzo\": \"1.0.1\"}"),
+        Err(EditError::Layout(_))
+    ));
+    assert!(matches!(
+        YamlDocument::parse("arazzo: 1.0.1\n---\narazzo: 1.0.1\n"),
+        Err(EditError::Parse(_) | EditError::Layout(_))
+    ));
+}
@@ -0,0 +1,83 @@
+use arazzo_core::{interpolate_env_with, parse_document_str, DocumentFormat, EnvVariable};
+
+const DOC: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Pets
+  version: 1.0.0
+sourceDescriptions:
+  - name: petStore
+    url: https://${API_HOST}/openapi.yaml
+workflows:
+  - workflowId: pets
+    parameters:
+      - name: X-Tenant
+        in: header
+        value: ${TENANT}
+    steps:
+      - stepId: list
+        operationId: listPets
+        parameters:
+          - name: filter
+            in: query
+            value:
+              region: ${REGION}
+              literal: $${REGION}
+          - reference: $components.parameters.token
+        description: ${API_HOST} is only expanded in URLs and parameter values
+components:
+  parameters:
+    token:
+      name: Authorization
+      in: header
+      value: Bearer {$inputs.token}
+"#;
+
+fn variable(name: &str, path: &str) -> EnvVariable {
+    EnvVariable {
+        name: name.to_string(),
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn env_placeholders_are_expanded_and_reported() {
+    let mut doc = parse_document_str(DOC, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let report = interpolate_env_with(&mut doc, |name| match name {
+        "API_HOST" => Some("staging.example.com".to_string()),
+        "REGION" => Some("eu".to_string()),
+        _ => None,
+    });
+
+    assert_eq!(
+        doc.source_descriptions[0].url,
+        "https://staging.example.com/openapi.yaml"
+    );
+    let step = serde_json::to_value(&doc.workflows[0].steps[0]).unwrap();
+    assert_eq!(step["parameters"][0]["value"]["region"], "eu");
+    assert_eq!(step["parameters"][0]["value"]["literal"], "${REGION}");
+    assert_eq!(
+        step["description"],
+        "${API_HOST} is only expanded in URLs and parameter values"
+    );
+
+    assert_eq!(
+        report.substituted,
+        [
+            variable("API_HOST", "$.sourceDescriptions[0].url"),
+            variable(
+                "REGION",
+                "$.workflows[0].steps[0].parameters[0].value.region"
+            ),
+        ]
+    );
+    assert_eq!(
+        report.missing,
+        [variable("TENANT", "$.workflows[0].parameters[0].value")]
+    );
+    assert!(!report.is_complete());
+    let workflow = serde_json::to_value(&doc.workflows[0]).unwrap();
+    assert_eq!(workflow["parameters"][0]["value"], "${TENANT}");
+}
@@ -0,0 +1,242 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use arazzo_core::expressions::{
+    eval_expression, eval_value, parse_pipeline, parse_template, referenced_steps, EvalContext,
+    EvalError, Function, FunctionError, RequestContext, ResponseContext, TemplateDelimiters,
+    TemplateError,
+};
+use serde_json::{json, Value as JsonValue};
+
+fn eval_with(
+    value: &JsonValue,
+    response: Option<ResponseContext<'_>>,
+) -> Result<JsonValue, EvalError> {
+    let inputs = json!({"user": {"name": "ada", "id": 7}, "cleared": null});
+    let steps = BTreeMap::from([(
+        "login".to_string(),
+        json!({"token": "abc123", "profile": {"roles": ["admin"]}}),
+    )]);
+    let ctx = EvalContext {
+        inputs: &inputs,
+        steps: &steps,
+        response,
+        delimiters: &TemplateDelimiters::default(),
+    };
+    eval_value(value, &ctx)
+}
+
+#[test]
+fn evaluates_inputs_step_outputs_and_templates() {
+    let value = json!({
+        "name": "$inputs.user.name",
+        "cleared": "$inputs.cleared",
+        "token": "$steps.login.outputs.token",
+        "role": "$steps.login.outputs.profile#/roles/0",
+        "auth": "Bearer { $steps.login.outputs.token } for {$inputs.user.id}",
+        "literal": "{not an expression}",
+        "list": [1, true, "$inputs.user.id"],
+    });
+    assert_eq!(
+        eval_with(&value, None).unwrap(),
+        json!({
+            "name": "ada",
+            "cleared": null,
+            "token": "abc123",
+            "role": "admin",
+            "auth": "Bearer abc123 for 7",
+            "literal": "{not an expression}",
+            "list": [1, true, 7],
+        })
+    );
+}
+
+#[test]
+fn evaluates_response_expressions() {
+    let headers = BTreeMap::from([("X-Trace".to_string(), "a\nb".to_string())]);
+    let body = br#"{"items": [{"id": 1}, {"id": 2}]}"#;
+    let response = ResponseContext {
+        status: 201,
+        headers: &headers,
+        body,
+        body_json: serde_json::from_slice(body).ok(),
+        request: None,
+    };
+    let value = json!([
+        "$statusCode",
+        "$response.header.x-trace",
+        "$response.header.X-Trace#all",
+        "$response.body#/items/1/id",
+        "$response.body$.items[*].id",
+        "$response.header.X-Missing",
+        "$response.header.X-Missing#all",
+    ]);
+    assert_eq!(
+        eval_with(&value, Some(response)).unwrap(),
+        json!([201, "a, b", ["a", "b"], 2, [1, 2], null, []])
+    );
+}
+
+#[test]
+fn evaluates_request_expressions() {
+    let headers = BTreeMap::from([("X-Request-Id".to_string(), "req-1".to_string())]);
+    let query = BTreeMap::from([("limit".to_string(), "5".to_string())]);
+    let path = BTreeMap::from([("petId".to_string(), "42".to_string())]);
+    let body = br#"{"tags": ["a", "b"]}"#;
+    let response = ResponseContext {
+        status: 200,
+        headers: &BTreeMap::new(),
+        body: b"",
+        body_json: None,
+        request: Some(RequestContext {
+            method: "POST",
+            url: "https://api.example.com/pets/42?limit=5",
+            headers: &headers,
+            query: &query,
+            path: &path,
+            body,
+            body_json: serde_json::from_slice(body).ok(),
+        }),
+    };
+    let value = json!([
+        "$method",
+        "$url",
+        "$request.header.x-request-id",
+        "$request.query.limit",
+        "$request.query.offset",
+        "$request.path.petId",
+        "$request.body#/tags/1",
+        "$request.body$.tags[0]",
+        "{ $request.header.X-Request-Id | toUpper }",
+    ]);
+    assert_eq!(
+        eval_with(&value, Some(response)).unwrap(),
+        json!([
+            "POST",
+            "https://api.example.com/pets/42?limit=5",
+            "req-1",
+            "5",
+            null,
+            "42",
+            "b",
+            ["a"],
+            "REQ-1",
+        ])
+    );
+}
+
+#[test]
+fn reports_what_could_not_be_resolved() {
+    let err = |expr: &str| eval_with(&json!(expr), None).unwrap_err();
+    assert_eq!(
+        err("$inputs.missing"),
+        EvalError::MissingInput("missing".into())
+    );
+    assert_eq!(
+        err("$steps.login.outputs.absent"),
+        EvalError::MissingStepOutput("absent".into())
+    );
+    assert_eq!(err("$steps.login.inputs"), EvalError::UnsupportedStepPath);
+    assert_eq!(err("$response.body"), EvalError::NoResponse);
+    assert!(matches!(err("$nope"), EvalError::RuntimeExpr(_)));
+    assert_eq!(err("$url"), EvalError::NoRequest);
+    assert_eq!(
+        err("$outputs.total").to_string(),
+        "unsupported runtime expression"
+    );
+
+    // Without a response `$statusCode` is 0.
+    let inputs = json!({});
+    let ctx = EvalContext {
+        inputs: &inputs,
+        steps: &BTreeMap::new(),
+        response: None,
+        delimiters: &TemplateDelimiters::default(),
+    };
+    assert_eq!(eval_expression("$statusCode", &ctx).unwrap(), json!(0));
+}
+
+#[test]
+fn referenced_steps_lists_step_outputs_read() {
+    let value = json!({
+        "a": "$steps.login.outputs.token",
+        "b": ["id={$steps.lookup.outputs.id}", "<% $steps.custom.outputs.x %>"],
+        "c": "$steps.other.inputs",
+        "d": "$inputs.user",
+    });
+    assert_eq!(
+        referenced_steps(&value, &TemplateDelimiters::default()),
+        BTreeSet::from(["login".to_string(), "lookup".to_string()])
+    );
+    assert!(referenced_steps(&value, &TemplateDelimiters::new("<%", "%>")).contains("custom"));
+}
+
+#[test]
+fn applies_functions_in_embedded_expressions() {
+    let value = json!({
+        "host": "https://{ $inputs.host | default('api.example.com') }/v1",
+        "cleared": "{ $inputs.cleared | default(\"none\") }",
+        "upper": "{ $inputs.user.name | toUpper }",
+        "label": "{$inputs.user.name | concat('-', $inputs.user.id, '-', $steps.login.outputs.token) | toLower}",
+        "port": "{ $inputs.port | default('8080') | number }",
+        "id": "{ $inputs.user.id | string }",
+        "fallback": "{ $steps.login.outputs.missing | default($inputs.user.id) }",
+    });
+    assert_eq!(
+        eval_with(&value, None).unwrap(),
+        json!({
+            "host": "https://api.example.com/v1",
+            "cleared": "none",
+            "upper": "ADA",
+            "label": "ada-7-abc123",
+            "port": 8080,
+            "id": "7",
+            "fallback": 7,
+        })
+    );
+
+    // Only a missing value falls back; other failures still surface.
+    assert_eq!(
+        eval_with(&json!("{ $response.body | default(1) }"), None).unwrap_err(),
+        EvalError::NoResponse
+    );
+    assert_eq!(
+        eval_with(&json!("{ $inputs.user.name | number }"), None)
+            .unwrap_err()
+            .to_string(),
+        "number() cannot convert \"ada\""
+    );
+}
+
+#[test]
+fn parses_function_pipelines() {
+    let pipeline =
+        parse_pipeline("$inputs.a | default('x, y') | concat(1, $inputs.b) | toUpper").unwrap();
+    let functions: Vec<Function> = pipeline.functions.iter().map(|c| c.function).collect();
+    assert_eq!(
+        functions,
+        vec![Function::Default, Function::Concat, Function::ToUpper]
+    );
+    assert_eq!(pipeline.expressions().count(), 2);
+    assert!(pipeline.has_default());
+
+    // A `|` that is not preceded by whitespace belongs to the expression.
+    let pipeline = parse_pipeline("$response.body#/a|b").unwrap();
+    assert!(pipeline.functions.is_empty());
+
+    assert_eq!(
+        parse_template("{ $inputs.a | shout }").unwrap_err(),
+        TemplateError::InvalidFunction(FunctionError::Unknown("shout".into()))
+    );
+    assert!(matches!(
+        parse_template("{ $inputs.a | default }").unwrap_err(),
+        TemplateError::InvalidFunction(FunctionError::Arity { found: 0, .. })
+    ));
+    assert!(matches!(
+        parse_template("{ $inputs.a | concat(bare) }").unwrap_err(),
+        TemplateError::InvalidFunction(FunctionError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        parse_template("{ $nope | toUpper }").unwrap_err(),
+        TemplateError::InvalidRuntimeExpr(_)
+    ));
+}
@@ -0,0 +1,84 @@
+use arazzo_core::{
+    parse_document_str, validate_document_with, DocumentFormat, ExtensionRegistry,
+    ValidationOptions,
+};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Retry {
+    limit: u32,
+    #[serde(rename = "backoffMs")]
+    backoff_ms: u64,
+}
+
+const DOC: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    x-timeout: 30
+    steps:
+      - stepId: s1
+        operationId: listPets
+        x-retry:
+          limit: 3
+          backoffMs: 250
+      - stepId: s2
+        operationId: getPet
+        x-retry:
+          limit: many
+        x-unregistered: anything
+"#;
+
+fn registry() -> ExtensionRegistry {
+    ExtensionRegistry::new()
+        .with_extension::<u64>("x-timeout")
+        .with_extension::<Retry>("x-retry")
+}
+
+#[test]
+fn registered_extensions_decode_into_their_types() {
+    let parsed = parse_document_str(DOC, DocumentFormat::Yaml).unwrap();
+    let values = registry().extract(&parsed.document);
+
+    assert_eq!(values.get::<u64>("$.workflows[0]", "x-timeout"), Some(&30));
+    assert_eq!(
+        values.get::<Retry>("$.workflows[0].steps[0]", "x-retry"),
+        Some(&Retry {
+            limit: 3,
+            backoff_ms: 250
+        })
+    );
+    // Asking for the wrong type finds nothing.
+    assert_eq!(values.get::<String>("$.workflows[0]", "x-timeout"), None);
+    assert_eq!(values.all::<Retry>("x-retry").count(), 1);
+
+    assert_eq!(values.diagnostics.len(), 1, "{:?}", values.diagnostics);
+    assert_eq!(
+        values.diagnostics[0].path,
+        "$.workflows[0].steps[1].x-retry"
+    );
+    assert!(values.diagnostics[0]
+        .message
+        .starts_with("invalid x-retry extension:"));
+}
+
+#[test]
+fn validation_rejects_malformed_registered_extensions() {
+    let parsed = parse_document_str(DOC, DocumentFormat::Yaml).unwrap();
+    // Without a registry every x- extension is opaque and accepted.
+    validate_document_with(&parsed.document, &ValidationOptions::default()).unwrap();
+
+    let options = ValidationOptions {
+        extensions: registry(),
+        ..Default::default()
+    };
+    let err = validate_document_with(&parsed.document, &options).unwrap_err();
+    let paths: Vec<&str> = err.violations.iter().map(|v| v.path.as_str()).collect();
+    assert_eq!(paths, ["$.workflows[0].steps[1].x-retry"]);
+}
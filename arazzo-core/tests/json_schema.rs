@@ -0,0 +1,132 @@
+#![cfg(feature = "json-schema-validation")]
+
+use arazzo_core::{
+    json_schema_violations, parse_document_str, parse_value_str, validate_document,
+    validation_report_with_schema, DocumentFormat, RuleLevel, RuleOverride, ValidationOptions,
+};
+
+const TOLERATED: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    dependsOn: [w2, w2]
+    steps:
+      - stepId: s1
+        operationId: listPets
+        successCriteria: []
+  - workflowId: w2
+    steps:
+      - stepId: s1
+        operationId: listPets
+"#;
+
+#[test]
+fn schema_catches_what_the_rules_tolerate() {
+    let parsed = parse_document_str(TOLERATED, DocumentFormat::Yaml).unwrap();
+    validate_document(&parsed.document).unwrap();
+
+    let raw = parse_value_str(TOLERATED, DocumentFormat::Yaml).unwrap();
+    let report =
+        validation_report_with_schema(&parsed.document, &raw, &ValidationOptions::default());
+    let paths: Vec<_> = report.errors().map(|v| v.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "$.workflows[0].dependsOn",
+            "$.workflows[0].steps[0].successCriteria"
+        ]
+    );
+    assert!(report.violations[1]
+        .message
+        .starts_with("does not match the Arazzo JSON Schema:"));
+
+    let options = ValidationOptions {
+        overrides: vec![RuleOverride::new(
+            "$.workflows[*].dependsOn",
+            RuleLevel::Off,
+        )],
+        ..Default::default()
+    };
+    let report = validation_report_with_schema(&parsed.document, &raw, &options);
+    assert_eq!(report.errors().count(), 1);
+}
+
+#[test]
+fn unknown_fields_are_reported_once_at_the_field() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: listPets
+        succesCriteria:
+          - condition: $statusCode == 200
+"#;
+    let raw: serde_json::Value = serde_yaml::from_str(doc).unwrap();
+    let schema = json_schema_violations(&raw);
+    assert_eq!(schema.len(), 1, "{schema:?}");
+    assert_eq!(schema[0].path, "$.workflows[0].steps[0].succesCriteria");
+
+    // The rules report the same field, so the schema finding is not repeated.
+    let parsed = parse_document_str(doc, DocumentFormat::Yaml).unwrap();
+    let report =
+        validation_report_with_schema(&parsed.document, &raw, &ValidationOptions::default());
+    assert_eq!(report.errors().count(), 1);
+}
+
+#[test]
+fn example_documents_match_the_schema() {
+    for name in [
+        "httpbin.arazzo.yaml",
+        "httpbin_advanced.arazzo.yaml",
+        "httpbin_parallel.arazzo.yaml",
+    ] {
+        let content =
+            std::fs::read_to_string(format!("{}/../examples/{name}", env!("CARGO_MANIFEST_DIR")))
+                .unwrap();
+        let raw: serde_json::Value = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(json_schema_violations(&raw), vec![], "{name}");
+    }
+}
+
+#[test]
+fn schema_checks_the_value_in_the_documents_own_format() {
+    let doc = r#"
+arazzo = "1.0.1"
+
+[info]
+title = "Example"
+version = "0.0.1"
+
+[[sourceDescriptions]]
+name = "petStore"
+url = "https://example.com/openapi.yaml"
+
+[[workflows]]
+workflowId = "w1"
+
+[[workflows.steps]]
+stepId = "s1"
+operationId = "listPets"
+successCriteria = []
+"#;
+    let parsed = parse_document_str(doc, DocumentFormat::Toml).unwrap();
+    let raw = parse_value_str(doc, DocumentFormat::Toml).unwrap();
+    let report =
+        validation_report_with_schema(&parsed.document, &raw, &ValidationOptions::default());
+    let paths: Vec<_> = report.errors().map(|v| v.path.as_str()).collect();
+    assert_eq!(paths, ["$.workflows[0].steps[0].successCriteria"]);
+}
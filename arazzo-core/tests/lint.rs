@@ -0,0 +1,185 @@
+use arazzo_core::{lint_document, parse_document_str, DocumentFormat, LintRule, Severity};
+
+fn lint(yaml: &str) -> Vec<(LintRule, String)> {
+    let parsed = parse_document_str(yaml, DocumentFormat::Yaml).unwrap();
+    lint_document(&parsed.document)
+        .into_iter()
+        .map(|d| (d.rule, d.path))
+        .collect()
+}
+
+#[test]
+fn lint_reports_each_rule_at_its_node() {
+    let found = lint(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: login
+    steps:
+      - stepId: auth
+        operationId: login
+        outputs:
+          token: $response.body#/token
+    outputs:
+      token: $steps.auth.outputs.token
+  - workflowId: audit
+    steps:
+      - stepId: ping
+        operationId: ping
+  - workflowId: pets
+    dependsOn: [login, audit]
+    steps:
+      - stepId: list
+        operationId: listPets
+        parameters:
+          - reference: $components.parameters.pageSize
+          - name: Authorization
+            in: header
+            value: "Bearer {$workflows.login.outputs.token}"
+        onSuccess:
+          - name: done
+            type: end
+        outputs:
+          first: $response.body#/0/id
+          count: $response.body#/length
+      - stepId: show
+        operationId: getPet
+        parameters:
+          - name: id
+            in: path
+            value: $steps.list.outputs.first
+components:
+  parameters:
+    pageSize:
+      name: limit
+      in: query
+      value: 10
+    unused:
+      name: offset
+      in: query
+      value: 0
+"#,
+    );
+
+    assert_eq!(
+        found,
+        vec![
+            (
+                LintRule::UnusedDependsOn,
+                "$.workflows[2].dependsOn[1]".to_string()
+            ),
+            (
+                LintRule::UnreachableStep,
+                "$.workflows[2].steps[1]".to_string()
+            ),
+            (
+                LintRule::UnusedStepOutput,
+                "$.workflows[2].steps[0].outputs.count".to_string()
+            ),
+            (
+                LintRule::UnusedComponentParameter,
+                "$.components.parameters.unused".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn goto_and_conditional_actions_keep_steps_reachable() {
+    let found = lint(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: first
+        operationId: a
+        onFailure:
+          - name: recover
+            type: goto
+            stepId: third
+        onSuccess:
+          - name: maybeStop
+            type: end
+            criteria:
+              - condition: $statusCode == 204
+      - stepId: second
+        operationId: b
+        onSuccess:
+          - reference: $components.successActions.stop
+      - stepId: third
+        operationId: c
+      - stepId: fourth
+        operationId: d
+components:
+  successActions:
+    stop:
+      name: stop
+      type: end
+"#,
+    );
+
+    assert!(found.is_empty(), "{found:?}");
+}
+
+#[test]
+fn references_in_conditions_and_templates_count_as_used() {
+    let found = lint(
+        r#"
+arazzo: 1.0.1
+x-template-delimiters:
+  open: "<<"
+  close: ">>"
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: auth
+        operationId: login
+        outputs:
+          token: $response.body#/token
+          expires: $response.body#/expires
+          scope: $response.body#/scope
+      - stepId: me
+        operationId: whoami
+        parameters:
+          - name: Authorization
+            in: header
+            value: "Bearer <<$steps.auth.outputs.token>>"
+          - name: scope
+            in: query
+            value: "<<$steps.auth.outputs.scope | default('read')>>"
+        successCriteria:
+          - condition: $statusCode == 200 && $steps.auth.outputs.expires > 0
+"#,
+    );
+
+    assert!(found.is_empty(), "{found:?}");
+}
+
+#[test]
+fn lint_rules_have_ids_and_severities() {
+    for rule in LintRule::ALL {
+        assert_eq!(rule.id().parse::<LintRule>(), Ok(rule));
+    }
+    assert_eq!(LintRule::UnreachableStep.severity(), Severity::Error);
+    assert_eq!(LintRule::UnusedStepOutput.severity(), Severity::Warning);
+    assert!("no-such-rule".parse::<LintRule>().is_err());
+}
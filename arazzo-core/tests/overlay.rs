@@ -0,0 +1,129 @@
+use arazzo_core::{
+    apply_overlay, parse_document_str, parse_overlay_str, DocumentFormat, OverlayError,
+};
+
+const BASE: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Pets
+  version: 1.0.0
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: pets
+    inputs:
+      type: object
+      properties:
+        limit:
+          type: integer
+          default: 10
+    steps:
+      - stepId: list
+        operationId: listPets
+      - stepId: audit
+        operationId: audit
+      - stepId: show
+        operationId: getPet
+"#;
+
+fn overlay(yaml: &str) -> Result<arazzo_core::ArazzoDocument, OverlayError> {
+    let doc = parse_document_str(BASE, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    apply_overlay(&doc, &parse_overlay_str(yaml)?)
+}
+
+#[test]
+fn overlay_patches_sources_inputs_and_steps() {
+    let doc = overlay(
+        r#"
+overlay: 1.0.0
+info:
+  title: Staging
+  version: 1.0.0
+actions:
+  - target: $.sourceDescriptions[?(@.name == 'petStore')]
+    update:
+      url: https://staging.example.com/openapi.yaml
+  - target: $.workflows[?(@.workflowId == 'pets')].inputs.properties.limit
+    update:
+      default: 50
+  - target: $.workflows[0].steps[?(@.stepId == 'audit')]
+    remove: true
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        doc.source_descriptions[0].url,
+        "https://staging.example.com/openapi.yaml"
+    );
+    let inputs = doc.workflows[0].inputs.as_ref().unwrap();
+    assert_eq!(inputs["properties"]["limit"]["default"], 50);
+    assert_eq!(inputs["properties"]["limit"]["type"], "integer");
+    let steps: Vec<&str> = doc.workflows[0]
+        .steps
+        .iter()
+        .map(|s| s.step_id.as_str())
+        .collect();
+    assert_eq!(steps, ["list", "show"]);
+}
+
+#[test]
+fn overlay_appends_to_selected_arrays() {
+    let doc = overlay(
+        r#"
+overlay: 1.0.0
+info: { title: Extra step, version: 1.0.0 }
+actions:
+  - target: $.workflows[0].steps
+    update:
+      stepId: cleanup
+      operationId: cleanup
+"#,
+    )
+    .unwrap();
+    assert_eq!(doc.workflows[0].steps.last().unwrap().step_id, "cleanup");
+}
+
+#[test]
+fn overlay_rejects_targets_that_select_nothing() {
+    let err = overlay(
+        r#"
+overlay: 1.0.0
+info: { title: Stale, version: 1.0.0 }
+actions:
+  - target: $.workflows[?(@.workflowId == 'gone')]
+    remove: true
+"#,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, OverlayError::NoMatch { index: 0, .. }),
+        "{err}"
+    );
+}
+
+#[test]
+fn overlay_documents_are_checked() {
+    let unknown = parse_overlay_str(
+        "overlay: 1.0.0\ninfo: { title: t, version: 1.0.0 }\nactions:\n  - target: $\n    updat: {}\n",
+    )
+    .unwrap_err();
+    assert_eq!(
+        unknown.to_string(),
+        "invalid overlay: unknown field $.actions[0].updat"
+    );
+
+    let version =
+        parse_overlay_str("overlay: 2.0.0\ninfo: { title: t, version: 1.0.0 }\nactions: []\n")
+            .unwrap_err();
+    assert!(matches!(version, OverlayError::Version(_)));
+
+    let broken = overlay(
+        "overlay: 1.0.0\ninfo: { title: t, version: 1.0.0 }\nactions:\n  - target: $.workflows[0].steps[0]\n    update: {stepId: 5}\n",
+    )
+    .unwrap_err();
+    assert!(matches!(broken, OverlayError::Document { .. }), "{broken}");
+}
@@ -1,4 +1,9 @@
-use arazzo_core::{parse_document_str, validate_document, DocumentFormat};
+use arazzo_core::{
+    parse_document_str, parse_document_str_with, parse_workflow_summaries, plan_document_with,
+    validate_document, validate_document_with, validation_report, DocumentFormat, ParseError,
+    ParseOptions, PlanOptions, RuleLevel, RuleOverride, SpecVersion, ValidationOptions,
+    VersionedField,
+};
 
 fn minimal_valid_yaml() -> &'static str {
     r#"
@@ -60,6 +65,23 @@ fn parse_auto_detects_json() {
     assert_eq!(parsed.format, DocumentFormat::Json);
 }
 
+#[test]
+fn parse_auto_skips_leading_hash_comment_before_yaml() {
+    let yaml = format!("# arazzo workflow\n{}", minimal_valid_yaml());
+    let parsed = parse_document_str(&yaml, DocumentFormat::Auto).unwrap();
+    assert_eq!(parsed.format, DocumentFormat::Yaml);
+    assert_eq!(parsed.document.workflows[0].workflow_id, "loginUser");
+}
+
+#[test]
+fn parse_auto_skips_leading_slash_comment_before_json() {
+    let json = r#"// generated by catalog-build
+{ "arazzo": "1.0.1", "info": { "title": "Example", "version": "0.0.1" }, "sourceDescriptions": [ { "name": "src1", "url": "https://example.com/openapi.yaml" } ], "workflows": [ { "workflowId": "w1", "steps": [ { "stepId": "s1", "operationId": "op1" } ] } ] }"#;
+    let parsed = parse_document_str(json, DocumentFormat::Auto).unwrap();
+    assert_eq!(parsed.format, DocumentFormat::Json);
+    assert_eq!(parsed.document.workflows[0].workflow_id, "w1");
+}
+
 #[test]
 fn parse_unknown_format_is_rejected() {
     let err = parse_document_str("not: [valid", DocumentFormat::Auto).unwrap_err();
@@ -78,6 +100,63 @@ fn invalid_spec_version_is_rejected() {
     assert!(err.violations.iter().any(|v| v.path == "$.arazzo"));
 }
 
+#[test]
+fn every_1_0_x_spec_version_is_accepted_and_recorded() {
+    for (declared, expected) in [
+        ("1.0.0", SpecVersion::V1_0_0),
+        ("1.0", SpecVersion::V1_0_0),
+        ("1.0.1", SpecVersion::LATEST),
+        ("1.0.7", SpecVersion::new(1, 0, 7)),
+        ("1.0.2-rc1", SpecVersion::new(1, 0, 2)),
+    ] {
+        let doc = minimal_valid_yaml().replace("arazzo: 1.0.1", &format!("arazzo: {declared}"));
+        let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+        assert_eq!(parsed.spec_version, Some(expected), "{declared}");
+        validate_document(&parsed.document).unwrap();
+    }
+
+    // A patch release newer than the latest known one is accepted with a warning.
+    let doc = minimal_valid_yaml().replace("arazzo: 1.0.1", "arazzo: 1.0.7");
+    let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+    let report = validation_report(&parsed.document, &ValidationOptions::default());
+    let warnings: Vec<_> = report.warnings().map(|v| v.path.as_str()).collect();
+    assert_eq!(warnings, ["$.arazzo"]);
+
+    for bad in ["1.0.x", "1", "v1.0.0", "1.0.0.0"] {
+        assert!(SpecVersion::parse(bad).is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn versioned_fields_require_the_declaring_version() {
+    let doc = |version: &str| {
+        minimal_valid_yaml()
+            .replace("arazzo: 1.0.1", &format!("arazzo: {version}"))
+            .replace(
+                "operationId: loginUser",
+                "operationId: loginUser\n        timeout: 30",
+            )
+    };
+    let options = ValidationOptions {
+        versioned_fields: vec![VersionedField::new(
+            "$.workflows[*].steps[*].timeout",
+            SpecVersion::new(1, 0, 2),
+        )],
+        ..Default::default()
+    };
+
+    let parsed = parse_document_str(&doc("1.0.2"), DocumentFormat::Yaml).unwrap();
+    validate_document_with(&parsed.document, &options).unwrap();
+
+    let parsed = parse_document_str(&doc("1.0.1"), DocumentFormat::Yaml).unwrap();
+    let err = validate_document_with(&parsed.document, &options).unwrap_err();
+    assert_eq!(err.violations.len(), 1, "{:?}", err.violations);
+    assert_eq!(
+        err.violations[0].message,
+        "requires Arazzo 1.0.2 or later, but the document declares 1.0.1"
+    );
+}
+
 #[test]
 fn duplicate_workflow_ids_are_rejected() {
     let bad = r#"
@@ -128,6 +207,42 @@ workflows:
     assert!(err.violations.iter().any(|v| v.path.ends_with(".steps[0]")));
 }
 
+#[test]
+fn conflicting_step_targets_are_listed_in_the_violation() {
+    let bad = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStoreDescription
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        operationPath: "{$sourceDescriptions.petStoreDescription.url}#/paths/~1pets/get"
+        workflowId: otherWorkflow
+      - stepId: s2
+"#;
+    let parsed = parse_document_str(bad, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    let message_at = |suffix: &str| {
+        err.violations
+            .iter()
+            .find(|v| v.path.ends_with(suffix) && v.message.starts_with("exactly one of"))
+            .map(|v| v.message.clone())
+            .unwrap_or_default()
+    };
+    assert!(
+        message_at(".steps[0]").ends_with("found operationId, operationPath, workflowId"),
+        "{:?}",
+        err.violations
+    );
+    assert!(message_at(".steps[1]").ends_with("none is set"));
+}
+
 #[test]
 fn operation_step_parameters_require_in() {
     let bad = r#"
@@ -182,6 +297,44 @@ workflows:
         .any(|v| v.message.contains("type=goto")));
 }
 
+#[test]
+fn retry_after_above_the_configured_cap_is_rejected() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStoreDescription
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        onFailure:
+          - name: wait
+            type: retry
+            retryAfter: 31536000
+            retryLimit: 3
+"#;
+    let parsed = parse_document_str(doc, DocumentFormat::Yaml).unwrap();
+    let options = ValidationOptions {
+        max_retry_after_seconds: Some(300.0),
+        ..Default::default()
+    };
+    let err = validate_document_with(&parsed.document, &options).unwrap_err();
+    assert!(err.violations.iter().any(|v| {
+        v.path.ends_with(".onFailure[0].retryAfter") && v.message == "must be at most 300 seconds"
+    }));
+
+    let unbounded = ValidationOptions {
+        max_retry_after_seconds: None,
+        ..Default::default()
+    };
+    assert!(validate_document_with(&parsed.document, &unbounded).is_ok());
+}
+
 #[test]
 fn jsonpath_criterion_requires_context() {
     let bad = r#"
@@ -266,6 +419,36 @@ workflows:
             && v.message.contains("invalid runtime expression")));
 }
 
+#[test]
+fn invalid_jsonpath_in_step_outputs_is_rejected() {
+    let bad = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStoreDescription
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        outputs:
+          ids: $response.body$.data[*].id
+          bad: $response.body$.data[?(
+"#;
+    let parsed = parse_document_str(bad, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    let paths: Vec<&str> = err
+        .violations
+        .iter()
+        .filter(|v| v.message.contains("invalid JSONPath query $.data[?("))
+        .map(|v| v.path.as_str())
+        .collect();
+    assert_eq!(paths, ["$.workflows[0].steps[0].outputs.bad"]);
+}
+
 #[test]
 fn invalid_template_expression_in_operation_path_is_rejected() {
     let bad = r#"
@@ -318,3 +501,514 @@ workflows:
         .any(|v| v.path.ends_with(".steps[0].requestBody.payload")
             && v.message.contains("invalid expression inside value")));
 }
+
+#[test]
+fn step_output_key_with_space_is_rejected() {
+    let bad = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStoreDescription
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        outputs:
+          user id: $statusCode
+"#;
+    let parsed = parse_document_str(bad, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert!(err
+        .violations
+        .iter()
+        .any(|v| v.path.ends_with(".steps[0].outputs.user id")
+            && v.message.contains("map key must match")));
+}
+
+#[test]
+fn duplicate_query_parameter_in_step_is_rejected() {
+    let bad = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStoreDescription
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        parameters:
+          - name: q
+            in: query
+            value: a
+          - name: q
+            in: query
+            value: b
+"#;
+    let parsed = parse_document_str(bad, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert!(err
+        .violations
+        .iter()
+        .any(|v| v.path.ends_with(".steps[0].parameters[1]")
+            && v.message.contains("duplicate parameter")));
+}
+
+#[test]
+fn reusable_parameter_clashing_with_inline_parameter_is_rejected() {
+    let bad = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStoreDescription
+    url: https://example.com/openapi.yaml
+components:
+  parameters:
+    search:
+      name: q
+      in: query
+      value: default
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        parameters:
+          - name: q
+            in: query
+            value: a
+          - reference: $components.parameters.search
+"#;
+    let parsed = parse_document_str(bad, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert!(err
+        .violations
+        .iter()
+        .any(|v| v.path.ends_with(".steps[0].parameters[1]")
+            && v.message.contains("duplicate parameter")));
+}
+
+fn payload_doc(delimiters: &str, payload: &str) -> String {
+    format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+{delimiters}
+sourceDescriptions:
+  - name: petStoreDescription
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        requestBody:
+          contentType: application/json
+          payload: '{payload}'
+"#
+    )
+}
+
+#[test]
+fn alternate_template_delimiters_leave_single_braces_literal() {
+    let payload = r#"{"note": "{$not an expression}", "id": "{{ $inputs.id }}"}"#;
+
+    let default = parse_document_str(&payload_doc("", payload), DocumentFormat::Yaml).unwrap();
+    assert!(validate_document(&default.document).is_err());
+
+    let doubled = payload_doc(
+        "x-template-delimiters: { open: '{{', close: '}}' }",
+        payload,
+    );
+    let parsed = parse_document_str(&doubled, DocumentFormat::Yaml).unwrap();
+    validate_document(&parsed.document).unwrap();
+}
+
+#[test]
+fn invalid_expression_inside_alternate_delimiters_is_rejected() {
+    let doc = payload_doc(
+        "x-template-delimiters: { open: '<%', close: '%>' }",
+        r#"{"id": "<% $inputs..id %>"}"#,
+    );
+    let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert!(err
+        .violations
+        .iter()
+        .any(|v| v.path.ends_with(".steps[0].requestBody.payload")
+            && v.message.contains("invalid expression inside value")));
+}
+
+#[test]
+fn malformed_template_delimiters_extension_is_rejected() {
+    let doc = payload_doc("x-template-delimiters: { open: '<%' }", "{}");
+    let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert!(err
+        .violations
+        .iter()
+        .any(|v| v.path == "$.x-template-delimiters"));
+}
+
+#[test]
+fn parse_template_with_custom_delimiters() {
+    use arazzo_core::expressions::{parse_template_with, Segment, TemplateDelimiters};
+
+    let tpl = parse_template_with(
+        "id=<% $inputs.id %>{x}",
+        &TemplateDelimiters::new("<%", "%>"),
+    )
+    .unwrap();
+    assert_eq!(
+        tpl.segments,
+        vec![
+            Segment::Literal("id=".to_string()),
+            Segment::Expr("$inputs.id".to_string()),
+            Segment::Literal("{x}".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn unreferenced_source_description_is_warned_about() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/pets.yaml
+  - name: userStore
+    url: https://example.com/users.yaml
+  - name: legacyStore
+    url: https://example.com/legacy.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: $sourceDescriptions.petStore.listPets
+      - stepId: s2
+        operationPath: '{$sourceDescriptions.userStore.url}#/paths/~1users/get'
+"#;
+    let parsed = parse_document_str(doc, DocumentFormat::Yaml).unwrap();
+    validate_document(&parsed.document).unwrap();
+
+    let warnings = arazzo_core::document_warnings(&parsed.document);
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].path, "$.sourceDescriptions[2]");
+    assert!(warnings[0].message.contains("'legacyStore'"));
+}
+
+#[test]
+fn warning_level_violations_do_not_fail_validation() {
+    use arazzo_core::{validation_report, Severity};
+
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/pets.yaml
+  - name: unused
+    url: https://example.com/unused.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: $sourceDescriptions.petStore.listPets
+        outputs:
+          bad: $nope
+"#;
+    let parsed = parse_document_str(doc, DocumentFormat::Yaml).unwrap();
+
+    let report = validation_report(&parsed.document, &ValidationOptions::default());
+    let warnings: Vec<_> = report.by_severity(Severity::Warning).collect();
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].path, "$.sourceDescriptions[1]");
+    let errors: Vec<_> = report.errors().collect();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(errors[0].severity, Severity::Error);
+
+    // The error fails validation and carries the warning along.
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert_eq!(err.by_severity(Severity::Warning).count(), 1);
+
+    // Without the error, the warning alone leaves the document valid.
+    let mut fixed = parsed.document.clone();
+    fixed.workflows[0].steps[0].outputs = None;
+    validate_document(&fixed).unwrap();
+    let report = validation_report(&fixed, &ValidationOptions::default());
+    assert!(report.is_valid());
+    assert_eq!(report.warnings().count(), 1);
+}
+
+#[test]
+fn deprecated_field_is_a_warning_not_an_error() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: listPets
+        timeoutSeconds: 30
+"#;
+    let parsed = parse_document_str(doc, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert!(err
+        .violations
+        .iter()
+        .any(|v| v.path == "$.workflows[0].steps[0].timeoutSeconds"));
+
+    let options = ValidationOptions {
+        deprecated_fields: vec![arazzo_core::DeprecatedField::new(
+            "$.workflows[*].steps[*].timeoutSeconds",
+            "configure timeouts in the executor instead",
+        )],
+        ..Default::default()
+    };
+    validate_document_with(&parsed.document, &options).unwrap();
+
+    let warnings = arazzo_core::document_warnings_with(&parsed.document, &options);
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].path, "$.workflows[0].steps[0].timeoutSeconds");
+    assert_eq!(
+        warnings[0].message,
+        "deprecated: configure timeouts in the executor instead"
+    );
+}
+
+#[test]
+fn rule_overrides_downgrade_and_suppress_violations() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+  - name: unused
+    url: https://example.com/other.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: $sourceDescriptions.petStore.listPets
+        timeoutSeconds: 30
+"#;
+    let parsed = parse_document_str(doc, DocumentFormat::Yaml).unwrap();
+    let options = ValidationOptions {
+        overrides: vec![
+            RuleOverride::new("$.workflows[*].steps", RuleLevel::Warning)
+                .with_message("unknown field"),
+            RuleOverride::new("$.sourceDescriptions[*]", RuleLevel::Off),
+        ],
+        ..Default::default()
+    };
+    let report = validation_report(&parsed.document, &options);
+    assert!(report.is_valid(), "{report:?}");
+    let warnings: Vec<_> = report.warnings().map(|v| v.path.as_str()).collect();
+    assert_eq!(warnings, ["$.workflows[0].steps[0].timeoutSeconds"]);
+
+    let outcome = plan_document_with(&parsed.document, PlanOptions::default(), &options).unwrap();
+    assert!(outcome.validation.is_valid);
+    assert!(outcome.validation.errors.is_empty());
+    assert_eq!(outcome.validation.warnings.len(), 1);
+    assert!(outcome.plan.is_some());
+
+    let escalated = ValidationOptions {
+        overrides: vec![RuleOverride::new("$.sourceDescriptions", RuleLevel::Error)],
+        ..Default::default()
+    };
+    let outcome = plan_document_with(&parsed.document, PlanOptions::default(), &escalated).unwrap();
+    assert!(!outcome.validation.is_valid);
+    assert_eq!(
+        outcome.validation.errors.len(),
+        2,
+        "{:?}",
+        outcome.validation
+    );
+}
+
+#[test]
+fn parse_errors_report_line_column_and_path() {
+    let yaml = r#"arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: listPets
+      - stepId: s2
+        operationId: getPet
+        parameters: oops
+"#;
+    let err = parse_document_str(yaml, DocumentFormat::Auto).unwrap_err();
+    let location = err.location().expect("yaml errors carry a location");
+    assert_eq!(location.path, "$.workflows[0].steps[1].parameters");
+    assert_eq!((location.line, location.column), (15, 21));
+
+    // Positions count the comment lines skipped before the JSON body.
+    let json = "# generated\n{\n  \"arazzo\": \"1.0.1\",\n  \"info\": {\"title\": 7}\n}";
+    let err = parse_document_str(json, DocumentFormat::Auto).unwrap_err();
+    assert!(matches!(err, ParseError::Json { .. }), "{err}");
+    let location = err.location().expect("json errors carry a location");
+    assert_eq!(location.path, "$.info.title");
+    assert_eq!(location.line, 4);
+}
+
+#[test]
+fn parses_toml_documents() {
+    let toml = r#"arazzo = "1.0.1"
+
+[info]
+title = "Example"
+version = "0.0.1"
+
+[[sourceDescriptions]]
+name = "petStore"
+url = "https://example.com/openapi.yaml"
+
+[[workflows]]
+workflowId = "w1"
+
+[[workflows.steps]]
+stepId = "s1"
+operationId = "listPets"
+successCriteria = [{ condition = "$statusCode == 200" }]
+"#;
+    let parsed = parse_document_str(toml, DocumentFormat::Toml).expect("parse");
+    assert_eq!(parsed.format, DocumentFormat::Toml);
+    assert_eq!(parsed.document.workflows[0].steps[0].step_id, "s1");
+    validate_document(&parsed.document).expect("valid");
+
+    // TOML is never sniffed.
+    assert!(parse_document_str(toml, DocumentFormat::Auto).is_err());
+
+    let bad = toml.replace("stepId = \"s1\"", "stepId = 5");
+    let err = parse_document_str(&bad, DocumentFormat::Toml).unwrap_err();
+    assert!(matches!(err, ParseError::Toml { .. }), "{err}");
+    let location = err.location().expect("toml errors carry a location");
+    assert_eq!(location.path, "$.workflows[0].steps[0].stepId");
+    assert_eq!(location.line, 15);
+}
+
+#[test]
+fn workflow_summaries_skip_step_contents() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+workflows:
+  - workflowId: listPets
+    summary: List every pet
+    steps:
+      - stepId: list
+        operationId: listPets
+        # A full parse rejects this, a summary never looks at it.
+        parameters: 42
+      - stepId: count
+        operationId: countPets
+  - workflowId: empty
+    description: Nothing yet
+    steps: []
+"#;
+    assert!(parse_document_str(doc, DocumentFormat::Auto).is_err());
+
+    let summaries = parse_workflow_summaries(doc, DocumentFormat::Auto).expect("summaries");
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0].workflow_id, "listPets");
+    assert_eq!(summaries[0].summary.as_deref(), Some("List every pet"));
+    assert_eq!(summaries[0].step_count, 2);
+    assert_eq!(summaries[1].description.as_deref(), Some("Nothing yet"));
+    assert_eq!(summaries[1].step_count, 0);
+
+    let json =
+        serde_json::to_string(&serde_yaml::from_str::<serde_json::Value>(doc).unwrap()).unwrap();
+    assert_eq!(
+        parse_workflow_summaries(&json, DocumentFormat::Json).unwrap(),
+        summaries
+    );
+
+    let err = parse_workflow_summaries(
+        "workflows:\n  - summary: no id\n    steps: []\n",
+        DocumentFormat::Yaml,
+    )
+    .unwrap_err();
+    assert_eq!(err.location().expect("location").path, "$.workflows[0]");
+}
+
+#[test]
+fn strict_parsing_rejects_unknown_fields() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petStore
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    x-owner: payments
+    steps:
+      - stepId: s1
+        operationId: listPets
+        succesCriteria:
+          - condition: $statusCode == 200
+        onSuccess:
+          - name: done
+            type: end
+            criteria:
+              - condition: $statusCode == 200
+                contxt: $response.body
+"#;
+    // Lenient parsing keeps the typos as extensions.
+    parse_document_str(doc, DocumentFormat::Yaml).unwrap();
+
+    let strict = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    let err = parse_document_str_with(doc, DocumentFormat::Yaml, &strict).unwrap_err();
+    let ParseError::UnknownFields(unknown) = err else {
+        panic!("expected unknown fields, got {err}");
+    };
+    let paths: Vec<&str> = unknown.iter().map(|v| v.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "$.workflows[0].steps[0].succesCriteria",
+            "$.workflows[0].steps[0].onSuccess[0].criteria[0].contxt",
+        ]
+    );
+
+    parse_document_str_with(minimal_valid_yaml(), DocumentFormat::Yaml, &strict).unwrap();
+}
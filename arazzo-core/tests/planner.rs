@@ -1,4 +1,9 @@
-use arazzo_core::{plan_from_str, DocumentFormat, PlanOptions};
+use arazzo_core::planner::PlannerError;
+use arazzo_core::{
+    parse_document_str, plan_document_with, plan_from_str, validate_document,
+    workflow_dependency_graph, DocumentFormat, PlanOptions, RuleLevel, RuleOverride,
+    ValidationOptions,
+};
 
 #[test]
 fn planner_builds_levels_from_step_data_dependencies() {
@@ -178,3 +183,277 @@ workflows:
     let plan = outcome.plan.unwrap();
     assert!(!plan.summary.missing_inputs.contains("userId"));
 }
+
+#[test]
+fn planner_treats_explicit_null_input_as_present_and_absent_as_missing() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: storeApi
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        parameters:
+          - name: q
+            in: query
+            value: $inputs.userId
+          - name: tag
+            in: query
+            value: $inputs.tag
+"#;
+
+    let outcome = plan_from_str(
+        doc,
+        DocumentFormat::Yaml,
+        PlanOptions {
+            workflow_id: Some("w1".to_string()),
+            inputs: Some(serde_json::json!({"userId": null})),
+        },
+    )
+    .unwrap();
+
+    let plan = outcome.plan.unwrap();
+    assert!(!plan.summary.missing_inputs.contains("userId"));
+    assert!(plan.summary.missing_inputs.contains("tag"));
+}
+
+fn chained_workflows(depends_on_c: &str) -> String {
+    format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: storeApi
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: a
+    dependsOn: [b]
+    steps:
+      - stepId: s1
+        operationId: op1
+  - workflowId: b
+    dependsOn: [c, $sourceDescriptions.storeApi.setup]
+    steps:
+      - stepId: s1
+        operationId: op1
+  - workflowId: c
+    dependsOn: {depends_on_c}
+    steps:
+      - stepId: s1
+        operationId: op1
+  - workflowId: d
+    steps:
+      - stepId: s1
+        operationId: op1
+"#
+    )
+}
+
+#[test]
+fn planner_orders_workflow_prerequisites() {
+    let doc = chained_workflows("[]");
+    let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+    let graph = workflow_dependency_graph(&parsed.document).unwrap();
+    assert_eq!(graph.topo_order, ["c", "d", "b", "a"]);
+    assert_eq!(graph.depends_on["b"], ["c"]);
+
+    let outcome = plan_from_str(
+        &doc,
+        DocumentFormat::Yaml,
+        PlanOptions {
+            workflow_id: Some("a".to_string()),
+            inputs: None,
+        },
+    )
+    .unwrap();
+    let plan = outcome.plan.unwrap();
+    assert_eq!(plan.summary.workflow_depends_on, ["b"]);
+    assert_eq!(plan.summary.workflow_prerequisites, ["c", "b"]);
+}
+
+#[test]
+fn workflow_depends_on_cycles_fail_validation_and_planning() {
+    let doc = chained_workflows("[a]");
+    let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+    let err = workflow_dependency_graph(&parsed.document).unwrap_err();
+    assert!(
+        matches!(&err, PlannerError::WorkflowCycle(cycle) if cycle == &["a", "b", "c", "a"]),
+        "{err}"
+    );
+
+    let err = validate_document(&parsed.document).unwrap_err();
+    let cycles: Vec<_> = err
+        .violations
+        .iter()
+        .filter(|v| v.message.contains("cycle"))
+        .map(|v| (v.path.as_str(), v.message.as_str()))
+        .collect();
+    assert_eq!(
+        cycles,
+        [(
+            "$.workflows[0].dependsOn[0]",
+            "workflow dependsOn cycle: a -> b -> c -> a"
+        )]
+    );
+
+    let outcome = plan_from_str(&doc, DocumentFormat::Yaml, PlanOptions::default()).unwrap();
+    assert!(!outcome.validation.is_valid);
+    assert!(outcome.plan.is_none());
+
+    // A workflow depending on itself is the shortest cycle.
+    let doc = chained_workflows("[c]");
+    let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+    let err = validate_document(&parsed.document).unwrap_err();
+    assert!(err
+        .violations
+        .iter()
+        .any(|v| v.path == "$.workflows[2].dependsOn[0]"
+            && v.message == "workflow dependsOn cycle: c -> c"));
+}
+
+#[test]
+fn planner_fills_missing_inputs_from_schema_defaults() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: storeApi
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    inputs:
+      type: object
+      required: [region]
+      properties:
+        region:
+          type: string
+          default: eu
+        paging:
+          type: object
+          properties:
+            limit:
+              type: integer
+              default: 20
+        tag:
+          type: string
+          default: pets
+    steps:
+      - stepId: s1
+        operationId: op1
+        parameters:
+          - name: region
+            in: query
+            value: $inputs.region
+          - name: limit
+            in: query
+            value: $inputs.paging.limit
+          - name: tag
+            in: query
+            value: $inputs.tag
+          - name: q
+            in: query
+            value: $inputs.query
+"#;
+
+    let outcome = plan_from_str(
+        doc,
+        DocumentFormat::Yaml,
+        PlanOptions {
+            workflow_id: Some("w1".to_string()),
+            inputs: Some(serde_json::json!({"paging": {}, "tag": null})),
+        },
+    )
+    .unwrap();
+    let plan = outcome.plan.unwrap();
+    assert_eq!(
+        plan.summary.effective_inputs,
+        serde_json::json!({"region": "eu", "paging": {"limit": 20}, "tag": null})
+    );
+    assert_eq!(
+        plan.summary.missing_inputs.iter().collect::<Vec<_>>(),
+        ["query"]
+    );
+
+    // Without an inputs file the defaults alone are the effective inputs.
+    let outcome = plan_from_str(doc, DocumentFormat::Yaml, PlanOptions::default()).unwrap();
+    let plan = outcome.plan.unwrap();
+    assert_eq!(
+        plan.summary.effective_inputs,
+        serde_json::json!({"region": "eu", "tag": "pets"})
+    );
+}
+
+#[test]
+fn planner_does_not_require_inputs_with_a_default_function() {
+    let doc = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: storeApi
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+        parameters:
+          - name: host
+            in: header
+            value: "{ $inputs.host | default('api.example.com') }"
+          - name: tenant
+            in: header
+            value: "{ $inputs.tenant | toLower }"
+"#;
+
+    let outcome = plan_from_str(doc, DocumentFormat::Yaml, PlanOptions::default()).unwrap();
+    let plan = outcome.plan.unwrap();
+    assert_eq!(
+        plan.summary.missing_inputs.iter().collect::<Vec<_>>(),
+        ["tenant"]
+    );
+}
+
+#[test]
+fn workflow_outside_a_cycle_is_still_planned() {
+    let doc = chained_workflows("[b]");
+    let parsed = parse_document_str(&doc, DocumentFormat::Yaml).unwrap();
+    let validation = ValidationOptions {
+        overrides: vec![RuleOverride::new(
+            "$.workflows[*].dependsOn",
+            RuleLevel::Warning,
+        )],
+        ..Default::default()
+    };
+    let plan_for = |id: &str| {
+        plan_document_with(
+            &parsed.document,
+            PlanOptions {
+                workflow_id: Some(id.to_string()),
+                inputs: None,
+            },
+            &validation,
+        )
+    };
+
+    let plan = plan_for("d").unwrap().plan.unwrap();
+    assert!(plan.summary.workflow_prerequisites.is_empty());
+
+    // `a` is not in the cycle, but it waits on workflows that are.
+    let err = plan_for("a").unwrap_err();
+    assert!(
+        matches!(&err, PlannerError::WorkflowCycle(cycle) if cycle == &["b", "c", "b"]),
+        "{err}"
+    );
+}
@@ -0,0 +1,255 @@
+use arazzo_core::{parse_document_str, DocumentFormat};
+
+const DOC: &str = r#"
+x-team: payments
+workflows:
+  - steps:
+      - operationId: listPets
+        description: List pets
+        stepId: list
+        x-note: first
+    workflowId: browse
+sourceDescriptions:
+  - type: openapi
+    url: https://example.com/openapi.yaml
+    name: petStore
+info:
+  version: 0.0.1
+  title: Example
+arazzo: 1.0.1
+"#;
+
+#[test]
+fn yaml_output_follows_spec_field_order() {
+    let parsed = parse_document_str(DOC, DocumentFormat::Yaml).unwrap();
+    let yaml = parsed.document.to_yaml().unwrap();
+    assert_eq!(
+        yaml,
+        r#"arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+- name: petStore
+  url: https://example.com/openapi.yaml
+  type: openapi
+workflows:
+- workflowId: browse
+  steps:
+  - description: List pets
+    stepId: list
+    operationId: listPets
+    x-note: first
+x-team: payments
+"#
+    );
+
+    let reparsed = parse_document_str(&yaml, DocumentFormat::Yaml).unwrap();
+    assert_eq!(reparsed.document, parsed.document);
+    assert_eq!(reparsed.document.to_yaml().unwrap(), yaml);
+}
+
+#[test]
+fn json_output_round_trips() {
+    let parsed = parse_document_str(DOC, DocumentFormat::Yaml).unwrap();
+    let json = parsed.document.to_json().unwrap();
+    assert!(json.starts_with("{\n  \"arazzo\": \"1.0.1\",\n  \"info\": {"));
+    assert!(json.ends_with("}\n"));
+
+    let reparsed = parse_document_str(&json, DocumentFormat::Json).unwrap();
+    assert_eq!(reparsed.document, parsed.document);
+    assert_eq!(reparsed.document.to_json().unwrap(), json);
+}
+
+/// Every object with every fixed field set, each written in reverse spec order.
+const EVERY_FIELD: &str = r#"
+components:
+  failureActions:
+    again:
+      type: retry
+      name: again
+  successActions:
+    done:
+      type: end
+      name: done
+  parameters:
+    page:
+      value: 1
+      in: query
+      name: page
+  inputs:
+    page:
+      type: integer
+workflows:
+- parameters:
+  - value: 'true'
+    in: header
+    name: verbose
+  outputs:
+    first: $steps.list.outputs.first
+  failureActions:
+  - reference: $components.failureActions.again
+  successActions:
+  - reference: $components.successActions.done
+  steps:
+  - outputs:
+      first: $response.body#/items/0
+    onFailure:
+    - criteria:
+      - condition: $statusCode == 503
+      retryLimit: 3
+      retryAfter: 1.5
+      stepId: list
+      workflowId: setup
+      type: retry
+      name: again
+    onSuccess:
+    - criteria:
+      - condition: $statusCode == 200
+      stepId: list
+      workflowId: setup
+      type: goto
+      name: done
+    successCriteria:
+    - type:
+        version: draft-goessner-dispatch-jsonpath-00
+        type: jsonpath
+      condition: $.items
+      context: $response.body
+    requestBody:
+      replacements:
+      - value: dog
+        target: /kind
+      payload:
+        kind: cat
+      contentType: application/json
+    parameters:
+    - value: 10
+      in: query
+      name: limit
+    - value: 2
+      reference: $components.parameters.page
+    workflowId: setup
+    operationPath: '{$sourceDescriptions.petStore.url}#/paths/~1pets/get'
+    operationId: listPets
+    stepId: list
+    description: List pets
+  dependsOn:
+  - setup
+  inputs:
+    type: object
+  description: List pets and pick one
+  summary: Browse pets
+  workflowId: browse
+sourceDescriptions:
+- type: openapi
+  url: https://example.com/openapi.yaml
+  name: petStore
+info:
+  version: 0.0.1
+  description: Every object with every field set
+  summary: Every field
+  title: Example
+arazzo: 1.0.1
+"#;
+
+#[test]
+fn every_object_is_written_in_spec_field_order() {
+    let parsed = parse_document_str(EVERY_FIELD, DocumentFormat::Yaml).unwrap();
+    let yaml = parsed.document.to_yaml().unwrap();
+    assert_eq!(
+        yaml,
+        r#"arazzo: 1.0.1
+info:
+  title: Example
+  summary: Every field
+  description: Every object with every field set
+  version: 0.0.1
+sourceDescriptions:
+- name: petStore
+  url: https://example.com/openapi.yaml
+  type: openapi
+workflows:
+- workflowId: browse
+  summary: Browse pets
+  description: List pets and pick one
+  inputs:
+    type: object
+  dependsOn:
+  - setup
+  steps:
+  - description: List pets
+    stepId: list
+    operationId: listPets
+    operationPath: '{$sourceDescriptions.petStore.url}#/paths/~1pets/get'
+    workflowId: setup
+    parameters:
+    - name: limit
+      in: query
+      value: 10
+    - reference: $components.parameters.page
+      value: 2
+    requestBody:
+      contentType: application/json
+      payload:
+        kind: cat
+      replacements:
+      - target: /kind
+        value: dog
+    successCriteria:
+    - context: $response.body
+      condition: $.items
+      type:
+        type: jsonpath
+        version: draft-goessner-dispatch-jsonpath-00
+    onSuccess:
+    - name: done
+      type: goto
+      workflowId: setup
+      stepId: list
+      criteria:
+      - condition: $statusCode == 200
+    onFailure:
+    - name: again
+      type: retry
+      workflowId: setup
+      stepId: list
+      retryAfter: 1.5
+      retryLimit: 3
+      criteria:
+      - condition: $statusCode == 503
+    outputs:
+      first: $response.body#/items/0
+  successActions:
+  - reference: $components.successActions.done
+  failureActions:
+  - reference: $components.failureActions.again
+  outputs:
+    first: $steps.list.outputs.first
+  parameters:
+  - name: verbose
+    in: header
+    value: 'true'
+components:
+  inputs:
+    page:
+      type: integer
+  parameters:
+    page:
+      name: page
+      in: query
+      value: 1
+  successActions:
+    done:
+      name: done
+      type: end
+  failureActions:
+    again:
+      name: again
+      type: retry
+"#
+    );
+
+    let reparsed = parse_document_str(&yaml, DocumentFormat::Yaml).unwrap();
+    assert_eq!(reparsed.document, parsed.document);
+}
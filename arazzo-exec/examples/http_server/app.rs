@@ -0,0 +1,133 @@
+//! Request handling for the `http_server` example. It is kept apart from the listener
+//! in `main.rs` so the integration test can drive it without opening a socket.
+
+use std::sync::Arc;
+
+use arazzo_exec::executor::{prepare_run, PrepareRunError, RunOptions};
+use arazzo_exec::{Compiler, Executor};
+use arazzo_store::StateStore;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Body;
+use hyper::{Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+pub struct AppState {
+    pub store: Arc<dyn StateStore>,
+    pub executor: Arc<Executor>,
+    pub compiler: Compiler,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartRun {
+    /// The Arazzo document, as YAML or JSON text.
+    document: String,
+    workflow_id: Option<String>,
+    #[serde(default)]
+    inputs: Option<JsonValue>,
+}
+
+/// `POST /runs` starts a run and answers `202` with its id; `GET /runs/{id}` reports it.
+pub async fn handle<B>(state: Arc<AppState>, req: Request<B>) -> Response<Full<Bytes>>
+where
+    B: Body,
+    B::Error: std::fmt::Display,
+{
+    let path = req.uri().path().trim_end_matches('/').to_string();
+    match (req.method().clone(), path.as_str()) {
+        (Method::POST, "/runs") => {
+            let body = match req.into_body().collect().await {
+                Ok(b) => b.to_bytes(),
+                Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+            };
+            start_run(state, &body).await
+        }
+        (Method::GET, p) => match p.strip_prefix("/runs/") {
+            Some(id) => run_status(&state, id).await,
+            None => error(StatusCode::NOT_FOUND, "not found"),
+        },
+        _ => error(StatusCode::NOT_FOUND, "not found"),
+    }
+}
+
+async fn start_run(state: Arc<AppState>, body: &[u8]) -> Response<Full<Bytes>> {
+    let request: StartRun = match serde_json::from_slice(body) {
+        Ok(r) => r,
+        Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+    };
+    let options = RunOptions {
+        workflow_id: request.workflow_id,
+        inputs: request.inputs,
+        ..RunOptions::default()
+    };
+    let prepared = match prepare_run(
+        state.store.as_ref(),
+        &state.compiler,
+        &request.document,
+        options,
+    )
+    .await
+    {
+        Ok(p) => p,
+        Err(PrepareRunError::Store(e)) => {
+            return error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+        }
+        Err(e) => return error(StatusCode::UNPROCESSABLE_ENTITY, &e.to_string()),
+    };
+
+    let run_id = prepared.run_id;
+    let executor = state.executor.clone();
+    tokio::spawn(async move {
+        // The outcome is recorded in the store; `GET /runs/{id}` reads it from there.
+        let _ = executor
+            .execute_run(
+                prepared.run_id,
+                &prepared.workflow,
+                &prepared.compiled,
+                &prepared.inputs,
+                Some(&prepared.document),
+            )
+            .await;
+    });
+
+    respond(
+        StatusCode::ACCEPTED,
+        &json!({ "run_id": run_id.to_string() }),
+    )
+}
+
+async fn run_status(state: &AppState, id: &str) -> Response<Full<Bytes>> {
+    let Ok(run_id) = Uuid::parse_str(id) else {
+        return error(StatusCode::BAD_REQUEST, &format!("invalid run id: {id}"));
+    };
+    match state.store.get_run(run_id).await {
+        Ok(Some(run)) => respond(
+            StatusCode::OK,
+            &json!({
+                "run_id": run.id.to_string(),
+                "workflow_id": run.workflow_id,
+                "status": run.status,
+                "error": run.error,
+            }),
+        ),
+        Ok(None) => error(StatusCode::NOT_FOUND, &format!("run not found: {run_id}")),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn respond(status: StatusCode, body: &JsonValue) -> Response<Full<Bytes>> {
+    let mut resp = Response::new(Full::new(Bytes::from(body.to_string())));
+    *resp.status_mut() = status;
+    resp.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    resp
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    respond(status, &json!({ "error": message }))
+}
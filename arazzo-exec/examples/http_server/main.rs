@@ -0,0 +1,72 @@
+//! Minimal HTTP API embedding the executor.
+//!
+//! ```sh
+//! arazzo migrate --store "$DATABASE_URL"
+//! ARAZZO_ALLOW_HOSTS=httpbin.org DATABASE_URL=postgres://... \
+//!     cargo run -p arazzo-exec --example http_server
+//!
+//! curl -s localhost:3000/runs -d "$(jq -n --rawfile doc examples/httpbin.arazzo.yaml '{document: $doc}')"
+//! curl -s localhost:3000/runs/<run_id>
+//! ```
+
+mod app;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use arazzo_exec::executor::{ExecutorConfig, ReqwestHttpClient, StoreEventSink};
+use arazzo_exec::policy::{PolicyConfig, PolicyGate};
+use arazzo_exec::secrets::EnvSecretsProvider;
+use arazzo_exec::{Compiler, Executor};
+use arazzo_store::{PostgresStore, StateStore};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let database_url =
+        std::env::var("ARAZZO_DATABASE_URL").or_else(|_| std::env::var("DATABASE_URL"))?;
+    let store: Arc<dyn StateStore> = Arc::new(PostgresStore::connect(&database_url, 10).await?);
+
+    let mut policy = PolicyConfig::default();
+    if let Ok(hosts) = std::env::var("ARAZZO_ALLOW_HOSTS") {
+        policy.network.allowed_hosts = hosts.split(',').map(|h| h.trim().to_string()).collect();
+    }
+    let executor = Executor::new(
+        ExecutorConfig::default(),
+        store.clone(),
+        Arc::new(ReqwestHttpClient::default()),
+        Arc::new(EnvSecretsProvider::default()),
+        Arc::new(PolicyGate::new(policy)),
+        Arc::new(StoreEventSink::new(store.clone())),
+    );
+    let state = Arc::new(app::AppState {
+        store,
+        executor: Arc::new(executor),
+        compiler: Compiler::default(),
+    });
+
+    let addr: SocketAddr = std::env::var("ARAZZO_LISTEN")
+        .unwrap_or_else(|_| "127.0.0.1:3000".to_string())
+        .parse()?;
+    let listener = TcpListener::bind(addr).await?;
+    println!("listening on http://{addr}");
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let state = state.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |req| {
+                let state = state.clone();
+                async move { Ok::<_, std::convert::Infallible>(app::handle(state, req).await) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                eprintln!("connection error: {e}");
+            }
+        });
+    }
+}
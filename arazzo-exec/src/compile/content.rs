@@ -0,0 +1,57 @@
+use arazzo_core::types::RequestBody;
+use serde_json::Value as JsonValue;
+
+/// `requestBody` extension listing preferred content types in order, e.g.
+/// `["application/json", "application/xml"]`. Entries may use `type/*` or `*/*` ranges.
+pub const CONTENT_TYPE_PREFERENCE_EXTENSION: &str = "x-content-type-preference";
+
+/// Preferred content types from [`CONTENT_TYPE_PREFERENCE_EXTENSION`]; a single string
+/// is treated as a one-element list.
+pub(crate) fn content_type_preferences(body: &RequestBody) -> Vec<String> {
+    match body.extensions.get(CONTENT_TYPE_PREFERENCE_EXTENSION) {
+        Some(JsonValue::String(s)) => vec![s.clone()],
+        Some(JsonValue::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Pick the content type to send: the first preference the operation offers, falling
+/// back to the declared `contentType`. When the operation declares no request body
+/// content, the declared type (or else the first preference) is used as-is.
+pub fn negotiate_content_type(body: &RequestBody, available: Option<&[String]>) -> Option<String> {
+    let prefs = content_type_preferences(body);
+    let Some(available) = available else {
+        return body.content_type.clone().or_else(|| prefs.first().cloned());
+    };
+    prefs
+        .iter()
+        .find_map(|pref| {
+            available
+                .iter()
+                .find(|offered| media_range_matches(pref, offered))
+                .cloned()
+        })
+        .or_else(|| body.content_type.clone())
+}
+
+/// Whether `offered` falls within the media range `pref`, ignoring parameters and case.
+fn media_range_matches(pref: &str, offered: &str) -> bool {
+    let essence = |s: &str| {
+        s.split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+    };
+    let (pref, offered) = (essence(pref), essence(offered));
+    if pref == "*/*" || pref == offered {
+        return true;
+    }
+    match (pref.split_once('/'), offered.split_once('/')) {
+        (Some((pref_type, "*")), Some((offered_type, _))) => pref_type == offered_type,
+        _ => false,
+    }
+}
@@ -1,7 +1,21 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 
 use arazzo_core::types::{ArazzoDocument, ParameterLocation, Step, Workflow};
 
+mod content;
+mod overrides;
+mod params;
+
+pub(crate) use content::content_type_preferences;
+pub use content::{negotiate_content_type, CONTENT_TYPE_PREFERENCE_EXTENSION};
+use overrides::apply_operation_overrides;
+pub use overrides::{OVERRIDE_METHOD_EXTENSION, OVERRIDE_PATH_EXTENSION};
+pub(crate) use params::{same_parameter, workflow_default_parameters};
+pub use params::{
+    MissingParameterSeverity, DEFAULT_PARAMETERS_EXTENSION, IGNORE_MISSING_PARAMS_EXTENSION,
+};
+
 use crate::openapi::{
     DiagnosticSeverity, OpenApiDiagnostic, OpenApiParamLocation, OpenApiResolver,
     ResolvedOperation, ResolvedSources,
@@ -13,6 +27,38 @@ pub struct CompiledPlan {
     pub steps: Vec<CompiledStep>,
 }
 
+impl CompiledPlan {
+    /// Per-step lines for [`DependencyGraph::to_dot_with_labels`]: the resolved
+    /// `METHOD /path` and the host it is sent to.
+    ///
+    /// [`DependencyGraph::to_dot_with_labels`]: arazzo_core::DependencyGraph::to_dot_with_labels
+    pub fn dot_labels(&self) -> BTreeMap<String, Vec<String>> {
+        self.steps
+            .iter()
+            .filter_map(|s| {
+                let op = s.operation.as_ref()?;
+                let mut lines = vec![format!("{} {}", op.method, op.path)];
+                if let Some(host) = url::Url::parse(&op.base_url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(str::to_string))
+                {
+                    lines.push(host);
+                }
+                Some((s.step_id.clone(), lines))
+            })
+            .collect()
+    }
+
+    /// Mark every step to send the OpenAPI `default` of each optional parameter it
+    /// does not set (see [`CompiledStep::fill_parameter_defaults`]).
+    pub fn with_parameter_defaults(mut self, enabled: bool) -> Self {
+        for step in &mut self.steps {
+            step.fill_parameter_defaults = enabled;
+        }
+        self
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CompiledStep {
     pub step_id: String,
@@ -21,6 +67,10 @@ pub struct CompiledStep {
     pub missing_required_parameters: Vec<MissingParameter>,
     pub request_body: Option<CompiledRequestBody>,
     pub missing_required_request_body: bool,
+    /// Send the OpenAPI `default` of each optional parameter the step does not set.
+    /// Off unless the run opts in.
+    #[serde(default)]
+    pub fill_parameter_defaults: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -39,16 +89,78 @@ pub struct CompiledRequestBody {
 #[derive(Default)]
 pub struct Compiler {
     resolver: OpenApiResolver,
+    missing_parameters: MissingParameterSeverity,
+    preloaded_sources: ResolvedSources,
 }
 
 impl Compiler {
+    pub fn with_resolver(mut self, resolver: OpenApiResolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Use already-loaded OpenAPI documents (e.g. specs baked into an image).
+    /// Sources present here are never fetched; any others still go through the resolver.
+    pub fn with_sources(mut self, sources: ResolvedSources) -> Self {
+        self.preloaded_sources = sources;
+        self
+    }
+
+    /// How to report steps that omit required parameters, for steps without their own
+    /// [`IGNORE_MISSING_PARAMS_EXTENSION`]. Defaults to an error.
+    pub fn with_missing_parameter_severity(mut self, severity: MissingParameterSeverity) -> Self {
+        self.missing_parameters = severity;
+        self
+    }
+
     pub async fn compile_workflow(
         &self,
         doc: &ArazzoDocument,
         workflow: &Workflow,
     ) -> CompiledPlan {
-        let sources = self.resolver.resolve_sources(doc).await;
-        compile_workflow_with_sources(&self.resolver, &sources, workflow).await
+        let sources = self.resolve_sources(doc).await;
+        compile_workflow_with_sources(&self.resolver, &sources, workflow, self.missing_parameters)
+            .await
+    }
+
+    /// Like [`Self::compile_workflow`], resolving relative source URLs against
+    /// `base_dir` (the directory of the document's file) instead of the resolver's.
+    pub async fn compile_workflow_in(
+        &self,
+        doc: &ArazzoDocument,
+        workflow: &Workflow,
+        base_dir: &Path,
+    ) -> CompiledPlan {
+        let sources = self
+            .resolver
+            .resolve_missing_sources_in(doc, self.preloaded_sources.clone(), Some(base_dir))
+            .await;
+        compile_workflow_with_sources(&self.resolver, &sources, workflow, self.missing_parameters)
+            .await
+    }
+
+    /// Compile every workflow in `doc`, resolving its source descriptions once
+    /// and sharing them across workflows.
+    pub async fn compile_document(&self, doc: &ArazzoDocument) -> Vec<(String, CompiledPlan)> {
+        let sources = self.resolve_sources(doc).await;
+        let mut plans = Vec::with_capacity(doc.workflows.len());
+        for workflow in &doc.workflows {
+            let plan = compile_workflow_with_sources(
+                &self.resolver,
+                &sources,
+                workflow,
+                self.missing_parameters,
+            )
+            .await;
+            plans.push((workflow.workflow_id.clone(), plan));
+        }
+        plans
+    }
+
+    async fn resolve_sources(&self, doc: &ArazzoDocument) -> ResolvedSources {
+        self.resolver
+            .resolve_missing_sources(doc, self.preloaded_sources.clone())
+            .await
     }
 }
 
@@ -56,6 +168,7 @@ async fn compile_workflow_with_sources(
     resolver: &OpenApiResolver,
     sources: &ResolvedSources,
     workflow: &Workflow,
+    missing_parameters: MissingParameterSeverity,
 ) -> CompiledPlan {
     let mut plan = CompiledPlan {
         diagnostics: sources.diagnostics.clone(),
@@ -74,9 +187,10 @@ async fn compile_workflow_with_sources(
                 .resolve_step_operation(sources, workflow, step)
                 .await
             {
-                Ok((resolved, mut extra_diags)) => {
+                Ok((mut resolved, mut extra_diags)) => {
                     diag.append(&mut extra_diags);
-                    missing = missing_required_params(step, &resolved);
+                    diag.extend(apply_operation_overrides(step, &mut resolved));
+                    missing = missing_required_params(workflow, step, &resolved);
                     rb = compiled_request_body(step, &resolved);
                     missing_rb_required = is_required_request_body_missing(step, &resolved);
                     op = Some(resolved);
@@ -88,9 +202,14 @@ async fn compile_workflow_with_sources(
         }
 
         // Promote missing requirements to diagnostics for CI friendliness.
-        if !missing.is_empty() {
+        let missing_severity = match MissingParameterSeverity::for_step(step, missing_parameters) {
+            MissingParameterSeverity::Error => Some(DiagnosticSeverity::Error),
+            MissingParameterSeverity::Warning => Some(DiagnosticSeverity::Warning),
+            MissingParameterSeverity::Suppress => None,
+        };
+        if let Some(severity) = missing_severity.filter(|_| !missing.is_empty()) {
             diag.push(OpenApiDiagnostic {
-                severity: DiagnosticSeverity::Error,
+                severity,
                 message: format!(
                     "missing required parameters: {}",
                     missing
@@ -102,10 +221,34 @@ async fn compile_workflow_with_sources(
                 source_name: None,
             });
         }
+        if let (Some(body), Some(offered)) = (
+            step.request_body.as_ref(),
+            op.as_ref()
+                .and_then(|o| o.shape.request_body_content_types.as_ref()),
+        ) {
+            let prefs = content_type_preferences(body);
+            let negotiated = rb.as_ref().and_then(|rb| rb.content_type.as_ref());
+            if !prefs.is_empty() && !negotiated.is_some_and(|ct| offered.contains(ct)) {
+                diag.push(OpenApiDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    message: format!(
+                        "none of the preferred content types ({}) is offered by the operation ({})",
+                        prefs.join(", "),
+                        offered.join(", ")
+                    ),
+                    source_name: None,
+                });
+            }
+        }
         if missing_rb_required {
+            let message = if step.request_body.is_some() {
+                "required requestBody has an empty payload"
+            } else {
+                "missing required requestBody"
+            };
             diag.push(OpenApiDiagnostic {
                 severity: DiagnosticSeverity::Error,
-                message: "missing required requestBody".to_string(),
+                message: message.to_string(),
                 source_name: None,
             });
         }
@@ -117,21 +260,26 @@ async fn compile_workflow_with_sources(
             missing_required_parameters: missing,
             request_body: rb,
             missing_required_request_body: missing_rb_required,
+            fill_parameter_defaults: false,
         });
     }
 
     plan
 }
 
-fn missing_required_params(step: &Step, op: &ResolvedOperation) -> Vec<MissingParameter> {
+fn missing_required_params(
+    workflow: &Workflow,
+    step: &Step,
+    op: &ResolvedOperation,
+) -> Vec<MissingParameter> {
     let mut provided = BTreeSet::<(OpenApiParamLocation, String)>::new();
-    if let Some(params) = &step.parameters {
-        for p in params {
-            if let arazzo_core::types::ParameterOrReusable::Parameter(p) = p {
-                if let Some(loc) = &p.r#in {
-                    if let Some(open_loc) = map_param_loc(loc) {
-                        provided.insert((open_loc, p.name.clone()));
-                    }
+    let defaults = workflow_default_parameters(workflow);
+    let step_params = step.parameters.as_deref().unwrap_or_default();
+    for p in defaults.iter().chain(step_params) {
+        if let arazzo_core::types::ParameterOrReusable::Parameter(p) = p {
+            if let Some(loc) = &p.r#in {
+                if let Some(open_loc) = map_param_loc(loc) {
+                    provided.insert((open_loc, p.name.clone()));
                 }
             }
         }
@@ -157,7 +305,7 @@ fn compiled_request_body(step: &Step, op: &ResolvedOperation) -> Option<Compiled
     let content_type = step
         .request_body
         .as_ref()
-        .and_then(|rb| rb.content_type.clone());
+        .and_then(|rb| negotiate_content_type(rb, op.shape.request_body_content_types.as_deref()));
 
     Some(CompiledRequestBody {
         content_type,
@@ -166,9 +314,22 @@ fn compiled_request_body(step: &Step, op: &ResolvedOperation) -> Option<Compiled
     })
 }
 
+/// A required body is missing when the step has no `requestBody`, or its payload is
+/// absent, `null` or an empty string.
 fn is_required_request_body_missing(step: &Step, op: &ResolvedOperation) -> bool {
     match op.shape.request_body_required {
-        Some(true) => step.request_body.is_none(),
+        Some(true) => step.request_body.as_ref().map_or(true, |rb| {
+            rb.payload.as_ref().map_or(true, is_empty_payload)
+        }),
+        _ => false,
+    }
+}
+
+/// Payload values that cannot satisfy a required request body.
+pub(crate) fn is_empty_payload(payload: &serde_json::Value) -> bool {
+    match payload {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(s) => s.is_empty(),
         _ => false,
     }
 }
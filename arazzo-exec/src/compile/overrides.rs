@@ -0,0 +1,69 @@
+use arazzo_core::types::Step;
+use serde_json::Value as JsonValue;
+
+use crate::openapi::{DiagnosticSeverity, OpenApiDiagnostic, ResolvedOperation};
+
+/// Step extension replacing the resolved operation's HTTP method, e.g. `PUT`.
+pub const OVERRIDE_METHOD_EXTENSION: &str = "x-override-method";
+
+/// Step extension replacing the resolved operation's path, e.g. `/v2/users`. The
+/// base URL still comes from the OpenAPI `servers`.
+pub const OVERRIDE_PATH_EXTENSION: &str = "x-override-path";
+
+/// Apply [`OVERRIDE_METHOD_EXTENSION`] and [`OVERRIDE_PATH_EXTENSION`] to `resolved`.
+/// Each override in effect is reported as a warning, so it is not left in by accident;
+/// a malformed one is an error and leaves the operation unchanged.
+pub(crate) fn apply_operation_overrides(
+    step: &Step,
+    resolved: &mut ResolvedOperation,
+) -> Vec<OpenApiDiagnostic> {
+    let mut diags = Vec::new();
+    let source_name = Some(resolved.source_name.clone());
+
+    if let Some(value) = step.extensions.get(OVERRIDE_METHOD_EXTENSION) {
+        match value {
+            JsonValue::String(m) if !m.is_empty() && m.chars().all(|c| c.is_ascii_alphabetic()) => {
+                let method = m.to_ascii_uppercase();
+                diags.push(OpenApiDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    message: format!(
+                        "{OVERRIDE_METHOD_EXTENSION} in effect: {method} instead of {}",
+                        resolved.method
+                    ),
+                    source_name: source_name.clone(),
+                });
+                resolved.method = method;
+            }
+            other => diags.push(OpenApiDiagnostic {
+                severity: DiagnosticSeverity::Error,
+                message: format!("{OVERRIDE_METHOD_EXTENSION} must be an HTTP method, got {other}"),
+                source_name: source_name.clone(),
+            }),
+        }
+    }
+
+    if let Some(value) = step.extensions.get(OVERRIDE_PATH_EXTENSION) {
+        match value {
+            JsonValue::String(p) if p.starts_with('/') => {
+                diags.push(OpenApiDiagnostic {
+                    severity: DiagnosticSeverity::Warning,
+                    message: format!(
+                        "{OVERRIDE_PATH_EXTENSION} in effect: {p} instead of {}",
+                        resolved.path
+                    ),
+                    source_name,
+                });
+                resolved.path = p.clone();
+            }
+            other => diags.push(OpenApiDiagnostic {
+                severity: DiagnosticSeverity::Error,
+                message: format!(
+                    "{OVERRIDE_PATH_EXTENSION} must be a path starting with '/', got {other}"
+                ),
+                source_name,
+            }),
+        }
+    }
+
+    diags
+}
@@ -0,0 +1,56 @@
+use arazzo_core::types::{Parameter, ParameterLocation, ParameterOrReusable, Step, Workflow};
+use serde_json::Value as JsonValue;
+
+/// Workflow-level extension listing parameters applied to every step of the workflow.
+/// A step parameter with the same name and location takes precedence.
+pub const DEFAULT_PARAMETERS_EXTENSION: &str = "x-default-parameters";
+
+/// Parameters from the workflow's [`DEFAULT_PARAMETERS_EXTENSION`], or none when the
+/// extension is absent or not a list of parameters.
+pub(crate) fn workflow_default_parameters(workflow: &Workflow) -> Vec<ParameterOrReusable> {
+    workflow
+        .extensions
+        .get(DEFAULT_PARAMETERS_EXTENSION)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Whether two parameters target the same request slot (header names are case-insensitive).
+pub(crate) fn same_parameter(a: &Parameter, b: &Parameter) -> bool {
+    if a.r#in != b.r#in {
+        return false;
+    }
+    match a.r#in {
+        Some(ParameterLocation::Header) => a.name.eq_ignore_ascii_case(&b.name),
+        _ => a.name == b.name,
+    }
+}
+
+/// Step extension overriding how missing required parameters are reported for that step:
+/// `true` or `"warn"` reports a warning, `"suppress"` reports nothing, `false` an error.
+pub const IGNORE_MISSING_PARAMS_EXTENSION: &str = "x-ignore-missing-params";
+
+/// Severity of the diagnostic emitted when a step omits required OpenAPI parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingParameterSeverity {
+    /// Report an error, which blocks execution.
+    #[default]
+    Error,
+    /// Report a warning; the step still runs.
+    Warning,
+    /// Report nothing.
+    Suppress,
+}
+
+impl MissingParameterSeverity {
+    /// The step's [`IGNORE_MISSING_PARAMS_EXTENSION`] setting, or `default` when absent.
+    pub(crate) fn for_step(step: &Step, default: Self) -> Self {
+        match step.extensions.get(IGNORE_MISSING_PARAMS_EXTENSION) {
+            Some(JsonValue::Bool(true)) => Self::Warning,
+            Some(JsonValue::Bool(false)) => Self::Error,
+            Some(JsonValue::String(s)) if s == "warn" => Self::Warning,
+            Some(JsonValue::String(s)) if s == "suppress" => Self::Suppress,
+            _ => default,
+        }
+    }
+}
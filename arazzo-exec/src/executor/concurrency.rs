@@ -1,11 +1,17 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
+/// Step extension naming a serialization group: steps in the same group never run at
+/// the same time, whatever the dependency graph allows.
+pub const SERIALIZE_GROUP_EXTENSION: &str = "x-serialize-group";
+
 pub struct ConcurrencyLimits {
     global: Arc<Semaphore>,
     per_source: Arc<HashMap<String, Arc<Semaphore>>>,
+    /// Single-permit semaphores, created on first use of each group name.
+    groups: Mutex<HashMap<String, Arc<Semaphore>>>,
 }
 
 impl ConcurrencyLimits {
@@ -21,10 +27,37 @@ impl ConcurrencyLimits {
                     .map(|(k, v)| (k.clone(), Arc::new(Semaphore::new(*v))))
                     .collect(),
             ),
+            groups: Mutex::new(HashMap::new()),
         }
     }
 
     pub async fn acquire(&self, source_name: Option<&str>) -> ConcurrencyPermit {
+        self.acquire_in_group(source_name, None).await
+    }
+
+    /// Like [`Self::acquire`], also holding the permit of `serialize_group`, if any.
+    pub async fn acquire_in_group(
+        &self,
+        source_name: Option<&str>,
+        serialize_group: Option<&str>,
+    ) -> ConcurrencyPermit {
+        // Take the group permit first so a step waiting on its group does not hold a
+        // global slot that other steps could use meanwhile.
+        let group = match serialize_group {
+            Some(name) => {
+                let sem = self
+                    .groups
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .entry(name.to_string())
+                    .or_insert_with(|| Arc::new(Semaphore::new(1)))
+                    .clone();
+                Some(sem.acquire_owned().await.unwrap_or_else(|_| {
+                    panic!("serialize-group semaphore closed unexpectedly. This is a bug - please report it.");
+                }))
+            }
+            None => None,
+        };
         // Semaphore acquire should never fail unless the semaphore is closed,
         // which should never happen in normal operation. If it does, it's a bug.
         let global = self
@@ -53,6 +86,7 @@ impl ConcurrencyLimits {
         ConcurrencyPermit {
             _global: global,
             _source: source,
+            _group: group,
         }
     }
 }
@@ -60,4 +94,5 @@ impl ConcurrencyLimits {
 pub struct ConcurrencyPermit {
     _global: OwnedSemaphorePermit,
     _source: Option<OwnedSemaphorePermit>,
+    _group: Option<OwnedSemaphorePermit>,
 }
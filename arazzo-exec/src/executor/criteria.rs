@@ -1,24 +1,96 @@
-use arazzo_core::expressions::{parse_runtime_expr, RuntimeExpr, Source};
+use std::collections::{BTreeMap, BTreeSet};
+
+use arazzo_core::expressions::{eval_expression, EvalContext, ResponseContext, TemplateDelimiters};
 use arazzo_core::types::{Criterion, CriterionType, KnownCriterionType};
 use regex::Regex;
 use serde_json::Value as JsonValue;
 use serde_json_path::JsonPath;
 
-use super::eval::ResponseContext;
+/// Context for jsonpath/regex criteria that omit `context`.
+const DEFAULT_CONTEXT: &str = "$response.body";
+
+/// Values beyond the response that criteria may reference: `$inputs.*` and the outputs
+/// of earlier steps (`$steps.<id>.outputs.*`), keyed by step id.
+#[derive(Debug, Clone, Default)]
+pub struct CriteriaScope {
+    pub inputs: JsonValue,
+    pub step_outputs: BTreeMap<String, JsonValue>,
+}
 
-pub fn evaluate_success(criteria: &[Criterion], resp: &ResponseContext<'_>) -> bool {
+/// Step ids named by `$steps.<id>` in the conditions or contexts of `criteria`, so their
+/// outputs can be loaded into a [`CriteriaScope`] before evaluation.
+pub fn referenced_steps<'a>(criteria: impl IntoIterator<Item = &'a Criterion>) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    for c in criteria {
+        for text in std::iter::once(c.condition.as_str()).chain(c.context.as_deref()) {
+            for (_, rest) in text
+                .match_indices("$steps.")
+                .map(|(i, m)| (i, &text[i + m.len()..]))
+            {
+                let id: String = rest
+                    .chars()
+                    .take_while(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-'))
+                    .collect();
+                if !id.is_empty() {
+                    ids.insert(id);
+                }
+            }
+        }
+    }
+    ids
+}
+
+pub fn evaluate_success(
+    criteria: &[Criterion],
+    resp: &ResponseContext<'_>,
+    scope: &CriteriaScope,
+) -> bool {
     if criteria.is_empty() {
         return (200..300).contains(&resp.status);
     }
     for c in criteria {
-        if !evaluate_criterion(c, resp) {
+        if !evaluate_criterion(c, resp, scope) {
             return false;
         }
     }
     true
 }
 
-fn evaluate_criterion(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
+/// OR-of-AND evaluation: true when every criterion of at least one group passes.
+pub fn evaluate_any_of(
+    groups: &[Vec<Criterion>],
+    resp: &ResponseContext<'_>,
+    scope: &CriteriaScope,
+) -> bool {
+    groups
+        .iter()
+        .any(|group| group.iter().all(|c| evaluate_criterion(c, resp, scope)))
+}
+
+/// Evaluate a single criterion against `value` as if it were a JSON response body with
+/// the given status, e.g. to unit-test criteria without performing a request.
+/// `$response.header.*` expressions resolve to null.
+pub fn evaluate_criterion_against(value: &JsonValue, criterion: &Criterion, status: u16) -> bool {
+    let headers = BTreeMap::new();
+    let body = serde_json::to_vec(value).unwrap_or_default();
+    let resp = ResponseContext {
+        status,
+        headers: &headers,
+        body: &body,
+        body_json: Some(value.clone()),
+        request: None,
+    };
+    evaluate_criterion(criterion, &resp, &CriteriaScope::default())
+}
+
+fn evaluate_criterion(c: &Criterion, resp: &ResponseContext<'_>, scope: &CriteriaScope) -> bool {
+    let delimiters = TemplateDelimiters::default();
+    let ctx = EvalContext {
+        inputs: &scope.inputs,
+        steps: &scope.step_outputs,
+        response: Some(resp.clone()),
+        delimiters: &delimiters,
+    };
     let criterion_type = c.r#type.as_ref().map(|t| match t {
         CriterionType::Known(k) => k.clone(),
         CriterionType::Custom(custom) => match custom.r#type {
@@ -30,22 +102,32 @@ fn evaluate_criterion(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
     });
 
     match criterion_type {
-        None | Some(KnownCriterionType::Simple) => evaluate_simple(c, resp),
-        Some(KnownCriterionType::Jsonpath) => evaluate_jsonpath(c, resp),
-        Some(KnownCriterionType::Regex) => evaluate_regex(c, resp),
+        None | Some(KnownCriterionType::Simple) => evaluate_simple(c, &ctx),
+        Some(KnownCriterionType::Jsonpath) => evaluate_jsonpath(c, &ctx),
+        Some(KnownCriterionType::Regex) => evaluate_regex(c, &ctx),
         Some(KnownCriterionType::Xpath) => false, // XPath not implemented
     }
 }
 
-fn evaluate_simple(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
+fn evaluate_simple(c: &Criterion, ctx: &EvalContext<'_>) -> bool {
     let cond = c.condition.trim();
 
-    // Parse as: <expr> <op> <literal>
+    // Parse as: <expr> <op> <literal or expr>
     let ops = ["==", "!=", "<=", ">=", "<", ">"];
     for op in ops {
         if let Some((lhs, rhs)) = cond.split_once(op) {
-            let lhs_val = resolve_runtime_expr(lhs.trim(), resp);
-            let rhs_val = parse_literal(rhs.trim());
+            let Some(lhs_val) = resolve_operand(lhs.trim(), ctx) else {
+                return false;
+            };
+            let rhs = rhs.trim();
+            let rhs_val = if rhs.starts_with('$') {
+                let Some(v) = resolve_operand(rhs, ctx) else {
+                    return false;
+                };
+                v
+            } else {
+                parse_literal(rhs)
+            };
             return compare_values(&lhs_val, &rhs_val, op);
         }
     }
@@ -53,13 +135,12 @@ fn evaluate_simple(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
     false
 }
 
-fn evaluate_jsonpath(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
-    let context_expr = match &c.context {
-        Some(ctx) => ctx.as_str(),
-        None => return false,
-    };
+fn evaluate_jsonpath(c: &Criterion, ctx: &EvalContext<'_>) -> bool {
+    let context_expr = c.context.as_deref().unwrap_or(DEFAULT_CONTEXT);
 
-    let context_json = resolve_runtime_expr(context_expr, resp);
+    let Some(context_json) = resolve_operand(context_expr, ctx) else {
+        return false;
+    };
     if context_json.is_null() {
         return false;
     }
@@ -109,13 +190,12 @@ fn evaluate_jsonpath(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
     !jsonpath.query(&query_target).all().is_empty()
 }
 
-fn evaluate_regex(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
-    let context_expr = match &c.context {
-        Some(ctx) => ctx.as_str(),
-        None => return false,
-    };
+fn evaluate_regex(c: &Criterion, ctx: &EvalContext<'_>) -> bool {
+    let context_expr = c.context.as_deref().unwrap_or(DEFAULT_CONTEXT);
 
-    let context_json = resolve_runtime_expr(context_expr, resp);
+    let Some(context_json) = resolve_operand(context_expr, ctx) else {
+        return false;
+    };
     let context_str = match context_json {
         JsonValue::String(s) => s,
         v => v.to_string(),
@@ -127,41 +207,17 @@ fn evaluate_regex(c: &Criterion, resp: &ResponseContext<'_>) -> bool {
         .unwrap_or(false)
 }
 
-/// Resolve an Arazzo runtime expression to a JSON value (sync, for criteria evaluation)
-fn resolve_runtime_expr(expr: &str, resp: &ResponseContext<'_>) -> JsonValue {
-    let parsed = match parse_runtime_expr(expr.trim()) {
-        Ok(p) => p,
-        Err(_) => return JsonValue::Null,
-    };
-
-    match parsed {
-        RuntimeExpr::StatusCode => JsonValue::Number(resp.status.into()),
-        RuntimeExpr::Response(source) => match source {
-            Source::Header(h) => {
-                let v = resp
-                    .headers
-                    .iter()
-                    .find(|(k, _)| k.eq_ignore_ascii_case(&h))
-                    .map(|(_, v)| v.clone())
-                    .unwrap_or_default();
-                JsonValue::String(v)
-            }
-            Source::Body { pointer } => {
-                let json = match &resp.body_json {
-                    Some(j) => j.clone(),
-                    None => return JsonValue::Null,
-                };
-                match pointer {
-                    Some(ptr) => json
-                        .pointer(ptr.as_str())
-                        .cloned()
-                        .unwrap_or(JsonValue::Null),
-                    None => json,
-                }
-            }
-            _ => JsonValue::Null,
-        },
-        _ => JsonValue::Null,
+/// The value of a criterion's runtime expression. A value that does not exist (an
+/// absent input or step output) is `null`; an expression that cannot be evaluated
+/// (e.g. it is malformed) is logged and fails the criterion.
+fn resolve_operand(expr: &str, ctx: &EvalContext<'_>) -> Option<JsonValue> {
+    match eval_expression(expr.trim(), ctx) {
+        Ok(v) => Some(v),
+        Err(e) if e.is_missing() => Some(JsonValue::Null),
+        Err(e) => {
+            tracing::warn!(expr, error = %e, "criterion expression failed");
+            None
+        }
     }
 }
 
@@ -260,6 +316,7 @@ mod tests {
             headers,
             body: body_bytes,
             body_json: serde_json::from_str(body).ok(),
+            request: None,
         }
     }
 
@@ -272,7 +329,59 @@ mod tests {
             r#type: None,
             extensions: Default::default(),
         };
-        assert!(evaluate_criterion(&c, &resp));
+        assert!(evaluate_criterion(&c, &resp, &CriteriaScope::default()));
+    }
+
+    #[test]
+    fn test_invalid_jsonpath_operand_fails_the_criterion() {
+        let resp = make_resp(200, r#"{"items": []}"#);
+        let c = Criterion {
+            context: None,
+            condition: "$response.body$.items[?( == null".to_string(),
+            r#type: None,
+            extensions: Default::default(),
+        };
+        assert!(!evaluate_criterion(&c, &resp, &CriteriaScope::default()));
+    }
+
+    #[test]
+    fn test_operands_resolve_like_other_expressions() {
+        let resp = make_resp(200, "{}");
+        let scope = CriteriaScope {
+            inputs: serde_json::json!({ "limit": 5 }),
+            step_outputs: BTreeMap::from([(
+                "login".to_string(),
+                serde_json::json!({ "userId": 7 }),
+            )]),
+        };
+        let holds = |condition: &str| {
+            let c = Criterion {
+                context: None,
+                condition: condition.to_string(),
+                r#type: None,
+                extensions: Default::default(),
+            };
+            evaluate_criterion(&c, &resp, &scope)
+        };
+        assert!(holds("$inputs.limit == 5"));
+        assert!(holds("$steps.login.outputs.userId == 7"));
+        // Values that do not exist are null.
+        assert!(holds("$inputs.offset == null"));
+        assert!(holds("$steps.login.outputs.token == null"));
+        // Expressions the evaluator rejects fail the criterion.
+        assert!(!holds("$steps.login.inputs == null"));
+    }
+
+    #[test]
+    fn test_jsonpath_context_defaults_to_body() {
+        let resp = make_resp(200, r#"{"authenticated": true}"#);
+        let c = Criterion {
+            context: None,
+            condition: "$.authenticated == true".to_string(),
+            r#type: Some(CriterionType::Known(KnownCriterionType::Jsonpath)),
+            extensions: Default::default(),
+        };
+        assert!(evaluate_criterion(&c, &resp, &CriteriaScope::default()));
     }
 
     #[test]
@@ -284,7 +393,7 @@ mod tests {
             r#type: Some(CriterionType::Known(KnownCriterionType::Jsonpath)),
             extensions: Default::default(),
         };
-        assert!(evaluate_criterion(&c, &resp));
+        assert!(evaluate_criterion(&c, &resp, &CriteriaScope::default()));
     }
 
     #[test]
@@ -296,7 +405,7 @@ mod tests {
             r#type: Some(CriterionType::Known(KnownCriterionType::Jsonpath)),
             extensions: Default::default(),
         };
-        assert!(evaluate_criterion(&c, &resp));
+        assert!(evaluate_criterion(&c, &resp, &CriteriaScope::default()));
     }
 
     #[test]
@@ -308,7 +417,7 @@ mod tests {
             r#type: Some(CriterionType::Known(KnownCriterionType::Regex)),
             extensions: Default::default(),
         };
-        assert!(evaluate_criterion(&c, &resp));
+        assert!(evaluate_criterion(&c, &resp, &CriteriaScope::default()));
     }
 
     #[test]
@@ -321,7 +430,7 @@ mod tests {
             extensions: Default::default(),
         };
         assert!(
-            evaluate_criterion(&c, &resp),
+            evaluate_criterion(&c, &resp, &CriteriaScope::default()),
             "filter existence check should pass"
         );
     }
@@ -336,7 +445,7 @@ mod tests {
             extensions: Default::default(),
         };
         assert!(
-            evaluate_criterion(&c, &resp),
+            evaluate_criterion(&c, &resp, &CriteriaScope::default()),
             "filter comparison should pass"
         );
     }
@@ -351,7 +460,7 @@ mod tests {
             extensions: Default::default(),
         };
         assert!(
-            !evaluate_criterion(&c, &resp),
+            !evaluate_criterion(&c, &resp, &CriteriaScope::default()),
             "filter should fail when field missing"
         );
     }
@@ -366,7 +475,7 @@ mod tests {
             extensions: Default::default(),
         };
         assert!(
-            evaluate_criterion(&c, &resp),
+            evaluate_criterion(&c, &resp, &CriteriaScope::default()),
             "bracket notation should work"
         );
     }
@@ -1,9 +1,14 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use async_trait::async_trait;
 use serde_json::json;
 use uuid::Uuid;
 
 use arazzo_store::{RunStatus, StateStore};
 
+use crate::executor::request::ResolvedParameter;
+use crate::policy::PolicyViolationKind;
+
 #[derive(Debug, Clone)]
 pub enum Event {
     RunStarted {
@@ -45,8 +50,25 @@ pub enum Event {
     PolicyDenied {
         run_id: Uuid,
         step_id: String,
+        kind: PolicyViolationKind,
         reason: String,
     },
+    /// An output expression could not be resolved; the output was recorded as `null`.
+    OutputUnresolved {
+        run_id: Uuid,
+        step_id: String,
+        output: String,
+        reason: String,
+    },
+    /// The request a step is about to send, emitted when
+    /// [`ExecutorConfig::debug_requests`](crate::executor::ExecutorConfig) is set.
+    StepDebug {
+        run_id: Uuid,
+        step_id: String,
+        parameters: Vec<ResolvedParameter>,
+        /// Request body after redaction, if there is one.
+        body: Option<String>,
+    },
 }
 
 #[async_trait]
@@ -84,20 +106,41 @@ impl EventSink for CompositeEventSink {
     }
 }
 
+/// Persists events through the store. An event the store rejects is counted in
+/// [`failures`](Self::failures) and handed to the fallback sink if one is set, or
+/// logged as a `tracing` warning otherwise.
 pub struct StoreEventSink {
     store: std::sync::Arc<dyn StateStore>,
+    fallback: Option<std::sync::Arc<dyn EventSink>>,
+    failures: AtomicU64,
 }
 
 impl StoreEventSink {
     pub fn new(store: std::sync::Arc<dyn StateStore>) -> Self {
-        Self { store }
+        Self {
+            store,
+            fallback: None,
+            failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Forward events the store fails to persist to `fallback` instead of dropping them.
+    pub fn with_fallback(mut self, fallback: std::sync::Arc<dyn EventSink>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Number of events the store failed to persist.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
     }
 }
 
 #[async_trait]
 impl EventSink for StoreEventSink {
     async fn emit(&self, event: Event) {
-        let (run_id, step_id, event_type, payload) = match event {
+        let original = event.clone();
+        let (run_id, step_id, event_type, mut payload) = match event {
             Event::RunStarted {
                 run_id,
                 workflow_id,
@@ -163,16 +206,42 @@ impl EventSink for StoreEventSink {
             Event::PolicyDenied {
                 run_id,
                 step_id,
+                kind,
                 reason,
             } => (
                 run_id,
                 None,
                 "policy.denied",
-                json!({ "step_id": step_id, "reason": reason }),
+                json!({ "step_id": step_id, "kind": kind.as_str(), "reason": reason }),
+            ),
+            Event::OutputUnresolved {
+                run_id,
+                step_id,
+                output,
+                reason,
+            } => (
+                run_id,
+                None,
+                "output.unresolved",
+                json!({ "step_id": step_id, "output": output, "reason": reason }),
+            ),
+            Event::StepDebug {
+                run_id,
+                step_id,
+                parameters,
+                body,
+            } => (
+                run_id,
+                None,
+                "step.debug",
+                json!({ "step_id": step_id, "parameters": parameters, "body": body }),
             ),
         };
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("ts".to_string(), json!(timestamp_now()));
+        }
 
-        let _ = self
+        let appended = self
             .store
             .append_event(arazzo_store::NewEvent {
                 run_id,
@@ -181,15 +250,32 @@ impl EventSink for StoreEventSink {
                 payload,
             })
             .await;
+        if let Err(e) = appended {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+            match &self.fallback {
+                Some(fallback) => fallback.emit(original).await,
+                None => tracing::warn!(
+                    %run_id,
+                    event_type,
+                    error = %e,
+                    "failed to store event"
+                ),
+            }
+        }
     }
 }
 
+/// RFC 3339 (ISO-8601) UTC timestamp taken at emit time.
+fn timestamp_now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
 pub struct StdoutEventSink;
 
-#[async_trait]
-impl EventSink for StdoutEventSink {
-    async fn emit(&self, event: Event) {
-        let json = match event {
+impl StdoutEventSink {
+    /// Renders an event as the JSON line printed by this sink.
+    pub fn to_json(event: Event) -> serde_json::Value {
+        let mut json = match event {
             Event::RunStarted {
                 run_id,
                 workflow_id,
@@ -233,11 +319,39 @@ impl EventSink for StdoutEventSink {
             Event::PolicyDenied {
                 run_id,
                 step_id,
+                kind,
                 reason,
             } => {
-                json!({ "type": "policy.denied", "run_id": run_id.to_string(), "step_id": step_id, "reason": reason })
+                json!({ "type": "policy.denied", "run_id": run_id.to_string(), "step_id": step_id, "kind": kind.as_str(), "reason": reason })
+            }
+            Event::OutputUnresolved {
+                run_id,
+                step_id,
+                output,
+                reason,
+            } => {
+                json!({ "type": "output.unresolved", "run_id": run_id.to_string(), "step_id": step_id, "output": output, "reason": reason })
+            }
+            Event::StepDebug {
+                run_id,
+                step_id,
+                parameters,
+                body,
+            } => {
+                json!({ "type": "step.debug", "run_id": run_id.to_string(), "step_id": step_id, "parameters": parameters, "body": body })
             }
         };
+        if let Some(obj) = json.as_object_mut() {
+            obj.insert("ts".to_string(), json!(timestamp_now()));
+        }
+        json
+    }
+}
+
+#[async_trait]
+impl EventSink for StdoutEventSink {
+    async fn emit(&self, event: Event) {
+        let json = Self::to_json(event);
         println!("{}", serde_json::to_string(&json).unwrap_or_default());
     }
 }
@@ -265,6 +379,72 @@ impl EventSink for BothEventSink {
     }
 }
 
+/// Appends each event as a JSON line (same shape as [`StdoutEventSink`]) to a file.
+pub struct FileEventSink {
+    file: std::sync::Mutex<std::fs::File>,
+}
+
+impl FileEventSink {
+    pub fn create(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(Self {
+            file: std::sync::Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl EventSink for FileEventSink {
+    async fn emit(&self, event: Event) {
+        use std::io::Write;
+
+        let line = serde_json::to_string(&StdoutEventSink::to_json(event)).unwrap_or_default();
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+}
+
+/// Build a sink from a comma-separated list such as `stdout,postgres,file:events.jsonl`.
+/// `postgres` writes to `store`, `both` is shorthand for `stdout,postgres`, and `none`
+/// (alone) disables events. Several entries are combined in a [`CompositeEventSink`].
+pub fn event_sink_from_spec(
+    spec: &str,
+    store: std::sync::Arc<dyn StateStore>,
+) -> Result<std::sync::Arc<dyn EventSink>, String> {
+    let mut sinks: Vec<Box<dyn EventSink>> = Vec::new();
+    for entry in spec.split(',').map(str::trim) {
+        match entry {
+            "none" => {}
+            "stdout" => sinks.push(Box::new(StdoutEventSink)),
+            "postgres" => sinks.push(Box::new(StoreEventSink::new(store.clone()))),
+            "both" => sinks.push(Box::new(BothEventSink::new(store.clone()))),
+            other => match other.strip_prefix("file:") {
+                Some(path) if !path.is_empty() => {
+                    let sink = FileEventSink::create(path)
+                        .map_err(|e| format!("cannot open event file {path}: {e}"))?;
+                    sinks.push(Box::new(sink));
+                }
+                _ => return Err(format!("unknown event sink: {other}")),
+            },
+        }
+    }
+    Ok(match sinks.len() {
+        0 => std::sync::Arc::new(NoOpEventSink),
+        1 => std::sync::Arc::from(sinks.remove(0)),
+        _ => {
+            let mut composite = CompositeEventSink::new();
+            for sink in sinks {
+                composite.add(sink);
+            }
+            std::sync::Arc::new(composite)
+        }
+    })
+}
+
 pub struct NoOpEventSink;
 
 #[async_trait]
@@ -4,6 +4,7 @@ use arazzo_core::types::{FailureActionOrReusable, FailureActionType, Step};
 use serde_json::json;
 
 use crate::executor::http::HttpError;
+use crate::executor::rng::RngSource;
 use crate::executor::worker::StepResult;
 use crate::policy::HttpResponseParts;
 use crate::retry::{decide_retry, RetryConfig, RetryDecision};
@@ -13,6 +14,7 @@ pub fn decide_failure(
     step: &Step,
     attempt_no: usize,
     resp: &HttpResponseParts,
+    rng: &RngSource,
 ) -> StepResult {
     let actions = step.on_failure.as_deref().unwrap_or(&[]);
     for a in actions {
@@ -29,7 +31,7 @@ pub fn decide_failure(
                         Some(&resp.headers),
                         false,
                         SystemTime::now(),
-                        || fastrand::u64(..),
+                        || rng.next_u64(),
                     );
                     if let RetryDecision::RetryAfter { delay, .. } = dec {
                         return StepResult::Retry {
@@ -59,6 +61,7 @@ pub fn decide_network_failure(
     step: &Step,
     attempt_no: usize,
     err: &HttpError,
+    rng: &RngSource,
 ) -> StepResult {
     let actions = step.on_failure.as_deref().unwrap_or(&[]);
     for a in actions {
@@ -74,7 +77,7 @@ pub fn decide_network_failure(
                     None,
                     true,
                     SystemTime::now(),
-                    || fastrand::u64(..),
+                    || rng.next_u64(),
                 );
                 if let RetryDecision::RetryAfter { delay, .. } = dec {
                     return StepResult::Retry {
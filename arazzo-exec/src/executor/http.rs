@@ -74,10 +74,22 @@ impl HttpClient for ReqwestHttpClient {
         let resp = rb.send().await.map_err(map_reqwest_error)?;
         let status = resp.status().as_u16();
 
-        let mut headers = BTreeMap::new();
-        for (k, v) in resp.headers().iter() {
-            if let Ok(s) = v.to_str() {
-                headers.insert(k.to_string(), s.to_string());
+        let mut headers = BTreeMap::<String, String>::new();
+        let mut repeated_headers = BTreeMap::<String, Vec<String>>::new();
+        for name in resp.headers().keys() {
+            let values: Vec<String> = resp
+                .headers()
+                .get_all(name)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .map(String::from)
+                .collect();
+            if values.is_empty() {
+                continue;
+            }
+            headers.insert(name.to_string(), values.join(", "));
+            if values.len() > 1 {
+                repeated_headers.insert(name.to_string(), values);
             }
         }
 
@@ -93,6 +105,7 @@ impl HttpClient for ReqwestHttpClient {
         Ok(HttpResponseParts {
             status,
             headers,
+            repeated_headers,
             body,
         })
     }
@@ -0,0 +1,299 @@
+use std::path::Path;
+
+use arazzo_core::types::{ArazzoDocument, Workflow};
+use arazzo_core::{
+    apply_overlay, interpolate_env, interpolate_env_with, parse_document_str, parse_overlay_str,
+    plan_document_with, DocumentFormat, PlanOptions, ValidationOptions,
+};
+use arazzo_store::{HashAlgorithm, StateStore, WorkflowDoc, WorkflowRun};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::compile::{CompiledPlan, Compiler};
+use crate::openapi::DiagnosticSeverity;
+
+/// A run recorded in the store, with everything [`crate::Executor::execute_run`] needs.
+#[derive(Debug, Clone)]
+pub struct PreparedRun {
+    pub run_id: Uuid,
+    pub document: ArazzoDocument,
+    pub workflow: Workflow,
+    pub compiled: CompiledPlan,
+    pub inputs: JsonValue,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PrepareRunError {
+    #[error("failed to parse document: {0}")]
+    Parse(#[from] arazzo_core::ParseError),
+    #[error("environment variables not set: {}", .0.join(", "))]
+    MissingEnv(Vec<String>),
+    #[error("overlay failed: {0}")]
+    Overlay(#[from] arazzo_core::OverlayError),
+    #[error("invalid run overrides: {0}")]
+    Overrides(#[from] serde_json::Error),
+    #[error("workflow validation failed: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+    #[error("planning failed: {0}")]
+    Plan(String),
+    #[error("OpenAPI compilation failed: {}", .0.join("; "))]
+    Compile(Vec<String>),
+    #[error("store error: {0}")]
+    Store(#[from] arazzo_store::StoreError),
+}
+
+/// How [`prepare_run`] reads the document and records the run. The default takes the
+/// document's only workflow, without inputs.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub workflow_id: Option<String>,
+    pub inputs: Option<JsonValue>,
+    /// Format of the document text; `None` detects it.
+    pub format: Option<DocumentFormat>,
+    /// Where the document came from (e.g. `source_path`, `git_commit`), stored with the run.
+    pub provenance: Option<JsonValue>,
+    pub idempotency_key: Option<String>,
+    pub hash_algorithm: HashAlgorithm,
+    /// Checks the document must pass before a run is created.
+    pub validation: ValidationOptions,
+    /// Expand `${VAR}` placeholders from the environment, as
+    /// [`arazzo_core::ParseOptions::interpolate_env`] does.
+    pub interpolate_env: bool,
+    /// Overlay documents (their text) applied in order after expansion.
+    pub overlays: Vec<String>,
+    /// Send OpenAPI defaults for optional parameters a step does not set. Recorded on
+    /// the run, so wherever it executes does the same.
+    pub fill_parameter_defaults: bool,
+}
+
+/// What turns a run's stored document into the one it executes, kept in the run's
+/// `overrides`. The store only ever holds the document as written: variables are
+/// recorded by name and read from the environment again wherever the run executes, so
+/// their values (often credentials) never reach `workflow_docs` or its hash.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentOverrides {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overlays: Vec<String>,
+    /// See [`RunOptions::fill_parameter_defaults`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fill_parameter_defaults: bool,
+}
+
+impl DocumentOverrides {
+    /// The overrides recorded on `run`.
+    pub fn of_run(run: &WorkflowRun) -> Result<Self, PrepareRunError> {
+        Ok(serde_json::from_value(run.overrides.clone())?)
+    }
+
+    /// `document` with the recorded variables expanded and the overlays applied. Other
+    /// placeholders are left alone.
+    pub fn apply(&self, document: &ArazzoDocument) -> Result<ArazzoDocument, PrepareRunError> {
+        let mut document = document.clone();
+        if !self.env.is_empty() {
+            let report = interpolate_env_with(&mut document, |name| {
+                if self.env.iter().any(|n| n == name) {
+                    std::env::var(name).ok()
+                } else {
+                    None
+                }
+            });
+            let missing = missing_names(&report);
+            if !missing.is_empty() {
+                return Err(PrepareRunError::MissingEnv(missing));
+            }
+        }
+        for overlay in &self.overlays {
+            document = apply_overlay(&document, &parse_overlay_str(overlay)?)?;
+        }
+        Ok(document)
+    }
+}
+
+fn missing_names(report: &arazzo_core::EnvReport) -> Vec<String> {
+    let mut names: Vec<String> = report.missing.iter().map(|v| v.name.clone()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// The document `run` executes: its stored document with the run's
+/// [`DocumentOverrides`] applied.
+pub fn run_document(
+    doc: &WorkflowDoc,
+    run: &WorkflowRun,
+) -> Result<ArazzoDocument, PrepareRunError> {
+    let format = match doc.format.as_str() {
+        "json" => DocumentFormat::Json,
+        "toml" => DocumentFormat::Toml,
+        _ => DocumentFormat::Yaml,
+    };
+    let parsed = parse_document_str(&doc.raw, format)?;
+    DocumentOverrides::of_run(run)?.apply(&parsed.document)
+}
+
+/// Parse, validate and compile `raw`, then store the document and create a run for the
+/// selected workflow. `raw` is stored as given; expansion and overlays are recorded on
+/// the run as [`DocumentOverrides`].
+///
+/// This is how `arazzo start` and `arazzo execute` create runs, and how callers
+/// embedding the engine (e.g. behind an HTTP API) should too.
+pub async fn prepare_run(
+    store: &dyn StateStore,
+    compiler: &Compiler,
+    raw: &str,
+    options: RunOptions,
+) -> Result<PreparedRun, PrepareRunError> {
+    let parsed = parse_document_str(raw, options.format.unwrap_or(DocumentFormat::Auto))?;
+    let mut env = Vec::new();
+    if options.interpolate_env {
+        let report = interpolate_env(&mut parsed.document.clone());
+        let missing = missing_names(&report);
+        if !missing.is_empty() {
+            return Err(PrepareRunError::MissingEnv(missing));
+        }
+        env = report.substituted.into_iter().map(|v| v.name).collect();
+        env.sort();
+        env.dedup();
+    }
+    let overrides = DocumentOverrides {
+        env,
+        overlays: options.overlays,
+        fill_parameter_defaults: options.fill_parameter_defaults,
+    };
+    let document = overrides.apply(&parsed.document)?;
+
+    let outcome = plan_document_with(
+        &document,
+        PlanOptions {
+            workflow_id: options.workflow_id,
+            inputs: options.inputs,
+        },
+        &options.validation,
+    )
+    .map_err(|e| PrepareRunError::Plan(e.to_string()))?;
+    if !outcome.validation.is_valid {
+        return Err(PrepareRunError::Invalid(outcome.validation.errors));
+    }
+    let plan = outcome
+        .plan
+        .ok_or_else(|| PrepareRunError::Plan("no plan generated".to_string()))?;
+    let workflow = document
+        .workflows
+        .iter()
+        .find(|w| w.workflow_id == plan.summary.workflow_id)
+        .cloned()
+        .ok_or_else(|| PrepareRunError::Plan("workflow not found".to_string()))?;
+
+    let compiled = compile_for_run(
+        compiler,
+        &document,
+        &workflow,
+        options.provenance.as_ref(),
+        &overrides,
+    )
+    .await;
+    let errors: Vec<String> = compiled
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Error)
+        .map(|d| d.message.clone())
+        .collect();
+    if !errors.is_empty() {
+        return Err(PrepareRunError::Compile(errors));
+    }
+
+    let workflow_doc = store
+        .upsert_workflow_doc(arazzo_store::NewWorkflowDoc {
+            doc_hash: arazzo_store::doc_hash(raw, options.hash_algorithm),
+            format: parsed.format.into(),
+            raw: raw.to_string(),
+            doc: serde_json::to_value(&parsed.document)
+                .map_err(|e| arazzo_store::StoreError::Other(e.to_string()))?,
+        })
+        .await?;
+
+    let steps: Vec<arazzo_store::NewRunStep> = plan
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(idx, s)| arazzo_store::NewRunStep {
+            step_id: s.step_id.clone(),
+            step_index: idx as i32,
+            source_name: compiled
+                .steps
+                .iter()
+                .find(|c| c.step_id == s.step_id)
+                .and_then(|c| c.operation.as_ref())
+                .map(|op| op.source_name.clone()),
+            operation_id: match &s.operation {
+                arazzo_core::PlanOperationRef::OperationId { operation_id, .. } => {
+                    Some(operation_id.clone())
+                }
+                _ => None,
+            },
+            depends_on: s.depends_on.clone(),
+        })
+        .collect();
+    let edges = steps
+        .iter()
+        .flat_map(|s| {
+            s.depends_on.iter().map(|dep| arazzo_store::RunStepEdge {
+                from_step_id: dep.clone(),
+                to_step_id: s.step_id.clone(),
+            })
+        })
+        .collect();
+
+    let inputs = plan.summary.effective_inputs;
+    let run_id = store
+        .create_run_and_steps(
+            arazzo_store::NewRun {
+                workflow_doc_id: workflow_doc.id,
+                workflow_id: workflow.workflow_id.clone(),
+                created_by: None,
+                idempotency_key: options.idempotency_key,
+                inputs: inputs.clone(),
+                overrides: serde_json::to_value(&overrides)?,
+                provenance: options.provenance.unwrap_or_else(|| serde_json::json!({})),
+            },
+            steps,
+            edges,
+        )
+        .await?;
+
+    Ok(PreparedRun {
+        run_id,
+        document,
+        workflow,
+        compiled,
+        inputs,
+    })
+}
+
+/// Directory of the file a run's document was read from, per the `source_path` in its
+/// provenance.
+pub fn source_dir(provenance: &JsonValue) -> Option<&Path> {
+    provenance
+        .get("source_path")?
+        .as_str()
+        .and_then(|p| Path::new(p).parent())
+}
+
+/// Compile `workflow` with relative source URLs resolved next to the run's document
+/// file when its provenance records one, and the run's recorded request options.
+pub(crate) async fn compile_for_run(
+    compiler: &Compiler,
+    document: &ArazzoDocument,
+    workflow: &Workflow,
+    provenance: Option<&JsonValue>,
+    overrides: &DocumentOverrides,
+) -> CompiledPlan {
+    let compiled = match provenance.and_then(source_dir) {
+        Some(dir) => compiler.compile_workflow_in(document, workflow, dir).await,
+        None => compiler.compile_workflow(document, workflow).await,
+    };
+    compiled.with_parameter_defaults(overrides.fill_parameter_defaults)
+}
@@ -1,6 +1,7 @@
 use crate::executor::{Event, EventSink};
 use arazzo_store::RunStatus;
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
@@ -89,15 +90,63 @@ impl RunMetrics {
 
 pub struct MetricsCollector {
     metrics: Arc<Mutex<RunMetrics>>,
+    /// When each step's first attempt started, for step durations.
+    step_started: Mutex<HashMap<String, Instant>>,
+    #[cfg(feature = "statsd")]
+    statsd: Option<crate::executor::statsd::StatsdEmitter>,
 }
 
 impl MetricsCollector {
     pub fn new(run_id: uuid::Uuid, workflow_id: String) -> Self {
         Self {
             metrics: Arc::new(Mutex::new(RunMetrics::new(run_id, workflow_id))),
+            step_started: Mutex::new(HashMap::new()),
+            #[cfg(feature = "statsd")]
+            statsd: None,
         }
     }
 
+    /// Also send each completed step to StatsD.
+    #[cfg(feature = "statsd")]
+    pub fn with_statsd(mut self, emitter: crate::executor::statsd::StatsdEmitter) -> Self {
+        self.statsd = Some(emitter);
+        self
+    }
+
+    /// Note the start of a step's attempt; only the first one counts towards its duration.
+    pub async fn record_step_started(&self, step_id: &str) {
+        self.step_started
+            .lock()
+            .await
+            .entry(step_id.to_string())
+            .or_insert_with(Instant::now);
+    }
+
+    /// Count a finished step and report it to the configured emitters.
+    pub async fn record_step_completion(&self, step_id: &str, succeeded: bool) {
+        if succeeded {
+            self.record_step_success().await;
+        } else {
+            self.record_step_failure().await;
+        }
+        let duration = self
+            .step_started
+            .lock()
+            .await
+            .get(step_id)
+            .map(|started| started.elapsed());
+
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &self.statsd {
+            let workflow_id = self.metrics.lock().await.workflow_id.clone();
+            statsd
+                .step_completed(&workflow_id, step_id, succeeded, duration)
+                .await;
+        }
+        #[cfg(not(feature = "statsd"))]
+        let _ = duration;
+    }
+
     pub async fn record_step_success(&self) {
         self.metrics.lock().await.record_step_success();
     }
@@ -147,22 +196,21 @@ impl EventSink for MetricsEventSink {
     async fn emit(&self, event: Event) {
         // Update metrics based on event
         match &event {
-            Event::StepSucceeded { .. } => {
-                self.collector.record_step_success().await;
+            Event::StepSucceeded { step_id, .. } => {
+                self.collector.record_step_completion(step_id, true).await;
             }
-            Event::StepFailed { .. } => {
-                self.collector.record_step_failure().await;
+            Event::StepFailed { step_id, .. } => {
+                self.collector.record_step_completion(step_id, false).await;
             }
             Event::StepRetryScheduled { .. } => {
                 self.collector.record_retry().await;
             }
-            Event::AttemptStarted { .. } => {
+            Event::AttemptStarted { step_id, .. } => {
                 self.collector.record_http_request().await;
+                self.collector.record_step_started(step_id).await;
             }
-            Event::AttemptFinished { succeeded, .. } => {
-                if !succeeded {
-                    self.collector.record_http_error().await;
-                }
+            Event::AttemptFinished { succeeded, .. } if !succeeded => {
+                self.collector.record_http_error().await;
             }
             Event::PolicyDenied { .. } => {
                 self.collector.record_policy_denial().await;
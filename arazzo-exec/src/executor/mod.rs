@@ -4,25 +4,46 @@ pub mod eval;
 pub mod events;
 pub mod failure;
 pub mod http;
+mod launch;
 pub mod metrics;
+mod policy_check;
+mod replay;
 mod request;
 pub mod response;
 mod result;
+mod rng;
+mod run_worker;
 mod scheduler;
+#[cfg(feature = "statsd")]
+mod statsd;
 mod step_runner;
 mod types;
 pub mod webhook;
 pub mod worker;
 
+pub use criteria::{evaluate_criterion_against, CriteriaScope};
 pub use metrics::{MetricsCollector, RunMetrics};
+pub use policy_check::{check_policy, PolicyDecision, StepPolicyCheck};
+pub use replay::{replay_events, ReplayedAttempt, ReplayedRun, ReplayedStep, StepDiscrepancy};
+pub use request::{
+    RequestBuildError, ResolvedParameter, IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_EXTENSION,
+};
 
 pub use events::{
-    BothEventSink, CompositeEventSink, Event, EventSink, NoOpEventSink, StdoutEventSink,
-    StoreEventSink,
+    event_sink_from_spec, BothEventSink, CompositeEventSink, Event, EventSink, FileEventSink,
+    NoOpEventSink, StdoutEventSink, StoreEventSink,
 };
 pub use http::{HttpClient, HttpError, ReqwestHttpClient};
+pub use launch::{
+    prepare_run, run_document, source_dir, DocumentOverrides, PrepareRunError, PreparedRun,
+    RunOptions,
+};
 pub use result::{ExecutionError, ExecutionResult};
+pub use rng::RngSource;
+pub use run_worker::RunWorker;
 pub use scheduler::Executor;
+#[cfg(feature = "statsd")]
+pub use statsd::StatsdEmitter;
 pub use types::{ExecutionOutcome, ExecutorConfig};
 pub use webhook::WebhookEventSink;
 pub use worker::{StepResult, Worker};
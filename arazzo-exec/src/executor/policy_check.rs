@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use arazzo_core::types::{ArazzoDocument, Workflow};
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::compile::CompiledPlan;
+use crate::executor::eval::StepOutputs;
+use crate::executor::request::{build_request, SecretsPolicyForSource};
+use crate::policy::{PolicyGate, PolicyOverrides};
+use crate::secrets::{SecretError, SecretRef, SecretValue, SecretsProvider};
+
+/// What the policy would do with one step's request.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct StepPolicyCheck {
+    pub step_id: String,
+    #[serde(flatten)]
+    pub decision: PolicyDecision,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "decision", rename_all = "lowercase")]
+pub enum PolicyDecision {
+    Allowed {
+        method: String,
+        url: String,
+    },
+    Denied {
+        /// [`PolicyViolationKind`](crate::policy::PolicyViolationKind) as a string, e.g. `host`.
+        kind: String,
+        reason: String,
+    },
+    /// No request could be built, e.g. the step calls a workflow or its operation did
+    /// not resolve.
+    Skipped {
+        reason: String,
+    },
+}
+
+/// Build every step's request and run it through `policy_gate` without sending it.
+///
+/// Secrets resolve to a dummy value and `$steps` outputs to placeholders, so the
+/// requests have the shape of a real run but not its values.
+pub async fn check_policy(
+    policy_gate: &PolicyGate,
+    workflow: &Workflow,
+    compiled: &CompiledPlan,
+    inputs: &JsonValue,
+    document: Option<&ArazzoDocument>,
+) -> Vec<StepPolicyCheck> {
+    let outputs = PlaceholderOutputs::for_workflow(workflow);
+    let run_id = Uuid::nil();
+    let mut checks = Vec::with_capacity(workflow.steps.len());
+
+    for step in &workflow.steps {
+        let compiled_step = compiled.steps.iter().find(|c| c.step_id == step.step_id);
+        let op = compiled_step.and_then(|c| c.operation.as_ref());
+        let (Some(compiled_step), Some(op)) = (compiled_step, op) else {
+            let reason = if step.workflow_id.is_some() {
+                "calls a workflow; no HTTP request is sent"
+            } else {
+                "operation was not resolved"
+            };
+            checks.push(StepPolicyCheck {
+                step_id: step.step_id.clone(),
+                decision: PolicyDecision::Skipped {
+                    reason: reason.to_string(),
+                },
+            });
+            continue;
+        };
+
+        let eff = policy_gate.effective_for_source(&op.source_name, &PolicyOverrides::default());
+        let secrets_policy = SecretsPolicyForSource {
+            allow_secrets_in_url: eff.allow_secrets_in_url,
+            strict: eff.strict_secrets,
+            inject_headers: eff.inject_headers.clone(),
+            auth: eff.auth.clone(),
+        };
+        let decision = match build_request(
+            &outputs,
+            &DummySecrets,
+            &secrets_policy,
+            run_id,
+            workflow,
+            step,
+            op,
+            compiled_step.fill_parameter_defaults,
+            inputs,
+            document,
+        )
+        .await
+        {
+            Err(e) => PolicyDecision::Skipped {
+                reason: format!("request could not be built: {e}"),
+            },
+            Ok(req) => match policy_gate.apply_request(
+                &op.source_name,
+                &req.parts,
+                &req.secret_derived_headers,
+                &req.secret_query_params,
+                &req.body_secret_pointers,
+            ) {
+                Ok(gated) => PolicyDecision::Allowed {
+                    method: gated.method,
+                    url: gated.url,
+                },
+                Err(e) => PolicyDecision::Denied {
+                    kind: e.kind().as_str().to_string(),
+                    reason: e.to_string(),
+                },
+            },
+        };
+        checks.push(StepPolicyCheck {
+            step_id: step.step_id.clone(),
+            decision,
+        });
+    }
+    checks
+}
+
+/// Resolves every secret ref to the same dummy value.
+struct DummySecrets;
+
+#[async_trait]
+impl SecretsProvider for DummySecrets {
+    async fn get(&self, _secret_ref: &SecretRef) -> Result<SecretValue, SecretError> {
+        Ok(SecretValue::from_string("dummy-secret".to_string()))
+    }
+}
+
+/// A placeholder for each output a step declares.
+struct PlaceholderOutputs {
+    outputs: BTreeMap<String, JsonValue>,
+}
+
+impl PlaceholderOutputs {
+    fn for_workflow(workflow: &Workflow) -> Self {
+        let outputs = workflow
+            .steps
+            .iter()
+            .map(|s| {
+                let values: serde_json::Map<String, JsonValue> = s
+                    .outputs
+                    .iter()
+                    .flat_map(|o| o.keys())
+                    .map(|k| (k.clone(), JsonValue::String(format!("{}-{k}", s.step_id))))
+                    .collect();
+                (s.step_id.clone(), JsonValue::Object(values))
+            })
+            .collect();
+        Self { outputs }
+    }
+}
+
+#[async_trait]
+impl StepOutputs for PlaceholderOutputs {
+    async fn step_outputs(&self, _run_id: Uuid, step_id: &str) -> Result<JsonValue, String> {
+        Ok(self
+            .outputs
+            .get(step_id)
+            .cloned()
+            .unwrap_or_else(|| JsonValue::Object(Default::default())))
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use arazzo_store::{RunEvent, RunStep, RunStepStatus, StateStore, StoreError};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Events read per `get_events_after` call while replaying.
+const PAGE_SIZE: i64 = 500;
+
+/// Run state rebuilt from the event log alone, as
+/// [`StoreEventSink`](crate::executor::StoreEventSink) recorded it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReplayedRun {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    /// `running` after `run.started`, the final status after `run.finished`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Steps that appear in at least one event, keyed by step id.
+    pub steps: BTreeMap<String, ReplayedStep>,
+    pub events_replayed: usize,
+    /// Event types this replay does not understand, in the order seen.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown_events: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReplayedStep {
+    pub status: String,
+    pub retries_scheduled: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<ReplayedAttempt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReplayedAttempt {
+    pub attempt_no: i32,
+    /// `None` while the attempt has no `attempt.finished` event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub succeeded: Option<bool>,
+}
+
+impl ReplayedStep {
+    fn new() -> Self {
+        Self {
+            status: RunStepStatus::Pending.as_str().to_string(),
+            retries_scheduled: 0,
+            attempts: Vec::new(),
+        }
+    }
+
+    fn set_status(&mut self, status: RunStepStatus) {
+        self.status = status.as_str().to_string();
+    }
+}
+
+/// A stored step whose status the event log does not account for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StepDiscrepancy {
+    pub step_id: String,
+    pub stored: String,
+    /// `None` when no event mentions the step.
+    pub replayed: Option<String>,
+}
+
+impl ReplayedRun {
+    /// Fold one event into the state. Events must be applied in id order.
+    pub fn apply(&mut self, event: &RunEvent) {
+        self.events_replayed += 1;
+        let payload = &event.payload;
+        let str_field = |name: &str| payload.get(name).and_then(|v| v.as_str());
+
+        match event.event_type.as_str() {
+            "run.started" => {
+                self.workflow_id = str_field("workflow_id").map(String::from);
+                self.status = Some("running".to_string());
+            }
+            "run.finished" => self.status = str_field("status").map(String::from),
+            "step.started"
+            | "step.succeeded"
+            | "step.failed"
+            | "step.retry_scheduled"
+            | "attempt.started"
+            | "attempt.finished" => {
+                let Some(step_id) = str_field("step_id") else {
+                    self.unknown_events.push(event.event_type.clone());
+                    return;
+                };
+                let step = self
+                    .steps
+                    .entry(step_id.to_string())
+                    .or_insert_with(ReplayedStep::new);
+                let attempt_no = payload
+                    .get("attempt_no")
+                    .and_then(|v| v.as_i64())
+                    .and_then(|n| i32::try_from(n).ok())
+                    .unwrap_or(0);
+                match event.event_type.as_str() {
+                    "step.started" => step.set_status(RunStepStatus::Running),
+                    "step.succeeded" => step.set_status(RunStepStatus::Succeeded),
+                    "step.failed" => step.set_status(RunStepStatus::Failed),
+                    "step.retry_scheduled" => {
+                        step.retries_scheduled += 1;
+                        step.set_status(RunStepStatus::Pending);
+                    }
+                    "attempt.started" => step.attempts.push(ReplayedAttempt {
+                        attempt_no,
+                        succeeded: None,
+                    }),
+                    _ => {
+                        let succeeded = payload.get("succeeded").and_then(|v| v.as_bool());
+                        match step
+                            .attempts
+                            .iter_mut()
+                            .rev()
+                            .find(|a| a.attempt_no == attempt_no)
+                        {
+                            Some(a) => a.succeeded = succeeded,
+                            None => step.attempts.push(ReplayedAttempt {
+                                attempt_no,
+                                succeeded,
+                            }),
+                        }
+                    }
+                }
+            }
+            // Informational; they do not change step or run state.
+            "policy.denied" | "output.unresolved" | "step.debug" => {}
+            other => self.unknown_events.push(other.to_string()),
+        }
+    }
+
+    /// Compare against the steps the store holds. A step no event mentions is only
+    /// reported when its stored status needs events to get there (pending and skipped
+    /// steps do not).
+    pub fn discrepancies(&self, stored: &[RunStep]) -> Vec<StepDiscrepancy> {
+        stored
+            .iter()
+            .filter_map(|s| {
+                let replayed = self.steps.get(&s.step_id).map(|r| r.status.clone());
+                let consistent = match &replayed {
+                    Some(status) => *status == s.status,
+                    None => matches!(
+                        s.status.parse::<RunStepStatus>(),
+                        Ok(RunStepStatus::Pending | RunStepStatus::Skipped)
+                    ),
+                };
+                (!consistent).then(|| StepDiscrepancy {
+                    step_id: s.step_id.clone(),
+                    stored: s.status.clone(),
+                    replayed,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Rebuild a run's state by reading its whole event log from `store`.
+pub async fn replay_events(
+    store: &dyn StateStore,
+    run_id: Uuid,
+) -> Result<ReplayedRun, StoreError> {
+    let mut run = ReplayedRun::default();
+    let mut after_id = 0;
+    loop {
+        let events = store.get_events_after(run_id, after_id, PAGE_SIZE).await?;
+        let Some(last) = events.last() else {
+            return Ok(run);
+        };
+        after_id = last.id;
+        for event in &events {
+            run.apply(event);
+        }
+    }
+}
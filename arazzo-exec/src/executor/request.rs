@@ -1,147 +1,331 @@
 use std::collections::BTreeMap;
 
-use arazzo_core::types::{ArazzoDocument, Parameter, ParameterOrReusable, Step};
+use arazzo_core::expressions::TemplateDelimiters;
+use arazzo_core::types::{ArazzoDocument, Parameter, ParameterOrReusable, Step, Workflow};
 use serde_json::Value as JsonValue;
 use uuid::Uuid;
 
-use crate::executor::eval::{eval_value, EvalContext};
-use crate::policy::HttpRequestParts;
+use crate::compile::{
+    is_empty_payload, negotiate_content_type, same_parameter, workflow_default_parameters,
+};
+use crate::executor::eval::{eval_value, EvalContext, StepOutputs};
+use crate::executor::response::is_json_content_type;
+use crate::openapi::OpenApiParamLocation;
+use crate::policy::{ApiKeyLocation, HttpRequestParts, SourceAuth};
 use crate::secrets::{SecretPlacement, SecretRef, SecretsProvider};
 
 pub struct RequestBuildResult {
     pub parts: HttpRequestParts,
     pub secret_derived_headers: Vec<String>,
-    pub body_contains_secrets: bool,
+    /// Query parameters carrying secrets, including `in: query` API keys.
+    pub secret_query_params: Vec<String>,
+    /// JSON pointers of body leaves whose values were resolved from secrets.
+    pub body_secret_pointers: Vec<String>,
+    /// The step's parameters as evaluated, with secret-derived values redacted.
+    pub parameters: Vec<ResolvedParameter>,
+}
+
+/// A step parameter after expression evaluation and secret resolution.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ResolvedParameter {
+    pub name: String,
+    /// `header`, `query`, `path` or `cookie`.
+    pub location: &'static str,
+    /// The value sent, or `<redacted>` when it came from a secret.
+    pub value: String,
+    pub secret: bool,
+}
+
+impl ResolvedParameter {
+    fn new(name: &str, location: &'static str, value: &str, secret: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            location,
+            value: if secret {
+                "<redacted>".to_string()
+            } else {
+                value.to_string()
+            },
+            secret,
+        }
+    }
+}
+
+/// Step extension that opts a step into an automatic [`IDEMPOTENCY_KEY_HEADER`].
+pub const IDEMPOTENT_EXTENSION: &str = "x-idempotent";
+
+/// Header carrying the key derived from the run id and step id; it is identical for
+/// every attempt of the same step, so servers can deduplicate retried requests.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RequestBuildError {
+    #[error("unresolved parameter reference: {0}")]
+    UnresolvedParameterRef(String),
+    #[error("eval error: {0}")]
+    Eval(String),
+    #[error("missing OpenAPI server base_url")]
+    MissingBaseUrl,
+    #[error("invalid request URL: {0}")]
+    UrlParse(#[from] url::ParseError),
+    #[error("failed to serialize request body: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("operation requires a request body but the payload is empty")]
+    EmptyRequiredBody,
+    #[error("unresolved secret {secret_ref}: {reason}")]
+    UnresolvedSecret { secret_ref: String, reason: String },
+    #[error("cannot encode request body as {content_type}: {reason}")]
+    UnsupportedBody {
+        content_type: String,
+        reason: String,
+    },
+}
+
+impl RequestBuildError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RequestBuildError::UnresolvedParameterRef(_) => "unresolved_parameter_ref",
+            RequestBuildError::Eval(_) => "eval",
+            RequestBuildError::MissingBaseUrl => "missing_base_url",
+            RequestBuildError::UrlParse(_) => "url_parse",
+            RequestBuildError::Serialize(_) => "serialize",
+            RequestBuildError::EmptyRequiredBody => "empty_required_body",
+            RequestBuildError::UnresolvedSecret { .. } => "unresolved_secret",
+            RequestBuildError::UnsupportedBody { .. } => "unsupported_body",
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct SecretsPolicyForSource {
     pub allow_secrets_in_url: bool,
+    /// Fail with [`RequestBuildError::UnresolvedSecret`] rather than sending a secret ref
+    /// that could not be resolved (or is not allowed where it appears) as a literal.
+    pub strict: bool,
+    /// Source-level headers from policy; a header set by the step itself takes precedence.
+    pub inject_headers: BTreeMap<String, String>,
+    /// Source-level credentials; like `inject_headers`, a header the step sets itself wins.
+    pub auth: Option<SourceAuth>,
 }
 
 #[allow(clippy::too_many_arguments)]
 pub async fn build_request(
-    store: &dyn arazzo_store::StateStore,
+    outputs: &dyn StepOutputs,
     secrets: &dyn SecretsProvider,
     secrets_policy: &SecretsPolicyForSource,
     run_id: Uuid,
+    workflow: &Workflow,
     step: &Step,
     resolved_op: &crate::openapi::ResolvedOperation,
+    fill_parameter_defaults: bool,
     inputs: &JsonValue,
     document: Option<&ArazzoDocument>,
-) -> Result<RequestBuildResult, String> {
+) -> Result<RequestBuildResult, RequestBuildError> {
     let mut headers = BTreeMap::<String, String>::new();
     let mut query = Vec::<(String, String)>::new();
     let mut path_params = BTreeMap::<String, String>::new();
     let mut secret_derived_headers = Vec::<String>::new();
+    let mut secret_query_params = Vec::<String>::new();
+    let mut resolved_params = Vec::<ResolvedParameter>::new();
+    let delimiters = document
+        .map(|d| TemplateDelimiters::from_extensions(&d.extensions))
+        .unwrap_or_default();
 
-    if let Some(params) = &step.parameters {
-        for param_or_ref in params {
-            let p = resolve_parameter(param_or_ref, document)?;
-            if let Some(p) = p {
-                let val = eval_value(
-                    &p.value,
-                    &EvalContext {
-                        run_id,
-                        inputs,
-                        store,
-                        response: None,
-                    },
-                )
-                .await
-                .map_err(|e| format!("eval error: {e}"))?;
-
-                let s = value_to_string(&val);
-                match &p.r#in {
-                    Some(arazzo_core::types::ParameterLocation::Header) => {
-                        let (val, is_secret) =
-                            resolve_secret(secrets, &s, SecretPlacement::Header, true).await;
-                        headers.insert(p.name.clone(), val);
-                        if is_secret {
-                            secret_derived_headers.push(p.name.clone());
-                        }
-                    }
-                    Some(arazzo_core::types::ParameterLocation::Query) => {
-                        let allowed = secrets_policy.allow_secrets_in_url;
-                        let (val, _) =
-                            resolve_secret(secrets, &s, SecretPlacement::UrlQuery, allowed).await;
-                        query.push((p.name.clone(), val));
-                    }
-                    Some(arazzo_core::types::ParameterLocation::Path) => {
-                        let allowed = secrets_policy.allow_secrets_in_url;
-                        let (val, _) =
-                            resolve_secret(secrets, &s, SecretPlacement::UrlPath, allowed).await;
-                        path_params.insert(p.name.clone(), val);
-                    }
-                    Some(arazzo_core::types::ParameterLocation::Cookie) => {
-                        let (val, is_secret) =
-                            resolve_secret(secrets, &s, SecretPlacement::Header, true).await;
-                        headers
-                            .entry("Cookie".to_string())
-                            .and_modify(|c| {
-                                c.push_str("; ");
-                                c.push_str(&format!("{}={}", p.name, val));
-                            })
-                            .or_insert_with(|| format!("{}={}", p.name, val));
-                        if is_secret {
-                            secret_derived_headers.push("Cookie".to_string());
-                        }
-                    }
-                    None => {}
+    // Workflow `x-default-parameters` apply first; a step parameter for the same slot wins.
+    let defaults = workflow_default_parameters(workflow);
+    let mut step_params = Vec::new();
+    for param_or_ref in step.parameters.as_deref().unwrap_or_default() {
+        step_params.extend(resolve_parameter(param_or_ref, document)?);
+    }
+    let mut params = Vec::new();
+    for param_or_ref in &defaults {
+        if let Some(p) = resolve_parameter(param_or_ref, document)? {
+            if !step_params.iter().any(|sp| same_parameter(sp, p)) {
+                params.push(p);
+            }
+        }
+    }
+    params.extend(step_params);
+
+    for p in params {
+        let val = eval_value(
+            &p.value,
+            &EvalContext {
+                run_id,
+                inputs,
+                outputs,
+                response: None,
+                delimiters: &delimiters,
+            },
+        )
+        .await
+        .map_err(RequestBuildError::Eval)?;
+
+        let s = value_to_string(&val);
+        match &p.r#in {
+            Some(arazzo_core::types::ParameterLocation::Header) => {
+                let (val, is_secret) =
+                    resolve_secret(secrets, secrets_policy, &s, SecretPlacement::Header).await?;
+                resolved_params.push(ResolvedParameter::new(&p.name, "header", &val, is_secret));
+                headers.insert(p.name.clone(), val);
+                if is_secret {
+                    secret_derived_headers.push(p.name.clone());
+                }
+            }
+            Some(arazzo_core::types::ParameterLocation::Query) => {
+                let (val, is_secret) =
+                    resolve_secret(secrets, secrets_policy, &s, SecretPlacement::UrlQuery).await?;
+                resolved_params.push(ResolvedParameter::new(&p.name, "query", &val, is_secret));
+                query.push((p.name.clone(), val));
+                if is_secret {
+                    secret_query_params.push(p.name.clone());
+                }
+            }
+            Some(arazzo_core::types::ParameterLocation::Path) => {
+                let (val, is_secret) =
+                    resolve_secret(secrets, secrets_policy, &s, SecretPlacement::UrlPath).await?;
+                resolved_params.push(ResolvedParameter::new(&p.name, "path", &val, is_secret));
+                path_params.insert(p.name.clone(), val);
+            }
+            Some(arazzo_core::types::ParameterLocation::Cookie) => {
+                let (val, is_secret) =
+                    resolve_secret(secrets, secrets_policy, &s, SecretPlacement::Header).await?;
+                resolved_params.push(ResolvedParameter::new(&p.name, "cookie", &val, is_secret));
+                headers
+                    .entry("Cookie".to_string())
+                    .and_modify(|c| {
+                        c.push_str("; ");
+                        c.push_str(&format!("{}={}", p.name, val));
+                    })
+                    .or_insert_with(|| format!("{}={}", p.name, val));
+                if is_secret {
+                    secret_derived_headers.push("Cookie".to_string());
                 }
             }
+            None => {}
+        }
+    }
+
+    for (name, raw) in &secrets_policy.inject_headers {
+        if headers.keys().any(|h| h.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+        let (val, is_secret) =
+            resolve_secret(secrets, secrets_policy, raw, SecretPlacement::Header).await?;
+        headers.insert(name.clone(), val);
+        if is_secret {
+            secret_derived_headers.push(name.clone());
         }
     }
 
+    if let Some(auth) = &secrets_policy.auth {
+        apply_source_auth(
+            secrets,
+            secrets_policy,
+            auth,
+            &mut headers,
+            &mut query,
+            &mut secret_derived_headers,
+            &mut secret_query_params,
+        )
+        .await?;
+    }
+
+    if fill_parameter_defaults {
+        apply_parameter_defaults(resolved_op, &mut headers, &mut query, &mut path_params);
+    }
+
+    let idempotent = step.extensions.get(IDEMPOTENT_EXTENSION) == Some(&JsonValue::Bool(true));
+    if idempotent
+        && !headers
+            .keys()
+            .any(|h| h.eq_ignore_ascii_case(IDEMPOTENCY_KEY_HEADER))
+    {
+        let key = Uuid::new_v5(&run_id, step.step_id.as_bytes());
+        headers.insert(IDEMPOTENCY_KEY_HEADER.to_string(), key.to_string());
+    }
+
     fn resolve_parameter<'a>(
         param_or_ref: &'a ParameterOrReusable,
         document: Option<&'a ArazzoDocument>,
-    ) -> Result<Option<&'a Parameter>, String> {
+    ) -> Result<Option<&'a Parameter>, RequestBuildError> {
         match param_or_ref {
             ParameterOrReusable::Parameter(p) => Ok(Some(p)),
             ParameterOrReusable::Reusable(r) => {
                 // Parse reference like $components.parameters.authHeader
                 let ref_str = r.reference.trim();
                 if let Some(name) = ref_str.strip_prefix("$components.parameters.") {
+                    let unresolved = |reason: String| {
+                        RequestBuildError::UnresolvedParameterRef(format!("{ref_str} ({reason})"))
+                    };
                     let doc = document.ok_or_else(|| {
-                        "document required to resolve component references".to_string()
+                        unresolved("document required to resolve component references".to_string())
                     })?;
-                    let components = doc.components.as_ref().ok_or_else(|| {
-                        format!("no components defined for reference {}", ref_str)
+                    let components = doc
+                        .components
+                        .as_ref()
+                        .ok_or_else(|| unresolved("no components defined".to_string()))?;
+                    let params = components
+                        .parameters
+                        .as_ref()
+                        .ok_or_else(|| unresolved("no parameters in components".to_string()))?;
+                    let param = params.get(name).ok_or_else(|| {
+                        unresolved(format!("parameter {name} not found in components"))
                     })?;
-                    let params = components.parameters.as_ref().ok_or_else(|| {
-                        format!("no parameters in components for reference {}", ref_str)
-                    })?;
-                    let param = params
-                        .get(name)
-                        .ok_or_else(|| format!("parameter {} not found in components", name))?;
                     Ok(Some(param))
                 } else {
-                    Err(format!("unsupported parameter reference: {}", ref_str))
+                    Err(RequestBuildError::UnresolvedParameterRef(format!(
+                        "{ref_str} (unsupported reference)"
+                    )))
                 }
             }
         }
     }
 
-    let (body_bytes, body_contains_secrets) = if let Some(rb) = &step.request_body {
+    let body_required = resolved_op.shape.request_body_required == Some(true);
+    let (body, body_secret_pointers) = if let Some(rb) = &step.request_body {
         if let Some(payload) = &rb.payload {
             let v = eval_value(
                 payload,
                 &EvalContext {
                     run_id,
                     inputs,
-                    store,
+                    outputs,
                     response: None,
+                    delimiters: &delimiters,
                 },
             )
             .await
-            .map_err(|e| format!("eval error: {e}"))?;
-            resolve_body_secrets(secrets, v).await?
+            .map_err(RequestBuildError::Eval)?;
+            if body_required && is_empty_payload(&v) {
+                return Err(RequestBuildError::EmptyRequiredBody);
+            }
+            let (v, pointers) = resolve_body_secrets(secrets, secrets_policy.strict, v).await?;
+            (Some((rb, v)), pointers)
+        } else if body_required {
+            return Err(RequestBuildError::EmptyRequiredBody);
         } else {
-            (Vec::new(), false)
+            (None, Vec::new())
         }
     } else {
-        (Vec::new(), false)
+        (None, Vec::new())
+    };
+
+    let body_bytes = match body {
+        Some((rb, value)) => {
+            let offered = resolved_op.shape.request_body_content_types.as_deref();
+            let declared = headers
+                .iter()
+                .find(|(h, _)| h.eq_ignore_ascii_case("content-type"))
+                .map(|(_, v)| v.clone());
+            let content_type = declared.or_else(|| {
+                let negotiated = negotiate_content_type(rb, offered)?;
+                headers.insert("Content-Type".to_string(), negotiated.clone());
+                Some(negotiated)
+            });
+            encode_body(&value, content_type.as_deref())?
+        }
+        None => Vec::new(),
     };
 
     let url = build_url(
@@ -159,73 +343,299 @@ pub async fn build_request(
             body: body_bytes,
         },
         secret_derived_headers,
-        body_contains_secrets,
+        secret_query_params,
+        body_secret_pointers,
+        parameters: resolved_params,
     })
 }
 
 async fn resolve_body_secrets(
     secrets: &dyn SecretsProvider,
+    strict: bool,
     value: JsonValue,
-) -> Result<(Vec<u8>, bool), String> {
-    let (resolved, has_secrets) = resolve_json_secrets(secrets, value).await;
-    let bytes = serde_json::to_vec(&resolved)
-        .map_err(|e| format!("failed to serialize request body: {e}"))?;
-    Ok((bytes, has_secrets))
+) -> Result<(JsonValue, Vec<String>), RequestBuildError> {
+    let mut pointers = Vec::new();
+    let resolved =
+        resolve_json_secrets(secrets, strict, value, String::new(), &mut pointers).await?;
+    Ok((resolved, pointers))
+}
+
+/// Encode an evaluated payload as `content_type`. JSON types (and a missing type) are
+/// serialized as JSON; `application/x-www-form-urlencoded` takes an object of scalars
+/// (arrays repeat the key); any other type takes a string sent as-is, and `text/*`
+/// also accepts a number or boolean.
+fn encode_body(
+    value: &JsonValue,
+    content_type: Option<&str>,
+) -> Result<Vec<u8>, RequestBuildError> {
+    let Some(content_type) = content_type else {
+        return Ok(serde_json::to_vec(value)?);
+    };
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let unsupported = |reason: &str| RequestBuildError::UnsupportedBody {
+        content_type: content_type.to_string(),
+        reason: reason.to_string(),
+    };
+    if is_json_content_type(&media_type) {
+        return Ok(serde_json::to_vec(value)?);
+    }
+    let scalar = |v: &JsonValue| match v {
+        JsonValue::String(s) => Some(s.clone()),
+        JsonValue::Number(n) => Some(n.to_string()),
+        JsonValue::Bool(b) => Some(b.to_string()),
+        _ => None,
+    };
+    match value {
+        JsonValue::Object(fields) if media_type == "application/x-www-form-urlencoded" => {
+            let mut form = url::form_urlencoded::Serializer::new(String::new());
+            for (name, field) in fields {
+                match field {
+                    JsonValue::Null => {}
+                    JsonValue::Array(items) => {
+                        for item in items {
+                            let item = scalar(item).ok_or_else(|| {
+                                unsupported(&format!("field {name} has a nested value"))
+                            })?;
+                            form.append_pair(name, &item);
+                        }
+                    }
+                    _ => {
+                        let field = scalar(field).ok_or_else(|| {
+                            unsupported(&format!("field {name} has a nested value"))
+                        })?;
+                        form.append_pair(name, &field);
+                    }
+                }
+            }
+            Ok(form.finish().into_bytes())
+        }
+        _ if media_type == "application/x-www-form-urlencoded" => {
+            Err(unsupported("payload must be an object"))
+        }
+        JsonValue::String(s) => Ok(s.clone().into_bytes()),
+        JsonValue::Number(_) | JsonValue::Bool(_) if media_type.starts_with("text/") => {
+            Ok(scalar(value).unwrap_or_default().into_bytes())
+        }
+        _ => Err(unsupported("payload must be a string")),
+    }
 }
 
+/// Resolves secret refs in string leaves, recording the JSON pointer of each
+/// resolved leaf so the persisted request can redact exactly those values.
 async fn resolve_json_secrets(
     secrets: &dyn SecretsProvider,
+    strict: bool,
     value: JsonValue,
-) -> (JsonValue, bool) {
-    match value {
+    pointer: String,
+    secret_pointers: &mut Vec<String>,
+) -> Result<JsonValue, RequestBuildError> {
+    Ok(match value {
         JsonValue::String(s) => {
             if let Ok(r) = SecretRef::parse(&s) {
-                if let Ok(v) = secrets.get(&r).await {
-                    let resolved = String::from_utf8_lossy(v.expose_bytes()).to_string();
-                    return (JsonValue::String(resolved), true);
+                match secrets.get(&r).await {
+                    Ok(v) => {
+                        secret_pointers.push(pointer);
+                        let resolved = String::from_utf8_lossy(v.expose_bytes()).to_string();
+                        return Ok(JsonValue::String(resolved));
+                    }
+                    Err(e) if strict => return Err(unresolved_secret(&r, e.to_string())),
+                    Err(_) => {}
                 }
             }
-            (JsonValue::String(s), false)
+            JsonValue::String(s)
         }
         JsonValue::Array(arr) => {
             let mut out = Vec::with_capacity(arr.len());
-            let mut any_secret = false;
-            for v in arr {
-                let (resolved, has) = Box::pin(resolve_json_secrets(secrets, v)).await;
-                any_secret |= has;
-                out.push(resolved);
+            for (idx, v) in arr.into_iter().enumerate() {
+                let child = format!("{pointer}/{idx}");
+                out.push(
+                    Box::pin(resolve_json_secrets(
+                        secrets,
+                        strict,
+                        v,
+                        child,
+                        secret_pointers,
+                    ))
+                    .await?,
+                );
             }
-            (JsonValue::Array(out), any_secret)
+            JsonValue::Array(out)
         }
         JsonValue::Object(map) => {
             let mut out = serde_json::Map::new();
-            let mut any_secret = false;
             for (k, v) in map {
-                let (resolved, has) = Box::pin(resolve_json_secrets(secrets, v)).await;
-                any_secret |= has;
+                let child = format!("{pointer}/{}", k.replace('~', "~0").replace('/', "~1"));
+                let resolved = Box::pin(resolve_json_secrets(
+                    secrets,
+                    strict,
+                    v,
+                    child,
+                    secret_pointers,
+                ))
+                .await?;
                 out.insert(k, resolved);
             }
-            (JsonValue::Object(out), any_secret)
+            JsonValue::Object(out)
         }
-        other => (other, false),
+        other => other,
+    })
+}
+
+/// Adds the source's credentials. Auth headers and query keys are always reported as
+/// secret-derived so they are redacted from persisted requests, even when configured as
+/// literals.
+async fn apply_source_auth(
+    secrets: &dyn SecretsProvider,
+    policy: &SecretsPolicyForSource,
+    auth: &SourceAuth,
+    headers: &mut BTreeMap<String, String>,
+    query: &mut Vec<(String, String)>,
+    secret_derived_headers: &mut Vec<String>,
+    secret_query_params: &mut Vec<String>,
+) -> Result<(), RequestBuildError> {
+    use base64::Engine as _;
+
+    let header_name = match auth {
+        SourceAuth::ApiKey {
+            location: ApiKeyLocation::Query,
+            ..
+        } => None,
+        SourceAuth::ApiKey { name, .. } => Some(name.as_str()),
+        SourceAuth::Basic { .. } | SourceAuth::Bearer { .. } => Some("Authorization"),
+    };
+    if header_name.is_some_and(|n| headers.keys().any(|h| h.eq_ignore_ascii_case(n))) {
+        return Ok(());
     }
+
+    let (name, value) = match auth {
+        SourceAuth::Basic { username, password } => {
+            let (user, _) =
+                resolve_secret(secrets, policy, username, SecretPlacement::Header).await?;
+            let (pass, _) =
+                resolve_secret(secrets, policy, password, SecretPlacement::Header).await?;
+            let encoded =
+                base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+            ("Authorization".to_string(), format!("Basic {encoded}"))
+        }
+        SourceAuth::Bearer { token } => {
+            let (token, _) =
+                resolve_secret(secrets, policy, token, SecretPlacement::Header).await?;
+            ("Authorization".to_string(), format!("Bearer {token}"))
+        }
+        SourceAuth::ApiKey {
+            name,
+            value,
+            location: ApiKeyLocation::Query,
+        } => {
+            if !query.iter().any(|(k, _)| k == name) {
+                let (value, _) =
+                    resolve_secret(secrets, policy, value, SecretPlacement::UrlQuery).await?;
+                query.push((name.clone(), value));
+                secret_query_params.push(name.clone());
+            }
+            return Ok(());
+        }
+        SourceAuth::ApiKey {
+            name,
+            value,
+            location: ApiKeyLocation::Header,
+        } => {
+            let (value, _) =
+                resolve_secret(secrets, policy, value, SecretPlacement::Header).await?;
+            (name.clone(), value)
+        }
+    };
+    headers.insert(name.clone(), value);
+    secret_derived_headers.push(name);
+    Ok(())
 }
 
+/// Resolves `s` when it is a secret ref. Outside strict mode a ref that cannot be used
+/// is passed through unchanged; the flag reports whether the value came from a secret.
 async fn resolve_secret(
     secrets: &dyn SecretsProvider,
+    policy: &SecretsPolicyForSource,
     s: &str,
-    _placement: SecretPlacement,
-    allowed: bool,
-) -> (String, bool) {
-    if !allowed {
-        return (s.to_string(), false);
+    placement: SecretPlacement,
+) -> Result<(String, bool), RequestBuildError> {
+    let Ok(r) = SecretRef::parse(s) else {
+        return Ok((s.to_string(), false));
+    };
+    let in_url = matches!(
+        placement,
+        SecretPlacement::UrlPath | SecretPlacement::UrlQuery
+    );
+    if in_url && !policy.allow_secrets_in_url {
+        if policy.strict {
+            return Err(unresolved_secret(
+                &r,
+                "secrets are not allowed in the URL for this source",
+            ));
+        }
+        return Ok((s.to_string(), false));
+    }
+    match secrets.get(&r).await {
+        Ok(v) => Ok((String::from_utf8_lossy(v.expose_bytes()).to_string(), true)),
+        Err(e) if policy.strict => Err(unresolved_secret(&r, e.to_string())),
+        Err(_) => Ok((s.to_string(), false)),
+    }
+}
+
+fn unresolved_secret(r: &SecretRef, reason: impl Into<String>) -> RequestBuildError {
+    RequestBuildError::UnresolvedSecret {
+        secret_ref: r.as_uri_string(),
+        reason: reason.into(),
     }
-    if let Ok(r) = SecretRef::parse(s) {
-        if let Ok(v) = secrets.get(&r).await {
-            return (String::from_utf8_lossy(v.expose_bytes()).to_string(), true);
+}
+
+/// Adds OpenAPI defaults for parameters nothing else set: the step, the workflow's
+/// default parameters, policy headers and source auth all take precedence.
+fn apply_parameter_defaults(
+    resolved_op: &crate::openapi::ResolvedOperation,
+    headers: &mut BTreeMap<String, String>,
+    query: &mut Vec<(String, String)>,
+    path_params: &mut BTreeMap<String, String>,
+) {
+    for p in &resolved_op.shape.parameters {
+        let Some(default) = &p.default else {
+            continue;
+        };
+        let value = value_to_string(default);
+        match p.location {
+            OpenApiParamLocation::Query => {
+                if !query.iter().any(|(k, _)| k == &p.name) {
+                    query.push((p.name.clone(), value));
+                }
+            }
+            OpenApiParamLocation::Header => {
+                if !headers.keys().any(|k| k.eq_ignore_ascii_case(&p.name)) {
+                    headers.insert(p.name.clone(), value);
+                }
+            }
+            OpenApiParamLocation::Path => {
+                path_params.entry(p.name.clone()).or_insert(value);
+            }
+            OpenApiParamLocation::Cookie => {
+                let pair = format!("{}={}", p.name, value);
+                let prefix = format!("{}=", p.name);
+                match headers.get_mut("Cookie") {
+                    Some(c) if c.split("; ").any(|kv| kv.starts_with(&prefix)) => {}
+                    Some(c) => {
+                        c.push_str("; ");
+                        c.push_str(&pair);
+                    }
+                    None => {
+                        headers.insert("Cookie".to_string(), pair);
+                    }
+                }
+            }
         }
     }
-    (s.to_string(), false)
 }
 
 fn value_to_string(v: &JsonValue) -> String {
@@ -243,15 +653,15 @@ fn build_url(
     path_template: &str,
     path_params: &BTreeMap<String, String>,
     query: &[(String, String)],
-) -> Result<url::Url, String> {
+) -> Result<url::Url, RequestBuildError> {
     if base_url.is_empty() {
-        return Err("missing OpenAPI server base_url".to_string());
+        return Err(RequestBuildError::MissingBaseUrl);
     }
     let mut path = path_template.to_string();
     for (k, v) in path_params {
         path = path.replace(&format!("{{{k}}}"), &urlencoding::encode(v));
     }
-    let mut url = url::Url::parse(base_url).map_err(|e| e.to_string())?;
+    let mut url = url::Url::parse(base_url)?;
     url.set_path(&path);
     {
         let mut qp = url.query_pairs_mut();
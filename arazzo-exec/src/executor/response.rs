@@ -1,21 +1,170 @@
-use arazzo_core::types::Step;
+use std::collections::BTreeMap;
+
+use arazzo_core::expressions::{
+    parse_runtime_expr, RuntimeExpr, Source, TemplateDelimiters, HEADER_VALUE_SEPARATOR,
+};
+use arazzo_core::types::{Criterion, Step};
 use serde_json::Value as JsonValue;
 use uuid::Uuid;
 
-use crate::executor::criteria;
+use crate::executor::criteria::{self, CriteriaScope};
 use crate::executor::eval::{eval_value, EvalContext, ResponseContext};
 use crate::policy::{HttpResponseParts, ResponseGateResult};
+use crate::retry::get_header_ci;
+
+/// The response headers as [`ResponseContext::headers`] takes them: a repeated header
+/// keeps its values apart so `$response.header.<name>#all` can list them.
+pub fn eval_headers(resp: &HttpResponseParts) -> BTreeMap<String, String> {
+    let mut headers = resp.headers.clone();
+    for (name, values) in &resp.repeated_headers {
+        let joined = values.join(&HEADER_VALUE_SEPARATOR.to_string());
+        headers.insert(name.clone(), joined);
+    }
+    headers
+}
 
+/// Parse the body as JSON when its `Content-Type` is JSON (`application/json` or a
+/// `+json` type). A response without `Content-Type` is parsed if it happens to be JSON.
 pub fn parse_body_json(resp: &HttpResponseParts) -> Option<JsonValue> {
+    if let Some(content_type) = get_header_ci(&resp.headers, "content-type") {
+        if !is_json_content_type(content_type) {
+            return None;
+        }
+    }
     let s = std::str::from_utf8(&resp.body).ok()?;
+    if s.trim().is_empty() {
+        return None;
+    }
     serde_json::from_str(s).ok()
 }
 
-pub fn evaluate_success(step: &Step, resp: &ResponseContext<'_>) -> bool {
+/// `application/json` or a structured `+json` type, ignoring parameters like `charset`.
+pub fn is_json_content_type(content_type: &str) -> bool {
+    let media_type = content_type
+        .split([';', ','])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    media_type == "application/json" || media_type.ends_with("+json")
+}
+
+/// Whether a response to `method` with `status` has a body (RFC 9110 6.4.1): never for
+/// HEAD, 1xx, 204 or 304, nor for a 2xx to CONNECT, whatever the server puts on the
+/// wire. Any other response, e.g. a 200 to OPTIONS without `Content-Length`, has the
+/// body that was read.
+pub fn response_has_body(method: &str, status: u16) -> bool {
+    if method.eq_ignore_ascii_case("HEAD") || (100..200).contains(&status) {
+        return false;
+    }
+    if status == 204 || status == 304 {
+        return false;
+    }
+    !(method.eq_ignore_ascii_case("CONNECT") && (200..300).contains(&status))
+}
+
+/// Step extension holding alternative criteria groups: `[[criterion, ...], ...]`.
+pub const SUCCESS_ANY_OF_EXTENSION: &str = "x-success-any-of";
+
+/// Without `successCriteria`, a response succeeds when its status is one of the
+/// operation's documented 2xx codes (`success_statuses`), or any 2xx if none are documented.
+///
+/// When the step carries [`SUCCESS_ANY_OF_EXTENSION`], the response must fully pass at
+/// least one of its groups, on top of any `successCriteria`.
+pub fn evaluate_success(step: &Step, success_statuses: &[u16], resp: &ResponseContext<'_>) -> bool {
+    evaluate_success_with(step, success_statuses, resp, &CriteriaScope::default())
+}
+
+/// [`evaluate_success`] with `$inputs`/`$steps` references in criteria resolved from `scope`.
+pub fn evaluate_success_with(
+    step: &Step,
+    success_statuses: &[u16],
+    resp: &ResponseContext<'_>,
+    scope: &CriteriaScope,
+) -> bool {
+    if let Some(groups) = success_any_of(step) {
+        let base = step.success_criteria.as_deref().unwrap_or_default();
+        return (base.is_empty() || criteria::evaluate_success(base, resp, scope))
+            && criteria::evaluate_any_of(&groups, resp, scope);
+    }
     let Some(ref crit) = step.success_criteria else {
-        return (200..300).contains(&resp.status);
+        if success_statuses.is_empty() {
+            return (200..300).contains(&resp.status);
+        }
+        return success_statuses.contains(&resp.status);
     };
-    criteria::evaluate_success(crit, resp)
+    criteria::evaluate_success(crit, resp, scope)
+}
+
+/// [`evaluate_success`] for criteria that may reference `$inputs` or earlier steps'
+/// outputs; referenced outputs are loaded from `store` first.
+pub async fn evaluate_success_async(
+    store: &dyn arazzo_store::StateStore,
+    run_id: Uuid,
+    inputs: &JsonValue,
+    step: &Step,
+    success_statuses: &[u16],
+    resp: &ResponseContext<'_>,
+) -> bool {
+    let scope = criteria_scope(store, run_id, inputs, step).await;
+    evaluate_success_with(step, success_statuses, resp, &scope)
+}
+
+/// Build the [`CriteriaScope`] for `step`: the run inputs plus the stored outputs of every
+/// step its criteria reference. Steps whose outputs cannot be loaded are left out.
+pub async fn criteria_scope(
+    store: &dyn arazzo_store::StateStore,
+    run_id: Uuid,
+    inputs: &JsonValue,
+    step: &Step,
+) -> CriteriaScope {
+    let any_of = success_any_of(step).unwrap_or_default();
+    let all = step
+        .success_criteria
+        .iter()
+        .flatten()
+        .chain(any_of.iter().flatten());
+    let mut step_outputs = std::collections::BTreeMap::new();
+    for id in criteria::referenced_steps(all) {
+        if let Ok(outputs) = store.get_step_outputs(run_id, &id).await {
+            step_outputs.insert(id, outputs);
+        }
+    }
+    CriteriaScope {
+        inputs: inputs.clone(),
+        step_outputs,
+    }
+}
+
+fn success_any_of(step: &Step) -> Option<Vec<Vec<Criterion>>> {
+    let raw = step.extensions.get(SUCCESS_ANY_OF_EXTENSION)?;
+    serde_json::from_value(raw.clone()).ok()
+}
+
+/// Step extension: when `true`, a `$response.body` output whose body is not JSON is
+/// captured as the body's UTF-8 text instead of `null`.
+pub const BODY_TEXT_FALLBACK_EXTENSION: &str = "x-body-text-fallback";
+
+fn body_text_fallback(step: &Step, expr: &str, resp: &ResponseContext<'_>) -> Option<JsonValue> {
+    if step.extensions.get(BODY_TEXT_FALLBACK_EXTENSION) != Some(&JsonValue::Bool(true))
+        || resp.body_json.is_some()
+        || resp.body.is_empty()
+    {
+        return None;
+    }
+    match parse_runtime_expr(expr) {
+        Ok(RuntimeExpr::Response(Source::Body { pointer: None })) => std::str::from_utf8(resp.body)
+            .ok()
+            .map(|s| JsonValue::String(s.to_string())),
+        _ => None,
+    }
+}
+
+/// An output that could not be computed and was recorded as `null`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedOutput {
+    pub name: String,
+    pub reason: String,
 }
 
 pub async fn compute_outputs(
@@ -24,23 +173,73 @@ pub async fn compute_outputs(
     inputs: &JsonValue,
     step: &Step,
     resp: &ResponseContext<'_>,
+    delimiters: &TemplateDelimiters,
 ) -> JsonValue {
+    compute_outputs_detailed(store, run_id, inputs, step, resp, delimiters)
+        .await
+        .0
+}
+
+/// Like [`compute_outputs`], also reporting each output that resolved to `null` because
+/// its expression failed or its body pointer matched nothing. Other outputs are unaffected.
+pub async fn compute_outputs_detailed(
+    store: &dyn arazzo_store::StateStore,
+    run_id: Uuid,
+    inputs: &JsonValue,
+    step: &Step,
+    resp: &ResponseContext<'_>,
+    delimiters: &TemplateDelimiters,
+) -> (JsonValue, Vec<UnresolvedOutput>) {
     let mut map = serde_json::Map::new();
+    let mut unresolved = Vec::new();
     if let Some(outputs) = &step.outputs {
         for (k, expr) in outputs {
+            if let Some(text) = body_text_fallback(step, expr, resp) {
+                map.insert(k.clone(), text);
+                continue;
+            }
             let ctx = EvalContext {
                 run_id,
                 inputs,
-                store,
+                outputs: &store,
                 response: Some(resp.clone()),
+                delimiters,
+            };
+            let v = match eval_value(&JsonValue::String(expr.clone()), &ctx).await {
+                Ok(v) => {
+                    if let Some(reason) = missing_body_pointer(expr, resp) {
+                        unresolved.push(UnresolvedOutput {
+                            name: k.clone(),
+                            reason,
+                        });
+                    }
+                    v
+                }
+                Err(reason) => {
+                    unresolved.push(UnresolvedOutput {
+                        name: k.clone(),
+                        reason,
+                    });
+                    JsonValue::Null
+                }
             };
-            let v = eval_value(&JsonValue::String(expr.clone()), &ctx)
-                .await
-                .unwrap_or(JsonValue::Null);
             map.insert(k.clone(), v);
         }
     }
-    JsonValue::Object(map)
+    (JsonValue::Object(map), unresolved)
+}
+
+/// `$response.body#/ptr` evaluates to `null` when the pointer is absent; tell that apart
+/// from a body that really holds `null` there.
+fn missing_body_pointer(expr: &str, resp: &ResponseContext<'_>) -> Option<String> {
+    let Ok(RuntimeExpr::Response(Source::Body { pointer: Some(ptr) })) = parse_runtime_expr(expr)
+    else {
+        return None;
+    };
+    let json = resp.body_json.as_ref()?;
+    json.pointer(ptr.as_str())
+        .is_none()
+        .then(|| format!("JSON pointer '{}' not found in response body", ptr.as_str()))
 }
 
 pub fn request_to_json(r: &crate::policy::RequestGateResult) -> JsonValue {
@@ -3,6 +3,8 @@ pub struct ExecutionResult {
     pub succeeded_steps: usize,
     pub failed_steps: usize,
     pub retries_scheduled: usize,
+    /// The run was canceled; steps that had not started were skipped.
+    pub canceled: bool,
 }
 
 impl ExecutionResult {
@@ -31,4 +33,6 @@ pub enum ExecutionError {
     MissingOperation(String),
     #[error("task join error: {0}")]
     TaskJoin(String),
+    #[error("missing inputs: {}", .0.join(", "))]
+    MissingInputs(Vec<String>),
 }
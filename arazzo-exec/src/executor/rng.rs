@@ -0,0 +1,25 @@
+use std::sync::{Arc, Mutex};
+
+/// Where the executor draws random numbers from (currently retry jitter).
+///
+/// The default uses fastrand's thread-local generator. [`RngSource::seeded`] shares one
+/// generator between clones, so a run's sequence of draws depends only on the seed.
+#[derive(Debug, Clone, Default)]
+pub struct RngSource {
+    seeded: Option<Arc<Mutex<fastrand::Rng>>>,
+}
+
+impl RngSource {
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seeded: Some(Arc::new(Mutex::new(fastrand::Rng::with_seed(seed)))),
+        }
+    }
+
+    pub fn next_u64(&self) -> u64 {
+        match &self.seeded {
+            Some(rng) => rng.lock().unwrap_or_else(|e| e.into_inner()).u64(..),
+            None => fastrand::u64(..),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arazzo_store::{RunStatus, StateStore, StoreError, WorkflowRun};
+use serde_json::json;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::compile::Compiler;
+use crate::executor::launch::{compile_for_run, run_document, DocumentOverrides};
+use crate::executor::scheduler::Executor;
+use crate::openapi::DiagnosticSeverity;
+
+/// Claims queued runs from the store and executes them, at most
+/// `max_concurrent_runs` at a time. This is the loop of a long-running worker process;
+/// several workers may share a store.
+#[derive(Clone)]
+pub struct RunWorker {
+    store: Arc<dyn StateStore>,
+    executor: Arc<Executor>,
+    compiler: Arc<Compiler>,
+    max_concurrent_runs: usize,
+    poll_interval: Duration,
+}
+
+impl RunWorker {
+    pub fn new(store: Arc<dyn StateStore>, executor: Arc<Executor>, compiler: Compiler) -> Self {
+        Self {
+            store,
+            executor,
+            compiler: Arc::new(compiler),
+            max_concurrent_runs: 4,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Upper bound on runs executing at once; at least 1.
+    pub fn with_max_concurrent_runs(mut self, max_concurrent_runs: usize) -> Self {
+        self.max_concurrent_runs = max_concurrent_runs.max(1);
+        self
+    }
+
+    /// How long to wait before asking for a queued run again after finding none.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Claim and execute runs until `shutdown` completes, then wait for the runs in
+    /// flight. Returns how many runs were claimed. Failed claims are logged and retried
+    /// after the poll interval; only a store that cannot claim runs at all is an error.
+    pub async fn run_until(&self, shutdown: impl Future<Output = ()>) -> Result<usize, StoreError> {
+        self.claim_loop(shutdown, false).await
+    }
+
+    /// Claim and execute runs until none is queued, then wait for them to finish.
+    /// Returns how many runs were claimed.
+    pub async fn drain(&self) -> Result<usize, StoreError> {
+        self.claim_loop(std::future::pending(), true).await
+    }
+
+    async fn claim_loop(
+        &self,
+        shutdown: impl Future<Output = ()>,
+        until_idle: bool,
+    ) -> Result<usize, StoreError> {
+        tokio::pin!(shutdown);
+        let permits = Arc::new(Semaphore::new(self.max_concurrent_runs));
+        let mut running = JoinSet::new();
+        let mut claimed = 0;
+
+        let outcome = loop {
+            let permit = tokio::select! {
+                _ = &mut shutdown => break Ok(claimed),
+                permit = permits.clone().acquire_owned() => {
+                    permit.expect("run permits are never closed")
+                }
+            };
+            while running.try_join_next().is_some() {}
+
+            match self.store.claim_queued_run().await {
+                Ok(Some(run)) => {
+                    claimed += 1;
+                    let worker = self.clone();
+                    running.spawn(async move {
+                        // Held until the run finishes, releasing its slot.
+                        let _permit = permit;
+                        worker.execute(run).await;
+                    });
+                }
+                Ok(None) if until_idle => break Ok(claimed),
+                Ok(None) => {
+                    drop(permit);
+                    tokio::select! {
+                        _ = &mut shutdown => break Ok(claimed),
+                        _ = tokio::time::sleep(self.poll_interval) => {}
+                    }
+                }
+                // Never succeeds; retrying would only spin.
+                Err(e @ StoreError::Unsupported(_)) => break Err(e),
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to claim a queued run; retrying");
+                    drop(permit);
+                    tokio::select! {
+                        _ = &mut shutdown => break Ok(claimed),
+                        _ = tokio::time::sleep(self.poll_interval) => {}
+                    }
+                }
+            }
+        };
+
+        while running.join_next().await.is_some() {}
+        outcome
+    }
+
+    /// Execute a claimed run. A run that cannot be started (its document no longer
+    /// parses or compiles) is marked failed, so no worker claims it again.
+    async fn execute(&self, run: WorkflowRun) {
+        let failure = match self.prepare_and_execute(&run).await {
+            Ok(()) => return,
+            Err(failure) => failure,
+        };
+        let _ = self
+            .store
+            .mark_run_finished(run.id, RunStatus::Failed, Some(failure))
+            .await;
+    }
+
+    async fn prepare_and_execute(&self, run: &WorkflowRun) -> Result<(), serde_json::Value> {
+        let prepare_error = |message: String| json!({ "type": "prepare", "message": message });
+
+        let doc = self
+            .store
+            .get_workflow_doc(run.workflow_doc_id)
+            .await
+            .map_err(|e| prepare_error(e.to_string()))?
+            .ok_or_else(|| {
+                prepare_error(format!("workflow doc {} not found", run.workflow_doc_id))
+            })?;
+        let document = run_document(&doc, run).map_err(|e| prepare_error(e.to_string()))?;
+        let overrides = DocumentOverrides::of_run(run).map_err(|e| prepare_error(e.to_string()))?;
+        let workflow = document
+            .workflows
+            .iter()
+            .find(|w| w.workflow_id == run.workflow_id)
+            .ok_or_else(|| prepare_error(format!("workflow not found: {}", run.workflow_id)))?;
+
+        let compiled = compile_for_run(
+            &self.compiler,
+            &document,
+            workflow,
+            Some(&run.provenance),
+            &overrides,
+        )
+        .await;
+        let errors: Vec<&str> = compiled
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+            .map(|d| d.message.as_str())
+            .collect();
+        if !errors.is_empty() {
+            return Err(prepare_error(format!(
+                "OpenAPI compilation failed: {}",
+                errors.join("; ")
+            )));
+        }
+
+        self.executor
+            .execute_run(run.id, workflow, &compiled, &run.inputs, Some(&document))
+            .await
+            .map(|_| ())
+            .map_err(|e| json!({ "type": "execution", "message": e.to_string() }))
+    }
+}
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
+use arazzo_core::expressions::TemplateDelimiters;
 use arazzo_core::types::{ArazzoDocument, Workflow};
-use arazzo_store::{RunStatus, StateStore};
+use arazzo_store::{RunStatus, RunStepStatus, StateStore};
 use uuid::Uuid;
 
 use crate::compile::CompiledPlan;
-use crate::executor::concurrency::ConcurrencyLimits;
+use crate::executor::concurrency::{ConcurrencyLimits, SERIALIZE_GROUP_EXTENSION};
 use crate::executor::events::{Event, EventSink};
 use crate::executor::http::HttpClient;
 use crate::executor::result::{ExecutionError, ExecutionResult};
@@ -43,6 +44,12 @@ impl Executor {
         }
     }
 
+    /// Replace the event sink, e.g. to route one run's events somewhere else.
+    pub fn with_event_sink(mut self, event_sink: Arc<dyn EventSink>) -> Self {
+        self.event_sink = event_sink;
+        self
+    }
+
     pub async fn execute_run(
         &self,
         run_id: Uuid,
@@ -51,23 +58,60 @@ impl Executor {
         inputs: &serde_json::Value,
         document: Option<&ArazzoDocument>,
     ) -> Result<ExecutionResult, ExecutionError> {
-        let limits = ConcurrencyLimits::new(
+        // Steps only act on inline parameters and actions. A reference that does not
+        // resolve is left alone and reported when its step builds the request.
+        let dereferenced =
+            document.map(|d| arazzo_core::dereference_workflow_best_effort(d, workflow).0);
+        let workflow = dereferenced.as_ref().unwrap_or(workflow);
+
+        let limits = Arc::new(ConcurrencyLimits::new(
             self.config.global_concurrency,
             &self.config.per_source_concurrency,
-        );
+        ));
 
         self.emit_run_started(run_id, workflow).await;
+
+        // Fail before any request rather than let steps fail one by one on eval errors.
+        let delimiters = document
+            .map(|d| TemplateDelimiters::from_extensions(&d.extensions))
+            .unwrap_or_default();
+        let inputs = &arazzo_core::apply_input_defaults(workflow, inputs);
+        let missing = arazzo_core::missing_inputs(workflow, inputs, &delimiters);
+        if !missing.is_empty() {
+            return Err(self.fail_missing_inputs(run_id, missing).await);
+        }
+
         let _ = self.store.mark_run_started(run_id).await;
 
         let mut result = ExecutionResult::default();
         loop {
+            // Steps spawned in the previous cycle have all finished by now, so a cancel
+            // observed here only has to keep the remaining pending steps from starting.
+            if self.is_run_canceled(run_id).await? {
+                self.store
+                    .skip_pending_steps(run_id, serde_json::json!({ "type": "canceled" }))
+                    .await
+                    .map_err(ExecutionError::Store)?;
+                self.emit_run_finished(run_id, RunStatus::Canceled).await;
+                result.canceled = true;
+                break;
+            }
+
             let claimed = self.claim_steps(run_id).await?;
             if claimed.is_empty() {
                 if self.is_run_complete(run_id).await? {
                     self.emit_run_finished(run_id, RunStatus::Succeeded).await;
                     break;
                 }
-                tokio::time::sleep(self.config.poll_interval).await;
+                // Stores that can't notify sleep here; a failed wait falls back to polling.
+                if self
+                    .store
+                    .wait_for_runnable(run_id, self.config.poll_interval)
+                    .await
+                    .is_err()
+                {
+                    tokio::time::sleep(self.config.poll_interval).await;
+                }
                 continue;
             }
 
@@ -91,6 +135,22 @@ impl Executor {
             .await;
     }
 
+    async fn fail_missing_inputs(
+        &self,
+        run_id: Uuid,
+        missing: std::collections::BTreeSet<String>,
+    ) -> ExecutionError {
+        let names: Vec<String> = missing.into_iter().collect();
+        let error = serde_json::json!({ "type": "missing_inputs", "inputs": names });
+        let _ = self
+            .store
+            .mark_run_finished(run_id, RunStatus::Failed, Some(error.clone()))
+            .await;
+        let _ = self.store.skip_pending_steps(run_id, error).await;
+        self.emit_run_finished(run_id, RunStatus::Failed).await;
+        ExecutionError::MissingInputs(names)
+    }
+
     async fn emit_run_finished(&self, run_id: Uuid, status: RunStatus) {
         self.event_sink
             .emit(Event::RunFinished { run_id, status })
@@ -101,12 +161,27 @@ impl Executor {
         &self,
         run_id: Uuid,
     ) -> Result<Vec<arazzo_store::RunStep>, ExecutionError> {
+        // Claim no more of a source's steps than its permits allow, so the rest stay
+        // pending instead of sitting in `running` while they wait for a permit.
         self.store
-            .claim_runnable_steps(run_id, self.config.global_concurrency as i64)
+            .claim_runnable_steps_within(
+                run_id,
+                self.config.global_concurrency as i64,
+                &self.config.per_source_concurrency,
+            )
             .await
             .map_err(ExecutionError::Store)
     }
 
+    async fn is_run_canceled(&self, run_id: Uuid) -> Result<bool, ExecutionError> {
+        let status = self
+            .store
+            .check_run_status(run_id)
+            .await
+            .map_err(ExecutionError::Store)?;
+        Ok(status == RunStatus::Canceled)
+    }
+
     async fn is_run_complete(&self, run_id: Uuid) -> Result<bool, ExecutionError> {
         let runnable = self
             .store
@@ -126,13 +201,18 @@ impl Executor {
             return Ok(false);
         }
 
-        let all_terminal = all_steps
-            .iter()
-            .all(|s| matches!(s.status.as_str(), "succeeded" | "failed" | "skipped"));
+        let all_terminal = all_steps.iter().all(|s| {
+            s.status
+                .parse::<RunStepStatus>()
+                .is_ok_and(|status| status.is_terminal())
+        });
 
         if all_terminal {
             if let Ok(Some(run)) = self.store.get_run(run_id).await {
-                if matches!(run.status.as_str(), "pending" | "queued" | "running") {
+                if matches!(
+                    run.status.parse(),
+                    Ok(RunStatus::Queued | RunStatus::Running)
+                ) {
                     let _ = self
                         .store
                         .mark_run_finished(run_id, RunStatus::Succeeded, None)
@@ -153,7 +233,7 @@ impl Executor {
         workflow: &Workflow,
         compiled: &CompiledPlan,
         inputs: &serde_json::Value,
-        limits: &ConcurrencyLimits,
+        limits: &Arc<ConcurrencyLimits>,
         document: Option<&ArazzoDocument>,
     ) -> Result<Vec<(String, tokio::task::JoinHandle<StepResult>)>, ExecutionError> {
         let mut handles = Vec::new();
@@ -178,16 +258,22 @@ impl Executor {
                 .as_ref()
                 .ok_or_else(|| ExecutionError::MissingOperation(step_id.clone()))?;
 
-            let permit = limits.acquire(step_row.source_name.as_deref()).await;
+            let serialize_group = step
+                .extensions
+                .get(SERIALIZE_GROUP_EXTENSION)
+                .and_then(|v| v.as_str())
+                .map(String::from);
 
             let ctx = StepContext {
                 run_id,
                 step_row_id: step_row.id,
                 step_id: step_id.clone(),
+                first_attempt: step_row.next_run_at.is_none(),
                 source_name: step_row.source_name.clone(),
                 step: step.clone(),
                 workflow: workflow.clone(),
                 resolved_op: resolved_op.clone(),
+                fill_parameter_defaults: compiled_step.fill_parameter_defaults,
                 inputs: inputs.clone(),
                 document: document.cloned(),
             };
@@ -199,9 +285,19 @@ impl Executor {
                 policy_gate: self.policy_gate.clone(),
                 retry: self.config.retry.clone(),
                 event_sink: self.event_sink.clone(),
+                debug_requests: self.config.debug_requests,
+                rng: self.config.rng.clone(),
             };
 
-            let handle = tokio::spawn(async move { run_step(ctx, deps, permit).await });
+            // Wait for permits inside the task, so a step waiting on its serialize group
+            // does not hold up the spawning of the steps claimed after it.
+            let limits = limits.clone();
+            let handle = tokio::spawn(async move {
+                let permit = limits
+                    .acquire_in_group(ctx.source_name.as_deref(), serialize_group.as_deref())
+                    .await;
+                run_step(ctx, deps, permit).await
+            });
             handles.push((step_id, handle));
         }
 
@@ -0,0 +1,112 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use tokio::net::{ToSocketAddrs, UdpSocket};
+
+/// Sends step metrics to a StatsD agent over UDP. Tags use the DogStatsD `|#key:value`
+/// extension; plain StatsD servers ignore them.
+///
+/// Sending is fire-and-forget: a lost or undeliverable packet never affects the run.
+pub struct StatsdEmitter {
+    socket: UdpSocket,
+    prefix: String,
+    tags: Vec<(String, String)>,
+}
+
+impl StatsdEmitter {
+    /// Emitter sending to the agent at `addr` (usually `127.0.0.1:8125`). Each address
+    /// `addr` resolves to is tried in turn, from a socket of the same family.
+    pub async fn connect(addr: impl ToSocketAddrs) -> std::io::Result<Self> {
+        let mut last_err = None;
+        for target in tokio::net::lookup_host(addr).await? {
+            let local: SocketAddr = match target {
+                SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+                SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+            };
+            let socket = match UdpSocket::bind(local).await {
+                Ok(socket) => socket,
+                Err(e) => {
+                    last_err = Some(e);
+                    continue;
+                }
+            };
+            if let Err(e) = socket.connect(target).await {
+                last_err = Some(e);
+                continue;
+            }
+            return Ok(Self {
+                socket,
+                prefix: "arazzo".to_string(),
+                tags: Vec::new(),
+            });
+        }
+        Err(last_err.unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "statsd address resolved to nothing",
+            )
+        }))
+    }
+
+    /// Prepended to every metric name with a `.`; `arazzo` by default, empty for none.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// A tag sent with every metric, e.g. `env:prod`, next to `workflow_id` and `step_id`.
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// `step.succeeded` or `step.failed` counter, plus a `step.duration` timer when the
+    /// step's start was seen.
+    pub(crate) async fn step_completed(
+        &self,
+        workflow_id: &str,
+        step_id: &str,
+        succeeded: bool,
+        duration: Option<Duration>,
+    ) {
+        let tags = self.tags_for(workflow_id, step_id);
+        let outcome = if succeeded {
+            "step.succeeded"
+        } else {
+            "step.failed"
+        };
+        self.send(&format!("{}:1|c{tags}", self.name(outcome)))
+            .await;
+        if let Some(duration) = duration {
+            self.send(&format!(
+                "{}:{}|ms{tags}",
+                self.name("step.duration"),
+                duration.as_millis()
+            ))
+            .await;
+        }
+    }
+
+    fn name(&self, metric: &str) -> String {
+        if self.prefix.is_empty() {
+            metric.to_string()
+        } else {
+            format!("{}.{metric}", self.prefix)
+        }
+    }
+
+    fn tags_for(&self, workflow_id: &str, step_id: &str) -> String {
+        let tags: Vec<String> = self
+            .tags
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .chain([("workflow_id", workflow_id), ("step_id", step_id)])
+            .map(|(k, v)| format!("{k}:{v}"))
+            .collect();
+        format!("|#{}", tags.join(","))
+    }
+
+    async fn send(&self, packet: &str) {
+        let _ = self.socket.send(packet.as_bytes()).await;
+    }
+}
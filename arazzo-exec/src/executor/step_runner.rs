@@ -7,6 +7,7 @@ use uuid::Uuid;
 use crate::executor::concurrency::ConcurrencyPermit;
 use crate::executor::events::{Event, EventSink};
 use crate::executor::http::HttpClient;
+use crate::executor::rng::RngSource;
 use crate::executor::worker::{execute_step_attempt, StepResult, Worker};
 use crate::openapi::ResolvedOperation;
 use crate::policy::PolicyGate;
@@ -17,10 +18,13 @@ pub struct StepContext {
     pub run_id: Uuid,
     pub step_row_id: Uuid,
     pub step_id: String,
+    /// False when the step was re-claimed after a scheduled retry.
+    pub first_attempt: bool,
     pub source_name: Option<String>,
     pub step: Step,
     pub workflow: Workflow,
     pub resolved_op: ResolvedOperation,
+    pub fill_parameter_defaults: bool,
     pub inputs: serde_json::Value,
     pub document: Option<ArazzoDocument>,
 }
@@ -32,15 +36,19 @@ pub struct StepDeps {
     pub policy_gate: Arc<PolicyGate>,
     pub retry: RetryConfig,
     pub event_sink: Arc<dyn EventSink>,
+    pub debug_requests: bool,
+    pub rng: RngSource,
 }
 
 pub async fn run_step(ctx: StepContext, deps: StepDeps, _permit: ConcurrencyPermit) -> StepResult {
-    deps.event_sink
-        .emit(Event::StepStarted {
-            run_id: ctx.run_id,
-            step_id: ctx.step_id.clone(),
-        })
-        .await;
+    if ctx.first_attempt {
+        deps.event_sink
+            .emit(Event::StepStarted {
+                run_id: ctx.run_id,
+                step_id: ctx.step_id.clone(),
+            })
+            .await;
+    }
 
     let worker = Worker {
         store: deps.store.as_ref(),
@@ -48,7 +56,10 @@ pub async fn run_step(ctx: StepContext, deps: StepDeps, _permit: ConcurrencyPerm
         secrets: deps.secrets.as_ref(),
         policy_gate: deps.policy_gate.as_ref(),
         retry: &deps.retry,
+        rng: &deps.rng,
         event_sink: deps.event_sink.as_ref(),
+        debug_requests: deps.debug_requests,
+        fill_parameter_defaults: ctx.fill_parameter_defaults,
     };
 
     let result = execute_step_attempt(
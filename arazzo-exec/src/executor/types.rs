@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::time::Duration;
 
+use crate::executor::rng::RngSource;
 use crate::policy::PolicyConfig;
 use crate::retry::RetryConfig;
 
@@ -11,6 +12,11 @@ pub struct ExecutorConfig {
     pub poll_interval: Duration,
     pub policy: PolicyConfig,
     pub retry: RetryConfig,
+    /// Emit [`Event::StepDebug`](crate::executor::Event) with each attempt's resolved
+    /// parameters and (redacted) body.
+    pub debug_requests: bool,
+    /// Randomness for retry jitter; seed it for reproducible runs.
+    pub rng: RngSource,
 }
 
 impl Default for ExecutorConfig {
@@ -21,6 +27,8 @@ impl Default for ExecutorConfig {
             poll_interval: Duration::from_millis(200),
             policy: PolicyConfig::default(),
             retry: RetryConfig::default(),
+            debug_requests: false,
+            rng: RngSource::default(),
         }
     }
 }
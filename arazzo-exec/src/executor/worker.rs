@@ -1,19 +1,26 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
+use arazzo_core::expressions::TemplateDelimiters;
 use arazzo_core::types::{ArazzoDocument, Step, Workflow};
 use arazzo_store::{AttemptStatus, StateStore};
 use serde_json::json;
 use uuid::Uuid;
 
-use crate::executor::eval::ResponseContext;
+use crate::executor::eval::{RequestContext, ResponseContext};
 use crate::executor::failure::{decide_failure, decide_network_failure};
-use crate::executor::http::HttpClient;
-use crate::executor::request::{build_request, SecretsPolicyForSource};
+use crate::executor::http::{HttpClient, HttpError};
+use crate::executor::request::{build_request, ResolvedParameter, SecretsPolicyForSource};
 use crate::executor::response::{
-    compute_outputs, evaluate_success, parse_body_json, request_to_json, response_to_json,
+    compute_outputs_detailed, eval_headers, evaluate_success_async, parse_body_json,
+    request_to_json, response_has_body, response_to_json,
 };
-use crate::policy::{PolicyGate, PolicyOverrides};
-use crate::retry::RetryConfig;
+use crate::executor::rng::RngSource;
+use crate::policy::{
+    HttpRequestParts, HttpResponseParts, PolicyGate, PolicyGateError, PolicyOverrides,
+    RedirectAction, RedirectPolicy,
+};
+use crate::retry::{get_header_ci, RetryConfig};
 use crate::secrets::SecretsProvider;
 
 #[derive(Debug)]
@@ -37,7 +44,14 @@ pub struct Worker<'a> {
     pub secrets: &'a dyn SecretsProvider,
     pub policy_gate: &'a PolicyGate,
     pub retry: &'a RetryConfig,
+    /// Source of retry jitter.
+    pub rng: &'a RngSource,
     pub event_sink: &'a dyn crate::executor::EventSink,
+    /// Emit a [`crate::executor::Event::StepDebug`] before each request is sent.
+    pub debug_requests: bool,
+    /// Send OpenAPI defaults for optional parameters the step does not set, as
+    /// [`crate::compile::CompiledStep::fill_parameter_defaults`] records.
+    pub fill_parameter_defaults: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -47,7 +61,7 @@ pub async fn execute_step_attempt(
     source_name: &str,
     step_row_id: Uuid,
     step: &Step,
-    _workflow: &Workflow,
+    workflow: &Workflow,
     resolved_op: &crate::openapi::ResolvedOperation,
     inputs: &serde_json::Value,
     document: Option<&ArazzoDocument>,
@@ -57,45 +71,91 @@ pub async fn execute_step_attempt(
         .effective_for_source(source_name, &PolicyOverrides::default());
     let secrets_policy = SecretsPolicyForSource {
         allow_secrets_in_url: eff_policy.allow_secrets_in_url,
+        strict: eff_policy.strict_secrets,
+        inject_headers: eff_policy.inject_headers.clone(),
+        auth: eff_policy.auth.clone(),
     };
 
     let req_result = build_request(
-        worker.store,
+        &worker.store,
         worker.secrets,
         &secrets_policy,
         run_id,
+        workflow,
         step,
         resolved_op,
+        worker.fill_parameter_defaults,
         inputs,
         document,
     )
     .await;
 
-    let (req_parts, secret_derived_headers, body_contains_secrets) = match req_result {
-        Ok(r) => (r.parts, r.secret_derived_headers, r.body_contains_secrets),
-        Err(e) => {
-            return StepResult::Failed {
-                error: json!({"type":"build","message":e}),
-                end_run: true,
+    let (req_parts, secret_derived_headers, secret_query_params, body_secret_pointers, parameters) =
+        match req_result {
+            Ok(r) => (
+                r.parts,
+                r.secret_derived_headers,
+                r.secret_query_params,
+                r.body_secret_pointers,
+                r.parameters,
+            ),
+            Err(e) => {
+                return StepResult::Failed {
+                    error: json!({"type":"build","kind":e.kind(),"message":e.to_string()}),
+                    end_run: true,
+                }
             }
-        }
-    };
+        };
 
     let request_sanitized = match worker.policy_gate.apply_request(
         source_name,
         &req_parts,
         &secret_derived_headers,
-        body_contains_secrets,
+        &secret_query_params,
+        &body_secret_pointers,
     ) {
         Ok(s) => s,
         Err(e) => {
+            emit_policy_denied(worker.event_sink, run_id, &step.step_id, &e).await;
             return StepResult::Failed {
-                error: json!({"type":"policy","message":e.to_string()}),
+                error: json!({"type":"policy","kind":e.kind().as_str(),"message":e.to_string()}),
                 end_run: true,
-            }
+            };
         }
     };
 
+    let request_query = parameter_values(&parameters, "query");
+    let request_path = parameter_values(&parameters, "path");
+    if worker.debug_requests {
+        // Literal values of headers the sanitized request redacts (e.g. a hardcoded
+        // `Authorization`) must not show up here either; cookies travel in `Cookie`.
+        let sensitive = &eff_policy.sensitive_headers;
+        let parameters = parameters
+            .into_iter()
+            .map(|mut p| {
+                let header = match p.location {
+                    "header" => Some(p.name.as_str()),
+                    "cookie" => Some("cookie"),
+                    _ => None,
+                };
+                if header.is_some_and(|h| sensitive.contains(h)) {
+                    p.value = "<redacted>".to_string();
+                }
+                p
+            })
+            .collect();
+        worker
+            .event_sink
+            .emit(crate::executor::Event::StepDebug {
+                run_id,
+                step_id: step.step_id.clone(),
+                parameters,
+                body: (!request_sanitized.body.bytes.is_empty())
+                    .then(|| String::from_utf8_lossy(&request_sanitized.body.bytes).into_owned()),
+            })
+            .await;
+    }
+
     let request_json = request_to_json(&request_sanitized);
     let (attempt_id, attempt_no) = match worker
         .store
@@ -120,51 +180,134 @@ pub async fn execute_step_attempt(
         })
         .await;
 
-    let timeout = Duration::from_secs(30);
+    let timeout = eff_policy.limits.request.timeout;
     let max_response_bytes = 4 * 1024 * 1024;
 
-    let sent = worker
-        .http
-        .send(req_parts, timeout, max_response_bytes)
-        .await;
+    let sent = send_following_redirects(
+        worker,
+        source_name,
+        &eff_policy.network.redirects,
+        req_parts,
+        &secret_derived_headers,
+        timeout,
+        max_response_bytes,
+    )
+    .await;
+    let sent = match sent {
+        Ok(resp) => Ok(resp),
+        Err(SendError::Http(e)) => Err(e),
+        Err(SendError::Policy(e)) => {
+            emit_policy_denied(worker.event_sink, run_id, &step.step_id, &e).await;
+            finish_attempt_failed(
+                worker.store,
+                worker.event_sink,
+                run_id,
+                &step.step_id,
+                attempt_id,
+                attempt_no,
+                &e.to_string(),
+            )
+            .await;
+            return StepResult::Failed {
+                error: json!({"type":"policy","kind":e.kind().as_str(),"message":e.to_string()}),
+                end_run: true,
+            };
+        }
+    };
 
     match sent {
         Ok(resp) => {
-            let resp_sanitized =
-                match worker
-                    .policy_gate
-                    .apply_response(source_name, &resp, &secret_derived_headers)
-                {
-                    Ok(s) => s,
-                    Err(e) => {
-                        finish_attempt_failed(
-                            worker.store,
-                            worker.event_sink,
-                            run_id,
-                            &step.step_id,
-                            attempt_id,
-                            attempt_no,
-                            &e.to_string(),
-                        )
-                        .await;
-                        return StepResult::Failed {
-                            error: json!({"type":"policy","message":e.to_string()}),
-                            end_run: true,
-                        };
-                    }
-                };
+            let resp_sanitized = match worker.policy_gate.apply_response(
+                source_name,
+                &resp,
+                &secret_derived_headers,
+            ) {
+                Ok(s) => s,
+                Err(e) => {
+                    emit_policy_denied(worker.event_sink, run_id, &step.step_id, &e).await;
+                    finish_attempt_failed(
+                        worker.store,
+                        worker.event_sink,
+                        run_id,
+                        &step.step_id,
+                        attempt_id,
+                        attempt_no,
+                        &e.to_string(),
+                    )
+                    .await;
+                    return StepResult::Failed {
+                        error: json!({"type":"policy","kind":e.kind().as_str(),"message":e.to_string()}),
+                        end_run: true,
+                    };
+                }
+            };
 
             let resp_json = response_to_json(&resp_sanitized);
-            let body_json = parse_body_json(&resp);
+            let (body, body_json): (&[u8], _) =
+                if response_has_body(&resolved_op.method, resp.status) {
+                    (&resp.body, parse_body_json(&resp))
+                } else {
+                    (&[], None)
+                };
+            let resp_headers = eval_headers(&resp);
             let resp_ctx = ResponseContext {
                 status: resp.status,
-                headers: &resp.headers,
-                body: &resp.body,
+                headers: &resp_headers,
+                body,
                 body_json,
+                request: Some(RequestContext {
+                    method: &request_sanitized.method,
+                    url: &request_sanitized.url,
+                    headers: &request_sanitized.headers.headers,
+                    query: &request_query,
+                    path: &request_path,
+                    body: &request_sanitized.body.bytes,
+                    body_json: serde_json::from_slice(&request_sanitized.body.bytes).ok(),
+                }),
             };
 
-            if evaluate_success(step, &resp_ctx) {
-                let outputs = compute_outputs(worker.store, run_id, inputs, step, &resp_ctx).await;
+            let mut success_statuses = resolved_op.shape.success_statuses.clone();
+            if eff_policy.network.redirects.action_for(resp.status) == Some(RedirectAction::Succeed)
+            {
+                if success_statuses.is_empty() {
+                    success_statuses.extend(200..300);
+                }
+                success_statuses.push(resp.status);
+            }
+
+            if evaluate_success_async(
+                worker.store,
+                run_id,
+                inputs,
+                step,
+                &success_statuses,
+                &resp_ctx,
+            )
+            .await
+            {
+                let delimiters = document
+                    .map(|d| TemplateDelimiters::from_extensions(&d.extensions))
+                    .unwrap_or_default();
+                let (outputs, unresolved) = compute_outputs_detailed(
+                    worker.store,
+                    run_id,
+                    inputs,
+                    step,
+                    &resp_ctx,
+                    &delimiters,
+                )
+                .await;
+                for missing in unresolved {
+                    worker
+                        .event_sink
+                        .emit(crate::executor::Event::OutputUnresolved {
+                            run_id,
+                            step_id: step.step_id.clone(),
+                            output: missing.name,
+                            reason: missing.reason,
+                        })
+                        .await;
+                }
                 let _ = worker
                     .store
                     .finish_attempt(
@@ -189,7 +332,7 @@ pub async fn execute_step_attempt(
                         None,
                     )
                     .await;
-                decide_failure(worker.retry, step, attempt_no as usize, &resp)
+                decide_failure(worker.retry, step, attempt_no as usize, &resp, worker.rng)
             }
         }
         Err(err) => {
@@ -213,8 +356,78 @@ pub async fn execute_step_attempt(
                     succeeded: false,
                 })
                 .await;
-            decide_network_failure(worker.retry, step, attempt_no as usize, &err)
+            decide_network_failure(worker.retry, step, attempt_no as usize, &err, worker.rng)
+        }
+    }
+}
+
+/// Values of the parameters sent in `location`, by name; secrets stay redacted.
+fn parameter_values(parameters: &[ResolvedParameter], location: &str) -> BTreeMap<String, String> {
+    parameters
+        .iter()
+        .filter(|p| p.location == location)
+        .map(|p| (p.name.clone(), p.value.clone()))
+        .collect()
+}
+
+enum SendError {
+    Http(HttpError),
+    Policy(PolicyGateError),
+}
+
+/// Send `req`, following 3xx responses as the redirect policy allows. Every hop goes
+/// back through the policy gate, and secret-derived headers are dropped once the
+/// redirect leaves the original origin.
+async fn send_following_redirects(
+    worker: &Worker<'_>,
+    source_name: &str,
+    redirects: &RedirectPolicy,
+    mut req: HttpRequestParts,
+    secret_derived_headers: &[String],
+    timeout: Duration,
+    max_response_bytes: usize,
+) -> Result<HttpResponseParts, SendError> {
+    let origin = req.url.origin();
+    let mut hops = 0;
+    loop {
+        let resp = worker
+            .http
+            .send(req.clone(), timeout, max_response_bytes)
+            .await
+            .map_err(SendError::Http)?;
+        if hops >= redirects.max_redirects
+            || redirects.action_for(resp.status) != Some(RedirectAction::Follow)
+        {
+            return Ok(resp);
+        }
+        let Some(next) =
+            get_header_ci(&resp.headers, "location").and_then(|l| req.url.join(l).ok())
+        else {
+            return Ok(resp);
+        };
+
+        // 303, and 301/302 after a POST, switch to a bodyless GET as browsers do.
+        if resp.status == 303 || (matches!(resp.status, 301 | 302) && req.method == "POST") {
+            req.method = "GET".to_string();
+            req.body.clear();
+            req.headers
+                .retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+        }
+        if next.origin() != origin {
+            req.headers.retain(|k, _| {
+                !k.eq_ignore_ascii_case("authorization")
+                    && !k.eq_ignore_ascii_case("cookie")
+                    && !secret_derived_headers
+                        .iter()
+                        .any(|s| s.eq_ignore_ascii_case(k))
+            });
         }
+        req.url = next;
+        worker
+            .policy_gate
+            .apply_request(source_name, &req, secret_derived_headers, &[], &[])
+            .map_err(SendError::Policy)?;
+        hops += 1;
     }
 }
 
@@ -246,3 +459,19 @@ async fn finish_attempt_failed(
         })
         .await;
 }
+
+async fn emit_policy_denied(
+    event_sink: &dyn crate::executor::EventSink,
+    run_id: Uuid,
+    step_id: &str,
+    err: &PolicyGateError,
+) {
+    event_sink
+        .emit(crate::executor::Event::PolicyDenied {
+            run_id,
+            step_id: step_id.to_string(),
+            kind: err.kind(),
+            reason: err.to_string(),
+        })
+        .await;
+}
@@ -1,22 +1,44 @@
-pub(crate) async fn load_openapi(
-    client: &reqwest::Client,
-    url_or_path: &str,
-) -> Result<serde_json::Value, String> {
-    if url_or_path.starts_with("http://") || url_or_path.starts_with("https://") {
-        let resp = client
-            .get(url_or_path)
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        let status = resp.status();
-        if !status.is_success() {
-            return Err(format!("HTTP {status}"));
+use async_trait::async_trait;
+
+/// Fetches and parses an OpenAPI document referenced by a source description URL.
+#[async_trait]
+pub trait Loader: Send + Sync {
+    async fn load(&self, url_or_path: &str) -> Result<serde_json::Value, String>;
+}
+
+/// Default loader: `http(s)://` URLs are fetched with `reqwest`, anything else is read from disk.
+#[derive(Default)]
+pub struct HttpLoader {
+    client: reqwest::Client,
+}
+
+impl HttpLoader {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Loader for HttpLoader {
+    async fn load(&self, url_or_path: &str) -> Result<serde_json::Value, String> {
+        if url_or_path.starts_with("http://") || url_or_path.starts_with("https://") {
+            let resp = self
+                .client
+                .get(url_or_path)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = resp.status();
+            if !status.is_success() {
+                return Err(format!("HTTP {status}"));
+            }
+            let body = resp.text().await.map_err(|e| e.to_string())?;
+            parse_openapi_str(&body)
+        } else {
+            let body =
+                std::fs::read_to_string(url_or_path).map_err(|e| format!("read file: {e}"))?;
+            parse_openapi_str(&body)
         }
-        let body = resp.text().await.map_err(|e| e.to_string())?;
-        parse_openapi_str(&body)
-    } else {
-        let body = std::fs::read_to_string(url_or_path).map_err(|e| format!("read file: {e}"))?;
-        parse_openapi_str(&body)
     }
 }
 
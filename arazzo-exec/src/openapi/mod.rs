@@ -6,8 +6,9 @@ mod refs;
 mod resolver;
 mod shape;
 
+pub use loader::{HttpLoader, Loader};
 pub use model::{
     CompiledOperationShape, DiagnosticSeverity, OpenApiDiagnostic, OpenApiDoc, OpenApiParam,
-    OpenApiParamLocation, ResolvedOperation,
+    OpenApiParamLocation, ResolvedOperation, SourceOverride,
 };
 pub use resolver::{OpenApiResolver, ResolvedSources};
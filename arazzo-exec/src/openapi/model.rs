@@ -6,6 +6,21 @@ pub struct OpenApiDoc {
     pub source_url: String,
     /// Parsed OpenAPI document as JSON value (works for both JSON and YAML inputs).
     pub raw: serde_json::Value,
+    /// Base URL used for every operation of this source instead of the document's `servers`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+/// Environment-specific replacement for a source description, keyed by source name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceOverride {
+    /// Load the OpenAPI document from here instead of the source description's `url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Send requests here instead of the base URL selected from `servers`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -41,6 +56,10 @@ pub struct CompiledOperationShape {
     pub request_body_required: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request_body_content_types: Option<Vec<String>>,
+    /// 2xx status codes documented in `responses`. Empty when the operation documents
+    /// none (or a `2XX` range), in which case any 2xx counts as success.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub success_statuses: Vec<u16>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -48,6 +67,10 @@ pub struct OpenApiParam {
     pub name: String,
     pub location: OpenApiParamLocation,
     pub required: bool,
+    /// `schema.default` of the parameter. Only sent for steps compiled with
+    /// [`crate::compile::CompiledStep::fill_parameter_defaults`] set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
 }
 
 #[derive(
@@ -101,6 +124,22 @@ pub(crate) fn collect_content_types(request_body: &serde_json::Value) -> Option<
     Some(keys)
 }
 
+pub(crate) fn collect_success_statuses(responses: Option<&serde_json::Value>) -> Vec<u16> {
+    let Some(responses) = responses.and_then(|r| r.as_object()) else {
+        return Vec::new();
+    };
+    if responses.keys().any(|k| k.eq_ignore_ascii_case("2XX")) {
+        return Vec::new();
+    }
+    let mut out: Vec<u16> = responses
+        .keys()
+        .filter_map(|k| k.parse::<u16>().ok())
+        .filter(|code| (200..300).contains(code))
+        .collect();
+    out.sort_unstable();
+    out
+}
+
 pub(crate) fn is_request_body_required(request_body: &serde_json::Value) -> Option<bool> {
     request_body.get("required").and_then(|v| v.as_bool())
 }
@@ -118,26 +157,39 @@ pub(crate) fn extract_parameter_obj(p: &serde_json::Value) -> Option<OpenApiPara
     if loc == OpenApiParamLocation::Path {
         required = true;
     }
+    let default = p
+        .get("schema")
+        .and_then(|s| s.get("default"))
+        .or_else(|| p.get("default"))
+        .cloned();
     Some(OpenApiParam {
         name: name.to_string(),
         location: loc,
         required,
+        default,
     })
 }
 
 pub(crate) fn dedupe_params(params: Vec<OpenApiParam>) -> Vec<OpenApiParam> {
-    let mut map: BTreeMap<(OpenApiParamLocation, String), bool> = BTreeMap::new();
-    for p in &params {
+    let mut map: BTreeMap<(OpenApiParamLocation, String), (bool, Option<serde_json::Value>)> =
+        BTreeMap::new();
+    for p in params {
         map.entry((p.location, p.name.clone()))
-            .and_modify(|req| *req = *req || p.required)
-            .or_insert(p.required);
+            .and_modify(|(req, default)| {
+                *req = *req || p.required;
+                if default.is_none() {
+                    *default = p.default.clone();
+                }
+            })
+            .or_insert((p.required, p.default));
     }
     let mut out = map
         .into_iter()
-        .map(|((loc, name), required)| OpenApiParam {
+        .map(|((loc, name), (required, default))| OpenApiParam {
             name,
             location: loc,
             required,
+            default,
         })
         .collect::<Vec<_>>();
     out.sort_by(|a, b| {
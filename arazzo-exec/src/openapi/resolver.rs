@@ -1,38 +1,90 @@
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use arazzo_core::types::{ArazzoDocument, SourceDescriptionType, Step, Workflow};
 
-use crate::openapi::loader::load_openapi;
-use crate::openapi::model::{DiagnosticSeverity, OpenApiDiagnostic, OpenApiDoc, ResolvedOperation};
+use crate::openapi::loader::{HttpLoader, Loader};
+use crate::openapi::model::{
+    DiagnosticSeverity, OpenApiDiagnostic, OpenApiDoc, ResolvedOperation, SourceOverride,
+};
 use crate::openapi::op_id::{
     find_operation_by_id, select_source_for_operation_id, OperationIdSelection,
 };
 use crate::openapi::op_path::parse_operation_path_ref;
 use crate::openapi::shape::{compile_operation_shape, select_base_url};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct ResolvedSources {
     pub openapi_docs: BTreeMap<String, OpenApiDoc>,
     pub diagnostics: Vec<OpenApiDiagnostic>,
 }
 
 pub struct OpenApiResolver {
-    client: reqwest::Client,
+    loader: Box<dyn Loader>,
+    overrides: BTreeMap<String, SourceOverride>,
+    base_dir: Option<PathBuf>,
 }
 
 impl Default for OpenApiResolver {
     fn default() -> Self {
-        Self {
-            client: reqwest::Client::new(),
-        }
+        Self::with_loader(HttpLoader::default())
     }
 }
 
 impl OpenApiResolver {
+    /// Resolve source descriptions through a custom [`Loader`] instead of the
+    /// default network/file loader.
+    pub fn with_loader(loader: impl Loader + 'static) -> Self {
+        Self {
+            loader: Box::new(loader),
+            overrides: BTreeMap::new(),
+            base_dir: None,
+        }
+    }
+
+    /// Point sources at other locations (e.g. a staging backend) before they are loaded.
+    /// Keys are source description names; unknown names are ignored.
+    pub fn with_source_overrides(mut self, overrides: BTreeMap<String, SourceOverride>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Resolve relative source URLs (e.g. `../specs/api.yaml`) against `dir`, usually the
+    /// directory of the workflow document, instead of the current working directory.
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
     pub async fn resolve_sources(&self, doc: &ArazzoDocument) -> ResolvedSources {
-        let mut out = ResolvedSources::default();
+        self.resolve_missing_sources(doc, ResolvedSources::default())
+            .await
+    }
+
+    /// Like [`Self::resolve_sources`], but only loads sources not already present in `known`.
+    pub async fn resolve_missing_sources(
+        &self,
+        doc: &ArazzoDocument,
+        known: ResolvedSources,
+    ) -> ResolvedSources {
+        self.resolve_missing_sources_in(doc, known, self.base_dir.as_deref())
+            .await
+    }
+
+    /// Like [`Self::resolve_missing_sources`], resolving relative source URLs against
+    /// `base_dir` instead of the directory given to [`Self::with_base_dir`].
+    pub async fn resolve_missing_sources_in(
+        &self,
+        doc: &ArazzoDocument,
+        known: ResolvedSources,
+        base_dir: Option<&Path>,
+    ) -> ResolvedSources {
+        let mut out = known;
 
         for src in &doc.source_descriptions {
+            if out.openapi_docs.contains_key(&src.name) {
+                continue;
+            }
             let ty = src
                 .source_type
                 .clone()
@@ -41,13 +93,19 @@ impl OpenApiResolver {
                 continue;
             }
 
-            match load_openapi(&self.client, &src.url).await {
+            let overridden = self.overrides.get(&src.name);
+            let url = match overridden.and_then(|o| o.url.as_deref()) {
+                Some(url) => url.to_string(),
+                None => resolve_relative_url(&src.url, base_dir),
+            };
+            match self.loader.load(&url).await {
                 Ok(raw) => {
                     out.openapi_docs.insert(
                         src.name.clone(),
                         OpenApiDoc {
-                            source_url: src.url.clone(),
+                            source_url: url,
                             raw,
+                            base_url: overridden.and_then(|o| o.base_url.clone()),
                         },
                     );
                 }
@@ -105,7 +163,7 @@ impl OpenApiResolver {
                     source_name: Some(source_name.clone()),
                 })?;
 
-            let (resolved, shape_diags) =
+            let (mut resolved, shape_diags) =
                 find_operation_by_id(&doc.raw, &source_name, &operation_id).ok_or_else(|| {
                     OpenApiDiagnostic {
                         severity: DiagnosticSeverity::Error,
@@ -124,6 +182,9 @@ impl OpenApiResolver {
                 });
             }
 
+            if let Some(base_url) = &doc.base_url {
+                resolved.base_url = base_url.clone();
+            }
             return Ok((resolved, diags));
         }
 
@@ -153,7 +214,11 @@ impl OpenApiResolver {
                 source_name: Some(source_name.clone()),
             })?;
 
-            let base_url = select_base_url(&doc.raw, &path, &method, op_obj).unwrap_or_default();
+            let base_url = doc
+                .base_url
+                .clone()
+                .or_else(|| select_base_url(&doc.raw, &path, &method, op_obj))
+                .unwrap_or_default();
             let (shape, shape_diags) =
                 compile_operation_shape(&doc.raw, &source_name, &path, &method, op_obj);
             for m in shape_diags {
@@ -188,3 +253,14 @@ impl OpenApiResolver {
         })
     }
 }
+
+/// Join a relative file path onto `base_dir`; URLs with a scheme and absolute paths are kept.
+fn resolve_relative_url(url: &str, base_dir: Option<&Path>) -> String {
+    let Some(base_dir) = base_dir else {
+        return url.to_string();
+    };
+    if url.contains("://") || Path::new(url).is_absolute() {
+        return url.to_string();
+    }
+    base_dir.join(url).to_string_lossy().into_owned()
+}
@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 
 use crate::openapi::model::{
-    collect_content_types, extract_parameter_obj, is_request_body_required, CompiledOperationShape,
+    collect_content_types, collect_success_statuses, extract_parameter_obj,
+    is_request_body_required, CompiledOperationShape,
 };
 use crate::openapi::refs::resolve_ref;
 
@@ -80,6 +81,7 @@ pub(crate) fn compile_operation_shape(
             parameters: params,
             request_body_required: rb_required,
             request_body_content_types: rb_cts,
+            success_statuses: collect_success_statuses(operation.get("responses")),
         },
         diagnostics,
     )
@@ -1,8 +1,10 @@
 use std::collections::BTreeMap;
 
 use crate::policy::config::{EffectivePolicy, PolicyConfig, PolicyOverrides};
-use crate::policy::network::{host_allowed, is_private_ip_literal};
-use crate::policy::sanitize::{redact_body_with_secrets, sanitize_headers, truncate_body};
+use crate::policy::network::{host_allowed, is_private_ip_literal, method_allowed};
+use crate::policy::sanitize::{
+    redact_body_with_secrets, sanitize_headers, sanitize_url, truncate_body,
+};
 
 #[derive(Debug, Clone)]
 pub struct HttpRequestParts {
@@ -15,7 +17,10 @@ pub struct HttpRequestParts {
 #[derive(Debug, Clone)]
 pub struct HttpResponseParts {
     pub status: u16,
+    /// Header names as received; repeated headers are joined with `", "`.
     pub headers: BTreeMap<String, String>,
+    /// Each value of a header received more than once, in order.
+    pub repeated_headers: BTreeMap<String, Vec<String>>,
     pub body: Vec<u8>,
 }
 
@@ -48,6 +53,8 @@ pub enum PolicyGateError {
     Host(String),
     #[error("private IP literal disallowed: {0}")]
     PrivateIp(String),
+    #[error("disallowed HTTP method: {0}")]
+    Method(String),
     #[error("request body exceeds max bytes ({len} > {max})")]
     RequestBodyTooLarge { len: usize, max: usize },
     #[error("response body exceeds max bytes ({len} > {max})")]
@@ -58,6 +65,46 @@ pub enum PolicyGateError {
     HeaderBytes { bytes: usize, max: usize },
 }
 
+/// Coarse category of a policy violation, suitable for alerting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyViolationKind {
+    Scheme,
+    Host,
+    PrivateIp,
+    Method,
+    BodySize,
+    Header,
+}
+
+impl PolicyViolationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyViolationKind::Scheme => "scheme",
+            PolicyViolationKind::Host => "host",
+            PolicyViolationKind::PrivateIp => "private-ip",
+            PolicyViolationKind::Method => "method",
+            PolicyViolationKind::BodySize => "body-size",
+            PolicyViolationKind::Header => "header",
+        }
+    }
+}
+
+impl PolicyGateError {
+    pub fn kind(&self) -> PolicyViolationKind {
+        match self {
+            PolicyGateError::Scheme(_) => PolicyViolationKind::Scheme,
+            PolicyGateError::Host(_) => PolicyViolationKind::Host,
+            PolicyGateError::PrivateIp(_) => PolicyViolationKind::PrivateIp,
+            PolicyGateError::Method(_) => PolicyViolationKind::Method,
+            PolicyGateError::RequestBodyTooLarge { .. }
+            | PolicyGateError::ResponseBodyTooLarge { .. } => PolicyViolationKind::BodySize,
+            PolicyGateError::HeaderCount { .. } | PolicyGateError::HeaderBytes { .. } => {
+                PolicyViolationKind::Header
+            }
+        }
+    }
+}
+
 pub struct PolicyGate {
     cfg: PolicyConfig,
     overrides: PolicyOverrides,
@@ -89,19 +136,24 @@ impl PolicyGate {
         source: &str,
         req: &HttpRequestParts,
         secret_derived_header_names: &[String],
-        body_contains_secrets: bool,
+        secret_query_params: &[String],
+        body_secret_pointers: &[String],
     ) -> Result<RequestGateResult, PolicyGateError> {
         let eff = self.cfg.effective_for_source(source, &self.overrides);
         enforce_request(&eff, req)?;
 
-        let body = if body_contains_secrets {
-            redact_body_with_secrets(&req.body, eff.limits.request.max_body_bytes)
+        let body = if !body_secret_pointers.is_empty() {
+            redact_body_with_secrets(
+                &req.body,
+                body_secret_pointers,
+                eff.limits.request.max_body_bytes,
+            )
         } else {
             truncate_body(&req.body, eff.limits.request.max_body_bytes)
         };
 
         Ok(RequestGateResult {
-            url: req.url.to_string(),
+            url: sanitize_url(&req.url, secret_query_params),
             method: req.method.clone(),
             headers: sanitize_headers(
                 &req.headers,
@@ -146,6 +198,9 @@ fn enforce_request(eff: &EffectivePolicy, req: &HttpRequestParts) -> Result<(),
     if eff.network.deny_private_ip_literals && is_private_ip_literal(&host) {
         return Err(PolicyGateError::PrivateIp(host));
     }
+    if !method_allowed(&eff.network.allowed_methods, &req.method) {
+        return Err(PolicyGateError::Method(req.method.clone()));
+    }
 
     enforce_headers(
         &req.headers,
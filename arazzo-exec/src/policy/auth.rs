@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Credentials attached to every request sent to a source. Values may be secret refs
+/// (e.g. `secrets://PETSTORE_TOKEN`); literal values are sent as given, so `Debug` never
+/// shows them.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scheme", rename_all = "lowercase")]
+pub enum SourceAuth {
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    #[serde(rename = "apikey")]
+    ApiKey {
+        name: String,
+        value: String,
+        #[serde(default, rename = "in")]
+        location: ApiKeyLocation,
+    },
+}
+
+impl std::fmt::Debug for SourceAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const REDACTED: &str = "<redacted>";
+        match self {
+            SourceAuth::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &REDACTED)
+                .finish(),
+            SourceAuth::Bearer { .. } => {
+                f.debug_struct("Bearer").field("token", &REDACTED).finish()
+            }
+            SourceAuth::ApiKey { name, location, .. } => f
+                .debug_struct("ApiKey")
+                .field("name", name)
+                .field("value", &REDACTED)
+                .field("location", location)
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
+
+/// Parse an auth file: a JSON/YAML map of source name to [`SourceAuth`].
+///
+/// ```yaml
+/// petstore:
+///   scheme: bearer
+///   token: secrets://PETSTORE_TOKEN
+/// ```
+pub fn parse_auth_config(content: &str) -> Result<BTreeMap<String, SourceAuth>, serde_yaml::Error> {
+    serde_yaml::from_str(content)
+}
@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 use std::time::Duration;
 
-use crate::policy::{LimitsConfig, NetworkConfig, SensitiveHeadersConfig};
+use crate::policy::{LimitsConfig, NetworkConfig, SensitiveHeadersConfig, SourceAuth};
 
 #[derive(Debug, Clone, Default)]
 pub struct PolicyConfig {
@@ -10,6 +10,8 @@ pub struct PolicyConfig {
     pub sensitive_headers: SensitiveHeadersConfig,
     /// Default: secrets not allowed in URL path/query.
     pub allow_secrets_in_url: bool,
+    /// Fail the step when a secret ref cannot be resolved instead of sending the ref literally.
+    pub strict_secrets: bool,
 
     /// Per-source overrides keyed by `sourceDescriptions[].name`.
     pub per_source: BTreeMap<String, SourcePolicyConfig>,
@@ -22,6 +24,13 @@ pub struct SourcePolicyConfig {
     pub sensitive_headers: Option<SensitiveHeadersConfig>,
     /// Override the global secrets policy for this source.
     pub allow_secrets_in_url: Option<bool>,
+    /// Headers added to every request sent to this source. Values may be secret refs.
+    pub inject_headers: BTreeMap<String, String>,
+    /// Credentials applied to every request sent to this source.
+    pub auth: Option<SourceAuth>,
+    /// Request timeout for this source, replacing `limits.request.timeout` without
+    /// overriding the other limits.
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -51,6 +60,9 @@ impl PolicyConfig {
             if let Some(s) = &src.sensitive_headers {
                 sensitive_headers = s.clone();
             }
+            if let Some(t) = src.timeout {
+                limits.request.timeout = t;
+            }
         }
 
         if let Some(v) = overrides.max_concurrent_steps {
@@ -72,11 +84,21 @@ impl PolicyConfig {
             .and_then(|s| s.allow_secrets_in_url)
             .unwrap_or(self.allow_secrets_in_url);
 
+        let inject_headers = self
+            .per_source
+            .get(source)
+            .map(|s| s.inject_headers.clone())
+            .unwrap_or_default();
+        let auth = self.per_source.get(source).and_then(|s| s.auth.clone());
+
         EffectivePolicy {
             network,
             limits,
             sensitive_headers,
             allow_secrets_in_url,
+            strict_secrets: self.strict_secrets,
+            inject_headers,
+            auth,
         }
     }
 }
@@ -87,4 +109,7 @@ pub struct EffectivePolicy {
     pub limits: LimitsConfig,
     pub sensitive_headers: SensitiveHeadersConfig,
     pub allow_secrets_in_url: bool,
+    pub strict_secrets: bool,
+    pub inject_headers: BTreeMap<String, String>,
+    pub auth: Option<SourceAuth>,
 }
@@ -12,6 +12,8 @@ pub struct RequestLimits {
     pub max_body_bytes: usize,
     pub max_headers_count: usize,
     pub max_headers_bytes: usize,
+    /// How long one attempt may take, from sending the request to reading the response.
+    pub timeout: Duration,
 }
 
 impl Default for RequestLimits {
@@ -20,6 +22,7 @@ impl Default for RequestLimits {
             max_body_bytes: 1024 * 1024, // 1MB
             max_headers_count: 100,
             max_headers_bytes: 16 * 1024, // 16KB
+            timeout: Duration::from_secs(30),
         }
     }
 }
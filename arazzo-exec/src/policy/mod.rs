@@ -1,12 +1,14 @@
 mod apply;
+mod auth;
 mod config;
 mod limits;
 mod network;
 pub mod sanitize;
 
-pub use apply::{HttpRequestParts, HttpResponseParts, PolicyGateError};
+pub use apply::{HttpRequestParts, HttpResponseParts, PolicyGateError, PolicyViolationKind};
 pub use apply::{PolicyGate, PolicyOutcome, RequestGateResult, ResponseGateResult};
+pub use auth::{parse_auth_config, ApiKeyLocation, SourceAuth};
 pub use config::{PolicyConfig, PolicyOverrides, SourcePolicyConfig};
 pub use limits::{LimitsConfig, RequestLimits, ResponseLimits, RunLimitsConfig};
-pub use network::{NetworkConfig, RedirectPolicy};
+pub use network::{NetworkConfig, RedirectAction, RedirectPolicy};
 pub use sanitize::{SanitizedBody, SanitizedHeaders, SensitiveHeadersConfig};
@@ -6,9 +6,11 @@ pub struct NetworkConfig {
     pub allowed_schemes: BTreeSet<String>,
     /// Allowed hosts/domains. If empty, requests are denied (secure-by-default).
     pub allowed_hosts: BTreeSet<String>,
+    /// Allowed HTTP methods (uppercase, e.g. `GET`). If empty, any method is allowed.
+    pub allowed_methods: BTreeSet<String>,
     /// Optional per-source base URLs (not enforced yet; reserved for stricter policy).
     pub allowed_base_urls: BTreeSet<String>,
-    /// Follow redirects?
+    /// Handling of 3xx responses.
     pub redirects: RedirectPolicy,
     /// Deny literal private IPs in host (SSRF guard).
     pub deny_private_ip_literals: bool,
@@ -19,6 +21,7 @@ impl Default for NetworkConfig {
         Self {
             allowed_schemes: ["https"].into_iter().map(|s| s.to_string()).collect(),
             allowed_hosts: BTreeSet::new(),
+            allowed_methods: BTreeSet::new(),
             allowed_base_urls: BTreeSet::new(),
             redirects: RedirectPolicy::default(),
             deny_private_ip_literals: true,
@@ -26,10 +29,42 @@ impl Default for NetworkConfig {
     }
 }
 
+/// How 3xx responses are handled. For a given status, `success_statuses` takes
+/// precedence over `follow`: a listed status is the step's final response and counts as
+/// success (explicit `successCriteria` still decide). Other 3xx responses are followed
+/// when `follow` is set, up to `max_redirects` hops; otherwise they fail like any non-2xx.
 #[derive(Debug, Clone, Default)]
 pub struct RedirectPolicy {
     pub follow: bool,
     pub max_redirects: usize,
+    /// 3xx statuses accepted as success without being followed.
+    pub success_statuses: BTreeSet<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectAction {
+    /// Issue a new request to the `Location` target.
+    Follow,
+    /// Keep the 3xx response and count it as success.
+    Succeed,
+    /// Keep the 3xx response and evaluate it like any other status.
+    Fail,
+}
+
+impl RedirectPolicy {
+    /// The action for `status`; `None` when it is not a redirect.
+    pub fn action_for(&self, status: u16) -> Option<RedirectAction> {
+        if !(300..400).contains(&status) {
+            return None;
+        }
+        Some(if self.success_statuses.contains(&status) {
+            RedirectAction::Succeed
+        } else if self.follow {
+            RedirectAction::Follow
+        } else {
+            RedirectAction::Fail
+        })
+    }
 }
 
 pub(crate) fn host_allowed(allowed_hosts: &BTreeSet<String>, host: &str) -> bool {
@@ -45,6 +80,13 @@ pub(crate) fn host_allowed(allowed_hosts: &BTreeSet<String>, host: &str) -> bool
         .any(|h| host.ends_with(&format!(".{h}")))
 }
 
+pub(crate) fn method_allowed(allowed_methods: &BTreeSet<String>, method: &str) -> bool {
+    allowed_methods.is_empty()
+        || allowed_methods
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(method))
+}
+
 pub(crate) fn is_private_ip_literal(host: &str) -> bool {
     // Only checks if host is a literal IP (no DNS resolution).
     if let Ok(ip) = host.parse::<std::net::IpAddr>() {
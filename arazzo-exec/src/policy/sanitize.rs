@@ -18,6 +18,15 @@ impl Default for SensitiveHeadersConfig {
     }
 }
 
+impl SensitiveHeadersConfig {
+    /// Whether header `name` is always redacted.
+    pub fn contains(&self, name: &str) -> bool {
+        self.always_redact
+            .iter()
+            .any(|h| h.eq_ignore_ascii_case(name))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SanitizedHeaders {
     pub headers: BTreeMap<String, String>,
@@ -45,6 +54,30 @@ pub(crate) fn sanitize_headers(
     SanitizedHeaders { headers: out }
 }
 
+/// `url` with the values of the `secret_params` query parameters redacted.
+pub(crate) fn sanitize_url(url: &url::Url, secret_params: &[String]) -> String {
+    if !url
+        .query_pairs()
+        .any(|(k, _)| secret_params.iter().any(|s| *s == k))
+    {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let v = if secret_params.iter().any(|s| *s == k) {
+                "<redacted>".to_string()
+            } else {
+                v.into_owned()
+            };
+            (k.into_owned(), v)
+        })
+        .collect();
+    let mut out = url.clone();
+    out.query_pairs_mut().clear().extend_pairs(pairs);
+    out.to_string()
+}
+
 pub(crate) fn truncate_body(body: &[u8], max_bytes: usize) -> SanitizedBody {
     if body.len() <= max_bytes {
         SanitizedBody {
@@ -59,8 +92,33 @@ pub(crate) fn truncate_body(body: &[u8], max_bytes: usize) -> SanitizedBody {
     }
 }
 
-pub(crate) fn redact_body_with_secrets(body: &[u8], max_bytes: usize) -> SanitizedBody {
+/// Masks the JSON leaves at `secret_pointers`, keeping the rest of the body
+/// intact. Falls back to redacting the whole body if it is not valid JSON or a
+/// pointer no longer resolves.
+pub(crate) fn redact_body_with_secrets(
+    body: &[u8],
+    secret_pointers: &[String],
+    max_bytes: usize,
+) -> SanitizedBody {
     const REDACTED: &[u8] = b"<body-redacted:contains-secrets>";
+
+    if let Ok(mut json) = serde_json::from_slice::<serde_json::Value>(body) {
+        let all_masked = secret_pointers
+            .iter()
+            .all(|ptr| match json.pointer_mut(ptr) {
+                Some(leaf) => {
+                    *leaf = serde_json::Value::String("<redacted>".to_string());
+                    true
+                }
+                None => false,
+            });
+        if all_masked {
+            if let Ok(bytes) = serde_json::to_vec(&json) {
+                return truncate_body(&bytes, max_bytes);
+            }
+        }
+    }
+
     let len = REDACTED.len().min(max_bytes);
     SanitizedBody {
         bytes: REDACTED[..len].to_vec(),
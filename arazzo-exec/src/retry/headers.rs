@@ -53,7 +53,10 @@ fn parse_vendor_value(v: &str, kind: VendorHeaderKind, now: SystemTime) -> Optio
     }
 }
 
-fn get_header_ci<'a>(headers: &'a BTreeMap<String, String>, name: &str) -> Option<&'a str> {
+pub(crate) fn get_header_ci<'a>(
+    headers: &'a BTreeMap<String, String>,
+    name: &str,
+) -> Option<&'a str> {
     headers
         .iter()
         .find(|(k, _)| k.eq_ignore_ascii_case(name))
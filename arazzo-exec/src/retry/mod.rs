@@ -4,4 +4,5 @@ mod headers;
 
 pub use config::{RetryConfig, RetryHeadersConfig, RetryVendorHeader, VendorHeaderKind};
 pub use decision::{decide_retry, RetryDecision, RetryReason};
+pub(crate) use headers::get_header_ci;
 pub use headers::parse_retry_after;
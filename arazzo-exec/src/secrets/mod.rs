@@ -14,7 +14,10 @@ mod gcp;
 pub use cache::{CacheConfig, CachingProvider};
 pub use error::{SecretError, SecretPolicyError};
 pub use policy::{SecretPlacement, SecretsPolicy};
-pub use provider::{CompositeProvider, EnvSecretsProvider, FileSecretsProvider, SecretsProvider};
+pub use provider::{
+    CompositeProvider, EnvSecretsProvider, FileSecretsProvider, SecretsProvider,
+    StdinSecretsProvider,
+};
 pub use r#ref::{SecretRef, SecretRefParseError};
 pub use redact::{redact_headers, RedactedHeaders, RedactionPolicy};
 pub use value::SecretValue;
@@ -1,7 +1,10 @@
 use std::collections::BTreeMap;
+use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 use async_trait::async_trait;
+use tokio::sync::OnceCell;
 
 use crate::secrets::{SecretError, SecretRef, SecretValue};
 
@@ -101,3 +104,105 @@ impl SecretsProvider for FileSecretsProvider {
         Ok(SecretValue::from_bytes(bytes))
     }
 }
+
+/// Reads secrets piped to the process on stdin, once, on the first lookup.
+///
+/// With [`Self::single`] the whole input (minus a trailing newline) is the value of one
+/// secret id; with [`Self::key_values`] each `KEY=VALUE` line defines a secret, so several
+/// refs can be fed through the same pipe. Blank lines and `#` comments are ignored.
+pub struct StdinSecretsProvider {
+    /// scheme to match, usually "secrets"
+    pub scheme: String,
+    single_id: Option<String>,
+    reader: Mutex<Option<Box<dyn Read + Send>>>,
+    values: OnceCell<Result<BTreeMap<String, String>, String>>,
+}
+
+impl StdinSecretsProvider {
+    /// Treat all of stdin as the value of the secret `id`.
+    pub fn single(id: impl Into<String>) -> Self {
+        Self::from_reader(Some(id.into()), Box::new(std::io::stdin()))
+    }
+
+    /// Parse stdin as `KEY=VALUE` lines.
+    pub fn key_values() -> Self {
+        Self::from_reader(None, Box::new(std::io::stdin()))
+    }
+
+    /// Read from `reader` instead of the process stdin.
+    pub fn with_reader(mut self, reader: impl Read + Send + 'static) -> Self {
+        self.reader = Mutex::new(Some(Box::new(reader)));
+        self
+    }
+
+    fn from_reader(single_id: Option<String>, reader: Box<dyn Read + Send>) -> Self {
+        Self {
+            scheme: "secrets".to_string(),
+            single_id,
+            reader: Mutex::new(Some(reader)),
+            values: OnceCell::new(),
+        }
+    }
+
+    /// Read the whole input on the blocking pool, so a slow pipe does not stall the
+    /// runtime.
+    async fn read_values(&self) -> Result<BTreeMap<String, String>, String> {
+        let reader = self.reader.lock().unwrap().take();
+        let input = tokio::task::spawn_blocking(move || {
+            let mut input = String::new();
+            if let Some(mut reader) = reader {
+                reader
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("read stdin: {e}"))?;
+            }
+            Ok::<_, String>(input)
+        })
+        .await
+        .map_err(|e| format!("read stdin: {e}"))??;
+        parse_stdin_values(self.single_id.as_deref(), &input)
+    }
+}
+
+/// Values are secrets, so a malformed line is reported by number only.
+fn parse_stdin_values(
+    single_id: Option<&str>,
+    input: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut values = BTreeMap::new();
+    match single_id {
+        Some(id) => {
+            let value = input.strip_suffix('\n').unwrap_or(input);
+            let value = value.strip_suffix('\r').unwrap_or(value);
+            values.insert(id.to_string(), value.to_string());
+        }
+        None => {
+            for (idx, line) in input.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected KEY=VALUE on stdin line {}", idx + 1))?;
+                values.insert(key.trim().to_string(), value.to_string());
+            }
+        }
+    }
+    Ok(values)
+}
+
+#[async_trait]
+impl SecretsProvider for StdinSecretsProvider {
+    async fn get(&self, secret_ref: &SecretRef) -> Result<SecretValue, SecretError> {
+        if secret_ref.scheme != self.scheme {
+            return Err(SecretError::NotFound(secret_ref.clone()));
+        }
+        match self.values.get_or_init(|| self.read_values()).await {
+            Ok(map) => map
+                .get(&secret_ref.id)
+                .map(|v| SecretValue::from_string(v.clone()))
+                .ok_or_else(|| SecretError::NotFound(secret_ref.clone())),
+            Err(e) => Err(SecretError::provider(secret_ref.clone(), e.clone())),
+        }
+    }
+}
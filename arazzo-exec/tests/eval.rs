@@ -1,169 +1,25 @@
-use chrono::{DateTime, Utc};
 use std::collections::BTreeMap;
 use uuid::Uuid;
 
-use arazzo_exec::executor::eval::{EvalContext, ResponseContext};
-use arazzo_store::StateStore;
+use arazzo_core::expressions::TemplateDelimiters;
+use arazzo_exec::executor::eval::{EvalContext, ResponseContext, StepOutputs};
 use async_trait::async_trait;
 use serde_json::json;
 
-struct MockStore;
+struct MockOutputs;
 
 #[async_trait]
-impl StateStore for MockStore {
-    async fn get_step_outputs(
+impl StepOutputs for MockOutputs {
+    async fn step_outputs(
         &self,
-        _run_id: uuid::Uuid,
+        _run_id: Uuid,
         _step_id: &str,
-    ) -> Result<serde_json::Value, arazzo_store::StoreError> {
+    ) -> Result<serde_json::Value, String> {
         Ok(json!({
             "token": "abc123",
             "userId": 42
         }))
     }
-
-    async fn upsert_workflow_doc(
-        &self,
-        _doc: arazzo_store::NewWorkflowDoc,
-    ) -> Result<arazzo_store::WorkflowDoc, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn get_workflow_doc(
-        &self,
-        _workflow_doc_id: uuid::Uuid,
-    ) -> Result<Option<arazzo_store::WorkflowDoc>, arazzo_store::StoreError> {
-        Ok(None)
-    }
-
-    async fn create_run_and_steps(
-        &self,
-        _run: arazzo_store::NewRun,
-        _steps: Vec<arazzo_store::NewRunStep>,
-        _edges: Vec<arazzo_store::RunStepEdge>,
-    ) -> Result<uuid::Uuid, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn claim_runnable_steps(
-        &self,
-        _run_id: uuid::Uuid,
-        _limit: i64,
-    ) -> Result<Vec<arazzo_store::RunStep>, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn mark_step_succeeded(
-        &self,
-        _run_id: uuid::Uuid,
-        _step_id: &str,
-        _outputs: serde_json::Value,
-    ) -> Result<(), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn mark_step_failed(
-        &self,
-        _run_id: uuid::Uuid,
-        _step_id: &str,
-        _error: serde_json::Value,
-    ) -> Result<(), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn schedule_retry(
-        &self,
-        _run_id: uuid::Uuid,
-        _step_id: &str,
-        _delay_ms: i64,
-        _error: serde_json::Value,
-    ) -> Result<(), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn mark_run_finished(
-        &self,
-        _run_id: uuid::Uuid,
-        _status: arazzo_store::RunStatus,
-        _error: Option<serde_json::Value>,
-    ) -> Result<(), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn mark_run_started(&self, _run_id: uuid::Uuid) -> Result<(), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn append_event(
-        &self,
-        _event: arazzo_store::NewEvent,
-    ) -> Result<(), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn insert_attempt_auto(
-        &self,
-        _run_step_id: uuid::Uuid,
-        _request: serde_json::Value,
-    ) -> Result<(uuid::Uuid, i32), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn finish_attempt(
-        &self,
-        _attempt_id: uuid::Uuid,
-        _status: arazzo_store::AttemptStatus,
-        _response: serde_json::Value,
-        _error: Option<serde_json::Value>,
-        _duration_ms: Option<i32>,
-        _finished_at: Option<DateTime<Utc>>,
-    ) -> Result<(), arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn get_run(
-        &self,
-        _run_id: uuid::Uuid,
-    ) -> Result<Option<arazzo_store::WorkflowRun>, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn get_run_steps(
-        &self,
-        _run_id: uuid::Uuid,
-    ) -> Result<Vec<arazzo_store::RunStep>, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn reset_stale_running_steps(
-        &self,
-        _run_id: uuid::Uuid,
-    ) -> Result<i64, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn get_step_attempts(
-        &self,
-        _run_step_id: uuid::Uuid,
-    ) -> Result<Vec<arazzo_store::StepAttempt>, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn get_events_after(
-        &self,
-        _run_id: uuid::Uuid,
-        _after_id: i64,
-        _limit: i64,
-    ) -> Result<Vec<arazzo_store::RunEvent>, arazzo_store::StoreError> {
-        unimplemented!()
-    }
-
-    async fn check_run_status(
-        &self,
-        _run_id: uuid::Uuid,
-    ) -> Result<String, arazzo_store::StoreError> {
-        unimplemented!()
-    }
 }
 
 #[tokio::test]
@@ -171,8 +27,9 @@ async fn eval_literal_value() {
     let ctx = EvalContext {
         run_id: Uuid::new_v4(),
         inputs: &json!({}),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: None,
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result = arazzo_exec::executor::eval::eval_value(&json!("hello"), &ctx)
@@ -191,8 +48,9 @@ async fn eval_inputs_expression() {
                 "value": 42
             }
         }),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: None,
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result = arazzo_exec::executor::eval::eval_value(&json!("$inputs.username"), &ctx)
@@ -211,8 +69,9 @@ async fn eval_steps_expression() {
     let ctx = EvalContext {
         run_id: Uuid::new_v4(),
         inputs: &json!({}),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: None,
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result =
@@ -227,8 +86,9 @@ async fn eval_steps_expression_with_pointer() {
     let ctx = EvalContext {
         run_id: Uuid::new_v4(),
         inputs: &json!({}),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: None,
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result =
@@ -247,13 +107,15 @@ async fn eval_status_code() {
         headers: &headers,
         body: b"{}",
         body_json: Some(json!({})),
+        request: None,
     };
 
     let ctx = EvalContext {
         run_id: Uuid::new_v4(),
         inputs: &json!({}),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: Some(response),
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result = arazzo_exec::executor::eval::eval_value(&json!("$statusCode"), &ctx)
@@ -271,13 +133,15 @@ async fn eval_response_header() {
         headers: &headers,
         body: b"{}",
         body_json: Some(json!({})),
+        request: None,
     };
 
     let ctx = EvalContext {
         run_id: Uuid::new_v4(),
         inputs: &json!({}),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: Some(response),
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result =
@@ -287,6 +151,79 @@ async fn eval_response_header() {
     assert_eq!(result, json!("test-value"));
 }
 
+#[tokio::test]
+async fn eval_response_header_all_returns_every_value() {
+    use std::io::{Read, Write};
+
+    use arazzo_exec::executor::{HttpClient, ReqwestHttpClient};
+    use arazzo_exec::policy::HttpRequestParts;
+
+    // Real server so the duplicate headers go through the HTTP client's folding.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = stream.read(&mut buf);
+        let _ = stream.write_all(
+            b"HTTP/1.1 200 OK\r\nSet-Cookie: a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT\r\nSet-Cookie: b=2\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+    });
+
+    let resp = ReqwestHttpClient::default()
+        .send(
+            HttpRequestParts {
+                method: "GET".to_string(),
+                url: url::Url::parse(&format!("http://{addr}/")).unwrap(),
+                headers: BTreeMap::new(),
+                body: vec![],
+            },
+            std::time::Duration::from_secs(5),
+            1024,
+        )
+        .await
+        .unwrap();
+
+    // Stored and emitted headers are folded the way HTTP folds them.
+    assert_eq!(
+        resp.headers["set-cookie"],
+        "a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT, b=2"
+    );
+
+    let headers = arazzo_exec::executor::response::eval_headers(&resp);
+    let ctx = EvalContext {
+        run_id: Uuid::new_v4(),
+        inputs: &json!({}),
+        outputs: &MockOutputs,
+        response: Some(ResponseContext {
+            status: resp.status,
+            headers: &headers,
+            body: b"",
+            body_json: None,
+            request: None,
+        }),
+        delimiters: &TemplateDelimiters::default(),
+    };
+
+    let all =
+        arazzo_exec::executor::eval::eval_value(&json!("$response.header.Set-Cookie#all"), &ctx)
+            .await
+            .unwrap();
+    assert_eq!(
+        all,
+        json!(["a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT", "b=2"])
+    );
+
+    let single =
+        arazzo_exec::executor::eval::eval_value(&json!("$response.header.set-cookie"), &ctx)
+            .await
+            .unwrap();
+    assert_eq!(
+        single,
+        json!("a=1; Expires=Wed, 21 Oct 2026 07:28:00 GMT, b=2")
+    );
+}
+
 #[tokio::test]
 async fn eval_response_body() {
     let headers = BTreeMap::new();
@@ -299,13 +236,15 @@ async fn eval_response_body() {
         headers: &headers,
         body: b"{\"id\":123,\"name\":\"test\"}",
         body_json: Some(body_json.clone()),
+        request: None,
     };
 
     let ctx = EvalContext {
         run_id: Uuid::new_v4(),
         inputs: &json!({}),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: Some(response),
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result = arazzo_exec::executor::eval::eval_value(&json!("$response.body"), &ctx)
@@ -325,13 +264,15 @@ async fn eval_response_body_with_pointer() {
             "id": 123,
             "name": "test"
         })),
+        request: None,
     };
 
     let ctx = EvalContext {
         run_id: Uuid::new_v4(),
         inputs: &json!({}),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: Some(response),
+        delimiters: &TemplateDelimiters::default(),
     };
 
     // Note: JSON pointer syntax in $response.body#/path may not be fully supported
@@ -356,8 +297,9 @@ async fn eval_embedded_template() {
         inputs: &json!({
             "user": "alice"
         }),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: None,
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result = arazzo_exec::executor::eval::eval_value(&json!("Hello { $inputs.user }!"), &ctx)
@@ -373,8 +315,9 @@ async fn eval_array() {
         inputs: &json!({
             "items": ["a", "b"]
         }),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: None,
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result = arazzo_exec::executor::eval::eval_value(&json!(["$inputs.items", "c"]), &ctx)
@@ -390,8 +333,9 @@ async fn eval_object() {
         inputs: &json!({
             "name": "test"
         }),
-        store: &MockStore,
+        outputs: &MockOutputs,
         response: None,
+        delimiters: &TemplateDelimiters::default(),
     };
 
     let result = arazzo_exec::executor::eval::eval_value(
@@ -411,3 +355,44 @@ async fn eval_object() {
         })
     );
 }
+
+#[tokio::test]
+async fn eval_template_with_custom_delimiters() {
+    let delimiters = TemplateDelimiters::new("<%", "%>");
+    let ctx = EvalContext {
+        run_id: Uuid::new_v4(),
+        inputs: &json!({"name": "rex"}),
+        outputs: &MockOutputs,
+        response: None,
+        delimiters: &delimiters,
+    };
+
+    let result = arazzo_exec::executor::eval::eval_value(
+        &json!(r#"{"name": "<% $inputs.name %>", "raw": "{$inputs.name}"}"#),
+        &ctx,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result, json!(r#"{"name": "rex", "raw": "{$inputs.name}"}"#));
+}
+
+#[tokio::test]
+async fn eval_explicit_null_input_is_null_and_absent_input_is_missing() {
+    let ctx = EvalContext {
+        run_id: Uuid::new_v4(),
+        inputs: &json!({"coupon": null}),
+        outputs: &MockOutputs,
+        response: None,
+        delimiters: &TemplateDelimiters::default(),
+    };
+
+    let present = arazzo_exec::executor::eval::eval_value(&json!("$inputs.coupon"), &ctx)
+        .await
+        .unwrap();
+    assert_eq!(present, json!(null));
+
+    let absent = arazzo_exec::executor::eval::eval_value(&json!("$inputs.discount"), &ctx)
+        .await
+        .unwrap_err();
+    assert!(absent.contains("missing input: discount"), "{absent}");
+}
@@ -2,12 +2,15 @@ use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use arazzo_exec::executor::events::{CompositeEventSink, Event, EventSink, StoreEventSink};
+use arazzo_exec::executor::events::{
+    event_sink_from_spec, CompositeEventSink, Event, EventSink, StdoutEventSink, StoreEventSink,
+};
 use arazzo_store::{RunStatus, StateStore};
 use async_trait::async_trait;
 
 struct MockStore {
     events: Arc<tokio::sync::Mutex<Vec<String>>>,
+    fail_appends: bool,
 }
 
 #[async_trait]
@@ -16,6 +19,11 @@ impl StateStore for MockStore {
         &self,
         event: arazzo_store::NewEvent,
     ) -> Result<(), arazzo_store::StoreError> {
+        if self.fail_appends {
+            return Err(arazzo_store::StoreError::Other(
+                "database unavailable".into(),
+            ));
+        }
         self.events.lock().await.push(event.r#type);
         Ok(())
     }
@@ -141,6 +149,14 @@ impl StateStore for MockStore {
         unimplemented!()
     }
 
+    async fn skip_pending_steps(
+        &self,
+        _run_id: uuid::Uuid,
+        _error: serde_json::Value,
+    ) -> Result<i64, arazzo_store::StoreError> {
+        unimplemented!()
+    }
+
     async fn get_step_attempts(
         &self,
         _run_step_id: uuid::Uuid,
@@ -160,7 +176,7 @@ impl StateStore for MockStore {
     async fn check_run_status(
         &self,
         _run_id: uuid::Uuid,
-    ) -> Result<String, arazzo_store::StoreError> {
+    ) -> Result<arazzo_store::RunStatus, arazzo_store::StoreError> {
         unimplemented!()
     }
 }
@@ -169,6 +185,7 @@ impl StateStore for MockStore {
 async fn store_event_sink_emits_run_started() {
     let store = Arc::new(MockStore {
         events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: false,
     });
     let sink = StoreEventSink::new(store.clone());
 
@@ -187,6 +204,7 @@ async fn store_event_sink_emits_run_started() {
 async fn store_event_sink_emits_run_finished() {
     let store = Arc::new(MockStore {
         events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: false,
     });
     let sink = StoreEventSink::new(store.clone());
 
@@ -205,6 +223,7 @@ async fn store_event_sink_emits_run_finished() {
 async fn store_event_sink_emits_step_events() {
     let store = Arc::new(MockStore {
         events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: false,
     });
     let sink = StoreEventSink::new(store.clone());
     let run_id = Uuid::new_v4();
@@ -238,9 +257,11 @@ async fn store_event_sink_emits_step_events() {
 async fn composite_event_sink_forwards_to_all_sinks() {
     let store1 = Arc::new(MockStore {
         events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: false,
     });
     let store2 = Arc::new(MockStore {
         events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: false,
     });
 
     let mut composite = CompositeEventSink::new();
@@ -259,3 +280,71 @@ async fn composite_event_sink_forwards_to_all_sinks() {
     assert_eq!(events1.len(), 1);
     assert_eq!(events2.len(), 1);
 }
+
+#[tokio::test]
+async fn store_event_sink_forwards_to_fallback_when_store_fails() {
+    let failing = Arc::new(MockStore {
+        events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: true,
+    });
+    let backup = Arc::new(MockStore {
+        events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: false,
+    });
+
+    let sink = StoreEventSink::new(failing.clone())
+        .with_fallback(Arc::new(StoreEventSink::new(backup.clone())));
+    sink.emit(Event::RunFinished {
+        run_id: Uuid::new_v4(),
+        status: RunStatus::Failed,
+    })
+    .await;
+
+    assert_eq!(sink.failures(), 1);
+    assert!(failing.events.lock().await.is_empty());
+    assert_eq!(
+        *backup.events.lock().await,
+        vec!["run.finished".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn comma_separated_spec_sends_events_to_every_sink() {
+    let store = Arc::new(MockStore {
+        events: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+        fail_appends: false,
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.jsonl");
+
+    let sink =
+        event_sink_from_spec(&format!("postgres,file:{}", path.display()), store.clone()).unwrap();
+    sink.emit(Event::StepStarted {
+        run_id: Uuid::new_v4(),
+        step_id: "step1".to_string(),
+    })
+    .await;
+
+    assert_eq!(*store.events.lock().await, vec!["step.started".to_string()]);
+    let lines = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<_> = lines.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let json: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(json["type"], "step.started");
+
+    assert!(event_sink_from_spec("stdout,kafka", store).is_err());
+}
+
+#[test]
+fn stdout_event_json_includes_parseable_ts() {
+    let run_id = Uuid::new_v4();
+    let json = StdoutEventSink::to_json(Event::StepStarted {
+        run_id,
+        step_id: "step1".to_string(),
+    });
+
+    assert_eq!(json["type"], "step.started");
+    assert_eq!(json["run_id"], run_id.to_string());
+    let ts = json["ts"].as_str().expect("ts should be a string");
+    assert!(DateTime::parse_from_rfc3339(ts).is_ok());
+}
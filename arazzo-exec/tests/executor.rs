@@ -1,8 +1,13 @@
 use std::collections::BTreeMap;
 use std::time::Duration;
 
-use arazzo_exec::executor::{EventSink, HttpClient, HttpError, StepResult, Worker};
-use arazzo_exec::policy::{HttpRequestParts, HttpResponseParts, PolicyConfig, PolicyGate};
+use arazzo_exec::executor::{
+    EventSink, HttpClient, HttpError, RequestBuildError, RngSource, StepResult, Worker,
+    IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_EXTENSION,
+};
+use arazzo_exec::policy::{
+    HttpRequestParts, HttpResponseParts, PolicyConfig, PolicyGate, PolicyViolationKind,
+};
 use arazzo_exec::retry::RetryConfig;
 use arazzo_exec::secrets::{SecretValue, SecretsProvider};
 use async_trait::async_trait;
@@ -29,6 +34,95 @@ impl HttpClient for MockHttpClient {
     }
 }
 
+// HTTP client that records the last request URL, headers and timeout
+#[derive(Default)]
+struct CapturingHttpClient {
+    last_url: std::sync::Mutex<Option<String>>,
+    last_headers: std::sync::Mutex<BTreeMap<String, String>>,
+    last_body: std::sync::Mutex<Vec<u8>>,
+    last_timeout: std::sync::Mutex<Option<Duration>>,
+}
+
+#[async_trait]
+impl HttpClient for CapturingHttpClient {
+    async fn send(
+        &self,
+        req: HttpRequestParts,
+        timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        *self.last_url.lock().unwrap() = Some(req.url.to_string());
+        *self.last_timeout.lock().unwrap() = Some(timeout);
+        *self.last_headers.lock().unwrap() = req.headers.clone();
+        *self.last_body.lock().unwrap() = req.body.clone();
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+// HTTP client that answers 503 to the first request and 200 afterwards, recording headers
+#[derive(Default)]
+struct FlakyHttpClient {
+    seen_headers: std::sync::Mutex<Vec<BTreeMap<String, String>>>,
+}
+
+#[async_trait]
+impl HttpClient for FlakyHttpClient {
+    async fn send(
+        &self,
+        req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        let mut seen = self.seen_headers.lock().unwrap();
+        seen.push(req.headers.clone());
+        Ok(HttpResponseParts {
+            status: if seen.len() == 1 { 503 } else { 200 },
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+// HTTP client answering 302 -> /users/next for /users, and 200 for everything else
+#[derive(Default)]
+struct RedirectingHttpClient {
+    urls: std::sync::Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl HttpClient for RedirectingHttpClient {
+    async fn send(
+        &self,
+        req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        self.urls.lock().unwrap().push(req.url.to_string());
+        if req.url.path() == "/users" {
+            return Ok(HttpResponseParts {
+                status: 302,
+                headers: [("location".to_string(), "/users/next".to_string())]
+                    .into_iter()
+                    .collect(),
+                repeated_headers: BTreeMap::new(),
+                body: Vec::new(),
+            });
+        }
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
 // Mock event sink for tests
 struct MockEventSink;
 
@@ -39,8 +133,25 @@ impl EventSink for MockEventSink {
     }
 }
 
+// Event sink that records emitted events for assertions
+#[derive(Default)]
+struct RecordingEventSink {
+    events: std::sync::Mutex<Vec<arazzo_exec::executor::Event>>,
+}
+
+#[async_trait]
+impl EventSink for RecordingEventSink {
+    async fn emit(&self, event: arazzo_exec::executor::Event) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
 // Mock store that doesn't require DB
-struct MockStore;
+#[derive(Default)]
+struct MockStore {
+    /// Outputs of earlier steps, by step id.
+    step_outputs: BTreeMap<String, serde_json::Value>,
+}
 
 #[async_trait::async_trait]
 impl arazzo_store::StateStore for MockStore {
@@ -107,9 +218,13 @@ impl arazzo_store::StateStore for MockStore {
     async fn get_step_outputs(
         &self,
         _run_id: uuid::Uuid,
-        _step_id: &str,
+        step_id: &str,
     ) -> Result<serde_json::Value, arazzo_store::StoreError> {
-        Ok(serde_json::json!({}))
+        Ok(self
+            .step_outputs
+            .get(step_id)
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({})))
     }
 
     async fn schedule_retry(
@@ -172,6 +287,14 @@ impl arazzo_store::StateStore for MockStore {
         Ok(0)
     }
 
+    async fn skip_pending_steps(
+        &self,
+        _run_id: uuid::Uuid,
+        _error: serde_json::Value,
+    ) -> Result<i64, arazzo_store::StoreError> {
+        Ok(0)
+    }
+
     async fn get_step_attempts(
         &self,
         _run_step_id: uuid::Uuid,
@@ -191,8 +314,8 @@ impl arazzo_store::StateStore for MockStore {
     async fn check_run_status(
         &self,
         _run_id: uuid::Uuid,
-    ) -> Result<String, arazzo_store::StoreError> {
-        Ok("succeeded".to_string())
+    ) -> Result<arazzo_store::RunStatus, arazzo_store::StoreError> {
+        Ok(arazzo_store::RunStatus::Succeeded)
     }
 }
 
@@ -209,6 +332,18 @@ impl SecretsProvider for NoOpSecretsProvider {
     }
 }
 
+struct StaticSecretsProvider(&'static str);
+
+#[async_trait]
+impl SecretsProvider for StaticSecretsProvider {
+    async fn get(
+        &self,
+        _ref_: &arazzo_exec::secrets::SecretRef,
+    ) -> Result<SecretValue, arazzo_exec::secrets::SecretError> {
+        Ok(SecretValue::from_string(self.0.to_string()))
+    }
+}
+
 fn make_step(step_id: &str) -> arazzo_core::types::Step {
     arazzo_core::types::Step {
         step_id: step_id.to_string(),
@@ -253,6 +388,7 @@ fn make_resolved_op() -> arazzo_exec::openapi::ResolvedOperation {
             parameters: vec![],
             request_body_required: None,
             request_body_content_types: None,
+            success_statuses: Vec::new(),
         },
     }
 }
@@ -266,6 +402,7 @@ fn make_policy() -> PolicyConfig {
                 .into_iter()
                 .map(|s| s.to_string())
                 .collect(),
+            allowed_methods: BTreeSet::new(),
             allowed_base_urls: BTreeSet::new(),
             redirects: Default::default(),
             deny_private_ip_literals: true,
@@ -273,17 +410,19 @@ fn make_policy() -> PolicyConfig {
         limits: Default::default(),
         sensitive_headers: Default::default(),
         allow_secrets_in_url: false,
+        strict_secrets: false,
         per_source: BTreeMap::new(),
     }
 }
 
 #[tokio::test]
 async fn successful_step_returns_outputs() {
-    let store = MockStore;
+    let store = MockStore::default();
     let http = MockHttpClient {
         response: HttpResponseParts {
             status: 200,
             headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
             body: b"{}".to_vec(),
         },
         fail_with: None,
@@ -291,6 +430,7 @@ async fn successful_step_returns_outputs() {
     let secrets = NoOpSecretsProvider;
     let policy_gate = PolicyGate::new(make_policy());
     let retry = RetryConfig::default();
+    let rng = RngSource::default();
 
     let event_sink = MockEventSink;
     let worker = Worker {
@@ -299,7 +439,10 @@ async fn successful_step_returns_outputs() {
         secrets: &secrets,
         policy_gate: &policy_gate,
         retry: &retry,
+        rng: &rng,
         event_sink: &event_sink,
+        debug_requests: false,
+        fill_parameter_defaults: false,
     };
 
     let result = arazzo_exec::executor::worker::execute_step_attempt(
@@ -324,11 +467,12 @@ async fn successful_step_returns_outputs() {
 
 #[tokio::test]
 async fn non_2xx_status_fails_step() {
-    let store = MockStore;
+    let store = MockStore::default();
     let http = MockHttpClient {
         response: HttpResponseParts {
             status: 404,
             headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
             body: b"{}".to_vec(),
         },
         fail_with: None,
@@ -336,6 +480,7 @@ async fn non_2xx_status_fails_step() {
     let secrets = NoOpSecretsProvider;
     let policy_gate = PolicyGate::new(make_policy());
     let retry = RetryConfig::default();
+    let rng = RngSource::default();
 
     let event_sink = MockEventSink;
     let worker = Worker {
@@ -344,7 +489,10 @@ async fn non_2xx_status_fails_step() {
         secrets: &secrets,
         policy_gate: &policy_gate,
         retry: &retry,
+        rng: &rng,
         event_sink: &event_sink,
+        debug_requests: false,
+        fill_parameter_defaults: false,
     };
 
     let result = arazzo_exec::executor::worker::execute_step_attempt(
@@ -365,11 +513,12 @@ async fn non_2xx_status_fails_step() {
 
 #[tokio::test]
 async fn network_error_fails_step() {
-    let store = MockStore;
+    let store = MockStore::default();
     let http = MockHttpClient {
         response: HttpResponseParts {
             status: 200,
             headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
             body: vec![],
         },
         fail_with: Some(HttpError::Timeout),
@@ -377,6 +526,7 @@ async fn network_error_fails_step() {
     let secrets = NoOpSecretsProvider;
     let policy_gate = PolicyGate::new(make_policy());
     let retry = RetryConfig::default();
+    let rng = RngSource::default();
 
     let event_sink = MockEventSink;
     let worker = Worker {
@@ -385,7 +535,10 @@ async fn network_error_fails_step() {
         secrets: &secrets,
         policy_gate: &policy_gate,
         retry: &retry,
+        rng: &rng,
         event_sink: &event_sink,
+        debug_requests: false,
+        fill_parameter_defaults: false,
     };
 
     let result = arazzo_exec::executor::worker::execute_step_attempt(
@@ -406,11 +559,12 @@ async fn network_error_fails_step() {
 
 #[tokio::test]
 async fn missing_base_url_fails_step() {
-    let store = MockStore;
+    let store = MockStore::default();
     let http = MockHttpClient {
         response: HttpResponseParts {
             status: 200,
             headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
             body: vec![],
         },
         fail_with: None,
@@ -418,6 +572,7 @@ async fn missing_base_url_fails_step() {
     let secrets = NoOpSecretsProvider;
     let policy_gate = PolicyGate::new(make_policy());
     let retry = RetryConfig::default();
+    let rng = RngSource::default();
 
     let event_sink = MockEventSink;
     let worker = Worker {
@@ -426,7 +581,10 @@ async fn missing_base_url_fails_step() {
         secrets: &secrets,
         policy_gate: &policy_gate,
         retry: &retry,
+        rng: &rng,
         event_sink: &event_sink,
+        debug_requests: false,
+        fill_parameter_defaults: false,
     };
 
     let mut op = make_resolved_op();
@@ -448,8 +606,1035 @@ async fn missing_base_url_fails_step() {
     match result {
         StepResult::Failed { error, end_run } => {
             assert!(end_run);
+            assert_eq!(error["kind"], "missing_base_url");
             assert!(error["message"].as_str().unwrap().contains("base_url"));
         }
         _ => panic!("expected Failed result"),
     }
 }
+
+#[tokio::test]
+async fn host_denied_request_emits_policy_denied_event() {
+    let store = MockStore::default();
+    let http = MockHttpClient {
+        response: HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: vec![],
+        },
+        fail_with: None,
+    };
+
+    let event_sink = RecordingEventSink::default();
+
+    let mut op = make_resolved_op();
+    op.base_url = "https://evil.example.com".to_string();
+
+    let result = run_attempt_with(
+        &http,
+        &store,
+        &make_step("step1"),
+        &op,
+        &serde_json::json!({}),
+        &AttemptSetup {
+            event_sink: &event_sink,
+            ..Default::default()
+        },
+    )
+    .await;
+
+    match result {
+        StepResult::Failed { error, .. } => assert_eq!(error["kind"], "host"),
+        _ => panic!("expected Failed result"),
+    }
+
+    let events = event_sink.events.lock().unwrap();
+    let denied = events
+        .iter()
+        .find_map(|e| match e {
+            arazzo_exec::executor::Event::PolicyDenied { kind, .. } => Some(*kind),
+            _ => None,
+        })
+        .expect("expected a PolicyDenied event");
+    assert_eq!(denied, PolicyViolationKind::Host);
+    assert_eq!(denied.as_str(), "host");
+}
+
+/// What [`run_attempt_with`] runs an attempt with besides its client, store and step.
+/// The default is the test policy, no secrets and no debug events, in a fresh run.
+struct AttemptSetup<'a> {
+    policy: PolicyConfig,
+    secrets: &'a dyn SecretsProvider,
+    event_sink: &'a dyn EventSink,
+    debug_requests: bool,
+    fill_parameter_defaults: bool,
+    workflow: arazzo_core::types::Workflow,
+    run_id: uuid::Uuid,
+    step_row_id: uuid::Uuid,
+}
+
+impl Default for AttemptSetup<'_> {
+    fn default() -> Self {
+        Self {
+            policy: make_policy(),
+            secrets: &NoOpSecretsProvider,
+            event_sink: &MockEventSink,
+            debug_requests: false,
+            fill_parameter_defaults: false,
+            workflow: make_workflow(),
+            run_id: uuid::Uuid::new_v4(),
+            step_row_id: uuid::Uuid::new_v4(),
+        }
+    }
+}
+
+/// Run one attempt of `step` against `op`'s source.
+async fn run_attempt_with(
+    http: &dyn HttpClient,
+    store: &MockStore,
+    step: &arazzo_core::types::Step,
+    op: &arazzo_exec::openapi::ResolvedOperation,
+    inputs: &serde_json::Value,
+    setup: &AttemptSetup<'_>,
+) -> StepResult {
+    let policy_gate = PolicyGate::new(setup.policy.clone());
+    let retry = RetryConfig::default();
+    let rng = RngSource::default();
+    let worker = Worker {
+        store,
+        http,
+        secrets: setup.secrets,
+        policy_gate: &policy_gate,
+        retry: &retry,
+        rng: &rng,
+        event_sink: setup.event_sink,
+        debug_requests: setup.debug_requests,
+        fill_parameter_defaults: setup.fill_parameter_defaults,
+    };
+
+    arazzo_exec::executor::worker::execute_step_attempt(
+        &worker,
+        setup.run_id,
+        &op.source_name,
+        setup.step_row_id,
+        step,
+        &setup.workflow,
+        op,
+        inputs,
+        None,
+    )
+    .await
+}
+
+/// Build and send `step` with no inputs or earlier outputs.
+async fn build_attempt(
+    step: &arazzo_core::types::Step,
+    op: &arazzo_exec::openapi::ResolvedOperation,
+) -> StepResult {
+    let http = CapturingHttpClient::default();
+    run_attempt_with(
+        &http,
+        &MockStore::default(),
+        step,
+        op,
+        &serde_json::json!({}),
+        &AttemptSetup::default(),
+    )
+    .await
+}
+
+fn build_error_kind(result: StepResult) -> String {
+    match result {
+        StepResult::Failed { error, .. } => {
+            assert_eq!(error["type"], "build");
+            error["kind"].as_str().unwrap_or_default().to_string()
+        }
+        other => panic!("expected Failed result, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn unresolved_parameter_ref_is_a_typed_build_error() {
+    let mut step = make_step("step1");
+    step.parameters = Some(vec![arazzo_core::types::ParameterOrReusable::Reusable(
+        arazzo_core::types::ReusableObject {
+            reference: "$components.parameters.missing".to_string(),
+            value: None,
+        },
+    )]);
+
+    let result = build_attempt(&step, &make_resolved_op()).await;
+    assert_eq!(build_error_kind(result), "unresolved_parameter_ref");
+}
+
+#[tokio::test]
+async fn failed_parameter_eval_is_a_typed_build_error() {
+    let mut step = make_step("step1");
+    step.parameters = Some(vec![arazzo_core::types::ParameterOrReusable::Parameter(
+        arazzo_core::types::Parameter {
+            name: "X-User".to_string(),
+            r#in: Some(arazzo_core::types::ParameterLocation::Header),
+            value: serde_json::json!("$inputs.missing"),
+            extensions: Default::default(),
+        },
+    )]);
+
+    let result = build_attempt(&step, &make_resolved_op()).await;
+    assert_eq!(build_error_kind(result), "eval");
+}
+
+#[tokio::test]
+async fn invalid_base_url_is_a_typed_build_error() {
+    let mut op = make_resolved_op();
+    op.base_url = "not a url".to_string();
+
+    let result = build_attempt(&make_step("step1"), &op).await;
+    assert_eq!(build_error_kind(result), "url_parse");
+}
+
+#[tokio::test]
+async fn null_payload_for_required_body_is_a_typed_build_error() {
+    let mut op = make_resolved_op();
+    op.method = "POST".to_string();
+    op.shape.request_body_required = Some(true);
+    let mut step = make_step("step1");
+    step.request_body = Some(arazzo_core::types::RequestBody {
+        content_type: Some("application/json".to_string()),
+        payload: Some(serde_json::Value::Null),
+        replacements: None,
+        extensions: Default::default(),
+    });
+
+    let result = build_attempt(&step, &op).await;
+    assert_eq!(build_error_kind(result), "empty_required_body");
+}
+
+#[test]
+fn serialize_failure_maps_to_serialize_kind() {
+    let json_err = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+    let err = RequestBuildError::from(json_err);
+    assert_eq!(err.kind(), "serialize");
+    assert!(err
+        .to_string()
+        .starts_with("failed to serialize request body"));
+}
+
+#[tokio::test]
+async fn omitted_parameter_is_filled_from_openapi_default_only_when_opted_in() {
+    let store = MockStore::default();
+    let http = CapturingHttpClient::default();
+
+    let mut op = make_resolved_op();
+    op.shape.parameters = vec![
+        arazzo_exec::openapi::OpenApiParam {
+            name: "limit".to_string(),
+            location: arazzo_exec::openapi::OpenApiParamLocation::Query,
+            required: false,
+            default: Some(serde_json::json!(20)),
+        },
+        arazzo_exec::openapi::OpenApiParam {
+            name: "sort".to_string(),
+            location: arazzo_exec::openapi::OpenApiParamLocation::Query,
+            required: false,
+            default: Some(serde_json::json!("asc")),
+        },
+    ];
+
+    let mut step = make_step("step1");
+    step.parameters = Some(vec![arazzo_core::types::ParameterOrReusable::Parameter(
+        arazzo_core::types::Parameter {
+            name: "sort".to_string(),
+            r#in: Some(arazzo_core::types::ParameterLocation::Query),
+            value: serde_json::json!("desc"),
+            extensions: Default::default(),
+        },
+    )]);
+
+    for fill_parameter_defaults in [false, true] {
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &step,
+            &op,
+            &serde_json::json!({}),
+            &AttemptSetup {
+                fill_parameter_defaults,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(result, StepResult::Succeeded { .. }));
+
+        let url = http.last_url.lock().unwrap().clone().unwrap();
+        assert_eq!(
+            url.contains("limit=20"),
+            fill_parameter_defaults,
+            "url: {url}"
+        );
+        assert!(url.contains("sort=desc"), "url: {url}");
+        assert!(!url.contains("sort=asc"), "url: {url}");
+    }
+}
+
+#[tokio::test]
+async fn source_policy_injects_headers_only_for_that_source() {
+    let store = MockStore::default();
+    let http = CapturingHttpClient::default();
+    let secrets = StaticSecretsProvider("k-123");
+    let mut policy = make_policy();
+    policy.per_source.insert(
+        "petstore".to_string(),
+        arazzo_exec::policy::SourcePolicyConfig {
+            inject_headers: [(
+                "X-Api-Key".to_string(),
+                "secrets://PETSTORE_KEY".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    );
+
+    for (source, expected) in [("petstore", Some("k-123")), ("billing", None)] {
+        let mut op = make_resolved_op();
+        op.source_name = source.to_string();
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &make_step("step1"),
+            &op,
+            &serde_json::json!({}),
+            &AttemptSetup {
+                policy: policy.clone(),
+                secrets: &secrets,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(result, StepResult::Succeeded { .. }));
+
+        let headers = http.last_headers.lock().unwrap().clone();
+        assert_eq!(
+            headers.get("X-Api-Key").map(String::as_str),
+            expected,
+            "source {source}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn source_timeout_overrides_the_default_only_for_that_source() {
+    let store = MockStore::default();
+    let http = CapturingHttpClient::default();
+    let mut policy = make_policy();
+    policy.per_source.insert(
+        "reports".to_string(),
+        arazzo_exec::policy::SourcePolicyConfig {
+            timeout: Some(Duration::from_secs(300)),
+            ..Default::default()
+        },
+    );
+
+    for (source, expected) in [
+        ("reports", Duration::from_secs(300)),
+        ("users", Duration::from_secs(30)),
+    ] {
+        let mut op = make_resolved_op();
+        op.source_name = source.to_string();
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &make_step("step1"),
+            &op,
+            &serde_json::json!({}),
+            &AttemptSetup {
+                policy: policy.clone(),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(result, StepResult::Succeeded { .. }));
+        assert_eq!(
+            *http.last_timeout.lock().unwrap(),
+            Some(expected),
+            "source {source}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn url_secrets_are_resolved_only_for_sources_that_allow_them() {
+    let store = MockStore::default();
+    let http = CapturingHttpClient::default();
+    let secrets = StaticSecretsProvider("sig-123");
+    let mut policy = make_policy();
+    policy.per_source.insert(
+        "signed".to_string(),
+        arazzo_exec::policy::SourcePolicyConfig {
+            allow_secrets_in_url: Some(true),
+            ..Default::default()
+        },
+    );
+    policy.per_source.insert(
+        "strict".to_string(),
+        arazzo_exec::policy::SourcePolicyConfig {
+            allow_secrets_in_url: Some(false),
+            ..Default::default()
+        },
+    );
+
+    let mut step = make_step("step1");
+    step.parameters = Some(vec![arazzo_core::types::ParameterOrReusable::Parameter(
+        arazzo_core::types::Parameter {
+            name: "signature".to_string(),
+            r#in: Some(arazzo_core::types::ParameterLocation::Query),
+            value: serde_json::json!("secrets://URL_SIGNATURE"),
+            extensions: Default::default(),
+        },
+    )]);
+
+    for (source, resolved) in [("signed", true), ("strict", false)] {
+        let mut op = make_resolved_op();
+        op.source_name = source.to_string();
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &step,
+            &op,
+            &serde_json::json!({}),
+            &AttemptSetup {
+                policy: policy.clone(),
+                secrets: &secrets,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(result, StepResult::Succeeded { .. }));
+
+        let url = http.last_url.lock().unwrap().clone().unwrap();
+        assert_eq!(
+            url.contains("signature=sig-123"),
+            resolved,
+            "{source}: {url}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn unresolved_header_secret_fails_the_step_in_strict_mode() {
+    let store = MockStore::default();
+
+    let mut step = make_step("step1");
+    step.parameters = Some(vec![arazzo_core::types::ParameterOrReusable::Parameter(
+        arazzo_core::types::Parameter {
+            name: "Authorization".to_string(),
+            r#in: Some(arazzo_core::types::ParameterLocation::Header),
+            value: serde_json::json!("secrets://MISSING_TOKEN"),
+            extensions: Default::default(),
+        },
+    )]);
+
+    for strict in [false, true] {
+        let http = CapturingHttpClient::default();
+        let mut policy = make_policy();
+        policy.strict_secrets = strict;
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &step,
+            &make_resolved_op(),
+            &serde_json::json!({}),
+            &AttemptSetup {
+                policy,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        if strict {
+            let StepResult::Failed { error, end_run } = result else {
+                panic!("expected the step to fail in strict mode");
+            };
+            assert!(end_run);
+            assert_eq!(error["kind"], "unresolved_secret");
+            assert!(error["message"]
+                .as_str()
+                .unwrap()
+                .contains("secrets://MISSING_TOKEN"));
+            assert!(http.last_url.lock().unwrap().is_none());
+        } else {
+            assert!(matches!(result, StepResult::Succeeded { .. }));
+            let headers = http.last_headers.lock().unwrap().clone();
+            assert_eq!(
+                headers.get("Authorization").map(String::as_str),
+                Some("secrets://MISSING_TOKEN")
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn workflow_default_parameters_apply_to_every_step_unless_overridden() {
+    let store = MockStore::default();
+    let http = CapturingHttpClient::default();
+
+    let mut workflow = make_workflow();
+    workflow.extensions.insert(
+        arazzo_exec::compile::DEFAULT_PARAMETERS_EXTENSION.to_string(),
+        serde_json::json!([
+            { "name": "X-Tenant", "in": "header", "value": "acme" },
+            { "name": "X-Trace", "in": "header", "value": "on" }
+        ]),
+    );
+
+    let plain = make_step("step1");
+    let mut overriding = make_step("step2");
+    overriding.parameters = Some(vec![arazzo_core::types::ParameterOrReusable::Parameter(
+        arazzo_core::types::Parameter {
+            name: "x-tenant".to_string(),
+            r#in: Some(arazzo_core::types::ParameterLocation::Header),
+            value: serde_json::json!("globex"),
+            extensions: Default::default(),
+        },
+    )]);
+
+    for (step, tenant_header, tenant) in [
+        (&plain, "X-Tenant", "acme"),
+        (&overriding, "x-tenant", "globex"),
+    ] {
+        let result = run_attempt_with(
+            &http,
+            &store,
+            step,
+            &make_resolved_op(),
+            &serde_json::json!({}),
+            &AttemptSetup {
+                workflow: workflow.clone(),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(result, StepResult::Succeeded { .. }));
+
+        let headers = http.last_headers.lock().unwrap().clone();
+        assert_eq!(
+            headers.get(tenant_header).map(String::as_str),
+            Some(tenant),
+            "step {}",
+            step.step_id
+        );
+        let tenant_headers = headers
+            .keys()
+            .filter(|k| k.eq_ignore_ascii_case("x-tenant"))
+            .count();
+        assert_eq!(tenant_headers, 1, "step {}", step.step_id);
+        assert_eq!(headers.get("X-Trace").map(String::as_str), Some("on"));
+    }
+}
+
+#[tokio::test]
+async fn idempotent_step_sends_the_same_key_on_every_retry() {
+    let store = MockStore::default();
+    let http = FlakyHttpClient::default();
+
+    let mut op = make_resolved_op();
+    op.method = "POST".to_string();
+    let mut step = make_step("createUser");
+    step.extensions
+        .insert(IDEMPOTENT_EXTENSION.to_string(), serde_json::json!(true));
+    step.on_failure = Some(vec![arazzo_core::types::FailureActionOrReusable::Action(
+        arazzo_core::types::FailureAction {
+            name: "retry".to_string(),
+            action_type: arazzo_core::types::FailureActionType::Retry,
+            retry_limit: Some(3),
+            retry_after_seconds: Some(0.0),
+            step_id: None,
+            workflow_id: None,
+            criteria: None,
+            extensions: Default::default(),
+        },
+    )]);
+
+    let run_id = uuid::Uuid::new_v4();
+    let step_row_id = uuid::Uuid::new_v4();
+    let mut results = Vec::new();
+    for _ in 0..2 {
+        results.push(
+            run_attempt_with(
+                &http,
+                &store,
+                &step,
+                &op,
+                &serde_json::json!({}),
+                &AttemptSetup {
+                    run_id,
+                    step_row_id,
+                    ..Default::default()
+                },
+            )
+            .await,
+        );
+    }
+    assert!(matches!(results[0], StepResult::Retry { .. }));
+    assert!(matches!(results[1], StepResult::Succeeded { .. }));
+
+    let seen = http.seen_headers.lock().unwrap();
+    let keys: Vec<_> = seen
+        .iter()
+        .map(|h| {
+            h.get(IDEMPOTENCY_KEY_HEADER)
+                .cloned()
+                .expect("idempotency key")
+        })
+        .collect();
+    assert_eq!(keys[0], keys[1]);
+    assert!(uuid::Uuid::parse_str(&keys[0]).is_ok());
+}
+
+#[tokio::test]
+async fn redirect_302_is_followed_accepted_or_failed_per_policy() {
+    let cases = [
+        ("follow", true, vec![], true, 2),
+        ("treat-as-success", true, vec![302], true, 1),
+        ("fail", false, vec![], false, 1),
+    ];
+    for (name, follow, success_statuses, succeeds, requests) in cases {
+        let store = MockStore::default();
+        let http = RedirectingHttpClient::default();
+        let mut policy = make_policy();
+        policy.network.redirects = arazzo_exec::policy::RedirectPolicy {
+            follow,
+            max_redirects: 5,
+            success_statuses: success_statuses.into_iter().collect(),
+        };
+
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &make_step("step1"),
+            &make_resolved_op(),
+            &serde_json::json!({}),
+            &AttemptSetup {
+                policy,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(
+            matches!(result, StepResult::Succeeded { .. }),
+            succeeds,
+            "{name}: {result:?}"
+        );
+
+        let urls = http.urls.lock().unwrap();
+        assert_eq!(urls.len(), requests, "{name}: {urls:?}");
+        if requests == 2 {
+            assert_eq!(urls[1], "https://api.test.local/users/next");
+        }
+    }
+}
+
+#[tokio::test]
+async fn head_operation_with_empty_body_succeeds() {
+    let store = MockStore::default();
+    let http = MockHttpClient {
+        response: HttpResponseParts {
+            status: 200,
+            headers: [("Content-Length".to_string(), "512".to_string())]
+                .into_iter()
+                .collect(),
+            repeated_headers: BTreeMap::new(),
+            body: Vec::new(),
+        },
+        fail_with: None,
+    };
+
+    let mut op = make_resolved_op();
+    op.method = "HEAD".to_string();
+    let mut step = make_step("step1");
+    step.outputs = Some(
+        [
+            ("length", "$response.header.Content-Length"),
+            ("body", "$response.body"),
+            ("id", "$response.body#/id"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect(),
+    );
+
+    let result = run_attempt_with(
+        &http,
+        &store,
+        &step,
+        &op,
+        &serde_json::json!({}),
+        &AttemptSetup::default(),
+    )
+    .await;
+
+    match result {
+        StepResult::Succeeded { outputs } => assert_eq!(
+            outputs,
+            serde_json::json!({ "length": "512", "body": null, "id": null })
+        ),
+        StepResult::Failed { error, .. } => panic!("expected Succeeded, got Failed: {error}"),
+        StepResult::Retry { error, .. } => panic!("expected Succeeded, got Retry: {error}"),
+    }
+}
+
+#[tokio::test]
+async fn options_response_without_content_length_keeps_its_body() {
+    let store = MockStore::default();
+    let http = MockHttpClient {
+        response: HttpResponseParts {
+            status: 200,
+            headers: [("Allow".to_string(), "GET, POST".to_string())]
+                .into_iter()
+                .collect(),
+            repeated_headers: BTreeMap::new(),
+            body: br#"{"methods":["GET","POST"]}"#.to_vec(),
+        },
+        fail_with: None,
+    };
+
+    let mut op = make_resolved_op();
+    op.method = "OPTIONS".to_string();
+    let mut step = make_step("step1");
+    step.outputs = Some(
+        [("first", "$response.body#/methods/0")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    );
+
+    let result = run_attempt_with(
+        &http,
+        &store,
+        &step,
+        &op,
+        &serde_json::json!({}),
+        &AttemptSetup::default(),
+    )
+    .await;
+
+    match result {
+        StepResult::Succeeded { outputs } => {
+            assert_eq!(outputs, serde_json::json!({ "first": "GET" }))
+        }
+        StepResult::Failed { error, .. } => panic!("expected Succeeded, got Failed: {error}"),
+        StepResult::Retry { error, .. } => panic!("expected Succeeded, got Retry: {error}"),
+    }
+}
+
+#[tokio::test]
+async fn source_auth_is_applied_only_to_its_source() {
+    let store = MockStore::default();
+    let secrets = StaticSecretsProvider("petstore-token");
+
+    let auth = arazzo_exec::policy::parse_auth_config(
+        "petstore:\n  scheme: bearer\n  token: secrets://PETSTORE_TOKEN\n",
+    )
+    .unwrap();
+    let mut policy = make_policy();
+    for (source, auth) in auth {
+        policy.per_source.insert(
+            source,
+            arazzo_exec::policy::SourcePolicyConfig {
+                auth: Some(auth),
+                ..Default::default()
+            },
+        );
+    }
+
+    for (source, expected) in [("petstore", Some("Bearer petstore-token")), ("other", None)] {
+        let http = CapturingHttpClient::default();
+        let mut op = make_resolved_op();
+        op.source_name = source.to_string();
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &make_step("step1"),
+            &op,
+            &serde_json::json!({}),
+            &AttemptSetup {
+                policy: policy.clone(),
+                secrets: &secrets,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(result, StepResult::Succeeded { .. }));
+        let headers = http.last_headers.lock().unwrap().clone();
+        assert_eq!(
+            headers.get("Authorization").map(String::as_str),
+            expected,
+            "source {source}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn debug_snapshot_lists_resolved_parameters_with_secrets_redacted() {
+    let store = MockStore::default();
+    let http = CapturingHttpClient::default();
+    let secrets = StaticSecretsProvider("k-123");
+    let event_sink = RecordingEventSink::default();
+
+    let param = |name: &str, location, value: serde_json::Value| {
+        arazzo_core::types::ParameterOrReusable::Parameter(arazzo_core::types::Parameter {
+            name: name.to_string(),
+            r#in: Some(location),
+            value,
+            extensions: Default::default(),
+        })
+    };
+    let mut step = make_step("step1");
+    step.parameters = Some(vec![
+        param(
+            "X-Api-Key",
+            arazzo_core::types::ParameterLocation::Header,
+            serde_json::json!("secrets://API_KEY"),
+        ),
+        param(
+            "limit",
+            arazzo_core::types::ParameterLocation::Query,
+            serde_json::json!("$inputs.limit"),
+        ),
+        param(
+            "Authorization",
+            arazzo_core::types::ParameterLocation::Header,
+            serde_json::json!("Bearer abc"),
+        ),
+    ]);
+    step.request_body = Some(arazzo_core::types::RequestBody {
+        content_type: Some("application/json".to_string()),
+        payload: Some(serde_json::json!({ "name": "rex", "token": "secrets://TOKEN" })),
+        replacements: None,
+        extensions: Default::default(),
+    });
+
+    let result = run_attempt_with(
+        &http,
+        &store,
+        &step,
+        &make_resolved_op(),
+        &serde_json::json!({ "limit": 10 }),
+        &AttemptSetup {
+            secrets: &secrets,
+            event_sink: &event_sink,
+            debug_requests: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(matches!(result, StepResult::Succeeded { .. }));
+
+    let events = event_sink.events.lock().unwrap();
+    let (parameters, body) = events
+        .iter()
+        .find_map(|e| match e {
+            arazzo_exec::executor::Event::StepDebug {
+                parameters, body, ..
+            } => Some((parameters.clone(), body.clone())),
+            _ => None,
+        })
+        .expect("expected a StepDebug event");
+
+    let summary: Vec<(&str, &str, &str, bool)> = parameters
+        .iter()
+        .map(|p| (p.name.as_str(), p.location, p.value.as_str(), p.secret))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("X-Api-Key", "header", "<redacted>", true),
+            ("limit", "query", "10", false),
+            // Not a secret, but a header the policy always redacts.
+            ("Authorization", "header", "<redacted>", false),
+        ]
+    );
+    let body: serde_json::Value = serde_json::from_str(&body.unwrap()).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({ "name": "rex", "token": "<redacted>" })
+    );
+    // The request itself still carries the real secret.
+    assert_eq!(
+        http.last_headers
+            .lock()
+            .unwrap()
+            .get("X-Api-Key")
+            .map(String::as_str),
+        Some("k-123")
+    );
+}
+
+#[tokio::test]
+async fn top_level_array_body_resolves_expressions_in_each_element() {
+    let store = MockStore {
+        step_outputs: BTreeMap::from([(
+            "createOwner".to_string(),
+            serde_json::json!({ "ownerId": 42 }),
+        )]),
+    };
+    let http = CapturingHttpClient::default();
+
+    let mut step = make_step("createPets");
+    step.request_body = Some(arazzo_core::types::RequestBody {
+        content_type: Some("application/json".to_string()),
+        payload: Some(serde_json::json!([
+            { "name": "$inputs.first", "owner": "$steps.createOwner.outputs.ownerId" },
+            { "name": "$inputs.second", "owner": "$steps.createOwner.outputs.ownerId" },
+            "tag-{$inputs.tag}"
+        ])),
+        replacements: None,
+        extensions: Default::default(),
+    });
+
+    let result = run_attempt_with(
+        &http,
+        &store,
+        &step,
+        &make_resolved_op(),
+        &serde_json::json!({ "first": "rex", "second": "fido", "tag": "dogs" }),
+        &AttemptSetup::default(),
+    )
+    .await;
+    assert!(matches!(result, StepResult::Succeeded { .. }));
+
+    let body: serde_json::Value = serde_json::from_slice(&http.last_body.lock().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!([
+            { "name": "rex", "owner": 42 },
+            { "name": "fido", "owner": 42 },
+            "tag-dogs"
+        ])
+    );
+    assert_eq!(
+        http.last_headers
+            .lock()
+            .unwrap()
+            .get("Content-Type")
+            .map(String::as_str),
+        Some("application/json")
+    );
+}
+
+#[tokio::test]
+async fn body_is_encoded_for_its_content_type() {
+    let store = MockStore::default();
+    let http = CapturingHttpClient::default();
+
+    let cases = [
+        (
+            "application/x-www-form-urlencoded",
+            serde_json::json!({ "name": "$inputs.name", "tags": ["a b", "c"], "age": 3 }),
+            Some("age=3&name=rex&tags=a+b&tags=c"),
+        ),
+        (
+            "text/plain; charset=utf-8",
+            serde_json::json!("hello {$inputs.name}"),
+            Some("hello rex"),
+        ),
+        (
+            "application/xml",
+            serde_json::json!("<pet/>"),
+            Some("<pet/>"),
+        ),
+        (
+            "application/xml",
+            serde_json::json!({ "name": "$inputs.name" }),
+            None,
+        ),
+        (
+            "application/x-www-form-urlencoded",
+            serde_json::json!({ "owner": { "id": 1 } }),
+            None,
+        ),
+    ];
+    for (content_type, payload, expected) in cases {
+        let mut step = make_step("createPet");
+        step.request_body = Some(arazzo_core::types::RequestBody {
+            content_type: Some(content_type.to_string()),
+            payload: Some(payload),
+            replacements: None,
+            extensions: Default::default(),
+        });
+        http.last_body.lock().unwrap().clear();
+
+        let result = run_attempt_with(
+            &http,
+            &store,
+            &step,
+            &make_resolved_op(),
+            &serde_json::json!({ "name": "rex" }),
+            &AttemptSetup::default(),
+        )
+        .await;
+        match expected {
+            Some(body) => {
+                assert!(
+                    matches!(result, StepResult::Succeeded { .. }),
+                    "{content_type}"
+                );
+                assert_eq!(
+                    String::from_utf8(http.last_body.lock().unwrap().clone()).unwrap(),
+                    body
+                );
+                assert_eq!(
+                    http.last_headers.lock().unwrap().get("Content-Type"),
+                    Some(&content_type.to_string())
+                );
+            }
+            None => assert_eq!(build_error_kind(result), "unsupported_body"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn policy_check_redacts_query_api_keys_from_the_reported_url() {
+    let auth = arazzo_exec::policy::parse_auth_config(
+        "petstore:\n  scheme: apikey\n  name: api_key\n  value: k-123\n  in: query\n",
+    )
+    .unwrap();
+    let mut policy = make_policy();
+    for (source, auth) in auth {
+        policy.per_source.insert(
+            source,
+            arazzo_exec::policy::SourcePolicyConfig {
+                auth: Some(auth),
+                ..Default::default()
+            },
+        );
+    }
+    let compiled = arazzo_exec::CompiledPlan {
+        diagnostics: Vec::new(),
+        steps: vec![arazzo_exec::compile::CompiledStep {
+            step_id: "step1".to_string(),
+            operation: Some(make_resolved_op()),
+            diagnostics: Vec::new(),
+            missing_required_parameters: Vec::new(),
+            request_body: None,
+            missing_required_request_body: false,
+            fill_parameter_defaults: false,
+        }],
+    };
+
+    let checks = arazzo_exec::executor::check_policy(
+        &PolicyGate::new(policy),
+        &make_workflow(),
+        &compiled,
+        &serde_json::json!({}),
+        None,
+    )
+    .await;
+    match &checks[0].decision {
+        arazzo_exec::executor::PolicyDecision::Allowed { url, .. } => {
+            assert_eq!(url, "https://api.test.local/users?api_key=%3Credacted%3E")
+        }
+        other => panic!("expected Allowed, got {other:?}"),
+    }
+}
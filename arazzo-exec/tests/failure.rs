@@ -2,6 +2,7 @@ use arazzo_core::types::{FailureAction, FailureActionOrReusable, FailureActionTy
 use arazzo_exec::executor::failure::{decide_failure, decide_network_failure};
 use arazzo_exec::executor::http::HttpError;
 use arazzo_exec::executor::worker::StepResult;
+use arazzo_exec::executor::RngSource;
 use arazzo_exec::policy::HttpResponseParts;
 use arazzo_exec::retry::RetryConfig;
 use std::collections::BTreeMap;
@@ -10,6 +11,7 @@ fn make_response(status: u16) -> HttpResponseParts {
     HttpResponseParts {
         status,
         headers: BTreeMap::new(),
+        repeated_headers: BTreeMap::new(),
         body: vec![],
     }
 }
@@ -45,12 +47,14 @@ fn decide_failure_returns_retry_when_retry_action_present() {
         extensions: BTreeMap::new(),
     })]);
 
-    let mut retry_cfg = RetryConfig::default();
-    retry_cfg.max_attempts = 5;
-    retry_cfg.max_delay = std::time::Duration::from_secs(10);
+    let mut retry_cfg = RetryConfig {
+        max_attempts: 5,
+        max_delay: std::time::Duration::from_secs(10),
+        ..Default::default()
+    };
     retry_cfg.retry_statuses.insert(500);
     let resp = make_response(500);
-    let result = decide_failure(&retry_cfg, &step, 1, &resp);
+    let result = decide_failure(&retry_cfg, &step, 1, &resp, &RngSource::default());
 
     match result {
         StepResult::Retry { delay_ms, .. } => {
@@ -76,7 +80,7 @@ fn decide_failure_returns_end_when_end_action_present() {
 
     let retry_cfg = RetryConfig::default();
     let resp = make_response(500);
-    let result = decide_failure(&retry_cfg, &step, 1, &resp);
+    let result = decide_failure(&retry_cfg, &step, 1, &resp, &RngSource::default());
 
     match result {
         StepResult::Failed { end_run, .. } => {
@@ -92,7 +96,7 @@ fn decide_failure_defaults_to_failed_when_no_actions() {
 
     let retry_cfg = RetryConfig::default();
     let resp = make_response(500);
-    let result = decide_failure(&retry_cfg, &step, 1, &resp);
+    let result = decide_failure(&retry_cfg, &step, 1, &resp, &RngSource::default());
 
     match result {
         StepResult::Failed { end_run, .. } => {
@@ -116,11 +120,13 @@ fn decide_network_failure_returns_retry_when_retry_action_present() {
         extensions: BTreeMap::new(),
     })]);
 
-    let mut retry_cfg = RetryConfig::default();
-    retry_cfg.max_attempts = 5;
-    retry_cfg.max_delay = std::time::Duration::from_secs(10);
+    let retry_cfg = RetryConfig {
+        max_attempts: 5,
+        max_delay: std::time::Duration::from_secs(10),
+        ..Default::default()
+    };
     let err = HttpError::Timeout;
-    let result = decide_network_failure(&retry_cfg, &step, 1, &err);
+    let result = decide_network_failure(&retry_cfg, &step, 1, &err, &RngSource::default());
 
     match result {
         StepResult::Retry { delay_ms, .. } => {
@@ -136,7 +142,7 @@ fn decide_network_failure_defaults_to_failed_when_no_retry() {
 
     let retry_cfg = RetryConfig::default();
     let err = HttpError::Network("connection failed".to_string());
-    let result = decide_network_failure(&retry_cfg, &step, 1, &err);
+    let result = decide_network_failure(&retry_cfg, &step, 1, &err, &RngSource::default());
 
     match result {
         StepResult::Failed { end_run, .. } => {
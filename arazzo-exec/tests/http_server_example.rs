@@ -0,0 +1,199 @@
+//! Drives the `http_server` example's handlers against an in-memory store.
+
+#[path = "../examples/http_server/app.rs"]
+#[allow(dead_code)]
+mod app;
+mod support;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arazzo_exec::executor::{ExecutorConfig, HttpClient, HttpError, NoOpEventSink};
+use arazzo_exec::openapi::{OpenApiDoc, ResolvedSources};
+use arazzo_exec::policy::{HttpRequestParts, HttpResponseParts, PolicyConfig, PolicyGate};
+use arazzo_exec::secrets::{SecretValue, SecretsProvider};
+use arazzo_exec::{Compiler, Executor};
+use arazzo_store::StateStore;
+use async_trait::async_trait;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::{Request, StatusCode};
+use serde_json::{json, Value as JsonValue};
+use support::MemoryStore;
+use uuid::Uuid;
+
+struct OkHttpClient;
+
+#[async_trait]
+impl HttpClient for OkHttpClient {
+    async fn send(
+        &self,
+        _req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: br#"{"id":42}"#.to_vec(),
+        })
+    }
+}
+
+struct NoOpSecretsProvider;
+
+#[async_trait]
+impl SecretsProvider for NoOpSecretsProvider {
+    async fn get(
+        &self,
+        ref_: &arazzo_exec::secrets::SecretRef,
+    ) -> Result<SecretValue, arazzo_exec::secrets::SecretError> {
+        Err(arazzo_exec::secrets::SecretError::NotFound(ref_.clone()))
+    }
+}
+
+const WORKFLOW: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petstore
+    url: https://specs.example.com/petstore.yaml
+workflows:
+  - workflowId: create-and-fetch
+    steps:
+      - stepId: create
+        operationId: createUser
+        outputs:
+          userId: $response.body#/id
+      - stepId: fetch
+        operationId: getUser
+        parameters:
+          - name: id
+            in: path
+            value: $steps.create.outputs.userId
+"#;
+
+fn app_state() -> (Arc<MemoryStore>, Arc<app::AppState>) {
+    let mut sources = ResolvedSources::default();
+    sources.openapi_docs.insert(
+        "petstore".to_string(),
+        OpenApiDoc {
+            source_url: "petstore.yaml".to_string(),
+            raw: json!({
+                "openapi": "3.0.0",
+                "info": { "title": "Pets", "version": "1.0.0" },
+                "servers": [{ "url": "https://api.test.local" }],
+                "paths": {
+                    "/users": {
+                        "post": {
+                            "operationId": "createUser",
+                            "responses": { "200": { "description": "ok" } }
+                        }
+                    },
+                    "/users/{id}": {
+                        "get": {
+                            "operationId": "getUser",
+                            "parameters": [
+                                { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+                            ],
+                            "responses": { "200": { "description": "ok" } }
+                        }
+                    }
+                }
+            }),
+            base_url: None,
+        },
+    );
+
+    let mut policy = PolicyConfig::default();
+    policy.network.allowed_hosts = ["api.test.local".to_string()].into_iter().collect();
+
+    let store = Arc::new(MemoryStore::default());
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(OkHttpClient),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(policy)),
+        Arc::new(NoOpEventSink),
+    );
+    let state = Arc::new(app::AppState {
+        store: store.clone(),
+        executor: Arc::new(executor),
+        compiler: Compiler::default().with_sources(sources),
+    });
+    (store, state)
+}
+
+async fn call(
+    state: &Arc<app::AppState>,
+    method: &str,
+    uri: &str,
+    body: JsonValue,
+) -> (StatusCode, JsonValue) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap();
+    let resp = app::handle(state.clone(), req).await;
+    let status = resp.status();
+    let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn posted_workflow_runs_to_completion_and_reports_status() {
+    let (store, state) = app_state();
+
+    let (status, body) = call(&state, "POST", "/runs", json!({ "document": WORKFLOW })).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{body}");
+    let run_id = body["run_id"].as_str().unwrap().to_string();
+
+    let mut run_status = JsonValue::Null;
+    for _ in 0..200 {
+        let (status, body) = call(&state, "GET", &format!("/runs/{run_id}"), JsonValue::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        run_status = body["status"].clone();
+        if run_status == "succeeded" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(run_status, "succeeded");
+
+    let run_id = Uuid::parse_str(&run_id).unwrap();
+    let outputs = store.get_step_outputs(run_id, "create").await.unwrap();
+    assert_eq!(outputs, json!({ "userId": 42 }));
+}
+
+#[tokio::test]
+async fn invalid_documents_and_unknown_runs_are_rejected() {
+    let (_, state) = app_state();
+
+    let (status, body) = call(
+        &state,
+        "POST",
+        "/runs",
+        json!({ "document": "arazzo: 1.0.1" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["error"].is_string());
+
+    let (status, _) = call(
+        &state,
+        "GET",
+        &format!("/runs/{}", Uuid::new_v4()),
+        JsonValue::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
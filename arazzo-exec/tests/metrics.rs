@@ -95,3 +95,71 @@ fn metrics_to_json() {
     assert_eq!(json["steps"]["succeeded"], 1);
     assert_eq!(json["http"]["requests"], 1);
 }
+
+#[cfg(feature = "statsd")]
+#[tokio::test]
+async fn completed_steps_are_sent_to_statsd_with_tags() {
+    use arazzo_exec::executor::StatsdEmitter;
+
+    let agent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let emitter = StatsdEmitter::connect(agent.local_addr().unwrap())
+        .await
+        .unwrap()
+        .with_prefix("myapp.arazzo")
+        .with_tag("env", "test");
+    let collector =
+        MetricsCollector::new(Uuid::new_v4(), "checkout".to_string()).with_statsd(emitter);
+
+    collector.record_step_started("pay").await;
+    collector.record_step_completion("pay", true).await;
+    collector.record_step_completion("ship", false).await;
+
+    let mut packets = Vec::new();
+    let mut buf = [0u8; 512];
+    for _ in 0..3 {
+        let n = tokio::time::timeout(std::time::Duration::from_secs(5), agent.recv(&mut buf))
+            .await
+            .expect("statsd packet")
+            .unwrap();
+        packets.push(String::from_utf8(buf[..n].to_vec()).unwrap());
+    }
+
+    assert_eq!(
+        packets[0],
+        "myapp.arazzo.step.succeeded:1|c|#env:test,workflow_id:checkout,step_id:pay"
+    );
+    assert!(packets[1].starts_with("myapp.arazzo.step.duration:"));
+    assert!(packets[1].ends_with("|ms|#env:test,workflow_id:checkout,step_id:pay"));
+    // `ship` never started, so it only has a counter.
+    assert_eq!(
+        packets[2],
+        "myapp.arazzo.step.failed:1|c|#env:test,workflow_id:checkout,step_id:ship"
+    );
+    assert_eq!(collector.get_metrics().await.steps_total, 2);
+}
+
+#[cfg(feature = "statsd")]
+#[tokio::test]
+async fn statsd_reaches_an_ipv6_agent() {
+    use arazzo_exec::executor::StatsdEmitter;
+
+    // Hosts without IPv6 loopback cannot run this.
+    let Ok(agent) = tokio::net::UdpSocket::bind("[::1]:0").await else {
+        return;
+    };
+    let emitter = StatsdEmitter::connect(agent.local_addr().unwrap())
+        .await
+        .unwrap()
+        .with_prefix("");
+    let collector =
+        MetricsCollector::new(Uuid::new_v4(), "checkout".to_string()).with_statsd(emitter);
+
+    collector.record_step_completion("pay", true).await;
+
+    let mut buf = [0u8; 512];
+    let n = tokio::time::timeout(std::time::Duration::from_secs(5), agent.recv(&mut buf))
+        .await
+        .expect("statsd packet")
+        .unwrap();
+    assert!(String::from_utf8_lossy(&buf[..n]).starts_with("step.succeeded:1|c"));
+}
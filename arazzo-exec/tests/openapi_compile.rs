@@ -1,6 +1,10 @@
-use std::io::Write;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use arazzo_core::{parse_document_str, DocumentFormat};
+use arazzo_exec::openapi::{Loader, OpenApiResolver};
 use arazzo_exec::Compiler;
 
 fn write_temp(contents: &str) -> tempfile::NamedTempFile {
@@ -100,6 +104,62 @@ workflows:
     );
 }
 
+#[tokio::test]
+async fn null_payload_for_required_request_body_is_diagnosed() {
+    let openapi = r#"
+openapi: 3.0.0
+info: { title: Store API, version: 1.0.0 }
+paths:
+  /orders:
+    post:
+      operationId: createOrder
+      requestBody:
+        required: true
+        content:
+          application/json: {}
+      responses: { "200": { description: ok } }
+"#;
+    let openapi_file = write_temp(openapi);
+
+    let arazzo = format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: storeApi
+    url: {}
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: createOrder
+        requestBody:
+          contentType: application/json
+          payload: null
+"#,
+        openapi_file.path().to_string_lossy()
+    );
+
+    let doc = parse_document_str(&arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let compiled = Compiler::default()
+        .compile_workflow(&doc, &doc.workflows[0])
+        .await;
+
+    let step = &compiled.steps[0];
+    assert!(step.missing_required_request_body);
+    assert!(
+        step.diagnostics.iter().any(|d| d.severity
+            == arazzo_exec::openapi::DiagnosticSeverity::Error
+            && d.message == "required requestBody has an empty payload"),
+        "diagnostics: {:?}",
+        step.diagnostics
+    );
+}
+
 #[tokio::test]
 async fn unqualified_operation_id_is_ambiguous_across_sources() {
     let openapi_a = r#"
@@ -224,3 +284,635 @@ workflows:
         step.diagnostics
     );
 }
+
+#[tokio::test]
+async fn parameter_defaults_are_compiled_and_filled_only_when_opted_in() {
+    let openapi = r#"
+openapi: 3.0.0
+info: { title: Pets, version: 1.0.0 }
+servers:
+  - url: https://api.test.local
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: limit
+          in: query
+          schema: { type: integer, default: 20 }
+      responses: { "200": { description: ok } }
+"#;
+    let f = write_temp(openapi);
+
+    let arazzo = format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: pets
+    url: {}
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: listPets
+"#,
+        f.path().to_string_lossy()
+    );
+
+    let doc = parse_document_str(&arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let wf = &doc.workflows[0];
+
+    let default_of = |plan: &arazzo_exec::CompiledPlan| {
+        plan.steps[0]
+            .operation
+            .as_ref()
+            .expect("operation resolved")
+            .shape
+            .parameters
+            .iter()
+            .find(|p| p.name == "limit")
+            .expect("limit param")
+            .default
+            .clone()
+    };
+
+    // Defaults are always compiled; only steps marked to fill them send them.
+    let plain = Compiler::default().compile_workflow(&doc, wf).await;
+    assert_eq!(default_of(&plain), Some(serde_json::json!(20)));
+    assert!(!plain.steps[0].fill_parameter_defaults);
+
+    let with_defaults = plain.with_parameter_defaults(true);
+    assert!(with_defaults.steps[0].fill_parameter_defaults);
+}
+
+#[tokio::test]
+async fn ignore_missing_params_extension_downgrades_diagnostic_to_warning() {
+    use arazzo_exec::compile::MissingParameterSeverity;
+    use arazzo_exec::openapi::DiagnosticSeverity;
+
+    let openapi = r#"
+openapi: 3.0.0
+info: { title: Pets, version: 1.0.0 }
+servers:
+  - url: https://api.test.local
+paths:
+  /pets/{petId}:
+    get:
+      operationId: getPet
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema: { type: string }
+      responses: { "200": { description: ok } }
+"#;
+    let f = write_temp(openapi);
+
+    let arazzo = format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: pets
+    url: {}
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: strict
+        operationId: getPet
+      - stepId: lenient
+        operationId: getPet
+        x-ignore-missing-params: true
+      - stepId: quiet
+        operationId: getPet
+        x-ignore-missing-params: suppress
+"#,
+        f.path().to_string_lossy()
+    );
+
+    let doc = parse_document_str(&arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let wf = &doc.workflows[0];
+
+    let missing_diag = |step: &arazzo_exec::CompiledStep| {
+        step.diagnostics
+            .iter()
+            .find(|d| d.message.starts_with("missing required parameters"))
+            .map(|d| d.severity)
+    };
+
+    let plan = Compiler::default().compile_workflow(&doc, wf).await;
+    assert_eq!(
+        missing_diag(&plan.steps[0]),
+        Some(DiagnosticSeverity::Error)
+    );
+    assert_eq!(
+        missing_diag(&plan.steps[1]),
+        Some(DiagnosticSeverity::Warning)
+    );
+    assert_eq!(missing_diag(&plan.steps[2]), None);
+    assert_eq!(plan.steps[1].missing_required_parameters.len(), 1);
+
+    let lenient = Compiler::default()
+        .with_missing_parameter_severity(MissingParameterSeverity::Warning)
+        .compile_workflow(&doc, wf)
+        .await;
+    assert_eq!(
+        missing_diag(&lenient.steps[0]),
+        Some(DiagnosticSeverity::Warning)
+    );
+}
+
+/// Serve `body` over plain HTTP on a loopback port, counting the requests received.
+fn serve_counting(body: &'static str) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            counter.fetch_add(1, Ordering::SeqCst);
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/yaml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(resp.as_bytes());
+        }
+    });
+    (format!("http://{addr}/openapi.yaml"), hits)
+}
+
+#[tokio::test]
+async fn compile_document_resolves_sources_once_for_all_workflows() {
+    let openapi = r#"
+openapi: 3.0.0
+info: { title: Pets, version: 1.0.0 }
+servers:
+  - url: https://api.test.local
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses: { "200": { description: ok } }
+    post:
+      operationId: createPet
+      responses: { "201": { description: created } }
+"#;
+    let (url, hits) = serve_counting(openapi);
+
+    let arazzo = format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: pets
+    url: {url}
+workflows:
+  - workflowId: list
+    steps:
+      - stepId: s1
+        operationId: listPets
+  - workflowId: create
+    steps:
+      - stepId: s1
+        operationId: createPet
+"#
+    );
+
+    let doc = parse_document_str(&arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+
+    let plans = Compiler::default().compile_document(&doc).await;
+
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    let ids: Vec<&str> = plans.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, ["list", "create"]);
+    for (id, plan) in &plans {
+        let op = plan.steps[0]
+            .operation
+            .as_ref()
+            .unwrap_or_else(|| panic!("workflow {id} did not resolve: {:?}", plan.steps[0]));
+        assert_eq!(op.path, "/pets");
+    }
+}
+
+struct InMemoryLoader {
+    specs: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+#[async_trait::async_trait]
+impl Loader for InMemoryLoader {
+    async fn load(&self, url_or_path: &str) -> Result<serde_json::Value, String> {
+        self.specs
+            .get(url_or_path)
+            .cloned()
+            .ok_or_else(|| format!("no canned spec for {url_or_path}"))
+    }
+}
+
+#[tokio::test]
+async fn resolver_uses_injected_loader() {
+    let spec = serde_json::json!({
+        "openapi": "3.0.0",
+        "info": { "title": "Pets", "version": "1.0.0" },
+        "servers": [{ "url": "https://api.test.local" }],
+        "paths": {
+            "/pets/{id}": {
+                "get": {
+                    "operationId": "getPet",
+                    "parameters": [
+                        { "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }
+                    ],
+                    "responses": { "200": { "description": "ok" } }
+                }
+            }
+        }
+    });
+    let loader = InMemoryLoader {
+        specs: [("mem://pets".to_string(), spec)].into_iter().collect(),
+    };
+
+    let arazzo = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: pets
+    url: mem://pets
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: getPet
+        parameters:
+          - name: id
+            in: path
+            value: "1"
+"#;
+    let doc = parse_document_str(arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+
+    let plan = Compiler::default()
+        .with_resolver(OpenApiResolver::with_loader(loader))
+        .compile_workflow(&doc, &doc.workflows[0])
+        .await;
+
+    assert!(plan.diagnostics.is_empty(), "{:?}", plan.diagnostics);
+    let op = plan.steps[0]
+        .operation
+        .as_ref()
+        .expect("operation resolved");
+    assert_eq!(op.base_url, "https://api.test.local");
+    assert_eq!(op.method, "GET");
+    assert_eq!(op.path, "/pets/{id}");
+    assert!(plan.steps[0].missing_required_parameters.is_empty());
+}
+
+#[tokio::test]
+async fn source_override_replaces_url_and_base_url_before_loading() {
+    use arazzo_exec::openapi::SourceOverride;
+
+    let spec = serde_json::json!({
+        "openapi": "3.0.0",
+        "info": { "title": "Pets", "version": "1.0.0" },
+        "servers": [{ "url": "https://api.prod.local" }],
+        "paths": {
+            "/pets": {
+                "get": {
+                    "operationId": "listPets",
+                    "responses": { "200": { "description": "ok" } }
+                }
+            }
+        }
+    });
+    // Only the staging location is loadable, so resolving proves the override was used.
+    let loader = InMemoryLoader {
+        specs: [("mem://staging/pets".to_string(), spec)]
+            .into_iter()
+            .collect(),
+    };
+
+    let arazzo = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: pets
+    url: mem://prod/pets
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: listPets
+"#;
+    let doc = parse_document_str(arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+
+    let overrides = [(
+        "pets".to_string(),
+        SourceOverride {
+            url: Some("mem://staging/pets".to_string()),
+            base_url: Some("https://api.staging.local".to_string()),
+        },
+    )]
+    .into_iter()
+    .collect();
+    let resolver = OpenApiResolver::with_loader(loader).with_source_overrides(overrides);
+
+    let sources = resolver.resolve_sources(&doc).await;
+    assert!(sources.diagnostics.is_empty(), "{:?}", sources.diagnostics);
+    assert_eq!(
+        sources.openapi_docs["pets"].source_url,
+        "mem://staging/pets"
+    );
+
+    let plan = Compiler::default()
+        .with_resolver(resolver)
+        .compile_workflow(&doc, &doc.workflows[0])
+        .await;
+    let op = plan.steps[0]
+        .operation
+        .as_ref()
+        .expect("operation resolved");
+    assert_eq!(op.base_url, "https://api.staging.local");
+}
+
+#[tokio::test]
+async fn relative_source_url_resolves_against_the_workflow_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("specs")).unwrap();
+    std::fs::create_dir_all(dir.path().join("workflows")).unwrap();
+    std::fs::write(
+        dir.path().join("specs/api.yaml"),
+        r#"
+openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: ok
+"#,
+    )
+    .unwrap();
+    let workflow_path = dir.path().join("workflows/pets.arazzo.yaml");
+    std::fs::write(
+        &workflow_path,
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: pets
+    url: ../specs/api.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: listPets
+"#,
+    )
+    .unwrap();
+
+    let raw = std::fs::read_to_string(&workflow_path).unwrap();
+    let doc = parse_document_str(&raw, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let wf = &doc.workflows[0];
+
+    let plan = Compiler::default()
+        .with_resolver(OpenApiResolver::default().with_base_dir(workflow_path.parent().unwrap()))
+        .compile_workflow(&doc, wf)
+        .await;
+    assert!(plan.diagnostics.is_empty(), "{:?}", plan.diagnostics);
+    let op = plan.steps[0]
+        .operation
+        .as_ref()
+        .expect("operation resolved");
+    assert_eq!(op.path, "/pets");
+}
+
+#[tokio::test]
+async fn preferred_content_type_is_negotiated_against_the_operation() {
+    let openapi = r#"
+openapi: 3.0.0
+info:
+  title: Orders
+  version: 1.0.0
+paths:
+  /orders:
+    post:
+      operationId: createOrder
+      requestBody:
+        content:
+          application/xml: {}
+          application/json: {}
+      responses:
+        "201":
+          description: created
+"#;
+    let openapi_file = write_temp(openapi);
+    let arazzo = format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: orders
+    url: {}
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: createOrder
+        requestBody:
+          contentType: application/xml
+          x-content-type-preference: [application/json, application/xml]
+          payload:
+            id: 1
+"#,
+        openapi_file.path().to_string_lossy()
+    );
+    let doc = parse_document_str(&arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+
+    let plan = Compiler::default()
+        .compile_workflow(&doc, &doc.workflows[0])
+        .await;
+    let step = &plan.steps[0];
+    assert!(step.diagnostics.is_empty(), "{:?}", step.diagnostics);
+    let body = step.request_body.as_ref().expect("request body compiled");
+    assert_eq!(body.content_type.as_deref(), Some("application/json"));
+}
+
+#[tokio::test]
+async fn documented_204_is_the_only_success_status_without_criteria() {
+    use std::collections::BTreeMap;
+
+    use arazzo_exec::executor::eval::ResponseContext;
+    use arazzo_exec::executor::response::evaluate_success;
+
+    let openapi = r#"
+openapi: 3.0.0
+info: { title: Pets, version: 1.0.0 }
+servers:
+  - url: https://api.test.local
+paths:
+  /pets/{id}:
+    delete:
+      operationId: deletePet
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema: { type: string }
+      responses:
+        "204": { description: deleted }
+        "404": { description: not found }
+"#;
+    let f = write_temp(openapi);
+
+    let arazzo = format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: pets
+    url: {}
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: deletePet
+        parameters:
+          - name: id
+            in: path
+            value: "1"
+"#,
+        f.path().to_string_lossy()
+    );
+
+    let doc = parse_document_str(&arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+    let wf = &doc.workflows[0];
+    let compiled = Compiler::default().compile_workflow(&doc, wf).await;
+    let op = compiled.steps[0]
+        .operation
+        .as_ref()
+        .expect("operation resolved");
+    assert_eq!(op.shape.success_statuses, vec![204]);
+
+    let headers = BTreeMap::new();
+    let resp = |status| ResponseContext {
+        status,
+        headers: &headers,
+        body: b"",
+        body_json: None,
+        request: None,
+    };
+    let step = &wf.steps[0];
+    assert!(evaluate_success(
+        step,
+        &op.shape.success_statuses,
+        &resp(204)
+    ));
+    assert!(!evaluate_success(
+        step,
+        &op.shape.success_statuses,
+        &resp(200)
+    ));
+}
+
+#[tokio::test]
+async fn step_overrides_replace_method_and_path_with_a_warning() {
+    use arazzo_exec::openapi::DiagnosticSeverity;
+
+    let openapi = r#"
+openapi: 3.0.0
+info: { title: Users, version: 1.0.0 }
+servers:
+  - url: https://api.test.local
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses: { "200": { description: ok } }
+"#;
+    let f = write_temp(openapi);
+    let arazzo = format!(
+        r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: users
+    url: {}
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: overridden
+        operationId: listUsers
+        x-override-method: post
+        x-override-path: /v2/users
+      - stepId: plain
+        operationId: listUsers
+"#,
+        f.path().to_string_lossy()
+    );
+    let doc = parse_document_str(&arazzo, DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+
+    let plan = Compiler::default()
+        .compile_workflow(&doc, &doc.workflows[0])
+        .await;
+
+    let overridden = &plan.steps[0];
+    let op = overridden.operation.as_ref().expect("operation resolved");
+    assert_eq!(op.method, "POST");
+    assert_eq!(op.path, "/v2/users");
+    assert_eq!(op.base_url, "https://api.test.local");
+    let warnings: Vec<&str> = overridden
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Warning)
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert!(warnings[0].contains("x-override-method"));
+    assert!(warnings[1].contains("/v2/users"));
+
+    let plain = &plan.steps[1];
+    let op = plain.operation.as_ref().expect("operation resolved");
+    assert_eq!((op.method.as_str(), op.path.as_str()), ("GET", "/users"));
+    assert!(plain.diagnostics.is_empty(), "{:?}", plain.diagnostics);
+}
@@ -1,7 +1,9 @@
 use std::collections::BTreeMap;
 use std::time::{Duration, SystemTime};
 
-use arazzo_exec::policy::{HttpRequestParts, PolicyConfig, PolicyGate};
+use arazzo_exec::policy::{
+    HttpRequestParts, PolicyConfig, PolicyGate, PolicyGateError, PolicyViolationKind,
+};
 use arazzo_exec::retry::{decide_retry, RetryConfig, RetryDecision, RetryReason};
 
 fn req(url: &str, body_len: usize) -> HttpRequestParts {
@@ -17,7 +19,7 @@ fn req(url: &str, body_len: usize) -> HttpRequestParts {
 fn policy_denies_when_host_allowlist_empty() {
     let gate = PolicyGate::new(PolicyConfig::default());
     let r = req("https://example.com/", 0);
-    let err = gate.apply_request("store", &r, &[], false).unwrap_err();
+    let err = gate.apply_request("store", &r, &[], &[], &[]).unwrap_err();
     assert!(format!("{err}").contains("disallowed host"));
 }
 
@@ -27,7 +29,7 @@ fn policy_allows_https_and_allowlisted_host() {
     cfg.network.allowed_hosts.insert("example.com".to_string());
     let gate = PolicyGate::new(cfg);
     let r = req("https://api.example.com/orders", 0);
-    let ok = gate.apply_request("store", &r, &[], false).unwrap();
+    let ok = gate.apply_request("store", &r, &[], &[], &[]).unwrap();
     assert_eq!(ok.method, "GET");
 }
 
@@ -37,7 +39,7 @@ fn policy_denies_http_by_default() {
     cfg.network.allowed_hosts.insert("example.com".to_string());
     let gate = PolicyGate::new(cfg);
     let r = req("http://example.com/", 0);
-    let err = gate.apply_request("store", &r, &[], false).unwrap_err();
+    let err = gate.apply_request("store", &r, &[], &[], &[]).unwrap_err();
     assert!(format!("{err}").contains("disallowed URL scheme"));
 }
 
@@ -48,10 +50,35 @@ fn policy_enforces_request_body_size() {
     cfg.limits.request.max_body_bytes = 10;
     let gate = PolicyGate::new(cfg);
     let r = req("https://example.com/", 11);
-    let err = gate.apply_request("store", &r, &[], false).unwrap_err();
+    let err = gate.apply_request("store", &r, &[], &[], &[]).unwrap_err();
     assert!(format!("{err}").contains("request body exceeds"));
 }
 
+fn read_only_gate() -> PolicyGate {
+    let mut cfg = PolicyConfig::default();
+    cfg.network.allowed_hosts.insert("example.com".to_string());
+    cfg.network.allowed_methods = ["GET", "HEAD"].into_iter().map(String::from).collect();
+    PolicyGate::new(cfg)
+}
+
+#[test]
+fn read_only_policy_allows_get() {
+    let gate = read_only_gate();
+    let r = req("https://example.com/orders", 0);
+    let ok = gate.apply_request("store", &r, &[], &[], &[]).unwrap();
+    assert_eq!(ok.method, "GET");
+}
+
+#[test]
+fn read_only_policy_denies_delete() {
+    let gate = read_only_gate();
+    let mut r = req("https://example.com/orders/1", 0);
+    r.method = "DELETE".to_string();
+    let err = gate.apply_request("store", &r, &[], &[], &[]).unwrap_err();
+    assert!(matches!(err, PolicyGateError::Method(ref m) if m == "DELETE"));
+    assert_eq!(err.kind(), PolicyViolationKind::Method);
+}
+
 #[test]
 fn retry_uses_retry_after_header_over_backoff() {
     let cfg = RetryConfig::default();
@@ -101,3 +128,36 @@ fn retry_stops_on_policy_failure() {
         }
     ));
 }
+
+#[test]
+fn policy_redacts_secret_query_params_in_the_persisted_url() {
+    let mut cfg = PolicyConfig::default();
+    cfg.network.allowed_hosts.insert("example.com".to_string());
+    let gate = PolicyGate::new(cfg);
+    let r = req("https://example.com/pets?api_key=k-123&limit=10", 0);
+    let ok = gate
+        .apply_request("store", &r, &[], &["api_key".to_string()], &[])
+        .unwrap();
+    assert_eq!(
+        ok.url,
+        "https://example.com/pets?api_key=%3Credacted%3E&limit=10"
+    );
+}
+
+#[test]
+fn source_auth_debug_hides_credentials() {
+    let auth = arazzo_exec::policy::parse_auth_config(
+        "a:\n  scheme: basic\n  username: ci\n  password: hunter2\n\
+         b:\n  scheme: bearer\n  token: abc\n\
+         c:\n  scheme: apikey\n  name: api_key\n  value: k-123\n  in: query\n",
+    )
+    .unwrap();
+    let debug = format!("{auth:?}");
+    for secret in ["hunter2", "abc", "k-123"] {
+        assert!(!debug.contains(secret), "{debug}");
+    }
+    assert!(
+        debug.contains("api_key") && debug.contains("Query"),
+        "{debug}"
+    );
+}
@@ -0,0 +1,128 @@
+//! Rebuilding run state from the stored event log.
+
+mod support;
+
+use std::sync::Arc;
+
+use arazzo_exec::executor::{replay_events, Event, EventSink, ReplayedAttempt, StoreEventSink};
+use arazzo_store::{RunStatus, StateStore};
+use support::MemoryStore;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn replay_rebuilds_step_statuses_from_events() {
+    let store = Arc::new(MemoryStore::default());
+    let sink = StoreEventSink::new(store.clone());
+    let run_id = Uuid::new_v4();
+    let step = |id: &str| id.to_string();
+
+    let events = [
+        Event::RunStarted {
+            run_id,
+            workflow_id: "checkout".to_string(),
+        },
+        Event::StepStarted {
+            run_id,
+            step_id: step("login"),
+        },
+        Event::AttemptStarted {
+            run_id,
+            step_id: step("login"),
+            attempt_no: 1,
+        },
+        Event::AttemptFinished {
+            run_id,
+            step_id: step("login"),
+            attempt_no: 1,
+            succeeded: true,
+        },
+        Event::StepSucceeded {
+            run_id,
+            step_id: step("login"),
+        },
+        Event::StepStarted {
+            run_id,
+            step_id: step("pay"),
+        },
+        Event::AttemptStarted {
+            run_id,
+            step_id: step("pay"),
+            attempt_no: 1,
+        },
+        Event::AttemptFinished {
+            run_id,
+            step_id: step("pay"),
+            attempt_no: 1,
+            succeeded: false,
+        },
+        Event::StepRetryScheduled {
+            run_id,
+            step_id: step("pay"),
+            delay_ms: 100,
+        },
+        Event::StepStarted {
+            run_id,
+            step_id: step("pay"),
+        },
+        Event::AttemptStarted {
+            run_id,
+            step_id: step("pay"),
+            attempt_no: 2,
+        },
+        Event::AttemptFinished {
+            run_id,
+            step_id: step("pay"),
+            attempt_no: 2,
+            succeeded: false,
+        },
+        Event::StepFailed {
+            run_id,
+            step_id: step("pay"),
+        },
+        Event::RunFinished {
+            run_id,
+            status: RunStatus::Failed,
+        },
+    ];
+    let total = events.len();
+    for event in events {
+        sink.emit(event).await;
+    }
+    // Another run's events are not part of the replay.
+    sink.emit(Event::StepStarted {
+        run_id: Uuid::new_v4(),
+        step_id: step("login"),
+    })
+    .await;
+
+    let replayed = replay_events(store.as_ref() as &dyn StateStore, run_id)
+        .await
+        .unwrap();
+
+    assert_eq!(replayed.workflow_id.as_deref(), Some("checkout"));
+    assert_eq!(replayed.status.as_deref(), Some("failed"));
+    assert_eq!(replayed.events_replayed, total);
+    assert!(replayed.unknown_events.is_empty());
+
+    let login = &replayed.steps["login"];
+    assert_eq!(login.status, "succeeded");
+    assert_eq!(login.retries_scheduled, 0);
+    assert_eq!(
+        login.attempts,
+        vec![ReplayedAttempt {
+            attempt_no: 1,
+            succeeded: Some(true),
+        }]
+    );
+
+    let pay = &replayed.steps["pay"];
+    assert_eq!(pay.status, "failed");
+    assert_eq!(pay.retries_scheduled, 1);
+    assert_eq!(
+        pay.attempts
+            .iter()
+            .map(|a| (a.attempt_no, a.succeeded))
+            .collect::<Vec<_>>(),
+        vec![(1, Some(false)), (2, Some(false))]
+    );
+}
@@ -1,15 +1,19 @@
 use std::collections::BTreeMap;
 
-use arazzo_core::types::Step;
+use arazzo_core::expressions::TemplateDelimiters;
+use arazzo_core::types::{Criterion, Step};
 use arazzo_exec::executor::eval::ResponseContext;
 use arazzo_exec::executor::response::{
-    compute_outputs, evaluate_success, parse_body_json, request_to_json, response_to_json,
+    compute_outputs, compute_outputs_detailed, evaluate_success, evaluate_success_async,
+    evaluate_success_with, parse_body_json, request_to_json, response_has_body, response_to_json,
+    BODY_TEXT_FALLBACK_EXTENSION, SUCCESS_ANY_OF_EXTENSION,
 };
+use arazzo_exec::executor::{evaluate_criterion_against, CriteriaScope};
 use arazzo_exec::policy::sanitize::{SanitizedBody, SanitizedHeaders};
 use arazzo_exec::policy::{HttpResponseParts, RequestGateResult, ResponseGateResult};
 use arazzo_store::StateStore;
 use async_trait::async_trait;
-use serde_json::json;
+use serde_json::{json, Value as JsonValue};
 use uuid::Uuid;
 
 struct MockStore;
@@ -19,9 +23,12 @@ impl StateStore for MockStore {
     async fn get_step_outputs(
         &self,
         _run_id: uuid::Uuid,
-        _step_id: &str,
+        step_id: &str,
     ) -> Result<serde_json::Value, arazzo_store::StoreError> {
-        Ok(json!({}))
+        match step_id {
+            "login" => Ok(json!({ "userId": 7 })),
+            _ => Ok(json!({})),
+        }
     }
 
     async fn upsert_workflow_doc(
@@ -144,6 +151,14 @@ impl StateStore for MockStore {
         unimplemented!()
     }
 
+    async fn skip_pending_steps(
+        &self,
+        _run_id: uuid::Uuid,
+        _error: serde_json::Value,
+    ) -> Result<i64, arazzo_store::StoreError> {
+        unimplemented!()
+    }
+
     async fn get_step_attempts(
         &self,
         _run_step_id: uuid::Uuid,
@@ -163,7 +178,7 @@ impl StateStore for MockStore {
     async fn check_run_status(
         &self,
         _run_id: uuid::Uuid,
-    ) -> Result<String, arazzo_store::StoreError> {
+    ) -> Result<arazzo_store::RunStatus, arazzo_store::StoreError> {
         unimplemented!()
     }
 }
@@ -173,6 +188,7 @@ fn parse_body_json_valid() {
     let resp = HttpResponseParts {
         status: 200,
         headers: BTreeMap::new(),
+        repeated_headers: BTreeMap::new(),
         body: b"{\"key\":\"value\"}".to_vec(),
     };
     let result = parse_body_json(&resp);
@@ -185,12 +201,90 @@ fn parse_body_json_invalid() {
     let resp = HttpResponseParts {
         status: 200,
         headers: BTreeMap::new(),
+        repeated_headers: BTreeMap::new(),
         body: b"not json".to_vec(),
     };
     let result = parse_body_json(&resp);
     assert!(result.is_none());
 }
 
+#[test]
+fn parse_body_json_follows_content_type() {
+    let resp = |content_type: &str| HttpResponseParts {
+        status: 200,
+        headers: [("Content-Type".to_string(), content_type.to_string())]
+            .into_iter()
+            .collect(),
+        repeated_headers: BTreeMap::new(),
+        body: b"{\"key\":\"value\"}".to_vec(),
+    };
+    assert_eq!(
+        parse_body_json(&resp("application/json; charset=utf-8")),
+        Some(json!({"key": "value"}))
+    );
+    assert_eq!(
+        parse_body_json(&resp("application/problem+json")),
+        Some(json!({"key": "value"}))
+    );
+    assert_eq!(parse_body_json(&resp("text/plain")), None);
+    // A repeated Content-Type is folded with ", "; the first one counts.
+    assert_eq!(
+        parse_body_json(&resp("application/json, text/plain")),
+        Some(json!({"key": "value"}))
+    );
+}
+
+#[tokio::test]
+async fn json_looking_text_body_stays_text() {
+    let mut extensions = BTreeMap::new();
+    extensions.insert(BODY_TEXT_FALLBACK_EXTENSION.to_string(), json!(true));
+    let step = Step {
+        step_id: "test".to_string(),
+        description: None,
+        operation_id: None,
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: None,
+        outputs: Some({
+            let mut m = BTreeMap::new();
+            m.insert("message".to_string(), "$response.body".to_string());
+            m
+        }),
+        on_success: None,
+        on_failure: None,
+        extensions,
+    };
+
+    let resp = HttpResponseParts {
+        status: 200,
+        headers: [("content-type".to_string(), "text/plain".to_string())]
+            .into_iter()
+            .collect(),
+        repeated_headers: BTreeMap::new(),
+        body: b"[1, 2]".to_vec(),
+    };
+    let resp_ctx = ResponseContext {
+        status: resp.status,
+        headers: &resp.headers,
+        body: &resp.body,
+        body_json: parse_body_json(&resp),
+        request: None,
+    };
+
+    let outputs = compute_outputs(
+        &MockStore,
+        Uuid::new_v4(),
+        &json!({}),
+        &step,
+        &resp_ctx,
+        &TemplateDelimiters::default(),
+    )
+    .await;
+    assert_eq!(outputs["message"], json!("[1, 2]"));
+}
+
 #[test]
 fn evaluate_success_defaults_to_2xx() {
     let step = Step {
@@ -214,16 +308,189 @@ fn evaluate_success_defaults_to_2xx() {
         headers: &headers,
         body: b"{}",
         body_json: None,
+        request: None,
     };
-    assert!(evaluate_success(&step, &resp_ctx));
+    assert!(evaluate_success(&step, &[], &resp_ctx));
 
     let resp_ctx_404 = ResponseContext {
         status: 404,
         headers: &headers,
         body: b"{}",
         body_json: None,
+        request: None,
+    };
+    assert!(!evaluate_success(&step, &[], &resp_ctx_404));
+}
+
+#[test]
+fn evaluate_success_passes_when_any_criteria_group_passes() {
+    let mut extensions = BTreeMap::new();
+    extensions.insert(
+        SUCCESS_ANY_OF_EXTENSION.to_string(),
+        json!([
+            [
+                { "condition": "$statusCode == 200" },
+                { "condition": "$response.body#/state == 'ready'" }
+            ],
+            [{ "condition": "$statusCode == 202" }]
+        ]),
+    );
+    let step = Step {
+        step_id: "test".to_string(),
+        description: None,
+        operation_id: None,
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: None,
+        outputs: None,
+        on_success: None,
+        on_failure: None,
+        extensions,
     };
-    assert!(!evaluate_success(&step, &resp_ctx_404));
+
+    let headers = BTreeMap::new();
+    let accepted = ResponseContext {
+        status: 202,
+        headers: &headers,
+        body: b"{\"state\":\"pending\"}",
+        body_json: Some(json!({"state": "pending"})),
+        request: None,
+    };
+    assert!(evaluate_success(&step, &[], &accepted));
+
+    let not_ready = ResponseContext {
+        status: 200,
+        headers: &headers,
+        body: b"{\"state\":\"pending\"}",
+        body_json: Some(json!({"state": "pending"})),
+        request: None,
+    };
+    assert!(!evaluate_success(&step, &[], &not_ready));
+}
+
+#[test]
+fn criteria_compare_body_fields_to_inputs_and_step_outputs() {
+    let step = Step {
+        step_id: "test".to_string(),
+        description: None,
+        operation_id: None,
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: Some(vec![
+            serde_json::from_value(
+                json!({ "condition": "$response.body#/tenant == $inputs.tenantId" }),
+            )
+            .unwrap(),
+            serde_json::from_value(
+                json!({ "condition": "$response.body#/owner == $steps.login.outputs.userId" }),
+            )
+            .unwrap(),
+        ]),
+        outputs: None,
+        on_success: None,
+        on_failure: None,
+        extensions: BTreeMap::new(),
+    };
+
+    let headers = BTreeMap::new();
+    let resp_ctx = ResponseContext {
+        status: 200,
+        headers: &headers,
+        body: b"{\"tenant\":\"acme\",\"owner\":7}",
+        body_json: Some(json!({"tenant": "acme", "owner": 7})),
+        request: None,
+    };
+    let mut scope = CriteriaScope {
+        inputs: json!({ "tenantId": "acme" }),
+        step_outputs: BTreeMap::from([("login".to_string(), json!({ "userId": 7 }))]),
+    };
+    assert!(evaluate_success_with(&step, &[], &resp_ctx, &scope));
+
+    scope.inputs = json!({ "tenantId": "globex" });
+    assert!(!evaluate_success_with(&step, &[], &resp_ctx, &scope));
+}
+
+#[tokio::test]
+async fn async_success_evaluation_loads_referenced_step_outputs() {
+    let step = Step {
+        step_id: "test".to_string(),
+        description: None,
+        operation_id: None,
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: Some(vec![serde_json::from_value(
+            json!({ "condition": "$response.body#/owner == $steps.login.outputs.userId" }),
+        )
+        .unwrap()]),
+        outputs: None,
+        on_success: None,
+        on_failure: None,
+        extensions: BTreeMap::new(),
+    };
+
+    let headers = BTreeMap::new();
+    let owned_by_login = ResponseContext {
+        status: 200,
+        headers: &headers,
+        body: b"{\"owner\":7}",
+        body_json: Some(json!({"owner": 7})),
+        request: None,
+    };
+    assert!(
+        evaluate_success_async(
+            &MockStore,
+            Uuid::new_v4(),
+            &json!({}),
+            &step,
+            &[],
+            &owned_by_login
+        )
+        .await
+    );
+    // Without the store, `$steps` resolves to null and the comparison fails.
+    assert!(!evaluate_success(&step, &[], &owned_by_login));
+}
+
+#[test]
+fn jsonpath_criterion_evaluates_against_plain_json() {
+    let criterion: Criterion = serde_json::from_value(json!({
+        "context": "$response.body",
+        "condition": "$[?(@.total > 1)]",
+        "type": "jsonpath"
+    }))
+    .unwrap();
+    let value = json!({ "total": 3, "items": ["a", "b", "c"] });
+
+    assert!(evaluate_criterion_against(&value, &criterion, 200));
+    assert!(!evaluate_criterion_against(
+        &json!({ "total": 0 }),
+        &criterion,
+        200
+    ));
+
+    let status: Criterion =
+        serde_json::from_value(json!({ "condition": "$statusCode == 201" })).unwrap();
+    assert!(evaluate_criterion_against(&value, &status, 201));
+    assert!(!evaluate_criterion_against(&value, &status, 200));
+}
+
+#[test]
+fn missing_header_resolves_to_null_against_plain_json() {
+    let is_null: Criterion =
+        serde_json::from_value(json!({ "condition": "$response.header.X-Request-Id == null" }))
+            .unwrap();
+    assert!(evaluate_criterion_against(&json!({}), &is_null, 200));
+
+    let is_empty: Criterion =
+        serde_json::from_value(json!({ "condition": "$response.header.X-Request-Id == ''" }))
+            .unwrap();
+    assert!(!evaluate_criterion_against(&json!({}), &is_empty, 200));
 }
 
 #[tokio::test]
@@ -254,12 +521,174 @@ async fn compute_outputs_extracts_from_response() {
         headers: &headers,
         body: b"{\"id\":123}",
         body_json: Some(json!({"id": 123})),
+        request: None,
     };
 
-    let outputs = compute_outputs(&MockStore, Uuid::new_v4(), &json!({}), &step, &resp_ctx).await;
+    let outputs = compute_outputs(
+        &MockStore,
+        Uuid::new_v4(),
+        &json!({}),
+        &step,
+        &resp_ctx,
+        &TemplateDelimiters::default(),
+    )
+    .await;
     assert_eq!(outputs["status"], json!(200));
 }
 
+#[tokio::test]
+async fn missing_body_pointer_yields_null_without_dropping_other_outputs() {
+    let step = Step {
+        step_id: "test".to_string(),
+        description: None,
+        operation_id: None,
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: None,
+        outputs: Some({
+            let mut m = BTreeMap::new();
+            m.insert("userId".to_string(), "$response.body#/id".to_string());
+            m.insert("email".to_string(), "$response.body#/email".to_string());
+            m
+        }),
+        on_success: None,
+        on_failure: None,
+        extensions: BTreeMap::new(),
+    };
+
+    let headers = BTreeMap::new();
+    let resp_ctx = ResponseContext {
+        status: 200,
+        headers: &headers,
+        body: b"{\"id\":123}",
+        body_json: Some(json!({"id": 123})),
+        request: None,
+    };
+
+    let (outputs, unresolved) = compute_outputs_detailed(
+        &MockStore,
+        Uuid::new_v4(),
+        &json!({}),
+        &step,
+        &resp_ctx,
+        &TemplateDelimiters::default(),
+    )
+    .await;
+    assert_eq!(outputs["userId"], json!(123));
+    assert_eq!(outputs["email"], JsonValue::Null);
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].name, "email");
+    assert!(unresolved[0].reason.contains("/email"));
+}
+
+#[tokio::test]
+async fn jsonpath_output_collects_every_match() {
+    let step = Step {
+        step_id: "test".to_string(),
+        description: None,
+        operation_id: None,
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: None,
+        outputs: Some({
+            let mut m = BTreeMap::new();
+            m.insert("ids".to_string(), "$response.body$.data[*].id".to_string());
+            m.insert("first".to_string(), "$response.body#/data/0/id".to_string());
+            m.insert("bad".to_string(), "$response.body$.data[".to_string());
+            m
+        }),
+        on_success: None,
+        on_failure: None,
+        extensions: BTreeMap::new(),
+    };
+
+    let headers = BTreeMap::new();
+    let body = json!({"data": [{"id": 1}, {"id": 2}, {"name": "no id"}]});
+    let raw = body.to_string();
+    let resp_ctx = ResponseContext {
+        status: 200,
+        headers: &headers,
+        body: raw.as_bytes(),
+        body_json: Some(body),
+        request: None,
+    };
+
+    let (outputs, unresolved) = compute_outputs_detailed(
+        &MockStore,
+        Uuid::new_v4(),
+        &json!({}),
+        &step,
+        &resp_ctx,
+        &TemplateDelimiters::default(),
+    )
+    .await;
+    assert_eq!(outputs["ids"], json!([1, 2]));
+    assert_eq!(outputs["first"], json!(1));
+    assert_eq!(outputs["bad"], JsonValue::Null);
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].name, "bad");
+    assert!(unresolved[0].reason.contains("invalid JSONPath"));
+}
+
+#[tokio::test]
+async fn plain_text_body_is_captured_when_text_fallback_is_enabled() {
+    let mut step = Step {
+        step_id: "test".to_string(),
+        description: None,
+        operation_id: None,
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: None,
+        outputs: Some({
+            let mut m = BTreeMap::new();
+            m.insert("message".to_string(), "$response.body".to_string());
+            m
+        }),
+        on_success: None,
+        on_failure: None,
+        extensions: BTreeMap::new(),
+    };
+
+    let headers = BTreeMap::new();
+    let resp_ctx = ResponseContext {
+        status: 200,
+        headers: &headers,
+        body: b"pong",
+        body_json: None,
+        request: None,
+    };
+
+    let outputs = compute_outputs(
+        &MockStore,
+        Uuid::new_v4(),
+        &json!({}),
+        &step,
+        &resp_ctx,
+        &TemplateDelimiters::default(),
+    )
+    .await;
+    assert_eq!(outputs["message"], JsonValue::Null);
+
+    step.extensions
+        .insert(BODY_TEXT_FALLBACK_EXTENSION.to_string(), json!(true));
+    let outputs = compute_outputs(
+        &MockStore,
+        Uuid::new_v4(),
+        &json!({}),
+        &step,
+        &resp_ctx,
+        &TemplateDelimiters::default(),
+    )
+    .await;
+    assert_eq!(outputs["message"], json!("pong"));
+}
+
 #[test]
 fn request_to_json_serializes() {
     let req = RequestGateResult {
@@ -307,3 +736,73 @@ fn response_to_json_serializes() {
     assert_eq!(json["body"], "{\"success\":true}");
     assert_eq!(json["body_truncated"], false);
 }
+
+#[test]
+fn stored_attempt_round_trips_through_typed_accessors() {
+    let headers: BTreeMap<String, String> =
+        [("Content-Type".to_string(), "application/json".to_string())]
+            .into_iter()
+            .collect();
+    let req = RequestGateResult {
+        url: "https://example.com/orders".to_string(),
+        method: "POST".to_string(),
+        headers: SanitizedHeaders {
+            headers: headers.clone(),
+        },
+        body: SanitizedBody {
+            bytes: b"{\"id\":1}".to_vec(),
+            truncated: false,
+        },
+    };
+    let resp = ResponseGateResult {
+        status: 201,
+        headers: SanitizedHeaders {
+            headers: headers.clone(),
+        },
+        body: SanitizedBody {
+            bytes: b"{\"ok\":true}".to_vec(),
+            truncated: true,
+        },
+    };
+
+    let mut attempt = arazzo_store::StepAttempt {
+        id: Uuid::new_v4(),
+        run_step_id: Uuid::new_v4(),
+        attempt_no: 1,
+        status: "succeeded".to_string(),
+        request: request_to_json(&req),
+        response: response_to_json(&resp),
+        error: None,
+        duration_ms: Some(12),
+        started_at: chrono::Utc::now(),
+        finished_at: None,
+    };
+
+    let request = attempt.request_parts().unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.url, "https://example.com/orders");
+    assert_eq!(request.headers, headers);
+    assert_eq!(request.body, "{\"id\":1}");
+    assert!(!request.body_truncated);
+
+    let response = attempt.response_parts().unwrap().expect("response stored");
+    assert_eq!(response.status, 201);
+    assert_eq!(response.headers, headers);
+    assert_eq!(response.body, "{\"ok\":true}");
+    assert!(response.body_truncated);
+
+    attempt.response = json!({});
+    assert!(attempt.response_parts().unwrap().is_none());
+}
+
+#[test]
+fn response_has_body_follows_method_and_status() {
+    assert!(!response_has_body("HEAD", 200));
+    assert!(!response_has_body("GET", 204));
+    assert!(!response_has_body("GET", 304));
+    assert!(!response_has_body("GET", 101));
+    assert!(!response_has_body("CONNECT", 200));
+    assert!(response_has_body("CONNECT", 407));
+    assert!(response_has_body("OPTIONS", 200));
+    assert!(response_has_body("GET", 404));
+}
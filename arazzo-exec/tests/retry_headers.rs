@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::time::{Duration, SystemTime};
 
 use arazzo_exec::retry::parse_retry_after;
-use arazzo_exec::retry::{RetryHeadersConfig, RetryVendorHeader, VendorHeaderKind};
+use arazzo_exec::retry::{RetryHeadersConfig, VendorHeaderKind};
 
 #[test]
 fn parse_retry_after_delta_seconds() {
@@ -0,0 +1,373 @@
+//! Run creation and the run-claiming loop against an in-memory store.
+
+mod support;
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use arazzo_core::{RuleLevel, RuleOverride, ValidationOptions};
+use arazzo_exec::executor::{
+    prepare_run, ExecutorConfig, HttpClient, HttpError, NoOpEventSink, PrepareRunError, RunOptions,
+    RunWorker,
+};
+use arazzo_exec::openapi::{OpenApiDoc, ResolvedSources};
+use arazzo_exec::policy::{HttpRequestParts, HttpResponseParts, PolicyConfig, PolicyGate};
+use arazzo_exec::secrets::{SecretValue, SecretsProvider};
+use arazzo_exec::{Compiler, Executor};
+use arazzo_store::StateStore;
+use async_trait::async_trait;
+use serde_json::json;
+use support::MemoryStore;
+
+/// Holds every request for a while and records the most requests in flight at once.
+#[derive(Default)]
+struct SlowHttpClient {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+#[async_trait]
+impl HttpClient for SlowHttpClient {
+    async fn send(
+        &self,
+        _req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+struct NoOpSecretsProvider;
+
+#[async_trait]
+impl SecretsProvider for NoOpSecretsProvider {
+    async fn get(
+        &self,
+        ref_: &arazzo_exec::secrets::SecretRef,
+    ) -> Result<SecretValue, arazzo_exec::secrets::SecretError> {
+        Err(arazzo_exec::secrets::SecretError::NotFound(ref_.clone()))
+    }
+}
+
+const WORKFLOW: &str = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petstore
+    url: https://specs.example.com/petstore.yaml
+workflows:
+  - workflowId: ping
+    steps:
+      - stepId: ping
+        operationId: ping
+"#;
+
+fn compiler() -> Compiler {
+    let mut sources = ResolvedSources::default();
+    sources.openapi_docs.insert(
+        "petstore".to_string(),
+        OpenApiDoc {
+            source_url: "petstore.yaml".to_string(),
+            raw: json!({
+                "openapi": "3.0.0",
+                "info": { "title": "Pets", "version": "1.0.0" },
+                "servers": [{ "url": "https://api.test.local" }],
+                "paths": {
+                    "/ping": {
+                        "get": {
+                            "operationId": "ping",
+                            "parameters": [{
+                                "name": "limit",
+                                "in": "query",
+                                "schema": { "type": "integer", "default": 20 }
+                            }],
+                            "responses": { "200": { "description": "ok" } }
+                        }
+                    }
+                }
+            }),
+            base_url: None,
+        },
+    );
+    Compiler::default().with_sources(sources)
+}
+
+#[tokio::test]
+async fn worker_runs_at_most_the_configured_number_of_runs_at_once() {
+    let store = Arc::new(MemoryStore::default());
+    let mut run_ids = Vec::new();
+    for _ in 0..5 {
+        let prepared = prepare_run(store.as_ref(), &compiler(), WORKFLOW, RunOptions::default())
+            .await
+            .unwrap();
+        run_ids.push(prepared.run_id);
+    }
+
+    let mut policy = PolicyConfig::default();
+    policy.network.allowed_hosts = ["api.test.local".to_string()].into_iter().collect();
+    let http = Arc::new(SlowHttpClient::default());
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        http.clone(),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(policy)),
+        Arc::new(NoOpEventSink),
+    );
+
+    let worker =
+        RunWorker::new(store.clone(), Arc::new(executor), compiler()).with_max_concurrent_runs(2);
+    assert_eq!(worker.drain().await.unwrap(), 5);
+
+    assert_eq!(http.max_in_flight.load(Ordering::SeqCst), 2);
+    for run_id in run_ids {
+        let run = store.get_run(run_id).await.unwrap().unwrap();
+        assert_eq!(run.status, "succeeded");
+    }
+}
+
+#[tokio::test]
+async fn worker_keeps_claiming_after_a_failed_claim() {
+    let store = Arc::new(MemoryStore::default());
+    let prepared = prepare_run(store.as_ref(), &compiler(), WORKFLOW, RunOptions::default())
+        .await
+        .unwrap();
+    store.failing_claims.store(2, Ordering::SeqCst);
+
+    let mut policy = PolicyConfig::default();
+    policy.network.allowed_hosts = ["api.test.local".to_string()].into_iter().collect();
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(SlowHttpClient::default()),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(policy)),
+        Arc::new(NoOpEventSink),
+    );
+    let worker = RunWorker::new(store.clone(), Arc::new(executor), compiler())
+        .with_poll_interval(Duration::from_millis(5));
+    assert_eq!(worker.drain().await.unwrap(), 1);
+
+    let run = store.get_run(prepared.run_id).await.unwrap().unwrap();
+    assert_eq!(run.status, "succeeded");
+}
+
+#[tokio::test]
+async fn prepare_run_applies_the_given_validation_overrides() {
+    let doc = WORKFLOW.replace(
+        "        operationId: ping\n",
+        "        operationId: ping\n        outputs:\n          not a key: $statusCode\n",
+    );
+    let store = MemoryStore::default();
+    let rejected = prepare_run(&store, &compiler(), &doc, RunOptions::default()).await;
+    assert!(
+        matches!(rejected, Err(PrepareRunError::Invalid(_))),
+        "{rejected:?}"
+    );
+
+    let options = RunOptions {
+        validation: ValidationOptions {
+            overrides: vec![RuleOverride::new(
+                "$.workflows[*].steps[*].outputs",
+                RuleLevel::Off,
+            )],
+            ..ValidationOptions::default()
+        },
+        ..RunOptions::default()
+    };
+    let prepared = prepare_run(&store, &compiler(), &doc, options)
+        .await
+        .unwrap();
+    assert!(store.get_run(prepared.run_id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn relative_sources_resolve_next_to_the_recorded_source_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = json!({
+        "openapi": "3.0.0",
+        "info": { "title": "Pets", "version": "1.0.0" },
+        "servers": [{ "url": "https://api.test.local" }],
+        "paths": {
+            "/ping": {
+                "get": {
+                    "operationId": "ping",
+                    "responses": { "200": { "description": "ok" } }
+                }
+            }
+        }
+    });
+    std::fs::write(dir.path().join("petstore.json"), spec.to_string()).unwrap();
+    let doc = WORKFLOW.replace("https://specs.example.com/petstore.yaml", "./petstore.json");
+
+    let store = Arc::new(MemoryStore::default());
+    let options = RunOptions {
+        provenance: Some(json!({
+            "source_path": dir.path().join("pets.arazzo.yaml").display().to_string(),
+        })),
+        ..RunOptions::default()
+    };
+    let prepared = prepare_run(store.as_ref(), &Compiler::default(), &doc, options)
+        .await
+        .unwrap();
+
+    let mut policy = PolicyConfig::default();
+    policy.network.allowed_hosts = ["api.test.local".to_string()].into_iter().collect();
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(SlowHttpClient::default()),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(policy)),
+        Arc::new(NoOpEventSink),
+    );
+    let worker = RunWorker::new(store.clone(), Arc::new(executor), Compiler::default());
+    assert_eq!(worker.drain().await.unwrap(), 1);
+
+    let run = store.get_run(prepared.run_id).await.unwrap().unwrap();
+    assert_eq!(run.status, "succeeded", "{:?}", run.error);
+}
+
+/// Answers every request with `200 {}` and keeps the URLs it was sent.
+#[derive(Default)]
+struct RecordingHttpClient {
+    urls: std::sync::Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl HttpClient for RecordingHttpClient {
+    async fn send(
+        &self,
+        req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        self.urls.lock().unwrap().push(req.url.to_string());
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn expanded_variables_stay_out_of_the_store_and_are_applied_again_by_the_worker() {
+    std::env::set_var("ARAZZO_RUN_WORKER_TEST_TOKEN", "s3cret");
+    let doc = WORKFLOW.replace(
+        "        operationId: ping\n",
+        "        operationId: ping\n        parameters:\n          - name: token\n            in: query\n            value: ${ARAZZO_RUN_WORKER_TEST_TOKEN}\n",
+    );
+    let overlay = r#"
+overlay: 1.0.0
+info:
+  title: Rename the token parameter
+  version: 1.0.0
+actions:
+  - target: $.workflows[0].steps[0].parameters[0]
+    update:
+      name: key
+"#;
+    let store = Arc::new(MemoryStore::default());
+    let options = RunOptions {
+        interpolate_env: true,
+        overlays: vec![overlay.to_string()],
+        ..RunOptions::default()
+    };
+    let prepared = prepare_run(store.as_ref(), &compiler(), &doc, options)
+        .await
+        .unwrap();
+
+    let run = store.get_run(prepared.run_id).await.unwrap().unwrap();
+    let stored = store
+        .get_workflow_doc(run.workflow_doc_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.raw, doc);
+    assert!(!stored.doc.to_string().contains("s3cret"));
+    assert_eq!(
+        run.overrides,
+        json!({ "env": ["ARAZZO_RUN_WORKER_TEST_TOKEN"], "overlays": [overlay] })
+    );
+
+    let mut policy = PolicyConfig::default();
+    policy.network.allowed_hosts = ["api.test.local".to_string()].into_iter().collect();
+    let http = Arc::new(RecordingHttpClient::default());
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        http.clone(),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(policy)),
+        Arc::new(NoOpEventSink),
+    );
+    let worker = RunWorker::new(store.clone(), Arc::new(executor), compiler());
+    assert_eq!(worker.drain().await.unwrap(), 1);
+
+    let urls = http.urls.lock().unwrap();
+    assert_eq!(urls.as_slice(), ["https://api.test.local/ping?key=s3cret"]);
+}
+
+#[tokio::test]
+async fn parameter_defaults_are_recorded_on_the_run_and_filled_by_the_worker() {
+    let store = Arc::new(MemoryStore::default());
+    let options = RunOptions {
+        fill_parameter_defaults: true,
+        ..RunOptions::default()
+    };
+    let prepared = prepare_run(store.as_ref(), &compiler(), WORKFLOW, options)
+        .await
+        .unwrap();
+    assert!(prepared.compiled.steps[0].fill_parameter_defaults);
+    let run = store.get_run(prepared.run_id).await.unwrap().unwrap();
+    assert_eq!(run.overrides, json!({ "fill_parameter_defaults": true }));
+
+    // The worker compiles with a compiler of its own; the run decides.
+    let mut policy = PolicyConfig::default();
+    policy.network.allowed_hosts = ["api.test.local".to_string()].into_iter().collect();
+    let http = Arc::new(RecordingHttpClient::default());
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        http.clone(),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(policy)),
+        Arc::new(NoOpEventSink),
+    );
+    let worker = RunWorker::new(store.clone(), Arc::new(executor), compiler());
+    assert_eq!(worker.drain().await.unwrap(), 1);
+
+    let urls = http.urls.lock().unwrap();
+    assert_eq!(urls.as_slice(), ["https://api.test.local/ping?limit=20"]);
+}
@@ -0,0 +1,1396 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arazzo_exec::executor::{
+    Event, EventSink, Executor, ExecutorConfig, HttpClient, HttpError, RngSource,
+};
+use arazzo_exec::openapi::{Loader, OpenApiDoc, OpenApiResolver, ResolvedSources};
+use arazzo_exec::policy::{HttpRequestParts, HttpResponseParts, PolicyConfig, PolicyGate};
+use arazzo_exec::secrets::{SecretValue, SecretsProvider};
+use arazzo_exec::{CompiledPlan, CompiledStep, Compiler};
+use arazzo_store::RunStatus;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+struct OkHttpClient;
+
+#[async_trait]
+impl HttpClient for OkHttpClient {
+    async fn send(
+        &self,
+        _req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+// Always answers 503, so steps with a retry action keep retrying until exhausted.
+struct UnavailableHttpClient;
+
+#[async_trait]
+impl HttpClient for UnavailableHttpClient {
+    async fn send(
+        &self,
+        _req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        Ok(HttpResponseParts {
+            status: 503,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: vec![],
+        })
+    }
+}
+
+// Answers 503 to the first `failures` requests and 200 after that.
+struct FlakyHttpClient {
+    failures: usize,
+    sent: AtomicUsize,
+}
+
+#[async_trait]
+impl HttpClient for FlakyHttpClient {
+    async fn send(
+        &self,
+        _req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        let failed = self.sent.fetch_add(1, Ordering::SeqCst) < self.failures;
+        Ok(HttpResponseParts {
+            status: if failed { 503 } else { 200 },
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+// Marks the run canceled (as `arazzo cancel` would) while serving the first request.
+struct CancelingHttpClient {
+    store: Arc<MockStore>,
+}
+
+#[async_trait]
+impl HttpClient for CancelingHttpClient {
+    async fn send(
+        &self,
+        _req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        *self.store.run_status.lock().unwrap() = RunStatus::Canceled;
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+// Holds each request briefly and records the most requests seen in flight at once.
+#[derive(Default)]
+struct OverlapHttpClient {
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+#[async_trait]
+impl HttpClient for OverlapHttpClient {
+    async fn send(
+        &self,
+        _req: HttpRequestParts,
+        _timeout: Duration,
+        _max_response_bytes: usize,
+    ) -> Result<HttpResponseParts, HttpError> {
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok(HttpResponseParts {
+            status: 200,
+            headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct RecordingEventSink {
+    events: Mutex<Vec<Event>>,
+}
+
+#[async_trait]
+impl EventSink for RecordingEventSink {
+    async fn emit(&self, event: Event) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+struct NoOpSecretsProvider;
+
+#[async_trait]
+impl SecretsProvider for NoOpSecretsProvider {
+    async fn get(
+        &self,
+        ref_: &arazzo_exec::secrets::SecretRef,
+    ) -> Result<SecretValue, arazzo_exec::secrets::SecretError> {
+        Err(arazzo_exec::secrets::SecretError::NotFound(ref_.clone()))
+    }
+}
+
+struct StaticSecretsProvider(&'static str);
+
+#[async_trait]
+impl SecretsProvider for StaticSecretsProvider {
+    async fn get(
+        &self,
+        _ref_: &arazzo_exec::secrets::SecretRef,
+    ) -> Result<SecretValue, arazzo_exec::secrets::SecretError> {
+        Ok(SecretValue::from_string(self.0.to_string()))
+    }
+}
+
+// In-memory store tracking step status so the scheduler loop can complete.
+struct MockStore {
+    steps: Mutex<Vec<arazzo_store::RunStep>>,
+    attempt_requests: Mutex<Vec<serde_json::Value>>,
+    run_status: Mutex<RunStatus>,
+    /// Step ids returned by each source-aware claim.
+    claims: Mutex<Vec<Vec<String>>>,
+    /// Run step row of every attempt, so attempt numbers count up per step.
+    attempt_steps: Mutex<Vec<Uuid>>,
+    retry_delays: Mutex<Vec<i64>>,
+}
+
+impl MockStore {
+    fn with_steps(run_id: Uuid, step_ids: &[&str]) -> Self {
+        let steps = step_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, step_id)| arazzo_store::RunStep {
+                id: Uuid::new_v4(),
+                run_id,
+                step_id: step_id.to_string(),
+                step_index: idx as i32,
+                status: "pending".to_string(),
+                source_name: Some("petstore".to_string()),
+                operation_id: Some("getUsers".to_string()),
+                depends_on: vec![],
+                deps_remaining: 0,
+                next_run_at: None,
+                outputs: serde_json::json!({}),
+                error: None,
+                started_at: None,
+                finished_at: None,
+            })
+            .collect();
+        Self {
+            steps: Mutex::new(steps),
+            attempt_requests: Mutex::new(Vec::new()),
+            run_status: Mutex::new(RunStatus::Running),
+            claims: Mutex::new(Vec::new()),
+            attempt_steps: Mutex::new(Vec::new()),
+            retry_delays: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn set_depends_on(&self, step_id: &str, depends_on: &[&str]) {
+        let mut steps = self.steps.lock().unwrap();
+        if let Some(s) = steps.iter_mut().find(|s| s.step_id == step_id) {
+            s.depends_on = depends_on.iter().map(|d| d.to_string()).collect();
+            s.deps_remaining = depends_on.len() as i32;
+        }
+    }
+}
+
+#[async_trait]
+impl arazzo_store::StateStore for MockStore {
+    async fn upsert_workflow_doc(
+        &self,
+        _doc: arazzo_store::NewWorkflowDoc,
+    ) -> Result<arazzo_store::WorkflowDoc, arazzo_store::StoreError> {
+        unimplemented!()
+    }
+
+    async fn get_workflow_doc(
+        &self,
+        _id: Uuid,
+    ) -> Result<Option<arazzo_store::WorkflowDoc>, arazzo_store::StoreError> {
+        Ok(None)
+    }
+
+    async fn create_run_and_steps(
+        &self,
+        _run: arazzo_store::NewRun,
+        _steps: Vec<arazzo_store::NewRunStep>,
+        _edges: Vec<arazzo_store::RunStepEdge>,
+    ) -> Result<Uuid, arazzo_store::StoreError> {
+        unimplemented!()
+    }
+
+    async fn claim_runnable_steps(
+        &self,
+        _run_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<arazzo_store::RunStep>, arazzo_store::StoreError> {
+        let mut steps = self.steps.lock().unwrap();
+        let ids =
+            arazzo_store::claimable_step_ids(&steps, limit as usize, &BTreeMap::new(), Utc::now());
+        let mut claimed = Vec::new();
+        for s in steps.iter_mut().filter(|s| ids.contains(&s.id)) {
+            s.status = "running".to_string();
+            claimed.push(s.clone());
+        }
+        Ok(claimed)
+    }
+
+    async fn claim_runnable_steps_within(
+        &self,
+        _run_id: Uuid,
+        limit: i64,
+        source_limits: &BTreeMap<String, usize>,
+    ) -> Result<Vec<arazzo_store::RunStep>, arazzo_store::StoreError> {
+        let mut steps = self.steps.lock().unwrap();
+        let ids =
+            arazzo_store::claimable_step_ids(&steps, limit as usize, source_limits, Utc::now());
+        let mut claimed = Vec::new();
+        for s in steps.iter_mut().filter(|s| ids.contains(&s.id)) {
+            s.status = "running".to_string();
+            claimed.push(s.clone());
+        }
+        self.claims
+            .lock()
+            .unwrap()
+            .push(claimed.iter().map(|s| s.step_id.clone()).collect());
+        Ok(claimed)
+    }
+
+    async fn insert_attempt_auto(
+        &self,
+        run_step_id: Uuid,
+        request: serde_json::Value,
+    ) -> Result<(Uuid, i32), arazzo_store::StoreError> {
+        self.attempt_requests.lock().unwrap().push(request);
+        let mut attempt_steps = self.attempt_steps.lock().unwrap();
+        attempt_steps.push(run_step_id);
+        let attempt_no = attempt_steps
+            .iter()
+            .filter(|id| **id == run_step_id)
+            .count();
+        Ok((Uuid::new_v4(), attempt_no as i32))
+    }
+
+    async fn finish_attempt(
+        &self,
+        _attempt_id: Uuid,
+        _status: arazzo_store::AttemptStatus,
+        _response: serde_json::Value,
+        _error: Option<serde_json::Value>,
+        _duration_ms: Option<i32>,
+        _finished_at: Option<DateTime<Utc>>,
+    ) -> Result<(), arazzo_store::StoreError> {
+        Ok(())
+    }
+
+    async fn mark_step_succeeded(
+        &self,
+        _run_id: Uuid,
+        step_id: &str,
+        outputs: serde_json::Value,
+    ) -> Result<(), arazzo_store::StoreError> {
+        // Mirrors the Postgres store: release pending dependents, and re-enable the ones
+        // an earlier failure of this step skipped.
+        let mut steps = self.steps.lock().unwrap();
+        let step = steps.iter_mut().find(|s| s.step_id == step_id).unwrap();
+        let failed_before = step.error.take().is_some();
+        step.status = "succeeded".to_string();
+        step.outputs = outputs;
+        for d in steps.iter_mut() {
+            if d.status == "pending" && d.depends_on.iter().any(|dep| dep == step_id) {
+                d.deps_remaining = (d.deps_remaining - 1).max(0);
+            }
+        }
+        if failed_before {
+            for (dependent, deps_remaining) in arazzo_store::dependents_to_reenable(&steps, step_id)
+            {
+                let d = steps.iter_mut().find(|s| s.step_id == dependent).unwrap();
+                d.status = "pending".to_string();
+                d.deps_remaining = deps_remaining;
+                d.next_run_at = None;
+                d.error = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_step_outputs(
+        &self,
+        _run_id: Uuid,
+        _step_id: &str,
+    ) -> Result<serde_json::Value, arazzo_store::StoreError> {
+        Ok(serde_json::json!({}))
+    }
+
+    async fn schedule_retry(
+        &self,
+        _run_id: Uuid,
+        step_id: &str,
+        delay_ms: i64,
+        error: serde_json::Value,
+    ) -> Result<(), arazzo_store::StoreError> {
+        self.retry_delays.lock().unwrap().push(delay_ms);
+        let mut steps = self.steps.lock().unwrap();
+        let step = steps.iter_mut().find(|s| s.step_id == step_id).unwrap();
+        step.status = "pending".to_string();
+        step.next_run_at = Some(Utc::now());
+        step.error = Some(error);
+        Ok(())
+    }
+
+    async fn mark_step_failed(
+        &self,
+        _run_id: Uuid,
+        step_id: &str,
+        error: serde_json::Value,
+    ) -> Result<(), arazzo_store::StoreError> {
+        // Mirrors the Postgres store: the failure cascades skips to pending dependents.
+        let mut steps = self.steps.lock().unwrap();
+        let step = steps.iter_mut().find(|s| s.step_id == step_id).unwrap();
+        step.status = "failed".to_string();
+        step.error = Some(error.clone());
+        let mut blockers = vec![step_id.to_string()];
+        while let Some(blocker) = blockers.pop() {
+            for d in steps.iter_mut() {
+                if d.status == "pending" && d.depends_on.contains(&blocker) {
+                    d.status = "skipped".to_string();
+                    d.error = Some(error.clone());
+                    blockers.push(d.step_id.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn mark_run_started(&self, _run_id: Uuid) -> Result<(), arazzo_store::StoreError> {
+        Ok(())
+    }
+
+    async fn mark_run_finished(
+        &self,
+        _run_id: Uuid,
+        _status: arazzo_store::RunStatus,
+        _error: Option<serde_json::Value>,
+    ) -> Result<(), arazzo_store::StoreError> {
+        Ok(())
+    }
+
+    async fn append_event(
+        &self,
+        _event: arazzo_store::NewEvent,
+    ) -> Result<(), arazzo_store::StoreError> {
+        Ok(())
+    }
+
+    async fn get_run(
+        &self,
+        _run_id: Uuid,
+    ) -> Result<Option<arazzo_store::WorkflowRun>, arazzo_store::StoreError> {
+        Ok(None)
+    }
+
+    async fn get_run_steps(
+        &self,
+        _run_id: Uuid,
+    ) -> Result<Vec<arazzo_store::RunStep>, arazzo_store::StoreError> {
+        Ok(self.steps.lock().unwrap().clone())
+    }
+
+    async fn reset_stale_running_steps(
+        &self,
+        _run_id: Uuid,
+    ) -> Result<i64, arazzo_store::StoreError> {
+        Ok(0)
+    }
+
+    async fn skip_pending_steps(
+        &self,
+        _run_id: Uuid,
+        _error: serde_json::Value,
+    ) -> Result<i64, arazzo_store::StoreError> {
+        let mut steps = self.steps.lock().unwrap();
+        let mut skipped = 0;
+        for s in steps.iter_mut().filter(|s| s.status == "pending") {
+            s.status = "skipped".to_string();
+            skipped += 1;
+        }
+        Ok(skipped)
+    }
+
+    async fn get_step_attempts(
+        &self,
+        _run_step_id: Uuid,
+    ) -> Result<Vec<arazzo_store::StepAttempt>, arazzo_store::StoreError> {
+        Ok(vec![])
+    }
+
+    async fn get_events_after(
+        &self,
+        _run_id: Uuid,
+        _after_id: i64,
+        _limit: i64,
+    ) -> Result<Vec<arazzo_store::RunEvent>, arazzo_store::StoreError> {
+        Ok(vec![])
+    }
+
+    async fn check_run_status(&self, _run_id: Uuid) -> Result<RunStatus, arazzo_store::StoreError> {
+        Ok(*self.run_status.lock().unwrap())
+    }
+}
+
+fn make_step(step_id: &str) -> arazzo_core::types::Step {
+    arazzo_core::types::Step {
+        step_id: step_id.to_string(),
+        description: None,
+        operation_id: Some("getUsers".to_string()),
+        operation_path: None,
+        workflow_id: None,
+        parameters: None,
+        request_body: None,
+        success_criteria: None,
+        on_success: None,
+        on_failure: None,
+        outputs: None,
+        extensions: Default::default(),
+    }
+}
+
+fn make_workflow(step_ids: &[&str]) -> arazzo_core::types::Workflow {
+    arazzo_core::types::Workflow {
+        workflow_id: "test-workflow".to_string(),
+        summary: None,
+        description: None,
+        inputs: None,
+        depends_on: None,
+        steps: step_ids.iter().map(|id| make_step(id)).collect(),
+        success_actions: None,
+        failure_actions: None,
+        outputs: None,
+        parameters: None,
+        extensions: Default::default(),
+    }
+}
+
+fn make_compiled(step_ids: &[&str]) -> CompiledPlan {
+    CompiledPlan {
+        diagnostics: vec![],
+        steps: step_ids
+            .iter()
+            .map(|id| CompiledStep {
+                step_id: id.to_string(),
+                operation: Some(arazzo_exec::openapi::ResolvedOperation {
+                    source_name: "petstore".to_string(),
+                    base_url: "https://api.test.local".to_string(),
+                    method: "GET".to_string(),
+                    path: "/users".to_string(),
+                    operation_id: Some("getUsers".to_string()),
+                    shape: arazzo_exec::openapi::CompiledOperationShape {
+                        parameters: vec![],
+                        request_body_required: None,
+                        request_body_content_types: None,
+                        success_statuses: Vec::new(),
+                    },
+                }),
+                diagnostics: vec![],
+                missing_required_parameters: vec![],
+                request_body: None,
+                missing_required_request_body: false,
+                fill_parameter_defaults: false,
+            })
+            .collect(),
+    }
+}
+
+fn make_policy() -> PolicyConfig {
+    let mut policy = PolicyConfig::default();
+    policy.network.allowed_hosts = ["api.test.local"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    policy
+}
+
+fn make_executor(
+    store: Arc<MockStore>,
+    secrets: Arc<dyn SecretsProvider>,
+    event_sink: Arc<dyn EventSink>,
+) -> Executor {
+    Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store,
+        Arc::new(OkHttpClient),
+        secrets,
+        Arc::new(PolicyGate::new(make_policy())),
+        event_sink,
+    )
+}
+
+#[tokio::test]
+async fn successful_step_emits_step_lifecycle_events() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let sink = Arc::new(RecordingEventSink::default());
+    let executor = make_executor(store.clone(), Arc::new(NoOpSecretsProvider), sink.clone());
+
+    let result = executor
+        .execute_run(
+            run_id,
+            &make_workflow(&["step1"]),
+            &make_compiled(&["step1"]),
+            &serde_json::json!({}),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 1);
+
+    let events = sink.events.lock().unwrap();
+    let step_events: Vec<&str> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::StepStarted { step_id, .. } if step_id == "step1" => Some("started"),
+            Event::StepSucceeded { step_id, .. } if step_id == "step1" => Some("succeeded"),
+            Event::StepFailed { step_id, .. } if step_id == "step1" => Some("failed"),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(step_events, vec!["started", "succeeded"]);
+}
+
+#[tokio::test]
+async fn retried_step_emits_step_started_once() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let sink = Arc::new(RecordingEventSink::default());
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(FlakyHttpClient {
+            failures: 2,
+            sent: AtomicUsize::new(0),
+        }),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(make_policy())),
+        sink.clone(),
+    );
+
+    let mut workflow = make_workflow(&["step1"]);
+    workflow.steps[0].on_failure = Some(vec![retry_action(3)]);
+    let result = executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&["step1"]),
+            &serde_json::json!({}),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 1);
+    assert_eq!(store.attempt_requests.lock().unwrap().len(), 3);
+
+    let started = sink
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| matches!(e, Event::StepStarted { .. }))
+        .count();
+    assert_eq!(started, 1);
+}
+
+#[tokio::test]
+async fn stored_request_masks_only_secret_leaves_in_body() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let executor = make_executor(
+        store.clone(),
+        Arc::new(StaticSecretsProvider("s3cr3t")),
+        Arc::new(RecordingEventSink::default()),
+    );
+
+    let mut workflow = make_workflow(&["step1"]);
+    workflow.steps[0].request_body = Some(arazzo_core::types::RequestBody {
+        content_type: Some("application/json".to_string()),
+        payload: Some(serde_json::json!({
+            "name": "alice",
+            "credentials": [
+                { "kind": "password", "value": "plain" },
+                { "kind": "token", "value": "secrets://API_TOKEN" }
+            ]
+        })),
+        replacements: None,
+        extensions: Default::default(),
+    });
+
+    executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&["step1"]),
+            &serde_json::json!({}),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let requests = store.attempt_requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let body = requests[0]["body"].as_str().unwrap();
+    assert!(!body.contains("s3cr3t"));
+    let stored: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(
+        stored,
+        serde_json::json!({
+            "name": "alice",
+            "credentials": [
+                { "kind": "password", "value": "plain" },
+                { "kind": "token", "value": "<redacted>" }
+            ]
+        })
+    );
+}
+
+// Loader that counts calls and never succeeds, so any fetch shows up as a failure.
+#[derive(Clone, Default)]
+struct CountingLoader {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Loader for CountingLoader {
+    async fn load(&self, url_or_path: &str) -> Result<serde_json::Value, String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Err(format!("unexpected fetch of {url_or_path}"))
+    }
+}
+
+#[tokio::test]
+async fn run_uses_preloaded_sources_without_loading() {
+    let arazzo = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: petstore
+    url: https://specs.example.com/petstore.yaml
+workflows:
+  - workflowId: test-workflow
+    steps:
+      - stepId: step1
+        operationId: getUsers
+"#;
+    let doc = arazzo_core::parse_document_str(arazzo, arazzo_core::DocumentFormat::Yaml)
+        .unwrap()
+        .document;
+
+    let mut sources = ResolvedSources::default();
+    sources.openapi_docs.insert(
+        "petstore".to_string(),
+        OpenApiDoc {
+            source_url: "/etc/specs/petstore.yaml".to_string(),
+            raw: serde_json::json!({
+                "openapi": "3.0.0",
+                "info": { "title": "Pets", "version": "1.0.0" },
+                "servers": [{ "url": "https://api.test.local" }],
+                "paths": {
+                    "/users": {
+                        "get": {
+                            "operationId": "getUsers",
+                            "responses": { "200": { "description": "ok" } }
+                        }
+                    }
+                }
+            }),
+            base_url: None,
+        },
+    );
+
+    let loader = CountingLoader::default();
+    let compiled = Compiler::default()
+        .with_resolver(OpenApiResolver::with_loader(loader.clone()))
+        .with_sources(sources)
+        .compile_workflow(&doc, &doc.workflows[0])
+        .await;
+    assert!(
+        compiled.diagnostics.is_empty(),
+        "{:?}",
+        compiled.diagnostics
+    );
+    assert_eq!(loader.calls.load(Ordering::SeqCst), 0);
+
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let executor = make_executor(
+        store,
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(RecordingEventSink::default()),
+    );
+    let result = executor
+        .execute_run(
+            run_id,
+            &doc.workflows[0],
+            &compiled,
+            &serde_json::json!({}),
+            Some(&doc),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 1);
+}
+
+#[tokio::test]
+async fn resume_with_overridden_input_only_affects_pending_steps() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1", "step2"]));
+    {
+        let mut steps = store.steps.lock().unwrap();
+        steps[0].status = "succeeded".to_string();
+        steps[0].outputs = serde_json::json!({ "petId": "old" });
+    }
+    let executor = make_executor(
+        store.clone(),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(RecordingEventSink::default()),
+    );
+
+    let mut workflow = make_workflow(&["step1", "step2"]);
+    workflow.steps[1].parameters = Some(vec![arazzo_core::types::ParameterOrReusable::Parameter(
+        arazzo_core::types::Parameter {
+            name: "petId".to_string(),
+            r#in: Some(arazzo_core::types::ParameterLocation::Query),
+            value: serde_json::json!("$inputs.petId"),
+            extensions: Default::default(),
+        },
+    )]);
+
+    // Stored inputs were {"petId":"old","limit":5}; the resume overrides petId only.
+    let resumed_inputs = serde_json::json!({ "petId": "new", "limit": 5 });
+    let result = executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&["step1", "step2"]),
+            &resumed_inputs,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 1);
+
+    let requests = store.attempt_requests.lock().unwrap();
+    assert_eq!(requests.len(), 1, "only the pending step should run");
+    let url = requests[0]["url"].as_str().unwrap();
+    assert!(url.contains("petId=new"), "url: {url}");
+
+    let steps = store.steps.lock().unwrap();
+    assert_eq!(steps[0].status, "succeeded");
+    assert_eq!(steps[0].outputs, serde_json::json!({ "petId": "old" }));
+}
+
+#[tokio::test]
+async fn canceling_mid_run_stops_claiming_and_skips_pending_steps() {
+    let run_id = Uuid::new_v4();
+    let step_ids = ["step1", "step2", "step3"];
+    let store = Arc::new(MockStore::with_steps(run_id, &step_ids));
+    let sink = Arc::new(RecordingEventSink::default());
+    let executor = Executor::new(
+        ExecutorConfig {
+            global_concurrency: 1,
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(CancelingHttpClient {
+            store: store.clone(),
+        }),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(make_policy())),
+        sink.clone(),
+    );
+
+    let result = executor
+        .execute_run(
+            run_id,
+            &make_workflow(&step_ids),
+            &make_compiled(&step_ids),
+            &serde_json::json!({}),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(result.canceled);
+    assert_eq!(result.succeeded_steps, 1);
+    assert_eq!(store.attempt_requests.lock().unwrap().len(), 1);
+
+    let statuses: Vec<String> = store
+        .steps
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|s| s.status.clone())
+        .collect();
+    assert_eq!(statuses, vec!["succeeded", "skipped", "skipped"]);
+    assert!(sink.events.lock().unwrap().iter().any(|e| matches!(
+        e,
+        Event::RunFinished {
+            status: arazzo_store::RunStatus::Canceled,
+            ..
+        }
+    )));
+}
+
+#[tokio::test]
+async fn steps_in_the_same_serialize_group_do_not_overlap() {
+    let step_ids = ["debit", "credit"];
+    for grouped in [false, true] {
+        let run_id = Uuid::new_v4();
+        let store = Arc::new(MockStore::with_steps(run_id, &step_ids));
+        let http = Arc::new(OverlapHttpClient::default());
+        let executor = Executor::new(
+            ExecutorConfig {
+                poll_interval: Duration::from_millis(5),
+                ..Default::default()
+            },
+            store.clone(),
+            http.clone(),
+            Arc::new(NoOpSecretsProvider),
+            Arc::new(PolicyGate::new(make_policy())),
+            Arc::new(RecordingEventSink::default()),
+        );
+
+        let mut workflow = make_workflow(&step_ids);
+        if grouped {
+            for step in &mut workflow.steps {
+                step.extensions.insert(
+                    arazzo_exec::executor::concurrency::SERIALIZE_GROUP_EXTENSION.to_string(),
+                    serde_json::json!("ledger"),
+                );
+            }
+        }
+
+        let result = executor
+            .execute_run(
+                run_id,
+                &workflow,
+                &make_compiled(&step_ids),
+                &serde_json::json!({}),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.succeeded_steps, 2);
+        let expected = if grouped { 1 } else { 2 };
+        assert_eq!(http.max_in_flight.load(Ordering::SeqCst), expected);
+    }
+}
+
+#[tokio::test]
+async fn claims_take_no_more_steps_of_a_source_than_its_concurrency() {
+    let step_ids = ["a", "b", "c"];
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &step_ids));
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            per_source_concurrency: [("petstore".to_string(), 2)].into_iter().collect(),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(OkHttpClient),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(make_policy())),
+        Arc::new(RecordingEventSink::default()),
+    );
+
+    let result = executor
+        .execute_run(
+            run_id,
+            &make_workflow(&step_ids),
+            &make_compiled(&step_ids),
+            &serde_json::json!({}),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 3);
+
+    let claims = store.claims.lock().unwrap();
+    let non_empty: Vec<&Vec<String>> = claims.iter().filter(|c| !c.is_empty()).collect();
+    assert_eq!(
+        non_empty,
+        vec![
+            &vec!["a".to_string(), "b".to_string()],
+            &vec!["c".to_string()]
+        ]
+    );
+}
+
+#[tokio::test]
+async fn seeded_rng_makes_retry_delays_reproducible() {
+    async fn retry_delays(seed: u64) -> Vec<i64> {
+        let run_id = Uuid::new_v4();
+        let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+        let executor = Executor::new(
+            ExecutorConfig {
+                poll_interval: Duration::from_millis(5),
+                rng: RngSource::seeded(seed),
+                ..Default::default()
+            },
+            store.clone(),
+            Arc::new(UnavailableHttpClient),
+            Arc::new(NoOpSecretsProvider),
+            Arc::new(PolicyGate::new(make_policy())),
+            Arc::new(RecordingEventSink::default()),
+        );
+
+        let mut workflow = make_workflow(&["step1"]);
+        workflow.steps[0].on_failure =
+            Some(vec![arazzo_core::types::FailureActionOrReusable::Action(
+                arazzo_core::types::FailureAction {
+                    name: "retry".to_string(),
+                    action_type: arazzo_core::types::FailureActionType::Retry,
+                    retry_limit: Some(3),
+                    retry_after_seconds: None,
+                    step_id: None,
+                    workflow_id: None,
+                    criteria: None,
+                    extensions: Default::default(),
+                },
+            )]);
+        executor
+            .execute_run(
+                run_id,
+                &workflow,
+                &make_compiled(&["step1"]),
+                &serde_json::json!({}),
+                None,
+            )
+            .await
+            .unwrap();
+        let delays = store.retry_delays.lock().unwrap().clone();
+        delays
+    }
+
+    let first = retry_delays(42).await;
+    assert_eq!(first.len(), 3);
+    assert_eq!(retry_delays(42).await, first);
+    assert_ne!(retry_delays(7).await, first);
+}
+
+fn retry_action(retry_limit: u32) -> arazzo_core::types::FailureActionOrReusable {
+    arazzo_core::types::FailureActionOrReusable::Action(arazzo_core::types::FailureAction {
+        name: "retry".to_string(),
+        action_type: arazzo_core::types::FailureActionType::Retry,
+        retry_limit: Some(retry_limit),
+        retry_after_seconds: Some(0.0),
+        step_id: None,
+        workflow_id: None,
+        criteria: None,
+        extensions: Default::default(),
+    })
+}
+
+#[tokio::test]
+async fn dependent_runs_once_its_retried_blocker_succeeds() {
+    let step_ids = ["charge", "receipt"];
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &step_ids));
+    store.set_depends_on("receipt", &["charge"]);
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(FlakyHttpClient {
+            failures: 1,
+            sent: AtomicUsize::new(0),
+        }),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(make_policy())),
+        Arc::new(RecordingEventSink::default()),
+    );
+
+    let mut workflow = make_workflow(&step_ids);
+    workflow.steps[0].on_failure = Some(vec![retry_action(2)]);
+    let result = executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&step_ids),
+            &serde_json::json!({}),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 2);
+    assert_eq!(store.retry_delays.lock().unwrap().len(), 1);
+    assert_eq!(store.attempt_requests.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn retrying_a_failed_step_reenables_the_dependents_it_skipped() {
+    use arazzo_store::StateStore;
+
+    let step_ids = ["charge", "receipt", "email"];
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &step_ids));
+    store.set_depends_on("receipt", &["charge"]);
+    store.set_depends_on("email", &["receipt"]);
+    let sink = Arc::new(RecordingEventSink::default());
+    let executor = |http: Arc<dyn HttpClient>| {
+        Executor::new(
+            ExecutorConfig {
+                poll_interval: Duration::from_millis(5),
+                ..Default::default()
+            },
+            store.clone(),
+            http,
+            Arc::new(NoOpSecretsProvider),
+            Arc::new(PolicyGate::new(make_policy())),
+            sink.clone(),
+        )
+    };
+    let statuses = || -> Vec<String> {
+        let steps = store.steps.lock().unwrap();
+        steps.iter().map(|s| s.status.clone()).collect()
+    };
+    let workflow = make_workflow(&step_ids);
+    let compiled = make_compiled(&step_ids);
+
+    executor(Arc::new(UnavailableHttpClient))
+        .execute_run(run_id, &workflow, &compiled, &serde_json::json!({}), None)
+        .await
+        .unwrap();
+    assert_eq!(statuses(), vec!["failed", "skipped", "skipped"]);
+
+    // Retrying the failed step goes through a claim, so it is `running` when it succeeds.
+    store
+        .schedule_retry(run_id, "charge", 0, serde_json::json!({"type": "http"}))
+        .await
+        .unwrap();
+    let result = executor(Arc::new(OkHttpClient))
+        .execute_run(run_id, &workflow, &compiled, &serde_json::json!({}), None)
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 3);
+    assert_eq!(statuses(), vec!["succeeded", "succeeded", "succeeded"]);
+
+    // Re-enabled dependents run for the first time, so each of them is announced.
+    let started: Vec<String> = sink
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|e| match e {
+            Event::StepStarted { step_id, .. } => Some(step_id.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(started, vec!["charge", "receipt", "email"]);
+}
+
+#[tokio::test]
+async fn reusable_failure_actions_are_resolved_from_components() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(UnavailableHttpClient),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(make_policy())),
+        Arc::new(RecordingEventSink::default()),
+    );
+    let document = arazzo_core::parse_document_str(
+        r#"
+arazzo: 1.0.1
+info: { title: Test, version: 1.0.0 }
+sourceDescriptions:
+  - name: petstore
+    url: https://api.test.local/openapi.json
+workflows:
+  - workflowId: test-workflow
+    steps:
+      - stepId: step1
+        operationId: getUsers
+        onFailure:
+          - reference: $components.failureActions.retryTwice
+components:
+  failureActions:
+    retryTwice:
+      name: retryTwice
+      type: retry
+      retryAfter: 0
+      retryLimit: 2
+"#,
+        arazzo_core::DocumentFormat::Yaml,
+    )
+    .unwrap()
+    .document;
+
+    executor
+        .execute_run(
+            run_id,
+            &document.workflows[0],
+            &make_compiled(&["step1"]),
+            &serde_json::json!({}),
+            Some(&document),
+        )
+        .await
+        .unwrap();
+    assert_eq!(store.retry_delays.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn missing_required_input_fails_the_run_before_any_request() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let sink = Arc::new(RecordingEventSink::default());
+    let executor = make_executor(store.clone(), Arc::new(NoOpSecretsProvider), sink.clone());
+
+    // `tenantId` is required by the schema even though no step references it.
+    let mut workflow = make_workflow(&["step1"]);
+    workflow.inputs = Some(serde_json::json!({
+        "type": "object",
+        "required": ["tenantId"],
+        "properties": { "tenantId": { "type": "string" } }
+    }));
+
+    let err = executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&["step1"]),
+            &serde_json::json!({ "other": 1 }),
+            None,
+        )
+        .await
+        .unwrap_err();
+    match err {
+        arazzo_exec::executor::ExecutionError::MissingInputs(names) => {
+            assert_eq!(names, vec!["tenantId"]);
+        }
+        other => panic!("expected missing inputs, got {other:?}"),
+    }
+    assert!(store.attempt_requests.lock().unwrap().is_empty());
+    assert_eq!(store.steps.lock().unwrap()[0].status, "skipped");
+    assert!(sink.events.lock().unwrap().iter().any(|e| matches!(
+        e,
+        Event::RunFinished {
+            status: arazzo_store::RunStatus::Failed,
+            ..
+        }
+    )));
+}
+
+#[tokio::test]
+async fn schema_defaults_fill_inputs_the_caller_left_out() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let executor = make_executor(
+        store.clone(),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(RecordingEventSink::default()),
+    );
+
+    let mut workflow = make_workflow(&["step1"]);
+    workflow.inputs = Some(serde_json::json!({
+        "type": "object",
+        "required": ["tenantId"],
+        "properties": {
+            "tenantId": { "type": "string", "default": "acme" },
+            "limit": { "type": "integer", "default": 10 }
+        }
+    }));
+    workflow.steps[0].request_body = Some(arazzo_core::types::RequestBody {
+        content_type: Some("application/json".to_string()),
+        payload: Some(serde_json::json!({
+            "tenant": "$inputs.tenantId",
+            "limit": "$inputs.limit"
+        })),
+        replacements: None,
+        extensions: Default::default(),
+    });
+
+    executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&["step1"]),
+            &serde_json::json!({ "limit": 50 }),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let requests = store.attempt_requests.lock().unwrap();
+    let body: serde_json::Value =
+        serde_json::from_str(requests[0]["body"].as_str().unwrap()).unwrap();
+    assert_eq!(body, serde_json::json!({ "tenant": "acme", "limit": 50 }));
+}
+
+#[tokio::test]
+async fn outputs_and_criteria_read_the_sanitized_request() {
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &["step1"]));
+    let executor = make_executor(
+        store.clone(),
+        Arc::new(StaticSecretsProvider("s3cr3t")),
+        Arc::new(RecordingEventSink::default()),
+    );
+
+    let param = |name: &str, location, value: &str| {
+        arazzo_core::types::ParameterOrReusable::Parameter(arazzo_core::types::Parameter {
+            name: name.to_string(),
+            r#in: Some(location),
+            value: serde_json::json!(value),
+            extensions: Default::default(),
+        })
+    };
+    let mut workflow = make_workflow(&["step1"]);
+    let step = &mut workflow.steps[0];
+    step.parameters = Some(vec![
+        param(
+            "X-Request-Id",
+            arazzo_core::types::ParameterLocation::Header,
+            "req-{$inputs.n}",
+        ),
+        param(
+            "Authorization",
+            arazzo_core::types::ParameterLocation::Header,
+            "secrets://API_TOKEN",
+        ),
+        param("limit", arazzo_core::types::ParameterLocation::Query, "5"),
+    ]);
+    step.success_criteria = Some(vec![arazzo_core::types::Criterion {
+        context: None,
+        condition: "$request.header.X-Request-Id == 'req-7'".to_string(),
+        r#type: None,
+        extensions: Default::default(),
+    }]);
+    step.outputs = Some(BTreeMap::from([
+        (
+            "requestId".to_string(),
+            "$request.header.X-Request-Id".to_string(),
+        ),
+        (
+            "auth".to_string(),
+            "$request.header.Authorization".to_string(),
+        ),
+        ("limit".to_string(), "$request.query.limit".to_string()),
+        ("method".to_string(), "$method".to_string()),
+        ("url".to_string(), "$url".to_string()),
+    ]));
+
+    let result = executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&["step1"]),
+            &serde_json::json!({ "n": 7 }),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 1);
+
+    let steps = store.steps.lock().unwrap();
+    assert_eq!(
+        steps[0].outputs,
+        serde_json::json!({
+            "requestId": "req-7",
+            "auth": "<redacted>",
+            "limit": "5",
+            "method": "GET",
+            "url": "https://api.test.local/users?limit=5",
+        })
+    );
+}
+
+#[tokio::test]
+async fn step_waiting_on_its_serialize_group_does_not_hold_up_other_steps() {
+    let step_ids = ["debit", "credit", "audit"];
+    let run_id = Uuid::new_v4();
+    let store = Arc::new(MockStore::with_steps(run_id, &step_ids));
+    let sink = Arc::new(RecordingEventSink::default());
+    let executor = Executor::new(
+        ExecutorConfig {
+            poll_interval: Duration::from_millis(5),
+            ..Default::default()
+        },
+        store.clone(),
+        Arc::new(OverlapHttpClient::default()),
+        Arc::new(NoOpSecretsProvider),
+        Arc::new(PolicyGate::new(make_policy())),
+        sink.clone(),
+    );
+
+    let mut workflow = make_workflow(&step_ids);
+    for step in workflow.steps.iter_mut().filter(|s| s.step_id != "audit") {
+        step.extensions.insert(
+            arazzo_exec::executor::concurrency::SERIALIZE_GROUP_EXTENSION.to_string(),
+            serde_json::json!("ledger"),
+        );
+    }
+
+    let result = executor
+        .execute_run(
+            run_id,
+            &workflow,
+            &make_compiled(&step_ids),
+            &serde_json::json!({}),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.succeeded_steps, 3);
+
+    // `audit` starts alongside `debit`; only `credit` waits for the group.
+    let started: Vec<String> = sink
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|e| match e {
+            Event::StepStarted { step_id, .. } => Some(step_id.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(started, ["debit", "audit", "credit"]);
+}
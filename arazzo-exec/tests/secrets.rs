@@ -35,7 +35,7 @@ fn redaction_strips_auth_cookie_and_secret_derived_headers() {
     let out = redact_headers(
         &headers,
         &RedactionPolicy::default(),
-        &vec!["X-Api-Key".to_string()],
+        &["X-Api-Key".to_string()],
     );
     assert_eq!(out.headers["Authorization"], "<redacted>");
     assert_eq!(out.headers["Cookie"], "<redacted>");
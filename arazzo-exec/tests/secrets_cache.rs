@@ -95,7 +95,7 @@ async fn caching_provider_enforces_max_entries() {
     };
 
     let v1 = cache.get(&ref1).await.unwrap();
-    let v2 = cache.get(&ref2).await.unwrap();
+    let _v2 = cache.get(&ref2).await.unwrap();
     let _ = cache.get(&ref3).await.unwrap();
 
     let v1_again = cache.get(&ref1).await.unwrap();
@@ -222,3 +222,65 @@ async fn secrets_provider_get_many() {
     std::env::remove_var("SECRET1");
     std::env::remove_var("SECRET2");
 }
+
+#[cfg(unix)]
+#[tokio::test]
+async fn stdin_secrets_provider_reads_key_values_from_a_pipe() {
+    use arazzo_exec::secrets::StdinSecretsProvider;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("printf")
+        .arg("# ci secrets\nAPI_TOKEN=tok-123\nDB_PASSWORD=a=b\n")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let pipe = child.stdout.take().unwrap();
+    let provider = StdinSecretsProvider::key_values().with_reader(pipe);
+
+    let get = |id: &str| SecretRef::parse(&format!("secrets://{id}")).unwrap();
+    let token = provider.get(&get("API_TOKEN")).await.unwrap();
+    assert_eq!(token.expose_bytes(), b"tok-123");
+    let password = provider.get(&get("DB_PASSWORD")).await.unwrap();
+    assert_eq!(password.expose_bytes(), b"a=b");
+    assert!(matches!(
+        provider.get(&get("MISSING")).await,
+        Err(SecretError::NotFound(_))
+    ));
+    child.wait().unwrap();
+}
+
+#[tokio::test]
+async fn stdin_secrets_provider_single_uses_the_whole_input() {
+    use arazzo_exec::secrets::StdinSecretsProvider;
+
+    let provider =
+        StdinSecretsProvider::single("API_TOKEN").with_reader(std::io::Cursor::new("tok-123\n"));
+    let secret_ref = SecretRef::parse("secrets://API_TOKEN").unwrap();
+    let value = provider.get(&secret_ref).await.unwrap();
+    assert_eq!(value.expose_bytes(), b"tok-123");
+}
+
+#[tokio::test]
+async fn stdin_secrets_provider_parse_error_leaves_out_the_line() {
+    use arazzo_exec::secrets::StdinSecretsProvider;
+
+    let provider = StdinSecretsProvider::key_values()
+        .with_reader(std::io::Cursor::new("API_TOKEN=tok-123\nhunter2\n"));
+    let secret_ref = SecretRef::parse("secrets://API_TOKEN").unwrap();
+    let err = provider.get(&secret_ref).await.unwrap_err().to_string();
+    assert!(err.contains("line 2"), "{err}");
+    assert!(!err.contains("hunter2"), "{err}");
+}
+
+#[tokio::test]
+async fn stdin_secrets_provider_reads_once_for_concurrent_lookups() {
+    use arazzo_exec::secrets::StdinSecretsProvider;
+
+    let provider =
+        StdinSecretsProvider::key_values().with_reader(std::io::Cursor::new("A=1\nB=2\n"));
+    let a = SecretRef::parse("secrets://A").unwrap();
+    let b = SecretRef::parse("secrets://B").unwrap();
+    let (a, b) = tokio::join!(provider.get(&a), provider.get(&b));
+    assert_eq!(a.unwrap().expose_bytes(), b"1");
+    assert_eq!(b.unwrap().expose_bytes(), b"2");
+}
@@ -0,0 +1,341 @@
+//! In-memory [`StateStore`] shared by the integration tests that run workflows end to end.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use arazzo_store::{
+    RunEvent, RunStatus, RunStep, StateStore, StoreError, WorkflowDoc, WorkflowRun,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value as JsonValue};
+use uuid::Uuid;
+
+/// Just enough of the Postgres store's semantics to run a workflow end to end.
+#[derive(Default)]
+pub struct MemoryStore {
+    docs: Mutex<Vec<WorkflowDoc>>,
+    runs: Mutex<Vec<WorkflowRun>>,
+    steps: Mutex<Vec<RunStep>>,
+    events: Mutex<Vec<RunEvent>>,
+    /// How many of the next `claim_queued_run` calls fail, as with a dropped connection.
+    pub failing_claims: AtomicUsize,
+}
+
+impl MemoryStore {
+    fn update_step(&self, run_id: Uuid, step_id: &str, f: impl FnOnce(&mut RunStep)) {
+        let mut steps = self.steps.lock().unwrap();
+        if let Some(s) = steps
+            .iter_mut()
+            .find(|s| s.run_id == run_id && s.step_id == step_id)
+        {
+            f(s);
+        }
+    }
+
+    fn update_run(&self, run_id: Uuid, f: impl FnOnce(&mut WorkflowRun)) {
+        if let Some(r) = self
+            .runs
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|r| r.id == run_id)
+        {
+            f(r);
+        }
+    }
+}
+
+#[async_trait]
+impl StateStore for MemoryStore {
+    async fn upsert_workflow_doc(
+        &self,
+        doc: arazzo_store::NewWorkflowDoc,
+    ) -> Result<WorkflowDoc, StoreError> {
+        let stored = WorkflowDoc {
+            id: Uuid::new_v4(),
+            doc_hash: doc.doc_hash,
+            format: doc.format.as_str().to_string(),
+            raw: doc.raw,
+            doc: doc.doc,
+            created_at: Utc::now(),
+        };
+        self.docs.lock().unwrap().push(stored.clone());
+        Ok(stored)
+    }
+
+    async fn get_workflow_doc(&self, id: Uuid) -> Result<Option<WorkflowDoc>, StoreError> {
+        Ok(self
+            .docs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|d| d.id == id)
+            .cloned())
+    }
+
+    async fn create_run_and_steps(
+        &self,
+        run: arazzo_store::NewRun,
+        steps: Vec<arazzo_store::NewRunStep>,
+        _edges: Vec<arazzo_store::RunStepEdge>,
+    ) -> Result<Uuid, StoreError> {
+        let run_id = Uuid::new_v4();
+        self.runs.lock().unwrap().push(WorkflowRun {
+            id: run_id,
+            workflow_doc_id: run.workflow_doc_id,
+            workflow_id: run.workflow_id,
+            status: "queued".to_string(),
+            created_by: run.created_by,
+            idempotency_key: run.idempotency_key,
+            inputs: run.inputs,
+            overrides: run.overrides,
+            provenance: run.provenance,
+            error: None,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        });
+        self.steps
+            .lock()
+            .unwrap()
+            .extend(steps.into_iter().map(|s| RunStep {
+                id: Uuid::new_v4(),
+                run_id,
+                step_id: s.step_id,
+                step_index: s.step_index,
+                status: "pending".to_string(),
+                source_name: s.source_name,
+                operation_id: s.operation_id,
+                deps_remaining: s.depends_on.len() as i32,
+                depends_on: s.depends_on,
+                next_run_at: None,
+                outputs: json!({}),
+                error: None,
+                started_at: None,
+                finished_at: None,
+            }));
+        Ok(run_id)
+    }
+
+    async fn claim_runnable_steps(
+        &self,
+        run_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<RunStep>, StoreError> {
+        let now = Utc::now();
+        let mut claimed = Vec::new();
+        for s in self.steps.lock().unwrap().iter_mut() {
+            if claimed.len() as i64 >= limit {
+                break;
+            }
+            if s.run_id == run_id
+                && s.status == "pending"
+                && s.deps_remaining == 0
+                && s.next_run_at.map_or(true, |at| at <= now)
+            {
+                s.status = "running".to_string();
+                claimed.push(s.clone());
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn insert_attempt_auto(
+        &self,
+        _run_step_id: Uuid,
+        _request: JsonValue,
+    ) -> Result<(Uuid, i32), StoreError> {
+        Ok((Uuid::new_v4(), 1))
+    }
+
+    async fn finish_attempt(
+        &self,
+        _attempt_id: Uuid,
+        _status: arazzo_store::AttemptStatus,
+        _response: JsonValue,
+        _error: Option<JsonValue>,
+        _duration_ms: Option<i32>,
+        _finished_at: Option<DateTime<Utc>>,
+    ) -> Result<(), StoreError> {
+        Ok(())
+    }
+
+    async fn mark_step_succeeded(
+        &self,
+        run_id: Uuid,
+        step_id: &str,
+        outputs: JsonValue,
+    ) -> Result<(), StoreError> {
+        self.update_step(run_id, step_id, |s| {
+            s.status = "succeeded".to_string();
+            s.outputs = outputs;
+        });
+        for s in self.steps.lock().unwrap().iter_mut() {
+            if s.run_id == run_id && s.depends_on.iter().any(|d| d == step_id) {
+                s.deps_remaining -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_step_outputs(&self, run_id: Uuid, step_id: &str) -> Result<JsonValue, StoreError> {
+        Ok(self
+            .steps
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.run_id == run_id && s.step_id == step_id)
+            .map(|s| s.outputs.clone())
+            .unwrap_or_else(|| json!({})))
+    }
+
+    async fn schedule_retry(
+        &self,
+        run_id: Uuid,
+        step_id: &str,
+        delay_ms: i64,
+        _error: JsonValue,
+    ) -> Result<(), StoreError> {
+        self.update_step(run_id, step_id, |s| {
+            s.status = "pending".to_string();
+            s.next_run_at = Some(Utc::now() + chrono::Duration::milliseconds(delay_ms));
+        });
+        Ok(())
+    }
+
+    async fn mark_step_failed(
+        &self,
+        run_id: Uuid,
+        step_id: &str,
+        error: JsonValue,
+    ) -> Result<(), StoreError> {
+        self.update_step(run_id, step_id, |s| {
+            s.status = "failed".to_string();
+            s.error = Some(error);
+        });
+        Ok(())
+    }
+
+    async fn mark_run_started(&self, run_id: Uuid) -> Result<(), StoreError> {
+        self.update_run(run_id, |r| {
+            r.status = "running".to_string();
+            r.started_at = Some(Utc::now());
+        });
+        Ok(())
+    }
+
+    async fn mark_run_finished(
+        &self,
+        run_id: Uuid,
+        status: arazzo_store::RunStatus,
+        error: Option<JsonValue>,
+    ) -> Result<(), StoreError> {
+        self.update_run(run_id, |r| {
+            r.status = status.as_str().to_string();
+            r.error = error;
+            r.finished_at = Some(Utc::now());
+        });
+        Ok(())
+    }
+
+    async fn append_event(&self, event: arazzo_store::NewEvent) -> Result<(), StoreError> {
+        let mut events = self.events.lock().unwrap();
+        let id = events.len() as i64 + 1;
+        events.push(RunEvent {
+            id,
+            run_id: event.run_id,
+            run_step_id: event.run_step_id,
+            ts: Utc::now(),
+            event_type: event.r#type,
+            payload: event.payload,
+        });
+        Ok(())
+    }
+
+    async fn get_run(&self, run_id: Uuid) -> Result<Option<WorkflowRun>, StoreError> {
+        Ok(self
+            .runs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.id == run_id)
+            .cloned())
+    }
+
+    async fn claim_queued_run(&self) -> Result<Option<WorkflowRun>, StoreError> {
+        let failing = self.failing_claims.load(Ordering::SeqCst);
+        if failing > 0 {
+            self.failing_claims.store(failing - 1, Ordering::SeqCst);
+            return Err(StoreError::Other("connection reset".to_string()));
+        }
+        Ok(arazzo_store::claim_queued_run(
+            &mut self.runs.lock().unwrap(),
+            Utc::now(),
+        ))
+    }
+
+    async fn update_run_inputs(&self, run_id: Uuid, inputs: JsonValue) -> Result<(), StoreError> {
+        self.update_run(run_id, |r| r.inputs = inputs);
+        Ok(())
+    }
+
+    async fn get_run_steps(&self, run_id: Uuid) -> Result<Vec<RunStep>, StoreError> {
+        Ok(self
+            .steps
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.run_id == run_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn reset_stale_running_steps(&self, _run_id: Uuid) -> Result<i64, StoreError> {
+        Ok(0)
+    }
+
+    async fn skip_pending_steps(&self, run_id: Uuid, error: JsonValue) -> Result<i64, StoreError> {
+        let mut skipped = 0;
+        for s in self.steps.lock().unwrap().iter_mut() {
+            if s.run_id == run_id && s.status == "pending" {
+                s.status = "skipped".to_string();
+                s.error = Some(error.clone());
+                skipped += 1;
+            }
+        }
+        Ok(skipped)
+    }
+
+    async fn get_step_attempts(
+        &self,
+        _run_step_id: Uuid,
+    ) -> Result<Vec<arazzo_store::StepAttempt>, StoreError> {
+        Ok(vec![])
+    }
+
+    async fn get_events_after(
+        &self,
+        run_id: Uuid,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<RunEvent>, StoreError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.run_id == run_id && e.id > after_id)
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn check_run_status(&self, run_id: Uuid) -> Result<RunStatus, StoreError> {
+        let run = self
+            .get_run(run_id)
+            .await?
+            .ok_or_else(|| StoreError::Other(format!("run {run_id} not found")))?;
+        run.status.parse().map_err(StoreError::Other)
+    }
+}
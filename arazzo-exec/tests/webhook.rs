@@ -26,6 +26,7 @@ impl HttpClient for MockHttpClient {
         Ok(HttpResponseParts {
             status: 200,
             headers: BTreeMap::new(),
+            repeated_headers: BTreeMap::new(),
             body: vec![],
         })
     }
@@ -0,0 +1,6 @@
+-- Provenance metadata (source path, git commit, author, ...) for auditing which
+-- version of a workflow document ran. It belongs to the run: the same document (same
+-- hash) can be started from different paths or commits, and each run keeps where it
+-- came from.
+ALTER TABLE workflow_runs
+  ADD COLUMN IF NOT EXISTS provenance jsonb NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,16 @@
+-- Wake executors waiting on a run (LISTEN arazzo_step_runnable) as soon as one of its
+-- steps becomes claimable, instead of leaving them to poll for it.
+CREATE OR REPLACE FUNCTION arazzo_notify_step_runnable() RETURNS trigger AS $$
+BEGIN
+  PERFORM pg_notify('arazzo_step_runnable', NEW.run_id::text);
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS run_steps_notify_runnable ON run_steps;
+CREATE TRIGGER run_steps_notify_runnable
+  AFTER INSERT OR UPDATE OF status, deps_remaining, next_run_at ON run_steps
+  FOR EACH ROW
+  WHEN (NEW.status = 'pending' AND NEW.deps_remaining = 0
+        AND (NEW.next_run_at IS NULL OR NEW.next_run_at <= now()))
+  EXECUTE FUNCTION arazzo_notify_step_runnable();
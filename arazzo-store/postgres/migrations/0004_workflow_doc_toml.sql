@@ -0,0 +1,4 @@
+-- Documents may also be authored in TOML.
+ALTER TABLE workflow_docs DROP CONSTRAINT IF EXISTS workflow_docs_format_check;
+ALTER TABLE workflow_docs
+  ADD CONSTRAINT workflow_docs_format_check CHECK (format IN ('yaml', 'json', 'toml'));
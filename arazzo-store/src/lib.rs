@@ -6,7 +6,8 @@ pub mod store;
 pub use crate::postgres::run_migrations;
 pub use crate::postgres::PostgresStore;
 pub use crate::store::{
-    AttemptStatus, DocFormat, NewAttempt, NewEvent, NewRun, NewRunStep, NewStep, NewWorkflowDoc,
-    RunEvent, RunStatus, RunStep, RunStepEdge, RunStepStatus, StateStore, StepAttempt, StoreError,
-    WorkflowDoc, WorkflowRun,
+    claim_queued_run, claimable_step_ids, dependents_to_reenable, doc_hash, doc_hash_matches,
+    AttemptRequest, AttemptResponse, AttemptStatus, DocFormat, HashAlgorithm, NewAttempt, NewEvent,
+    NewRun, NewRunStep, NewStep, NewWorkflowDoc, RunEvent, RunStatus, RunStep, RunStepEdge,
+    RunStepStatus, StateStore, StepAttempt, StoreError, WorkflowDoc, WorkflowRun,
 };
@@ -1,5 +1,6 @@
 mod events;
 mod migrate;
+mod notify;
 mod runs;
 mod steps;
 mod store;
@@ -0,0 +1,54 @@
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::store::StoreError;
+
+/// Channel the `run_steps_notify_runnable` trigger notifies, with the run id as payload.
+pub const RUNNABLE_CHANNEL: &str = "arazzo_step_runnable";
+
+/// One `LISTEN` connection per store, shared by everything waiting on it. A task owns
+/// the listener and fans each notified run id out to the subscribed waiters.
+#[derive(Default)]
+pub struct RunnableListener {
+    task: Mutex<Option<(broadcast::Receiver<Uuid>, JoinHandle<()>)>>,
+}
+
+impl RunnableListener {
+    /// Subscribe to runnable notifications, (re)starting the listening task when it is
+    /// not running. Waiters subscribed to a task that fails see the channel close.
+    pub async fn subscribe(&self, pool: &PgPool) -> Result<broadcast::Receiver<Uuid>, StoreError> {
+        let mut task = self.task.lock().await;
+        if let Some((receiver, handle)) = task.as_ref() {
+            if !handle.is_finished() {
+                return Ok(receiver.resubscribe());
+            }
+        }
+
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(RUNNABLE_CHANNEL).await?;
+        let (sender, receiver) = broadcast::channel(64);
+        let handle = tokio::spawn(async move {
+            // `recv` reconnects on its own; an error means it could not, so stop and let
+            // the next subscriber start over.
+            while let Ok(notification) = listener.recv().await {
+                if let Ok(run_id) = notification.payload().parse() {
+                    let _ = sender.send(run_id);
+                }
+            }
+        });
+        let subscribed = receiver.resubscribe();
+        *task = Some((receiver, handle));
+        Ok(subscribed)
+    }
+}
+
+impl Drop for RunnableListener {
+    fn drop(&mut self) {
+        if let Some((_, handle)) = self.task.get_mut().take() {
+            handle.abort();
+        }
+    }
+}
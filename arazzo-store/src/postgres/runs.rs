@@ -96,7 +96,7 @@ pub async fn get_run(pool: &PgPool, run_id: Uuid) -> Result<Option<WorkflowRun>,
     let rec = sqlx::query_as::<_, WorkflowRun>(
         r#"
 SELECT id, workflow_doc_id, workflow_id, status, created_by, idempotency_key,
-       inputs, overrides, error, created_at, started_at, finished_at
+       inputs, overrides, provenance, error, created_at, started_at, finished_at
 FROM workflow_runs WHERE id = $1
         "#,
     )
@@ -106,6 +106,46 @@ FROM workflow_runs WHERE id = $1
     Ok(rec)
 }
 
+/// `SKIP LOCKED` lets concurrent workers each take a different run instead of waiting
+/// on (and then double-claiming) the same row.
+pub async fn claim_queued_run(pool: &PgPool) -> Result<Option<WorkflowRun>, StoreError> {
+    let rec = sqlx::query_as::<_, WorkflowRun>(
+        r#"
+UPDATE workflow_runs SET status = 'running', started_at = COALESCE(started_at, now())
+WHERE id = (
+  SELECT id FROM workflow_runs
+  WHERE status = 'queued'
+  ORDER BY created_at, id
+  LIMIT 1
+  FOR UPDATE SKIP LOCKED
+)
+RETURNING id, workflow_doc_id, workflow_id, status, created_by, idempotency_key,
+          inputs, overrides, provenance, error, created_at, started_at, finished_at
+        "#,
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(rec)
+}
+
+pub async fn update_run_inputs(
+    pool: &PgPool,
+    run_id: Uuid,
+    inputs: JsonValue,
+) -> Result<(), StoreError> {
+    sqlx::query(
+        r#"
+UPDATE workflow_runs SET inputs = $2
+WHERE id = $1
+        "#,
+    )
+    .bind(run_id)
+    .bind(inputs)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 pub async fn mark_run_finished_enum(
     pool: &PgPool,
     run_id: Uuid,
@@ -159,12 +199,12 @@ WHERE id = $1
     Ok(())
 }
 
-pub async fn check_run_status(pool: &PgPool, run_id: Uuid) -> Result<String, StoreError> {
+pub async fn check_run_status(pool: &PgPool, run_id: Uuid) -> Result<RunStatus, StoreError> {
     let rec: (String,) = sqlx::query_as(r#"SELECT status FROM workflow_runs WHERE id = $1"#)
         .bind(run_id)
         .fetch_one(pool)
         .await?;
-    Ok(rec.0)
+    rec.0.parse().map_err(StoreError::Other)
 }
 
 async fn insert_steps(
@@ -224,8 +264,9 @@ async fn insert_run(tx: &mut Transaction<'_, Postgres>, run: NewRun) -> Result<U
         let inserted: Option<(Uuid,)> = sqlx::query_as(
             r#"
 INSERT INTO workflow_runs
-  (workflow_doc_id, workflow_id, status, created_by, idempotency_key, inputs, overrides)
-VALUES ($1, $2, 'queued', $3, $4, $5, $6)
+  (workflow_doc_id, workflow_id, status, created_by, idempotency_key, inputs, overrides,
+   provenance)
+VALUES ($1, $2, 'queued', $3, $4, $5, $6, $7)
 ON CONFLICT (created_by, idempotency_key) DO NOTHING
 RETURNING id
             "#,
@@ -236,6 +277,7 @@ RETURNING id
         .bind(&run.idempotency_key)
         .bind(&run.inputs)
         .bind(&run.overrides)
+        .bind(&run.provenance)
         .fetch_optional(&mut **tx)
         .await?;
 
@@ -257,8 +299,9 @@ RETURNING id
     let rec: (Uuid,) = sqlx::query_as(
         r#"
 INSERT INTO workflow_runs
-  (workflow_doc_id, workflow_id, status, created_by, idempotency_key, inputs, overrides)
-VALUES ($1, $2, 'queued', $3, $4, $5, $6)
+  (workflow_doc_id, workflow_id, status, created_by, idempotency_key, inputs, overrides,
+   provenance)
+VALUES ($1, $2, 'queued', $3, $4, $5, $6, $7)
 RETURNING id
         "#,
     )
@@ -268,6 +311,7 @@ RETURNING id
     .bind(&run.idempotency_key)
     .bind(&run.inputs)
     .bind(&run.overrides)
+    .bind(&run.provenance)
     .fetch_one(&mut **tx)
     .await?;
 
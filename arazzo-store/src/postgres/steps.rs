@@ -1,9 +1,15 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
 use serde_json::Value as JsonValue;
 use sqlx::PgPool;
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
-use crate::store::{AttemptStatus, RunStep, StepAttempt, StoreError};
+use super::notify::RunnableListener;
+
+use crate::store::{dependents_to_reenable, AttemptStatus, RunStep, StepAttempt, StoreError};
 
 pub async fn claim_runnable_steps(
     pool: &PgPool,
@@ -38,6 +44,105 @@ RETURNING s.id, s.run_id, s.step_id, s.step_index, s.status, s.source_name, s.op
     Ok(rows)
 }
 
+/// [`claim_runnable_steps`] capped per source: each step's rank within its source (by
+/// `step_index`) must not exceed that source's limit. Unlisted sources are uncapped.
+pub async fn claim_runnable_steps_within(
+    pool: &PgPool,
+    run_id: Uuid,
+    limit: i64,
+    source_limits: &BTreeMap<String, usize>,
+) -> Result<Vec<RunStep>, StoreError> {
+    let (sources, limits): (Vec<String>, Vec<i64>) = source_limits
+        .iter()
+        .map(|(source, max)| (source.clone(), *max as i64))
+        .unzip();
+    let mut tx = pool.begin().await?;
+
+    let rows = sqlx::query_as::<_, RunStep>(
+        r#"
+WITH limits AS (
+  SELECT * FROM unnest($3::text[], $4::bigint[]) AS l(source_name, max_claim)
+),
+ranked AS (
+  SELECT s.id, l.max_claim,
+         row_number() OVER (PARTITION BY s.source_name ORDER BY s.step_index) AS rank
+  FROM run_steps s
+  LEFT JOIN limits l ON l.source_name = s.source_name
+  WHERE s.run_id = $1 AND s.status = 'pending' AND s.deps_remaining = 0
+    AND (s.next_run_at IS NULL OR s.next_run_at <= now())
+),
+picked AS (
+  SELECT id FROM run_steps
+  WHERE id IN (SELECT id FROM ranked WHERE max_claim IS NULL OR rank <= max_claim)
+  ORDER BY step_index
+  FOR UPDATE SKIP LOCKED
+  LIMIT $2
+)
+UPDATE run_steps s
+SET status = 'running', started_at = COALESCE(started_at, now())
+FROM picked WHERE s.id = picked.id
+RETURNING s.id, s.run_id, s.step_id, s.step_index, s.status, s.source_name, s.operation_id,
+          s.depends_on, s.deps_remaining, s.next_run_at, s.outputs, s.error, s.started_at, s.finished_at
+        "#,
+    )
+    .bind(run_id)
+    .bind(limit)
+    .bind(sources)
+    .bind(limits)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(rows)
+}
+
+/// Block until `run_id` is notified on [`RUNNABLE_CHANNEL`](super::notify::RUNNABLE_CHANNEL)
+/// or `timeout` elapses. Waits share the store's listener connection; an error means
+/// it is unavailable and the caller should poll instead.
+pub async fn wait_for_runnable(
+    pool: &PgPool,
+    listener: &RunnableListener,
+    run_id: Uuid,
+    timeout: Duration,
+) -> Result<bool, StoreError> {
+    let mut notifications = listener.subscribe(pool).await?;
+
+    // A step that became runnable before subscribing sent its notification already.
+    let (runnable,): (bool,) = sqlx::query_as(
+        r#"
+SELECT EXISTS (
+  SELECT 1 FROM run_steps
+  WHERE run_id = $1 AND status = 'pending' AND deps_remaining = 0
+    AND (next_run_at IS NULL OR next_run_at <= now())
+)
+        "#,
+    )
+    .bind(run_id)
+    .fetch_one(pool)
+    .await?;
+    if runnable {
+        return Ok(true);
+    }
+
+    let notified = async {
+        loop {
+            match notifications.recv().await {
+                Ok(id) if id == run_id => return Ok(()),
+                Ok(_) => {}
+                // Ours may have been among the dropped ones; waking early is harmless.
+                Err(RecvError::Lagged(_)) => return Ok(()),
+                Err(RecvError::Closed) => {
+                    return Err(StoreError::Other("runnable listener stopped".to_string()))
+                }
+            }
+        }
+    };
+    match tokio::time::timeout(timeout, notified).await {
+        Ok(result) => result.map(|_| true),
+        Err(_) => Ok(false),
+    }
+}
+
 /// Reset steps that are stuck in 'running' state (e.g., after executor crash).
 /// This allows them to be picked up again by claim_runnable_steps.
 pub async fn reset_stale_running_steps(pool: &PgPool, run_id: Uuid) -> Result<i64, StoreError> {
@@ -53,6 +158,24 @@ WHERE run_id = $1 AND status = 'running'
     Ok(result.rows_affected() as i64)
 }
 
+pub async fn skip_pending_steps(
+    pool: &PgPool,
+    run_id: Uuid,
+    error: JsonValue,
+) -> Result<i64, StoreError> {
+    let result = sqlx::query(
+        r#"
+UPDATE run_steps SET status = 'skipped', finished_at = now(), error = $2
+WHERE run_id = $1 AND status = 'pending'
+        "#,
+    )
+    .bind(run_id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() as i64)
+}
+
 pub async fn get_run_steps(pool: &PgPool, run_id: Uuid) -> Result<Vec<RunStep>, StoreError> {
     let rows = sqlx::query_as::<_, RunStep>(
         r#"
@@ -75,6 +198,16 @@ pub async fn mark_step_succeeded(
 ) -> Result<(), StoreError> {
     let mut tx = pool.begin().await?;
 
+    // The claim already moved the step to `running`, so an earlier failure only shows in
+    // the error that `schedule_retry` or `mark_step_failed` left behind.
+    let failed_before: Option<(bool,)> = sqlx::query_as(
+        r#"SELECT error IS NOT NULL FROM run_steps WHERE run_id = $1 AND step_id = $2 FOR UPDATE"#,
+    )
+    .bind(run_id)
+    .bind(step_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
     sqlx::query(
         r#"
 UPDATE run_steps SET status = 'succeeded', finished_at = now(), outputs = $3, error = NULL
@@ -100,6 +233,34 @@ WHERE e.run_id = $1 AND e.from_step_id = $2 AND e.to_step_id = d.step_id
     .execute(&mut *tx)
     .await?;
 
+    // A step that failed earlier skipped its dependents; give them another chance.
+    if failed_before.is_some_and(|(failed,)| failed) {
+        let steps = sqlx::query_as::<_, RunStep>(
+            r#"
+SELECT id, run_id, step_id, step_index, status, source_name, operation_id,
+       depends_on, deps_remaining, next_run_at, outputs, error, started_at, finished_at
+FROM run_steps WHERE run_id = $1
+            "#,
+        )
+        .bind(run_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        for (dependent, deps_remaining) in dependents_to_reenable(&steps, step_id) {
+            sqlx::query(
+                r#"
+UPDATE run_steps
+SET status = 'pending', deps_remaining = $3, next_run_at = NULL, error = NULL, finished_at = NULL
+WHERE run_id = $1 AND step_id = $2 AND status = 'skipped'
+                "#,
+            )
+            .bind(run_id)
+            .bind(dependent)
+            .bind(deps_remaining)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
     tx.commit().await?;
     Ok(())
 }
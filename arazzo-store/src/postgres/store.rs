@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
 use serde_json::Value as JsonValue;
 use sqlx::PgPool;
@@ -9,16 +12,21 @@ use crate::store::{
 };
 
 use super::events;
+use super::notify::RunnableListener;
 use super::runs;
 use super::steps;
 
 pub struct PostgresStore {
     pool: PgPool,
+    runnable: RunnableListener,
 }
 
 impl PostgresStore {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            runnable: RunnableListener::default(),
+        }
     }
 
     pub async fn connect(database_url: &str, max_connections: u32) -> Result<Self, StoreError> {
@@ -26,7 +34,7 @@ impl PostgresStore {
             .max_connections(max_connections)
             .connect(database_url)
             .await?;
-        Ok(Self { pool })
+        Ok(Self::new(pool))
     }
 
     pub fn pool(&self) -> &PgPool {
@@ -100,6 +108,19 @@ impl StateStore for PostgresStore {
         steps::claim_runnable_steps(&self.pool, run_id, limit).await
     }
 
+    async fn claim_runnable_steps_within(
+        &self,
+        run_id: Uuid,
+        limit: i64,
+        source_limits: &BTreeMap<String, usize>,
+    ) -> Result<Vec<RunStep>, StoreError> {
+        steps::claim_runnable_steps_within(&self.pool, run_id, limit, source_limits).await
+    }
+
+    async fn wait_for_runnable(&self, run_id: Uuid, timeout: Duration) -> Result<bool, StoreError> {
+        steps::wait_for_runnable(&self.pool, &self.runnable, run_id, timeout).await
+    }
+
     async fn insert_attempt_auto(
         &self,
         run_step_id: Uuid,
@@ -182,6 +203,14 @@ impl StateStore for PostgresStore {
         runs::get_run(&self.pool, run_id).await
     }
 
+    async fn claim_queued_run(&self) -> Result<Option<WorkflowRun>, StoreError> {
+        runs::claim_queued_run(&self.pool).await
+    }
+
+    async fn update_run_inputs(&self, run_id: Uuid, inputs: JsonValue) -> Result<(), StoreError> {
+        runs::update_run_inputs(&self.pool, run_id, inputs).await
+    }
+
     async fn get_run_steps(&self, run_id: Uuid) -> Result<Vec<RunStep>, StoreError> {
         steps::get_run_steps(&self.pool, run_id).await
     }
@@ -190,6 +219,10 @@ impl StateStore for PostgresStore {
         steps::reset_stale_running_steps(&self.pool, run_id).await
     }
 
+    async fn skip_pending_steps(&self, run_id: Uuid, error: JsonValue) -> Result<i64, StoreError> {
+        steps::skip_pending_steps(&self.pool, run_id, error).await
+    }
+
     async fn get_step_attempts(&self, run_step_id: Uuid) -> Result<Vec<StepAttempt>, StoreError> {
         steps::get_step_attempts(&self.pool, run_step_id).await
     }
@@ -203,7 +236,7 @@ impl StateStore for PostgresStore {
         events::get_events_after(&self.pool, run_id, after_id, limit).await
     }
 
-    async fn check_run_status(&self, run_id: Uuid) -> Result<String, StoreError> {
+    async fn check_run_status(&self, run_id: Uuid) -> Result<RunStatus, StoreError> {
         runs::check_run_status(&self.pool, run_id).await
     }
 }
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::store::{RunStatus, RunStep, RunStepStatus, WorkflowRun};
+
+/// Ids of the steps one claim should take: runnable steps (pending, no remaining
+/// dependencies, retry delay elapsed) in `step_index` order, at most `limit` in total
+/// and at most `source_limits[source]` for each listed source.
+///
+/// This is the in-memory counterpart of the Postgres claim query.
+pub fn claimable_step_ids(
+    steps: &[RunStep],
+    limit: usize,
+    source_limits: &BTreeMap<String, usize>,
+    now: DateTime<Utc>,
+) -> Vec<Uuid> {
+    let mut runnable: Vec<&RunStep> = steps
+        .iter()
+        .filter(|s| {
+            s.status.parse() == Ok(RunStepStatus::Pending)
+                && s.deps_remaining == 0
+                && s.next_run_at.map_or(true, |at| at <= now)
+        })
+        .collect();
+    runnable.sort_by_key(|s| s.step_index);
+
+    let mut taken = BTreeMap::<&str, usize>::new();
+    let mut ids = Vec::new();
+    for s in runnable {
+        if ids.len() >= limit {
+            break;
+        }
+        if let Some(source) = s.source_name.as_deref() {
+            if let Some(&max) = source_limits.get(source) {
+                let count = taken.entry(source).or_default();
+                if *count >= max {
+                    continue;
+                }
+                *count += 1;
+            }
+        }
+        ids.push(s.id);
+    }
+    ids
+}
+
+/// Take the oldest queued run (by `created_at`, then `id`): mark it running, stamp
+/// `started_at` unless it already has one, and return a copy. `None` when nothing is
+/// queued.
+///
+/// This is the in-memory counterpart of the Postgres `claim_queued_run` query; callers
+/// hold `runs` under one lock so concurrent claims never take the same run.
+pub fn claim_queued_run(runs: &mut [WorkflowRun], now: DateTime<Utc>) -> Option<WorkflowRun> {
+    let run = runs
+        .iter_mut()
+        .filter(|r| r.status.parse() == Ok(RunStatus::Queued))
+        .min_by_key(|r| (r.created_at, r.id))?;
+    run.status = RunStatus::Running.to_string();
+    run.started_at.get_or_insert(now);
+    Some(run.clone())
+}
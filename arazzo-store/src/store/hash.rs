@@ -0,0 +1,66 @@
+use std::fmt;
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+
+/// Digest used to compute [`crate::NewWorkflowDoc::doc_hash`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// The algorithm that produced a hash returned by [`doc_hash`].
+    pub fn of_hash(hash: &str) -> Self {
+        if hash.starts_with("blake3:") {
+            HashAlgorithm::Blake3
+        } else {
+            HashAlgorithm::Sha256
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sha256" | "sha-256" => Ok(HashAlgorithm::Sha256),
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            other => Err(format!(
+                "unknown hash algorithm '{other}', expected sha256 or blake3"
+            )),
+        }
+    }
+}
+
+/// Hex digest identifying a workflow document's raw content.
+///
+/// SHA-256 hashes are bare hex, as they have always been stored; other algorithms are
+/// prefixed with their name (e.g. `blake3:...`) so hashes never collide across algorithms.
+pub fn doc_hash(content: &str, algo: HashAlgorithm) -> String {
+    match algo {
+        HashAlgorithm::Sha256 => hex::encode(Sha256::digest(content.as_bytes())),
+        HashAlgorithm::Blake3 => format!("blake3:{}", blake3::hash(content.as_bytes()).to_hex()),
+    }
+}
+
+/// Whether `content` hashes to `stored`, using the algorithm `stored` was computed with.
+pub fn doc_hash_matches(stored: &str, content: &str) -> bool {
+    doc_hash(content, HashAlgorithm::of_hash(stored)) == stored
+}
@@ -1,6 +1,12 @@
+mod claim;
+mod hash;
+mod reenable;
 mod trait_store;
 mod types;
 
+pub use claim::{claim_queued_run, claimable_step_ids};
+pub use hash::{doc_hash, doc_hash_matches, HashAlgorithm};
+pub use reenable::dependents_to_reenable;
 pub use trait_store::StateStore;
 pub use trait_store::StoreError;
 pub use types::*;
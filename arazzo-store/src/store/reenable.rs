@@ -0,0 +1,74 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::store::{RunStep, RunStepStatus};
+
+/// Steps to move from `skipped` back to `pending` now that `succeeded_step_id`, which
+/// had failed and cascaded skips to its dependents, has succeeded on a retry.
+///
+/// Only skipped steps reachable from `succeeded_step_id` through other skipped steps are
+/// considered, and a step stays skipped while any dependency is still failed or skipped
+/// for another reason. Returns `(step_id, deps_remaining)` pairs, ordered by step id.
+pub fn dependents_to_reenable(steps: &[RunStep], succeeded_step_id: &str) -> Vec<(String, i32)> {
+    let status: BTreeMap<&str, Option<RunStepStatus>> = steps
+        .iter()
+        .map(|s| {
+            let st = if s.step_id == succeeded_step_id {
+                Some(RunStepStatus::Succeeded)
+            } else {
+                s.status.parse().ok()
+            };
+            (s.step_id.as_str(), st)
+        })
+        .collect();
+
+    let mut candidates = BTreeSet::<&str>::new();
+    let mut frontier = vec![succeeded_step_id];
+    while let Some(blocker) = frontier.pop() {
+        for s in steps {
+            if s.status.parse() == Ok(RunStepStatus::Skipped)
+                && s.depends_on.iter().any(|d| d == blocker)
+                && candidates.insert(s.step_id.as_str())
+            {
+                frontier.push(s.step_id.as_str());
+            }
+        }
+    }
+
+    loop {
+        let blocked: Vec<&str> = candidates
+            .iter()
+            .copied()
+            .filter(|c| {
+                let step = steps.iter().find(|s| s.step_id == *c).expect("candidate");
+                step.depends_on
+                    .iter()
+                    .any(|d| match status.get(d.as_str()).copied().flatten() {
+                        Some(RunStepStatus::Failed) => true,
+                        Some(RunStepStatus::Skipped) => !candidates.contains(d.as_str()),
+                        _ => false,
+                    })
+            })
+            .collect();
+        if blocked.is_empty() {
+            break;
+        }
+        for c in blocked {
+            candidates.remove(c);
+        }
+    }
+
+    candidates
+        .into_iter()
+        .map(|c| {
+            let step = steps.iter().find(|s| s.step_id == c).expect("candidate");
+            let remaining = step
+                .depends_on
+                .iter()
+                .filter(|d| {
+                    status.get(d.as_str()).copied().flatten() != Some(RunStepStatus::Succeeded)
+                })
+                .count();
+            (c.to_string(), remaining as i32)
+        })
+        .collect()
+}
@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde_json::Value as JsonValue;
@@ -24,6 +27,27 @@ pub trait StateStore: Send + Sync {
         limit: i64,
     ) -> Result<Vec<RunStep>, StoreError>;
 
+    /// Like [`claim_runnable_steps`](Self::claim_runnable_steps), but claims at most
+    /// `source_limits[source]` steps of each listed source, so steps the executor could
+    /// not start yet stay `pending`. The default ignores `source_limits`.
+    async fn claim_runnable_steps_within(
+        &self,
+        run_id: Uuid,
+        limit: i64,
+        source_limits: &BTreeMap<String, usize>,
+    ) -> Result<Vec<RunStep>, StoreError> {
+        let _ = source_limits;
+        self.claim_runnable_steps(run_id, limit).await
+    }
+
+    /// Wait up to `timeout` for a step of the run to become runnable. Returns `true` when
+    /// woken early; stores without change notifications just sleep, i.e. poll.
+    async fn wait_for_runnable(&self, run_id: Uuid, timeout: Duration) -> Result<bool, StoreError> {
+        let _ = run_id;
+        tokio::time::sleep(timeout).await;
+        Ok(false)
+    }
+
     /// Insert a new attempt with an automatically computed `attempt_no` (append-only).
     async fn insert_attempt_auto(
         &self,
@@ -79,11 +103,28 @@ pub trait StateStore: Send + Sync {
 
     async fn get_run(&self, run_id: Uuid) -> Result<Option<WorkflowRun>, StoreError>;
 
+    /// Take the oldest `queued` run for this process to execute, marking it `running`.
+    /// Stores that don't hand out queued runs return [`StoreError::Unsupported`].
+    async fn claim_queued_run(&self) -> Result<Option<WorkflowRun>, StoreError> {
+        Err(StoreError::Unsupported("claim_queued_run"))
+    }
+
+    /// Replace the stored inputs of a run (e.g. when resuming with corrected inputs).
+    /// Stores that don't support it return [`StoreError::Unsupported`].
+    async fn update_run_inputs(&self, run_id: Uuid, inputs: JsonValue) -> Result<(), StoreError> {
+        let _ = (run_id, inputs);
+        Err(StoreError::Unsupported("update_run_inputs"))
+    }
+
     async fn get_run_steps(&self, run_id: Uuid) -> Result<Vec<RunStep>, StoreError>;
 
     /// Reset steps stuck in 'running' state (after crash). Returns count of reset steps.
     async fn reset_stale_running_steps(&self, run_id: Uuid) -> Result<i64, StoreError>;
 
+    /// Mark all still-pending steps of a run as skipped (e.g. once it is canceled).
+    /// Returns count of skipped steps.
+    async fn skip_pending_steps(&self, run_id: Uuid, error: JsonValue) -> Result<i64, StoreError>;
+
     async fn get_step_attempts(&self, run_step_id: Uuid) -> Result<Vec<StepAttempt>, StoreError>;
 
     async fn get_events_after(
@@ -93,13 +134,15 @@ pub trait StateStore: Send + Sync {
         limit: i64,
     ) -> Result<Vec<RunEvent>, StoreError>;
 
-    async fn check_run_status(&self, run_id: Uuid) -> Result<String, StoreError>;
+    async fn check_run_status(&self, run_id: Uuid) -> Result<RunStatus, StoreError>;
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum StoreError {
     #[error("store error: {0}")]
     Other(String),
+    #[error("store does not support {0}")]
+    Unsupported(&'static str),
 }
 
 impl From<sqlx::Error> for StoreError {
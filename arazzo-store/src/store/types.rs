@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
+use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use uuid::Uuid;
 
@@ -6,6 +9,7 @@ use uuid::Uuid;
 pub enum DocFormat {
     Yaml,
     Json,
+    Toml,
 }
 
 impl DocFormat {
@@ -13,6 +17,21 @@ impl DocFormat {
         match self {
             DocFormat::Yaml => "yaml",
             DocFormat::Json => "json",
+            DocFormat::Toml => "toml",
+        }
+    }
+}
+
+/// The format a document was actually parsed as; `Auto` never comes back from the
+/// parser, and is recorded as YAML since YAML also accepts JSON.
+impl From<arazzo_core::DocumentFormat> for DocFormat {
+    fn from(format: arazzo_core::DocumentFormat) -> Self {
+        match format {
+            arazzo_core::DocumentFormat::Json => DocFormat::Json,
+            arazzo_core::DocumentFormat::Toml => DocFormat::Toml,
+            arazzo_core::DocumentFormat::Yaml | arazzo_core::DocumentFormat::Auto => {
+                DocFormat::Yaml
+            }
         }
     }
 }
@@ -54,6 +73,35 @@ impl RunStatus {
             RunStatus::Canceled => "canceled",
         }
     }
+
+    /// Whether the run has finished and will not be picked up again.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            RunStatus::Succeeded | RunStatus::Failed | RunStatus::Canceled
+        )
+    }
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for RunStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(RunStatus::Queued),
+            "running" => Ok(RunStatus::Running),
+            "succeeded" => Ok(RunStatus::Succeeded),
+            "failed" => Ok(RunStatus::Failed),
+            "canceled" => Ok(RunStatus::Canceled),
+            other => Err(format!("unknown run status '{other}'")),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -64,6 +112,8 @@ pub struct NewRun {
     pub idempotency_key: Option<String>,
     pub inputs: JsonValue,
     pub overrides: JsonValue,
+    /// Where the run's document came from (e.g. `source_path`, `git_commit`, `author`).
+    pub provenance: JsonValue,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -85,6 +135,35 @@ impl RunStepStatus {
             RunStepStatus::Skipped => "skipped",
         }
     }
+
+    /// Whether the step has finished, successfully or not.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            RunStepStatus::Succeeded | RunStepStatus::Failed | RunStepStatus::Skipped
+        )
+    }
+}
+
+impl std::fmt::Display for RunStepStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for RunStepStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(RunStepStatus::Pending),
+            "running" => Ok(RunStepStatus::Running),
+            "succeeded" => Ok(RunStepStatus::Succeeded),
+            "failed" => Ok(RunStepStatus::Failed),
+            "skipped" => Ok(RunStepStatus::Skipped),
+            other => Err(format!("unknown step status '{other}'")),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -152,6 +231,46 @@ pub struct StepAttempt {
     pub finished_at: Option<DateTime<Utc>>,
 }
 
+/// Sanitized request as persisted on a [`StepAttempt`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AttemptRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub body_truncated: bool,
+}
+
+/// Sanitized response as persisted on a [`StepAttempt`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AttemptResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub body_truncated: bool,
+}
+
+impl StepAttempt {
+    pub fn request_parts(&self) -> Result<AttemptRequest, serde_json::Error> {
+        AttemptRequest::deserialize(&self.request)
+    }
+
+    /// Returns `None` for attempts that never received a response (e.g. network errors),
+    /// which are stored with an empty object.
+    pub fn response_parts(&self) -> Result<Option<AttemptResponse>, serde_json::Error> {
+        if self.response.as_object().is_some_and(|o| o.is_empty()) {
+            return Ok(None);
+        }
+        AttemptResponse::deserialize(&self.response).map(Some)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NewEvent {
     pub run_id: Uuid,
@@ -182,6 +301,7 @@ pub struct WorkflowRun {
     pub idempotency_key: Option<String>,
     pub inputs: JsonValue,
     pub overrides: JsonValue,
+    pub provenance: JsonValue,
     pub error: Option<JsonValue>,
     pub created_at: DateTime<Utc>,
     pub started_at: Option<DateTime<Utc>>,
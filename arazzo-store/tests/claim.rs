@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use arazzo_store::{claim_queued_run, claimable_step_ids, RunStep, WorkflowRun};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+fn step(step_index: i32, source: Option<&str>, status: &str) -> RunStep {
+    RunStep {
+        id: Uuid::from_u128(step_index as u128 + 1),
+        run_id: Uuid::nil(),
+        step_id: format!("s{step_index}"),
+        step_index,
+        status: status.to_string(),
+        source_name: source.map(str::to_string),
+        operation_id: None,
+        depends_on: vec![],
+        deps_remaining: 0,
+        next_run_at: None,
+        outputs: serde_json::json!({}),
+        error: None,
+        started_at: None,
+        finished_at: None,
+    }
+}
+
+fn run(n: u128, status: &str, created_minutes_ago: i64) -> WorkflowRun {
+    WorkflowRun {
+        id: Uuid::from_u128(n),
+        workflow_doc_id: Uuid::nil(),
+        workflow_id: "wf".to_string(),
+        status: status.to_string(),
+        created_by: None,
+        idempotency_key: None,
+        inputs: serde_json::json!({}),
+        overrides: serde_json::json!({}),
+        provenance: serde_json::json!({}),
+        error: None,
+        created_at: Utc::now() - Duration::minutes(created_minutes_ago),
+        started_at: None,
+        finished_at: None,
+    }
+}
+
+fn claimed_indices(steps: &[RunStep], limit: usize, limits: &[(&str, usize)]) -> Vec<i32> {
+    let limits: BTreeMap<String, usize> = limits.iter().map(|(s, n)| (s.to_string(), *n)).collect();
+    let ids = claimable_step_ids(steps, limit, &limits, Utc::now());
+    steps
+        .iter()
+        .filter(|s| ids.contains(&s.id))
+        .map(|s| s.step_index)
+        .collect()
+}
+
+#[test]
+fn claims_at_most_the_source_budget_per_source() {
+    let steps = vec![
+        step(0, Some("petstore"), "pending"),
+        step(1, Some("petstore"), "pending"),
+        step(2, Some("billing"), "pending"),
+        step(3, Some("petstore"), "pending"),
+        step(4, None, "pending"),
+    ];
+    assert_eq!(
+        claimed_indices(&steps, 10, &[("petstore", 1)]),
+        vec![0, 2, 4]
+    );
+    assert_eq!(claimed_indices(&steps, 10, &[]), vec![0, 1, 2, 3, 4]);
+    assert_eq!(claimed_indices(&steps, 2, &[("petstore", 1)]), vec![0, 2]);
+}
+
+#[test]
+fn only_runnable_steps_are_claimable() {
+    let mut blocked = step(1, Some("petstore"), "pending");
+    blocked.deps_remaining = 1;
+    let mut delayed = step(2, Some("petstore"), "pending");
+    delayed.next_run_at = Some(Utc::now() + Duration::minutes(1));
+    let steps = vec![
+        step(0, Some("petstore"), "running"),
+        blocked,
+        delayed,
+        step(3, Some("petstore"), "pending"),
+    ];
+    assert_eq!(claimed_indices(&steps, 10, &[("petstore", 1)]), vec![3]);
+}
+
+#[test]
+fn queued_runs_are_claimed_oldest_first() {
+    let mut runs = vec![
+        run(1, "queued", 1),
+        run(2, "running", 10),
+        run(3, "queued", 5),
+    ];
+    let now = Utc::now();
+    let first = claim_queued_run(&mut runs, now).expect("a queued run");
+    assert_eq!(first.id, Uuid::from_u128(3));
+    assert_eq!(first.status, "running");
+    assert_eq!(first.started_at, Some(now));
+    assert_eq!(runs[2].status, "running");
+
+    let second = claim_queued_run(&mut runs, now).expect("a queued run");
+    assert_eq!(second.id, Uuid::from_u128(1));
+    assert!(claim_queued_run(&mut runs, now).is_none());
+}
+
+#[test]
+fn concurrent_claims_take_different_queued_runs() {
+    let runs = Mutex::new((1..=8).map(|n| run(n, "queued", 0)).collect::<Vec<_>>());
+    let claimed: Vec<Uuid> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                scope.spawn(|| {
+                    claim_queued_run(&mut runs.lock().unwrap(), Utc::now())
+                        .expect("a queued run")
+                        .id
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let distinct: std::collections::BTreeSet<Uuid> = claimed.iter().copied().collect();
+    assert_eq!(distinct.len(), claimed.len());
+    assert!(runs.lock().unwrap().iter().all(|r| r.status == "running"));
+    assert!(claim_queued_run(&mut runs.lock().unwrap(), Utc::now()).is_none());
+}
@@ -0,0 +1,25 @@
+use arazzo_store::{doc_hash, doc_hash_matches, HashAlgorithm};
+
+#[test]
+fn doc_hash_is_stable_per_algorithm() {
+    assert_eq!(
+        doc_hash("abc", HashAlgorithm::Sha256),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        doc_hash("", HashAlgorithm::Blake3),
+        "blake3:af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+    assert_eq!(HashAlgorithm::default(), HashAlgorithm::Sha256);
+    assert_eq!("BLAKE3".parse(), Ok(HashAlgorithm::Blake3));
+    assert!("md5".parse::<HashAlgorithm>().is_err());
+}
+
+#[test]
+fn doc_hash_matches_detects_changed_content() {
+    for algo in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+        let stored = doc_hash("arazzo: 1.0.1\n", algo);
+        assert!(doc_hash_matches(&stored, "arazzo: 1.0.1\n"), "{algo}");
+        assert!(!doc_hash_matches(&stored, "arazzo: 1.0.0\n"), "{algo}");
+    }
+}
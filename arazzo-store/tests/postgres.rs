@@ -0,0 +1,277 @@
+//! These need a Postgres database; set `ARAZZO_TEST_DATABASE_URL` to run them.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use arazzo_store::{
+    doc_hash, run_migrations, DocFormat, HashAlgorithm, NewRun, NewRunStep, NewWorkflowDoc,
+    PostgresStore, RunStepEdge, StateStore,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn test_store() -> Option<PostgresStore> {
+    let Ok(url) = std::env::var("ARAZZO_TEST_DATABASE_URL") else {
+        eprintln!("ARAZZO_TEST_DATABASE_URL not set; skipping");
+        return None;
+    };
+    let store = PostgresStore::connect(&url, 4).await.unwrap();
+    run_migrations(store.pool()).await.unwrap();
+    Some(store)
+}
+
+fn step(step_id: &str, step_index: i32, source: Option<&str>, depends_on: &[&str]) -> NewRunStep {
+    NewRunStep {
+        step_id: step_id.to_string(),
+        step_index,
+        source_name: source.map(str::to_string),
+        operation_id: None,
+        depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+async fn create_run(store: &PostgresStore, steps: Vec<NewRunStep>) -> Uuid {
+    create_run_with_provenance(store, steps, json!({})).await
+}
+
+async fn create_run_with_provenance(
+    store: &PostgresStore,
+    steps: Vec<NewRunStep>,
+    provenance: serde_json::Value,
+) -> Uuid {
+    let raw = "arazzo: 1.0.1 # postgres store tests";
+    let doc = store
+        .upsert_workflow_doc(NewWorkflowDoc {
+            doc_hash: doc_hash(raw, HashAlgorithm::default()),
+            format: DocFormat::Yaml,
+            raw: raw.to_string(),
+            doc: json!({}),
+        })
+        .await
+        .unwrap();
+    let edges = steps
+        .iter()
+        .flat_map(|s| {
+            s.depends_on.iter().map(|dep| RunStepEdge {
+                from_step_id: dep.clone(),
+                to_step_id: s.step_id.clone(),
+            })
+        })
+        .collect();
+    // The inherent `PostgresStore::create_run_and_steps` takes a different shape.
+    StateStore::create_run_and_steps(
+        store,
+        NewRun {
+            workflow_doc_id: doc.id,
+            workflow_id: "test".to_string(),
+            created_by: None,
+            idempotency_key: None,
+            inputs: json!({}),
+            overrides: json!({}),
+            provenance,
+        },
+        steps,
+        edges,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn newly_runnable_step_wakes_the_waiter() {
+    let Some(store) = test_store().await else {
+        return;
+    };
+    let run_id = create_run(
+        &store,
+        vec![
+            step("first", 0, None, &[]),
+            step("second", 1, None, &["first"]),
+        ],
+    )
+    .await;
+    let claimed = store.claim_runnable_steps(run_id, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+
+    // Nothing is runnable until `first` succeeds, so the wait runs out.
+    assert!(!store
+        .wait_for_runnable(run_id, Duration::from_millis(200))
+        .await
+        .unwrap());
+
+    let store = std::sync::Arc::new(store);
+    let waiter = {
+        let store = store.clone();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let woken = store
+                .wait_for_runnable(run_id, Duration::from_secs(30))
+                .await
+                .unwrap();
+            (woken, started.elapsed())
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    store
+        .mark_step_succeeded(run_id, "first", json!({}))
+        .await
+        .unwrap();
+
+    let (woken, elapsed) = waiter.await.unwrap();
+    assert!(woken);
+    assert!(elapsed < Duration::from_secs(5), "woke after {elapsed:?}");
+}
+
+#[tokio::test]
+async fn claim_respects_per_source_limits() {
+    let Some(store) = test_store().await else {
+        return;
+    };
+    let run_id = create_run(
+        &store,
+        vec![
+            step("a", 0, Some("petstore"), &[]),
+            step("b", 1, Some("petstore"), &[]),
+            step("c", 2, Some("billing"), &[]),
+            step("d", 3, Some("petstore"), &[]),
+        ],
+    )
+    .await;
+    let limits: BTreeMap<String, usize> = [("petstore".to_string(), 1)].into_iter().collect();
+
+    let ids = |steps: Vec<arazzo_store::RunStep>| {
+        let mut ids: Vec<String> = steps.into_iter().map(|s| s.step_id).collect();
+        ids.sort();
+        ids
+    };
+    let first = store
+        .claim_runnable_steps_within(run_id, 10, &limits)
+        .await
+        .unwrap();
+    assert_eq!(ids(first), vec!["a", "c"]);
+    let second = store
+        .claim_runnable_steps_within(run_id, 10, &limits)
+        .await
+        .unwrap();
+    assert_eq!(ids(second), vec!["b"]);
+}
+
+#[tokio::test]
+async fn concurrent_claims_take_different_queued_runs() {
+    let Some(store) = test_store().await else {
+        return;
+    };
+    create_run(&store, vec![step("only", 0, None, &[])]).await;
+    create_run(&store, vec![step("only", 0, None, &[])]).await;
+
+    let (first, second) = tokio::join!(store.claim_queued_run(), store.claim_queued_run());
+    let first = first.unwrap().expect("a queued run");
+    let second = second.unwrap().expect("a queued run");
+    assert_ne!(first.id, second.id);
+    for run in [&first, &second] {
+        assert_eq!(run.status, "running");
+        assert!(run.started_at.is_some());
+    }
+}
+
+#[tokio::test]
+async fn retried_step_success_reenables_skipped_dependents() {
+    let Some(store) = test_store().await else {
+        return;
+    };
+    let run_id = create_run(
+        &store,
+        vec![
+            step("charge", 0, None, &[]),
+            step("receipt", 1, None, &["charge"]),
+        ],
+    )
+    .await;
+    let status = |steps: &[arazzo_store::RunStep], id: &str| {
+        steps
+            .iter()
+            .find(|s| s.step_id == id)
+            .unwrap()
+            .status
+            .clone()
+    };
+
+    store.claim_runnable_steps(run_id, 10).await.unwrap();
+    store
+        .mark_step_failed(run_id, "charge", json!({"type": "http", "status": 503}))
+        .await
+        .unwrap();
+    let steps = store.get_run_steps(run_id).await.unwrap();
+    assert_eq!(status(&steps, "receipt"), "skipped");
+
+    // The retry goes through a claim, so the step is `running` when it succeeds.
+    store
+        .schedule_retry(run_id, "charge", 0, json!({"type": "http", "status": 503}))
+        .await
+        .unwrap();
+    let claimed = store.claim_runnable_steps(run_id, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    store
+        .mark_step_succeeded(run_id, "charge", json!({}))
+        .await
+        .unwrap();
+
+    let claimed = store.claim_runnable_steps(run_id, 10).await.unwrap();
+    let ids: Vec<&str> = claimed.iter().map(|s| s.step_id.as_str()).collect();
+    assert_eq!(ids, vec!["receipt"]);
+    // A re-enabled step has never been attempted, so its claim is a first attempt.
+    assert!(claimed[0].next_run_at.is_none());
+}
+
+#[tokio::test]
+async fn waits_share_one_listener_connection() {
+    let Ok(url) = std::env::var("ARAZZO_TEST_DATABASE_URL") else {
+        eprintln!("ARAZZO_TEST_DATABASE_URL not set; skipping");
+        return;
+    };
+    // A name of its own tells this store's connections apart from other tests'.
+    let app = format!("arazzo-listener-{}", std::process::id());
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let store = PostgresStore::connect(&format!("{url}{separator}application_name={app}"), 4)
+        .await
+        .unwrap();
+    run_migrations(store.pool()).await.unwrap();
+    let run_id = create_run(&store, vec![step("first", 0, None, &[])]).await;
+    store.claim_runnable_steps(run_id, 10).await.unwrap();
+
+    let mut listeners = Vec::new();
+    for _ in 0..3 {
+        assert!(!store
+            .wait_for_runnable(run_id, Duration::from_millis(50))
+            .await
+            .unwrap());
+        let pids: Vec<(i32,)> = sqlx::query_as(
+            "SELECT pid FROM pg_stat_activity WHERE application_name = $1 AND query LIKE 'LISTEN%'",
+        )
+        .bind(&app)
+        .fetch_all(store.pool())
+        .await
+        .unwrap();
+        listeners.push(pids);
+    }
+    assert_eq!(listeners[0].len(), 1, "{listeners:?}");
+    assert!(listeners.iter().all(|pids| *pids == listeners[0]));
+}
+
+#[tokio::test]
+async fn each_run_keeps_its_own_provenance() {
+    let Some(store) = test_store().await else {
+        return;
+    };
+    // Both runs store the same document, so they share one workflow_docs row.
+    let first = json!({ "source_path": "/repo/a/pets.yaml", "git_commit": "9f1c2ab" });
+    let second = json!({ "source_path": "/repo/b/pets.yaml" });
+    let first_run = create_run_with_provenance(&store, vec![], first.clone()).await;
+    let second_run = create_run_with_provenance(&store, vec![], second.clone()).await;
+
+    let first_run = store.get_run(first_run).await.unwrap().unwrap();
+    let second_run = store.get_run(second_run).await.unwrap().unwrap();
+    assert_eq!(first_run.workflow_doc_id, second_run.workflow_doc_id);
+    assert_eq!(first_run.provenance, first);
+    assert_eq!(second_run.provenance, second);
+}
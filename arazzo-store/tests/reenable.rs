@@ -0,0 +1,39 @@
+use arazzo_store::{dependents_to_reenable, RunStep};
+use uuid::Uuid;
+
+fn step(step_id: &str, status: &str, depends_on: &[&str]) -> RunStep {
+    RunStep {
+        id: Uuid::nil(),
+        run_id: Uuid::nil(),
+        step_id: step_id.to_string(),
+        step_index: 0,
+        status: status.to_string(),
+        source_name: None,
+        operation_id: None,
+        depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        deps_remaining: depends_on.len() as i32,
+        next_run_at: None,
+        outputs: serde_json::json!({}),
+        error: None,
+        started_at: None,
+        finished_at: None,
+    }
+}
+
+#[test]
+fn retried_step_success_reenables_its_skipped_dependents() {
+    // `charge` failed and cascaded skips; it is now being marked succeeded after a retry.
+    let steps = vec![
+        step("charge", "failed", &[]),
+        step("audit", "failed", &[]),
+        step("receipt", "skipped", &["charge"]),
+        step("email", "skipped", &["receipt"]),
+        step("ledger", "skipped", &["charge", "audit"]),
+        step("unrelated", "skipped", &["audit"]),
+    ];
+
+    assert_eq!(
+        dependents_to_reenable(&steps, "charge"),
+        vec![("email".to_string(), 1), ("receipt".to_string(), 0)]
+    );
+}
@@ -0,0 +1,41 @@
+use arazzo_store::{RunStatus, RunStepStatus};
+
+#[test]
+fn run_status_round_trips_through_strings() {
+    for status in [
+        RunStatus::Queued,
+        RunStatus::Running,
+        RunStatus::Succeeded,
+        RunStatus::Failed,
+        RunStatus::Canceled,
+    ] {
+        assert_eq!(status.as_str().parse::<RunStatus>(), Ok(status));
+        assert_eq!(status.to_string(), status.as_str());
+    }
+    assert!("pending".parse::<RunStatus>().is_err());
+    assert!("Succeeded".parse::<RunStatus>().is_err());
+}
+
+#[test]
+fn run_step_status_round_trips_through_strings() {
+    for status in [
+        RunStepStatus::Pending,
+        RunStepStatus::Running,
+        RunStepStatus::Succeeded,
+        RunStepStatus::Failed,
+        RunStepStatus::Skipped,
+    ] {
+        assert_eq!(status.as_str().parse::<RunStepStatus>(), Ok(status));
+        assert_eq!(status.to_string(), status.as_str());
+    }
+    assert!("queued".parse::<RunStepStatus>().is_err());
+    assert!("".parse::<RunStepStatus>().is_err());
+}
+
+#[test]
+fn terminal_statuses() {
+    assert!(!RunStatus::Running.is_terminal());
+    assert!(RunStatus::Canceled.is_terminal());
+    assert!(!RunStepStatus::Pending.is_terminal());
+    assert!(RunStepStatus::Skipped.is_terminal());
+}
@@ -0,0 +1,31 @@
+use arazzo_core::{parse_document_str, DocumentFormat};
+use arazzo_store::DocFormat;
+
+#[test]
+fn doc_format_records_the_parsed_format() {
+    let json = r#"{
+  "arazzo": "1.0.1",
+  "info": { "title": "Example", "version": "0.0.1" },
+  "sourceDescriptions": [{ "name": "api", "url": "https://example.com/openapi.yaml" }],
+  "workflows": [{ "workflowId": "w1", "steps": [{ "stepId": "s1", "operationId": "op1" }] }]
+}"#;
+    let parsed = parse_document_str(json, DocumentFormat::Auto).unwrap();
+    assert_eq!(DocFormat::from(parsed.format), DocFormat::Json);
+
+    let yaml = r#"
+arazzo: 1.0.1
+info:
+  title: Example
+  version: 0.0.1
+sourceDescriptions:
+  - name: api
+    url: https://example.com/openapi.yaml
+workflows:
+  - workflowId: w1
+    steps:
+      - stepId: s1
+        operationId: op1
+"#;
+    let parsed = parse_document_str(yaml, DocumentFormat::Auto).unwrap();
+    assert_eq!(DocFormat::from(parsed.format), DocFormat::Yaml);
+}